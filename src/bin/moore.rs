@@ -13,6 +13,7 @@ use moore::errors::*;
 use moore::name::Name;
 use moore::score::{ScoreBoard, ScoreContext};
 use moore::svlog::{hir::Visitor as _, QueryDatabase as _};
+use moore::vhdl::driver::{self, Phase};
 use moore::*;
 use std::path::Path;
 use moore_rhdl::syntax::ast::DesignUnit;
@@ -47,6 +48,32 @@ fn main() {
                 .long("trace-scoreboard")
                 .global(true),
         )
+        .arg(
+            Arg::with_name("warnings-as-errors")
+                .long("warnings-as-errors")
+                .help("Treat all warnings as errors")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("tab-width")
+                .long("tab-width")
+                .value_name("N")
+                .help("Count tabs as N columns when reporting source locations")
+                .takes_value(true)
+                .number_of_values(1)
+                .validator(validate_tab_width)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("max-literal-bits")
+                .long("max-literal-bits")
+                .value_name("N")
+                .help("Warn about integer literals that need more than N bits")
+                .takes_value(true)
+                .number_of_values(1)
+                .validator(validate_max_literal_bits)
+                .global(true),
+        )
         .arg(
             Arg::with_name("verbosity-opts")
                 .short("V")
@@ -100,11 +127,29 @@ fn main() {
                 .long("syntax")
                 .help("Preprocess and check the input for syntax errors"),
         )
+        .arg(
+            Arg::with_name("stop-after")
+                .long("stop-after")
+                .value_name("PHASE")
+                .help("Stop after a compilation phase")
+                .takes_value(true)
+                .possible_values(&["lex", "parse", "score", "codegen"]),
+        )
         .arg(
             Arg::with_name("emit_pkgs")
                 .long("emit-pkgs")
                 .help("Dump VHDL packages for debugging"),
         )
+        .arg(
+            Arg::with_name("emit-sensitivity")
+                .long("emit-sensitivity")
+                .help("Annotate the output with the signals each VHDL process is sensitive to"),
+        )
+        .arg(
+            Arg::with_name("report-unsupported")
+                .long("report-unsupported")
+                .help("List the VHDL constructs that cannot be lowered yet"),
+        )
         .arg(
             Arg::with_name("opt-level")
                 .short("O")
@@ -114,6 +159,15 @@ fn main() {
                 .takes_value(true)
                 .number_of_values(1),
         )
+        .arg(
+            Arg::with_name("vhdl-std")
+                .long("vhdl-std")
+                .value_name("YEAR")
+                .help("Revision of the VHDL standard to accept")
+                .default_value("2008")
+                .takes_value(true)
+                .possible_values(&["1993", "2002", "2008"]),
+        )
         .arg(
             Arg::with_name("lib")
                 .short("l")
@@ -133,6 +187,15 @@ fn main() {
                 .takes_value(true)
                 .number_of_values(1),
         )
+        .arg(
+            Arg::with_name("init-signal")
+                .long("init-signal")
+                .value_name("SIGNAL=VALUE")
+                .help("Override the initial value of a VHDL signal")
+                .multiple(true)
+                .takes_value(true)
+                .number_of_values(1),
+        )
         .arg(
             Arg::with_name("output")
                 .short("o")
@@ -159,6 +222,7 @@ fn main() {
     // Configure the session.
     let mut session = Session::new();
     session.opts.trace_scoreboard = matches.is_present("trace_scoreboard");
+    session.opts.warnings_as_errors = matches.is_present("warnings-as-errors");
     for v in matches
         .values_of("verbosity-opts")
         .into_iter()
@@ -178,6 +242,10 @@ fn main() {
         };
     }
     session.opts.opt_level = matches.value_of("opt-level").unwrap().parse().unwrap();
+    session.opts.tab_width = matches.value_of("tab-width").map(|w| w.parse().unwrap());
+    session.opts.max_literal_bits = matches
+        .value_of("max-literal-bits")
+        .map(|b| b.parse().unwrap());
 
     // Invoke the compiler.
     score(&session, &matches);
@@ -207,6 +275,13 @@ fn score(sess: &Session, matches: &ArgMatches) {
     // should be made configurable per entity.
     let lib = get_name_table().intern(matches.value_of("lib").unwrap_or("work"), true);
 
+    // Determine the revision of the VHDL standard to accept.
+    let vhdl_revision: vhdl::syntax::lexer::Revision =
+        matches.value_of("vhdl-std").unwrap().parse().unwrap();
+
+    // Determine the phase after which to stop.
+    let stop_after = stop_after_phase(matches);
+
     // Parse the input files.
     let mut failed = false;
     let mut asts = Vec::new();
@@ -280,10 +355,17 @@ fn score(sess: &Session, matches: &ArgMatches) {
                     Err(()) => failed = true,
                 }
             }
-            Language::Vhdl => match vhdl::syntax::parse(source) {
-                Ok(x) => asts.push(score::Ast::Vhdl(x)),
-                Err(()) => failed = true,
-            },
+            Language::Vhdl if stop_after == Phase::Lex => {
+                for token in vhdl::syntax::lexer::dump_tokens(source, sess) {
+                    println!("{}", token);
+                }
+            }
+            Language::Vhdl => {
+                match vhdl::syntax::parse_with_revision(source, sess, vhdl_revision) {
+                    Ok(x) => asts.push(score::Ast::Vhdl(x)),
+                    Err(()) => failed = true,
+                }
+            }
             Language::Rhdl => match rhdl::syntax::parse(source) {
                 Ok(x) => asts.push(score::Ast::Rhdl(x)),
                 Err(()) => failed = true,
@@ -293,7 +375,7 @@ fn score(sess: &Session, matches: &ArgMatches) {
     if failed || sess.failed() {
         std::process::exit(1);
     }
-    if matches.is_present("preproc") {
+    if matches.is_present("preproc") || stop_after == Phase::Lex {
         return;
     }
 
@@ -315,7 +397,7 @@ fn score(sess: &Session, matches: &ArgMatches) {
     }
 
     // Stop processing if requested.
-    if matches.is_present("check-syntax") {
+    if matches.is_present("check-syntax") || stop_after == Phase::Parse {
         std::process::exit(0);
     }
 
@@ -340,6 +422,20 @@ fn score(sess: &Session, matches: &ArgMatches) {
             svlog: &svlog_sb,
         };
         let lib_id = ctx.add_library(lib, &asts);
+        for init in matches.values_of("init-signal").into_iter().flatten() {
+            match parse_signal_init(init) {
+                Ok((name, value)) => ctx
+                    .vhdl()
+                    .override_signal_init(name, vhdl::konst::ConstInt::new(None, value)),
+                Err(()) => {
+                    sess.emit(DiagBuilder2::error(format!(
+                        "invalid signal initialization `{}`; expected `SIGNAL=VALUE`",
+                        init
+                    )));
+                    failed = true;
+                }
+            }
+        }
         if let Some(names) = matches.values_of("elaborate") {
             debug!("lib_id = {:?}", lib_id);
             debug!("{:?}", sb);
@@ -372,6 +468,32 @@ fn score(sess: &Session, matches: &ArgMatches) {
     }
 }
 
+/// Determine the phase after which compilation stops.
+fn stop_after_phase(matches: &ArgMatches) -> Phase {
+    matches
+        .value_of("stop-after")
+        .map(|phase| phase.parse().unwrap())
+        .unwrap_or(Phase::Codegen)
+}
+
+/// Check that a tab width is a positive number of columns.
+fn validate_tab_width(width: String) -> Result<(), String> {
+    match width.parse::<usize>() {
+        Ok(0) => Err(String::from("tab width must be at least 1")),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("`{}` is not a number of columns", width)),
+    }
+}
+
+/// Check that a literal width is a positive number of bits.
+fn validate_max_literal_bits(bits: String) -> Result<(), String> {
+    match bits.parse::<u64>() {
+        Ok(0) => Err(String::from("literal width must be at least 1 bit")),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("`{}` is not a number of bits", bits)),
+    }
+}
+
 /// Resolve an entity/module specificaiton of the form `[lib.]entity[.arch]` for
 /// elaboration.
 fn elaborate_name(
@@ -507,11 +629,24 @@ fn elaborate_name(
 
     // Generate the LLHD definition for whatever we're elaborating.
     match elab {
+        Elaborate::VhdlEntity(_entity, arch) if stop_after_phase(matches) == Phase::Score => {
+            use moore::vhdl::typeck::{Typeck, TypeckContext};
+            let sbc = ctx.vhdl();
+            let tyc = TypeckContext::new(&sbc);
+            tyc.typeck(arch);
+            if !tyc.finish() {
+                return Err(());
+            }
+        }
+        Elaborate::VhdlEntity(_entity, arch) if matches.is_present("report-unsupported") => {
+            ctx.vhdl().report_unsupported(arch);
+        }
         Elaborate::VhdlEntity(_entity, arch) => {
             // let decl = ctx.vhdl.lldecl(arch);
             // println!("Architecture declared as {:?}", decl);
-            let def = ctx.vhdl().llunit(arch)?;
-            eprintln!("Architecture declared as {:?}", def);
+            let defs = driver::codegen(&ctx.vhdl(), &[arch], ctx.sess.opts.opt_level)?;
+            debug!("Architecture declared as {:?}", defs[0]);
+            emit_output(matches, ctx, &ctx.vhdl.llmod.borrow())?;
         }
         Elaborate::VhdlPkg(pkg) => {
             use moore::vhdl::typeck::{Typeck, TypeckContext};
@@ -612,7 +747,7 @@ fn emit_output(
 
     // Open the output.
     let stdout = std::io::stdout();
-    let output: Box<dyn std::io::Write> = match matches.value_of("output") {
+    let mut output: Box<dyn std::io::Write> = match matches.value_of("output") {
         Some("-") | None => Box::new(stdout.lock()),
         Some(x) => Box::new(std::fs::File::create(x).map_err(|e| {
             ctx.sess.emit(
@@ -623,6 +758,31 @@ fn emit_output(
         })?),
     };
 
+    // Annotate the processes with the signals they are sensitive to, as
+    // comments ahead of the module.
+    if matches.is_present("emit-sensitivity") {
+        use std::io::Write;
+        let comment = match fmt {
+            OutputFormat::Llhd => ";",
+            OutputFormat::Mlir => "//",
+        };
+        let sensitivities = ctx.vhdl.sensitivities.borrow();
+        let mut units: Vec<_> = sensitivities.iter().collect();
+        units.sort_by_key(|&(unit, _)| unit);
+        for (unit, signals) in units {
+            let signals: Vec<_> = signals.iter().map(|s| s.to_string()).collect();
+            let signals = if signals.is_empty() {
+                String::from("none")
+            } else {
+                signals.join(", ")
+            };
+            writeln!(output, "{} sensitivity of {}: {}", comment, unit, signals).map_err(|e| {
+                ctx.sess
+                    .emit(DiagBuilder2::fatal("unable to write output").add_note(format!("{}", e)));
+            })?;
+        }
+    }
+
     // Emit the appropriate output.
     match fmt {
         OutputFormat::Llhd => llhd::assembly::write_module(output, &module),
@@ -696,6 +856,17 @@ fn parse_elaborate_name<S: AsRef<str>>(name: S) -> Result<(Option<Name>, Name, O
     Ok((lib, ent, third))
 }
 
+/// Parse a signal initialization of the form `name=value`, where `value` is
+/// an integer.
+fn parse_signal_init(init: &str) -> Result<(&str, num::BigInt), ()> {
+    let pos = init.find('=').ok_or(())?;
+    let (name, value) = (init[..pos].trim(), init[pos + 1..].trim());
+    if name.is_empty() {
+        return Err(());
+    }
+    Ok((name, value.parse().map_err(|_| ())?))
+}
+
 /// A visitor that emits detailed type information to stdout.
 pub struct TypeVerbosityVisitor<'a, 'gcx>(&'a svlog::GlobalContext<'gcx>, svlog::ParamEnv);
 
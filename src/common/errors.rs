@@ -3,6 +3,8 @@
 //! Utilities to implement diagnostics and error reporting facilities.
 
 use crate::source::Span;
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::fmt;
 
 /// Print debug information. Omitted in release builds.
@@ -52,6 +54,57 @@ where
     }
 }
 
+/// Collects diagnostics instead of printing them.
+///
+/// Analyzing a design may emit the same diagnostic more than once, for example
+/// when a node is evaluated repeatedly, and the order of the diagnostics
+/// depends on the order in which nodes are evaluated. The collector provides a
+/// stable view of the diagnostics, suitable for tools and golden tests.
+#[derive(Debug, Default)]
+pub struct DiagCollector {
+    diags: RefCell<Vec<DiagBuilder2>>,
+}
+
+impl DiagCollector {
+    /// Create a new empty collector.
+    pub fn new() -> DiagCollector {
+        Default::default()
+    }
+
+    /// Obtain the collected diagnostics in the order they were emitted.
+    pub fn emitted(&self) -> Vec<DiagBuilder2> {
+        self.diags.borrow().clone()
+    }
+
+    /// Obtain the collected diagnostics, sorted and deduplicated.
+    ///
+    /// Diagnostics with the same message and spans are reported only once.
+    /// The diagnostics are sorted by the location of their first span, with
+    /// diagnostics that carry no span last. Diagnostics at the same location
+    /// retain the order in which they were emitted.
+    pub fn diagnostics(&self) -> Vec<DiagBuilder2> {
+        let mut seen = HashSet::new();
+        let mut diags: Vec<DiagBuilder2> = self
+            .diags
+            .borrow()
+            .iter()
+            .filter(|diag| seen.insert((diag.message.clone(), diag.spans())))
+            .cloned()
+            .collect();
+        diags.sort_by_key(|diag| {
+            let span = diag.spans().into_iter().next();
+            (span.is_none(), span)
+        });
+        diags
+    }
+}
+
+impl DiagEmitter for DiagCollector {
+    fn emit(&self, diag: DiagBuilder2) {
+        self.diags.borrow_mut().push(diag);
+    }
+}
+
 /// Emit errors as diagnostics.
 ///
 /// Useful if implemented on the error types returned from results. Allows these
@@ -137,6 +190,21 @@ impl DiagBuilder2 {
         self.severity
     }
 
+    /// Promote a warning to an error.
+    ///
+    /// Diagnostics of any other severity are returned unchanged. This is used
+    /// to implement a strict mode where warnings are treated as errors.
+    pub fn promote_warning(self) -> DiagBuilder2 {
+        if self.severity == Severity::Warning {
+            DiagBuilder2 {
+                severity: Severity::Error,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+
     pub fn get_message(&self) -> &String {
         &self.message
     }
@@ -144,6 +212,36 @@ impl DiagBuilder2 {
     pub fn get_segments(&self) -> &[DiagSegment] {
         &self.segments
     }
+
+    /// The spans the diagnostic refers to, in order.
+    pub fn spans(&self) -> Vec<Span> {
+        self.segments
+            .iter()
+            .filter_map(|segment| match *segment {
+                DiagSegment::Span(span) => Some(span),
+                DiagSegment::Note(_) => None,
+            })
+            .collect()
+    }
+
+    /// Prepare the diagnostic for display with a given tab width.
+    ///
+    /// The reported columns advance to the next multiple of `tab_width` at
+    /// each tab, such that they line up with the columns shown by an editor.
+    /// Tabs count as a single column if `tab_width` is `None`, which is what
+    /// the `Display` implementation of the diagnostic does.
+    pub fn display(&self, tab_width: Option<usize>) -> DiagDisplay<'_> {
+        DiagDisplay {
+            diag: self,
+            tab_width,
+        }
+    }
+}
+
+/// A diagnostic prepared for display. See `DiagBuilder2::display`.
+pub struct DiagDisplay<'a> {
+    diag: &'a DiagBuilder2,
+    tab_width: Option<usize>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -175,7 +273,14 @@ impl fmt::Display for Severity {
 
 impl fmt::Display for DiagBuilder2 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut colorcode = match self.get_severity() {
+        self.display(None).fmt(f)
+    }
+}
+
+impl fmt::Display for DiagDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let diag = self.diag;
+        let mut colorcode = match diag.get_severity() {
             Severity::Bug | Severity::Fatal | Severity::Error => "\x1B[31;1m",
             Severity::Warning => "\x1B[33;1m",
             Severity::Note => "\x1B[36;1m",
@@ -184,17 +289,18 @@ impl fmt::Display for DiagBuilder2 {
             f,
             "{}{}:\x1B[m\x1B[1m {}\x1B[m\n",
             colorcode,
-            self.get_severity(),
-            self.get_message()
+            diag.get_severity(),
+            diag.get_message()
         )?;
 
-        for segment in &self.segments {
+        for segment in &diag.segments {
             match *segment {
                 DiagSegment::Span(sp) => {
                     let c = sp.source.get_content();
 
                     // Look for the start of the line.
-                    let (line, col, line_offset) = sp.begin().human();
+                    let tab_width = self.tab_width.unwrap_or(1);
+                    let (line, col, line_offset) = sp.begin().human_with_tab_width(tab_width);
 
                     // Print the line in question.
                     let text: String = c
@@ -259,7 +365,7 @@ impl fmt::Display for DiagBuilder2 {
             }
         }
 
-        if self.get_severity() == Severity::Bug {
+        if diag.get_severity() == Severity::Bug {
             write!(
                 f,
                 "\nYou have encountered a compiler bug. Sorry about that! We would appreciate if \
@@ -272,3 +378,33 @@ impl fmt::Display for DiagBuilder2 {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::get_source_manager;
+
+    #[test]
+    fn collector_dedups_and_sorts() {
+        let src = get_source_manager().add_anonymous("a\nb\nc\n");
+        let line = |n: usize| Span::new(src, 2 * n, 2 * n + 1);
+        let diags = DiagCollector::new();
+        diags.emit(DiagBuilder2::error("third").span(line(2)));
+        diags.emit(DiagBuilder2::error("first").span(line(0)));
+        diags.emit(DiagBuilder2::warning("no span"));
+        diags.emit(DiagBuilder2::error("third").span(line(2)));
+        diags.emit(DiagBuilder2::error("second").span(line(1)));
+        diags.emit(DiagBuilder2::error("first").span(line(0)));
+        diags.emit(DiagBuilder2::error("also first").span(line(0)));
+        let messages: Vec<_> = diags
+            .diagnostics()
+            .into_iter()
+            .map(|diag| diag.message)
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["first", "also first", "second", "third", "no span"]
+        );
+        assert_eq!(diags.emitted().len(), 7);
+    }
+}
@@ -12,11 +12,13 @@ use std::marker::PhantomData;
 mod filter;
 mod lookahead;
 mod peek;
+mod rewind;
 pub mod utf8;
 
 pub use self::filter::Filter;
 pub use self::lookahead::Lookahead;
 pub use self::peek::Peekable;
+pub use self::rewind::Rewind;
 
 pub trait Chisel: Default {
     type Value;
@@ -99,6 +101,14 @@ pub trait Grinder {
         Lookahead::new(self)
     }
 
+    #[inline]
+    fn rewindable(self) -> Rewind<Self>
+    where
+        Self: Sized,
+    {
+        Rewind::new(self)
+    }
+
     #[inline]
     fn filter<F>(self, f: F) -> Filter<Self, F>
     where
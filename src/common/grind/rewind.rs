@@ -0,0 +1,126 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A grinder that can look ahead by several items and rewind.
+
+use crate::grind::Grinder;
+use std::collections::VecDeque;
+
+/// A grinder that allows peeking ahead an arbitrary number of items.
+///
+/// Every call to `peek` advances a cursor by one item, without consuming the
+/// item. The items peeked at so far can then either be consumed with
+/// `commit`, or returned to the stream with `rewind`. This allows a lexer to
+/// try and match multi-character tokens such as `<=` or `16#FF#`, and to fall
+/// back to a shorter token if the match fails.
+pub struct Rewind<T: Grinder> {
+    inner: T,
+    buffer: VecDeque<T::Item>,
+    pos: usize,
+}
+
+impl<T> Rewind<T>
+where
+    T: Grinder,
+{
+    pub fn new(inner: T) -> Rewind<T> {
+        Rewind {
+            inner,
+            buffer: VecDeque::new(),
+            pos: 0,
+        }
+    }
+
+    /// Peek at the item under the cursor and advance the cursor.
+    pub fn peek(&mut self) -> &T::Item {
+        if self.pos == self.buffer.len() {
+            self.buffer.push_back(self.inner.next());
+        }
+        self.pos += 1;
+        &self.buffer[self.pos - 1]
+    }
+
+    /// The number of items peeked at since the last commit or rewind.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Consume the items peeked at so far.
+    pub fn commit(&mut self) {
+        self.buffer.drain(..self.pos);
+        self.pos = 0;
+    }
+
+    /// Move the cursor back to the next item to be consumed.
+    pub fn rewind(&mut self) {
+        self.pos = 0;
+    }
+
+    /// Return a consumed item to the stream, such that it is produced again
+    /// by the next call to `next` or `peek`.
+    ///
+    /// Must not be called while items are being peeked at.
+    pub fn undo(&mut self, item: T::Item) {
+        debug_assert_eq!(self.pos, 0, "undo while peeking");
+        self.buffer.push_front(item);
+    }
+}
+
+impl<T> Grinder for Rewind<T>
+where
+    T: Grinder,
+{
+    type Item = T::Item;
+    type Error = T::Error;
+
+    fn emit(&mut self, err: Self::Error) {
+        self.inner.emit(err)
+    }
+
+    fn next(&mut self) -> Self::Item {
+        match self.buffer.pop_front() {
+            Some(v) => {
+                self.pos = self.pos.saturating_sub(1);
+                v
+            }
+            None => self.inner.next(),
+        }
+    }
+}
+
+impl<T> From<T> for Rewind<T>
+where
+    T: Grinder,
+{
+    fn from(inner: T) -> Rewind<T> {
+        Rewind::new(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grind::{self, Grinder};
+
+    #[test]
+    fn peek_commit_rewind() {
+        let mut g = grind::from_iter(b"<=>".iter().cloned()).rewindable();
+
+        // Peek at `<=` and `>`, then fall back to just `<=`.
+        assert_eq!(*g.peek(), Some(b'<'));
+        assert_eq!(*g.peek(), Some(b'='));
+        assert_eq!(*g.peek(), Some(b'>'));
+        assert_eq!(g.position(), 3);
+        g.rewind();
+        assert_eq!(g.position(), 0);
+        assert_eq!(*g.peek(), Some(b'<'));
+        assert_eq!(*g.peek(), Some(b'='));
+        g.commit();
+        assert_eq!(g.position(), 0);
+
+        // The rewound item is produced again, followed by the end.
+        assert_eq!(*g.peek(), Some(b'>'));
+        assert_eq!(*g.peek(), None);
+        g.rewind();
+        assert_eq!(g.next(), Some(b'>'));
+        assert_eq!(g.next(), None);
+    }
+}
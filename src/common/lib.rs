@@ -25,6 +25,8 @@ pub struct Session {
     pub opts: SessionOptions,
     /// Whether any error diagnostics were produced.
     pub failed: Cell<bool>,
+    /// The number of error diagnostics produced.
+    errors: Cell<usize>,
 }
 
 impl Session {
@@ -33,20 +35,40 @@ impl Session {
         Session {
             opts: Default::default(),
             failed: Cell::new(false),
+            errors: Cell::new(0),
         }
     }
 
     pub fn failed(&self) -> bool {
         self.failed.get()
     }
+
+    /// The number of error diagnostics emitted so far.
+    ///
+    /// Warnings promoted to errors by `warnings_as_errors` are counted as well,
+    /// such that a caller can check whether an operation emitted any errors by
+    /// comparing the count before and after.
+    pub fn num_errors(&self) -> usize {
+        self.errors.get()
+    }
 }
 
 impl DiagEmitter for Session {
+    /// Emit a diagnostic.
+    ///
+    /// This is the single place where the diagnostic options of the session
+    /// are applied, such as promoting warnings to errors.
     fn emit(&self, diag: DiagBuilder2) {
+        let diag = if self.opts.warnings_as_errors {
+            diag.promote_warning()
+        } else {
+            diag
+        };
         if diag.severity >= Severity::Error {
             self.failed.set(true);
+            self.errors.set(self.errors.get() + 1);
         }
-        eprintln!("{}", diag);
+        eprintln!("{}", diag.display(self.opts.tab_width));
     }
 }
 
@@ -75,6 +97,14 @@ pub struct SessionOptions {
     pub verbosity: Verbosity,
     /// The optimization level.
     pub opt_level: usize,
+    /// Treat warnings as errors.
+    pub warnings_as_errors: bool,
+    /// The tab width used to compute the columns reported in diagnostics.
+    /// Tabs count as a single column if `None`.
+    pub tab_width: Option<usize>,
+    /// The number of bits above which integer literals are reported as
+    /// unreasonably large. Uses the frontend's default if `None`.
+    pub max_literal_bits: Option<u64>,
 }
 
 bitflags! {
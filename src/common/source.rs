@@ -222,6 +222,20 @@ impl SourceManager {
     /// since there is no name associated with it by which it could be referred
     /// to.
     pub fn add_anonymous<S>(&self, content: S) -> Source
+    where
+        S: Into<String>,
+    {
+        self.add_virtual("<anonymous>", content)
+    }
+
+    /// Create a virtual file from the contents of a string and add it to the
+    /// source manager under a name of the caller's choosing.
+    ///
+    /// This is intended for generated code, which has no file on disk but
+    /// should still be referred to by a sensible name in diagnostics. Unlike
+    /// `add()`, the name is not made available to `open()`, and may be used
+    /// for multiple files.
+    pub fn add_virtual<S>(&self, name: &str, content: S) -> Source
     where
         S: Into<String>,
     {
@@ -229,7 +243,7 @@ impl SourceManager {
         let new_id = Source(vect.len() as u32 + 1);
         vect.push(Box::new(VirtualSourceFile {
             id: new_id,
-            filename: RcStr::new("<anonymous>"),
+            filename: RcStr::new(name),
             content: Rc::new(VirtualSourceContent(content.into())),
         }));
         new_id
@@ -376,15 +390,24 @@ impl Location {
 
     /// Determine the line and column information at this location.
     ///
-    /// Returns a tuple `(line, column, line_offset)`.
+    /// Returns a tuple `(line, column, line_offset)`. Tabs count as a single
+    /// column.
     pub fn human(self) -> (usize, usize, usize) {
+        self.human_with_tab_width(1)
+    }
+
+    /// Determine the line and column information at this location, with tabs
+    /// advancing the column to the next multiple of `tab_width`.
+    ///
+    /// Returns a tuple `(line, column, line_offset)`.
+    pub fn human_with_tab_width(self, tab_width: usize) -> (usize, usize, usize) {
         let c = self.source.get_content();
         let mut iter = c.extract_iter(0, self.offset);
 
         // Look for the start of the line.
-        let mut col = 1;
         let mut line = 1;
         let mut line_offset = self.offset;
+        let mut line_chars = Vec::new();
         while let Some(c) = iter.next_back() {
             match c.1 {
                 '\n' => {
@@ -393,7 +416,7 @@ impl Location {
                 }
                 '\r' => continue,
                 _ => {
-                    col += 1;
+                    line_chars.push(c.1);
                     line_offset = c.0;
                 }
             }
@@ -406,6 +429,17 @@ impl Location {
             }
         }
 
+        // Determine the column, expanding tabs.
+        let tab_width = std::cmp::max(tab_width, 1);
+        let mut col = 1;
+        for c in line_chars.into_iter().rev() {
+            if c == '\t' {
+                col = ((col - 1) / tab_width + 1) * tab_width + 1;
+            } else {
+                col += 1;
+            }
+        }
+
         (line, col, line_offset)
     }
 
@@ -615,6 +649,17 @@ mod tests {
         assert_eq!(source, id);
     }
 
+    #[test]
+    fn virtual_file_name_in_diagnostics() {
+        use crate::errors::DiagBuilder2;
+        let sm = get_source_manager();
+        let src = sm.add_virtual("<generated>/fifo.vhd", "entity fifo is end;\n");
+        let diag = DiagBuilder2::error("oops").span(Span::new(src, 7, 11));
+        let text = format!("{}", diag.display(None));
+        assert!(text.contains("<generated>/fifo.vhd:1:8"), "{}", text);
+        assert_eq!(sm.open("<generated>/fifo.vhd"), None);
+    }
+
     #[test]
     fn inexistent_file() {
         let sm = get_source_manager();
@@ -630,6 +675,20 @@ mod tests {
         assert_eq!(elements, vec![(0, '老'), (3, '虎'), (6, '.')]);
     }
 
+    #[test]
+    fn tab_width() {
+        let sm = get_source_manager();
+        let source = sm.add_anonymous("\tfoo\n\t\tbar x\tbaz");
+        let foo = Location::new(source, 1);
+        assert_eq!(foo.human(), (1, 2, 0));
+        assert_eq!(foo.human_with_tab_width(4), (1, 5, 0));
+        assert_eq!(foo.human_with_tab_width(8), (1, 9, 0));
+        let baz = Location::new(source, 13);
+        assert_eq!(baz.human(), (2, 9, 5));
+        assert_eq!(baz.human_with_tab_width(4), (2, 17, 5));
+        assert_eq!(baz.human_with_tab_width(8), (2, 25, 5));
+    }
+
     #[test]
     fn file() {
         use std::fs::File;
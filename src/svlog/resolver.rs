@@ -464,12 +464,13 @@ impl<'a> ScopedNode<'a> for ast::GenerateBlock<'a> {}
 impl<'a> Eq for &'a dyn ScopedNode<'a> {}
 impl<'a> PartialEq for &'a dyn ScopedNode<'a> {
     fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self.as_ptr(), other.as_ptr())
+        std::ptr::eq(self.as_ptr(), other.as_ptr()) && self.id() == other.id()
     }
 }
 impl<'a> Hash for &'a dyn ScopedNode<'a> {
     fn hash<H: Hasher>(&self, h: &mut H) {
-        std::ptr::hash(self.as_ptr(), h)
+        std::ptr::hash(self.as_ptr(), h);
+        self.id().hash(h)
     }
 }
 
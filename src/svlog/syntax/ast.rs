@@ -58,12 +58,13 @@ pub trait AnyNode<'a>: BasicNode<'a> + AnyNodeData + std::fmt::Display + Send +
 impl<'a> Eq for &'a dyn AnyNode<'a> {}
 impl<'a> PartialEq for &'a dyn AnyNode<'a> {
     fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self.as_ptr(), other.as_ptr())
+        std::ptr::eq(self.as_ptr(), other.as_ptr()) && self.id() == other.id()
     }
 }
 impl<'a> Hash for &'a dyn AnyNode<'a> {
     fn hash<H: Hasher>(&self, h: &mut H) {
-        std::ptr::hash(self.as_ptr(), h)
+        std::ptr::hash(self.as_ptr(), h);
+        self.id().hash(h)
     }
 }
 
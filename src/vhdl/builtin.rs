@@ -4,7 +4,7 @@
 
 #![allow(dead_code)]
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 use num::BigInt;
@@ -21,6 +21,15 @@ use crate::score::{
 };
 use crate::ty::*;
 
+/// The index of the `NOTE` literal of `SEVERITY_LEVEL`.
+pub const SEVERITY_NOTE: usize = 0;
+/// The index of the `WARNING` literal of `SEVERITY_LEVEL`.
+pub const SEVERITY_WARNING: usize = 1;
+/// The index of the `ERROR` literal of `SEVERITY_LEVEL`.
+pub const SEVERITY_ERROR: usize = 2;
+/// The index of the `FAILURE` literal of `SEVERITY_LEVEL`.
+pub const SEVERITY_FAILURE: usize = 3;
+
 // Define some global references for the builtins.
 lazy_static! {
     /// A reference to the root scope where all builtins are declared.
@@ -38,6 +47,8 @@ lazy_static! {
     pub static ref BOOLEAN_TYPE: BuiltinType = BuiltinType::new_enum("BOOLEAN");
     /// The builtin `BIT` type.
     pub static ref BIT_TYPE: BuiltinType = BuiltinType::new_enum("BIT");
+    /// The builtin `CHARACTER` type.
+    pub static ref CHARACTER_TYPE: BuiltinType = BuiltinType::new_enum("CHARACTER");
     /// The builtin `SEVERITY_LEVEL` type.
     pub static ref SEVERITY_LEVEL_TYPE: BuiltinType = BuiltinType::new_enum("SEVERITY_LEVEL");
     /// A reference to the type `INTEGER`.
@@ -82,6 +93,11 @@ lazy_static! {
         1.into(),
         i32::max_value().into()
     ));
+    /// The builtin `STRING` type.
+    pub static ref STRING_TYPE: BuiltinType = BuiltinType::new("STRING", ArrayTy::new(
+        vec![ArrayIndex::Unbounded(Box::new(POSITIVE_TYPE.named_ty()))],
+        Box::new(CHARACTER_TYPE.named_ty())
+    ));
     /// The builtin `BOOLEAN_VECTOR` type.
     pub static ref BOOLEAN_VECTOR_TYPE: BuiltinType = BuiltinType::new("BOOLEAN_VECTOR", ArrayTy::new(
         vec![ArrayIndex::Unbounded(Box::new(NATURAL_TYPE.named_ty()))],
@@ -158,17 +174,27 @@ lazy_static! {
         BuiltinBinaryOp::new(BinaryOp::Pow),
     ];
 
+    /// The base types of the builtin subtypes of package `STD`.
+    static ref STANDARD_SUBTYPE_BASES: HashMap<TypeDeclRef, TypeDeclRef> = {
+        let mut bases = HashMap::new();
+        bases.insert(NATURAL_TYPE.id, INTEGER_TYPE.id);
+        bases.insert(POSITIVE_TYPE.id, INTEGER_TYPE.id);
+        bases
+    };
+
     /// The builtins of package `STD`.
     static ref STANDARD_BUILTINS: Vec<(Builtin, Vec<Builtin>)> = {
         let mut bi = Vec::new();
         bi.push(wrapup_type_builtin(&BOOLEAN_TYPE));
         bi.push(wrapup_type_builtin(&BIT_TYPE));
+        bi.push(wrapup_type_builtin(&CHARACTER_TYPE));
         bi.push(wrapup_type_builtin(&SEVERITY_LEVEL_TYPE));
         bi.push(wrapup_type_builtin(&INTEGER_TYPE));
         bi.push(wrapup_type_builtin(&TIME_TYPE));
         bi.push(wrapup_type_builtin(&DELAY_LENGTH_TYPE));
-        bi.push(wrapup_type_builtin(&NATURAL_TYPE));
-        bi.push(wrapup_type_builtin(&POSITIVE_TYPE));
+        bi.push(wrapup_subtype_builtin(&NATURAL_TYPE));
+        bi.push(wrapup_subtype_builtin(&POSITIVE_TYPE));
+        bi.push(wrapup_type_builtin(&STRING_TYPE));
         bi.push(wrapup_type_builtin(&BOOLEAN_VECTOR_TYPE));
         bi.push(wrapup_type_builtin(&BIT_VECTOR_TYPE));
         bi.push(wrapup_type_builtin(&INTEGER_VECTOR_TYPE));
@@ -179,6 +205,13 @@ lazy_static! {
     };
 }
 
+/// Determine the base type of a builtin subtype.
+///
+/// Returns `None` if the type is not a builtin subtype.
+pub fn builtin_subtype_base(id: TypeDeclRef) -> Option<TypeDeclRef> {
+    STANDARD_SUBTYPE_BASES.get(&id).cloned()
+}
+
 /// Add the definition for a builtin resolvable name to a scope.
 fn define_builtin(scope: &mut Scope, name: ResolvableName, def: Def) {
     scope
@@ -278,6 +311,31 @@ fn wrapup_type_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
     (bi, aux)
 }
 
+/// Takes a builtin integer subtype and produces the builtin.
+///
+/// Subtypes have no operators of their own; their values use the operators of
+/// `INTEGER`.
+fn wrapup_subtype_builtin(bt: &BuiltinType) -> (Builtin, Vec<Builtin>) {
+    (
+        Builtin::new(Def::Type(bt.id), bt.name).ty(bt.ty.clone()),
+        vec![],
+    )
+}
+
+/// The names of the control characters of `CHARACTER`.
+///
+/// These are the literals at positions 0 to 31, followed by the ones at
+/// positions 127 to 159. All other literals are character literals whose
+/// position is their Latin-1 code point.
+static CHARACTER_CONTROL_NAMES: [&str; 65] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FSP", "GSP", "RSP", "USP", "DEL", "C128", "C129", "C130", "C131", "C132", "C133", "C134",
+    "C135", "C136", "C137", "C138", "C139", "C140", "C141", "C142", "C143", "C144", "C145", "C146",
+    "C147", "C148", "C149", "C150", "C151", "C152", "C153", "C154", "C155", "C156", "C157", "C158",
+    "C159",
+];
+
 // Define the scopes of the builtins.
 lazy_static! {
     /// The root scope.
@@ -328,12 +386,23 @@ lazy_static! {
         define_builtin_bit(&mut scope, '0', Def::Enum(EnumRef(BIT_TYPE.id, 0)));
         define_builtin_bit(&mut scope, '1', Def::Enum(EnumRef(BIT_TYPE.id, 1)));
 
+        // `type CHARACTER is (NUL, SOH, ..., ' ', '!', ..., 'ÿ')`
+        // define_builtin_ident(&mut scope, "CHARACTER", Def::Type(CHARACTER_TYPE.id));
+        for (index, name) in CHARACTER_CONTROL_NAMES.iter().enumerate() {
+            let index = if index < 32 { index } else { index - 32 + 127 };
+            define_builtin_ident(&mut scope, name, Def::Enum(EnumRef(CHARACTER_TYPE.id, index)));
+        }
+        for index in (32..127).chain(160..256) {
+            let chr = std::char::from_u32(index as u32).unwrap();
+            define_builtin_bit(&mut scope, chr, Def::Enum(EnumRef(CHARACTER_TYPE.id, index)));
+        }
+
         // `type SEVERITY_LEVEL is (NOTE, WARNING, ERROR, FAILURE)`
         // define_builtin_ident(&mut scope, "SEVERITY_LEVEL", Def::Type(SEVERITY_LEVEL_TYPE.id));
-        define_builtin_ident(&mut scope, "NOTE", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, 0)));
-        define_builtin_ident(&mut scope, "WARNING", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, 1)));
-        define_builtin_ident(&mut scope, "ERROR", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, 2)));
-        define_builtin_ident(&mut scope, "FAILURE", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, 3)));
+        define_builtin_ident(&mut scope, "NOTE", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, SEVERITY_NOTE)));
+        define_builtin_ident(&mut scope, "WARNING", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, SEVERITY_WARNING)));
+        define_builtin_ident(&mut scope, "ERROR", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, SEVERITY_ERROR)));
+        define_builtin_ident(&mut scope, "FAILURE", Def::Enum(EnumRef(SEVERITY_LEVEL_TYPE.id, SEVERITY_FAILURE)));
 
         // `type INTEGER is range ... to ...`
         // define_builtin_ident(&mut scope, "INTEGER", Def::Type(INTEGER_TYPE.id));
@@ -358,6 +427,9 @@ lazy_static! {
         // `subtype POSITIVE is INTEGER range 1 to INTEGER'HIGH`
         // define_builtin_ident(&mut scope, "POSITIVE", Def::Type(POSITIVE_TYPE.id));
 
+        // `type STRING is array (POSITIVE range <>) of CHARACTER`
+        // define_builtin_ident(&mut scope, "STRING", Def::Type(STRING_TYPE.id));
+
         // `type BOOLEAN_VECTOR is array (NATURAL range <>) of BOOLEAN`
         // define_builtin_ident(&mut scope, "BOOLEAN_VECTOR", Def::Type(BOOLEAN_VECTOR_TYPE.id));
 
@@ -402,6 +474,26 @@ lazy_static! {
         .collect();
 }
 
+/// Determine the number of literals of a builtin enum type.
+///
+/// Returns `None` if `decl` does not refer to one of the builtin enums.
+pub fn builtin_enum_len(decl: TypeDeclRef) -> Option<usize> {
+    let num = STANDARD_PKG_SCOPE
+        .defs
+        .values()
+        .flat_map(|defs| defs.iter())
+        .filter(|def| match def.value {
+            Def::Enum(EnumRef(d, _)) => d == decl,
+            _ => false,
+        })
+        .count();
+    if num > 0 {
+        Some(num)
+    } else {
+        None
+    }
+}
+
 /// Add the builtins to a scoreboard.
 pub fn register_builtins<'ast, 'ctx>(sb: &ScoreBoard<'ast, 'ctx>) {
     use std::iter::once;
@@ -0,0 +1,184 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Caching of generated LLHD units across compilations.
+//!
+//! A tool that compiles the same design repeatedly, for example after every
+//! edit, need not generate the code for architectures whose source did not
+//! change. A `UnitCache` outlives the scoreboards of the individual
+//! compilations and keeps the units generated for each architecture, keyed
+//! by a hash of the source text the units depend on. This comprises the
+//! architecture, its entity, their context clauses, every package and context
+//! declared in the library, and the names and signatures of the instantiated
+//! entities. A change to a package therefore invalidates all architectures of
+//! the library, whereas a change to an architecture only invalidates the
+//! architecture itself and the architectures instantiating its entity, if
+//! the change affects the entity's ports.
+
+#![deny(missing_docs)]
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use llhd;
+
+/// A cache of the LLHD units generated for architectures.
+#[derive(Default)]
+pub struct UnitCache {
+    /// The cached units, by the name of the architecture's unit.
+    entries: RefCell<HashMap<String, CacheEntry>>,
+    /// The number of lookups that found a unit.
+    hits: Cell<usize>,
+    /// The number of lookups that found no unit.
+    misses: Cell<usize>,
+}
+
+/// The units generated for an architecture.
+#[derive(Clone)]
+pub struct CacheEntry {
+    /// The hash of the source text the units were generated from.
+    pub key: u64,
+    /// The name of the architecture's entity unit.
+    pub name: llhd::ir::UnitName,
+    /// The signature of the architecture's entity unit.
+    pub sig: llhd::ir::Signature,
+    /// The assembly of the entity unit and the units generated for the
+    /// architecture's processes and subprograms. LLHD units cannot be cloned,
+    /// so they are cached in serialized form, see `write_unit` and
+    /// `read_units`.
+    pub units: String,
+}
+
+impl UnitCache {
+    /// Create a new empty cache.
+    pub fn new() -> UnitCache {
+        Default::default()
+    }
+
+    /// Find the units generated for an architecture.
+    ///
+    /// Returns `None` if no units were cached for the architecture's unit
+    /// `name`, or if they were generated from a different source, as
+    /// indicated by `key`.
+    pub fn lookup(&self, name: &str, key: u64) -> Option<CacheEntry> {
+        let entry = self
+            .entries
+            .borrow()
+            .get(name)
+            .filter(|entry| entry.key == key)
+            .cloned();
+        match entry {
+            Some(_) => self.hits.set(self.hits.get() + 1),
+            None => self.misses.set(self.misses.get() + 1),
+        }
+        entry
+    }
+
+    /// Store the units generated for an architecture.
+    ///
+    /// Replaces the units cached for an earlier version of the architecture.
+    pub fn insert(&self, entry: CacheEntry) {
+        self.entries
+            .borrow_mut()
+            .insert(entry.name.to_string(), entry);
+    }
+
+    /// The number of lookups that found cached units.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// The number of lookups that found no cached units, such that the code
+    /// for the architecture had to be generated.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+}
+
+/// Write the assembly of a unit.
+///
+/// The unit is moved into a module to be written, and then moved back out.
+pub fn write_unit(unit: llhd::ir::UnitData) -> (llhd::ir::UnitData, String) {
+    let mut module = llhd::ir::Module::new();
+    let id = module.add_unit(unit);
+    let asm = llhd::assembly::write_module_string(&module);
+    (take_unit(&mut module, id), asm)
+}
+
+/// Read the units written by `write_unit`, together with their names.
+pub fn read_units(asm: &str) -> Vec<(String, llhd::ir::UnitData)> {
+    let mut module =
+        llhd::assembly::parse_module_unchecked(asm).expect("cached units should be valid assembly");
+    let ids: Vec<_> = module.units().map(|unit| unit.id()).collect();
+    ids.into_iter()
+        .map(|id| {
+            (
+                module.unit(id).name().to_string(),
+                take_unit(&mut module, id),
+            )
+        })
+        .collect()
+}
+
+/// Move a unit out of a module, leaving an empty entity in its place.
+fn take_unit(module: &mut llhd::ir::Module, id: llhd::ir::UnitId) -> llhd::ir::UnitData {
+    let empty = llhd::ir::UnitData::new(
+        llhd::ir::UnitKind::Entity,
+        llhd::ir::UnitName::anonymous(0),
+        llhd::ir::Signature::new(),
+    );
+    std::mem::replace(&mut module[id], empty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, score_test};
+    use moore_common::Session;
+
+    /// Generate the units for all architectures in a set of source files.
+    fn compile(cache: &UnitCache, sources: &[&str]) {
+        let units: Vec<_> = sources
+            .iter()
+            .flat_map(|src| crate::syntax::parse_str(src).unwrap())
+            .collect();
+        let sess = Session::new();
+        score_context!(ctx, &sess, |sb| {
+            sb.unit_cache = Some(cache);
+        });
+        let lib = add_work(&ctx, &units);
+        for archs in ctx.archs(lib).unwrap().by_entity.values() {
+            for &arch in &archs.ordered {
+                ctx.llunit(arch).unwrap();
+            }
+        }
+    }
+
+    const FOO: &str = "entity foo is end;
+        architecture bar of foo is signal a : integer := 1; begin end;";
+    const BAZ: &str = "entity baz is end;
+        architecture qux of baz is signal b : integer := 1; begin end;";
+
+    #[test]
+    fn edit_regenerates_changed_unit_only() {
+        score_test(|| {
+            let cache = UnitCache::new();
+            compile(&cache, &[FOO, BAZ]);
+            assert_eq!((cache.hits(), cache.misses()), (0, 2));
+            compile(&cache, &[FOO, &BAZ.replace(":= 1", ":= 2")]);
+            assert_eq!((cache.hits(), cache.misses()), (1, 3));
+        });
+    }
+
+    #[test]
+    fn package_edit_regenerates_library() {
+        score_test(|| {
+            let pkg = "package p is constant c : integer := 1; end;";
+            let cache = UnitCache::new();
+            compile(&cache, &[pkg, FOO, BAZ]);
+            compile(&cache, &[pkg, FOO, BAZ]);
+            assert_eq!((cache.hits(), cache.misses()), (2, 2));
+            compile(&cache, &[&pkg.replace(":= 1", ":= 2"), FOO, BAZ]);
+            assert_eq!((cache.hits(), cache.misses()), (2, 4));
+        });
+    }
+}
@@ -2,20 +2,248 @@
 
 //! LLHD code generation for VHDL.
 
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::common::name::{get_name_table, Name};
 use crate::hir;
 use crate::konst::*;
+use crate::op::{BinaryOp, LogicalOp, RelationalOp, UnaryOp};
 use crate::score::*;
+use crate::syntax::ast;
+use crate::syntax::lexer::token::Literal;
 use crate::ty::*;
 use llhd;
 use moore_common::errors::*;
 use moore_common::score::Result;
-use num::{Signed, ToPrimitive, Zero};
+use moore_common::source::{Span, Spanned};
+use moore_common::util::{HasDesc, HasSpan};
+use num::{BigInt, BigRational, One, Signed, ToPrimitive, Zero};
 
 /// Generates LLHD code.
 pub trait Codegen<I, C> {
     fn codegen(&self, id: I, ctx: &mut C) -> Result<()>;
 }
 
+/// A unit being generated.
+///
+/// Keeps track of the LLHD values that correspond to the signals which are
+/// visible within the unit, such that statements and expressions can refer to
+/// them.
+pub struct UnitContext<'u> {
+    /// The builder used to populate the unit.
+    pub builder: llhd::ir::UnitBuilder<'u>,
+    /// The values of the signals accessible in the unit.
+    pub signals: HashMap<SignalRef, llhd::ir::Value>,
+    /// The storage slots of the variables accessible in the unit.
+    pub variables: HashMap<VarDeclRef, llhd::ir::Value>,
+    /// The values of the constants accessible in the unit.
+    pub constants: HashMap<ConstRef, llhd::ir::Value>,
+    /// The loops enclosing the statement currently being generated, innermost
+    /// last.
+    pub loops: Vec<LoopBlocks>,
+    /// The values of the signals read by an impure function, which the
+    /// function receives as implicit arguments.
+    pub signal_values: HashMap<SignalRef, llhd::ir::Value>,
+    /// The storage slots holding the value each signal had when the process
+    /// last suspended, used to evaluate attributes such as `'event`.
+    pub prev_values: HashMap<SignalRef, llhd::ir::Value>,
+    /// The signals declared in the unit that no statement drives. These are
+    /// driven to their initial value at time zero. See `SignalDeclRef`.
+    pub undriven: HashSet<SignalDeclRef>,
+    /// The external units declared in the unit.
+    externs: HashMap<llhd::ir::UnitName, llhd::ir::ExtUnit>,
+    /// The statements generated so far, if their spans are recorded. See
+    /// `StmtInsts`.
+    pub spans: Option<Vec<StmtInsts>>,
+}
+
+/// The range of instructions generated for a statement.
+///
+/// Instructions are numbered in the order they are created, so the ones
+/// generated for a statement are those created after `after` and up to and
+/// including `last`. Nested statements come before their parent statement.
+#[derive(Debug, Clone, Copy)]
+pub struct StmtInsts {
+    /// The last instruction created before the statement, if any.
+    pub after: Option<llhd::ir::Inst>,
+    /// The last instruction created for the statement, if any.
+    pub last: Option<llhd::ir::Inst>,
+    /// The span of the statement.
+    pub span: Span,
+}
+
+/// The blocks that `next` and `exit` statements within a loop branch to.
+#[derive(Debug, Clone, Copy)]
+pub struct LoopBlocks {
+    /// The label of the loop.
+    pub label: Option<Name>,
+    /// The block that starts the next iteration of the loop.
+    pub next: llhd::ir::Block,
+    /// The block following the loop.
+    pub exit: llhd::ir::Block,
+}
+
+/// The bit patterns of the literals of an enum type.
+///
+/// Enum types are encoded as the index of their literals by default. An
+/// `enum_encoding` attribute overrides this, e.g. to obtain a one-hot encoding
+/// of the states of a state machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumEncoding {
+    /// The number of bits of each pattern.
+    pub width: usize,
+    /// The pattern of each literal, in the order of their declaration.
+    pub codes: Vec<BigInt>,
+}
+
+/// The policy by which integers are given a bit width in LLHD.
+///
+/// Integer types are mapped to at least as many bits as needed to represent
+/// every value in their range. Integer constants whose type does not constrain
+/// them, such as universal integers, are given the default width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntWidthPolicy {
+    /// The width of integers whose type does not determine their width.
+    pub default_width: usize,
+    /// Whether integer types are sized to the minimal number of bits needed
+    /// for their range. If `false`, types whose range fits into the default
+    /// width are widened to the default width.
+    pub minimal: bool,
+}
+
+impl Default for IntWidthPolicy {
+    /// Size integer types minimally, and unconstrained integers to the 32 bits
+    /// of `integer`.
+    fn default() -> IntWidthPolicy {
+        IntWidthPolicy {
+            default_width: 32,
+            minimal: true,
+        }
+    }
+}
+
+impl<'u> UnitContext<'u> {
+    /// Create a new context that populates a unit.
+    pub fn new(unit: &'u mut llhd::ir::UnitData) -> UnitContext<'u> {
+        UnitContext {
+            builder: llhd::ir::UnitBuilder::new_anonymous(unit),
+            signals: HashMap::new(),
+            variables: HashMap::new(),
+            constants: HashMap::new(),
+            loops: Vec::new(),
+            signal_values: HashMap::new(),
+            prev_values: HashMap::new(),
+            undriven: HashSet::new(),
+            externs: HashMap::new(),
+            spans: None,
+        }
+    }
+
+    /// The instruction created last in the unit.
+    ///
+    /// Code generation only ever appends instructions to the end of a block,
+    /// so this is the greatest last instruction of any block.
+    pub fn last_inst(&self) -> Option<llhd::ir::Inst> {
+        self.builder
+            .blocks()
+            .filter_map(|bb| self.builder.last_inst(bb))
+            .max()
+    }
+
+    /// Declare an external unit, or reuse an earlier declaration.
+    pub fn extern_unit(
+        &mut self,
+        name: llhd::ir::UnitName,
+        sig: llhd::ir::Signature,
+    ) -> llhd::ir::ExtUnit {
+        let builder = &mut self.builder;
+        *self
+            .externs
+            .entry(name.clone())
+            .or_insert_with(|| builder.add_extern(name, sig))
+    }
+}
+
+/// The signals accessed by a sequence of statements.
+#[derive(Debug, Default)]
+pub struct SignalAccesses {
+    /// The signals which are read.
+    pub read: BTreeSet<SignalRef>,
+    /// The signals which are driven.
+    pub written: BTreeSet<SignalRef>,
+    /// The signals whose value before the current simulation cycle is needed,
+    /// for example to evaluate `'event`.
+    pub sampled: BTreeSet<SignalRef>,
+    /// The shared variables which are accessed.
+    pub shared: BTreeSet<VarDeclRef>,
+}
+
+/// A construct in a design for which no code can be generated yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsupported {
+    /// What the construct is, e.g. "concurrent procedure call".
+    pub desc: &'static str,
+    /// Where the construct appears.
+    pub span: Span,
+}
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} is not yet supported", self.desc)
+    }
+}
+
+thread_local! {
+    /// The number of lowering spans currently entered on this thread.
+    static LOWER_DEPTH: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// The lowering of a node, logged when it is entered and left.
+///
+/// Spans nest: a statement is lowered within the span of its process, which in
+/// turn is lowered within the span of its entity. Messages are indented by the
+/// nesting depth and carry the id of the node, such that an error or `unimp!`
+/// in a large design can be traced back to the node being lowered.
+pub(crate) struct LowerSpan<I: std::fmt::Debug> {
+    level: log::Level,
+    what: &'static str,
+    id: I,
+}
+
+impl<I: std::fmt::Debug> LowerSpan<I> {
+    /// Enter the span of lowering a node. The span is left when the returned
+    /// guard is dropped.
+    pub(crate) fn enter(level: log::Level, what: &'static str, id: I) -> LowerSpan<I> {
+        let depth = LOWER_DEPTH.with(|d| d.replace(d.get() + 1));
+        log!(
+            level,
+            "{:indent$}> {} {:?}",
+            "",
+            what,
+            id,
+            indent = 2 * depth
+        );
+        LowerSpan { level, what, id }
+    }
+}
+
+impl<I: std::fmt::Debug> Drop for LowerSpan<I> {
+    fn drop(&mut self) {
+        let depth = LOWER_DEPTH.with(|d| {
+            d.set(d.get() - 1);
+            d.get()
+        });
+        log!(
+            self.level,
+            "{:indent$}< {} {:?}",
+            "",
+            self.what,
+            self.id,
+            indent = 2 * depth
+        );
+    }
+}
+
 /// This macro implements the `Codegen` trait for a specific combination of
 /// identifier and context types.
 macro_rules! impl_codegen {
@@ -44,29 +272,30 @@ macro_rules! unimp {
 
 impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
     /// Map a VHDL type to the corresponding LLHD type.
+    ///
+    /// The result is cached per canonical type, such that different spellings
+    /// of the same type are only mapped once.
     pub fn map_type(&self, ty: &Ty) -> Result<llhd::Type> {
+        let id = self.canonical_ty(ty)?;
+        if let Some(llty) = self.sb.llty_table.borrow().get(&id) {
+            return Ok(llty.clone());
+        }
+        let llty = self.map_type_uncached(self.canonical_ty_def(id))?;
+        self.sb.llty_table.borrow_mut().insert(id, llty.clone());
+        Ok(llty)
+    }
+
+    /// Map a VHDL type to the corresponding LLHD type, bypassing the cache.
+    fn map_type_uncached(&self, ty: &Ty) -> Result<llhd::Type> {
         let ty = self.deref_named_type(ty)?;
         Ok(match *ty {
             Ty::Named(..) => unreachable!(),
             Ty::Null => llhd::void_ty(),
-            Ty::Int(ref ty) => {
-                let diff = match ty.dir {
-                    hir::Dir::To => &ty.right_bound - &ty.left_bound,
-                    hir::Dir::Downto => &ty.left_bound - &ty.right_bound,
-                };
-                if diff.is_negative() {
-                    llhd::void_ty()
-                } else {
-                    llhd::int_ty(diff.bits() as usize)
-                }
-            }
-            Ty::Enum(ref ty) => {
-                let hir = self.lazy_hir(ty.decl)?;
-                match hir.data.as_ref().unwrap().value {
-                    hir::TypeData::Enum(ref lits) => llhd::enum_ty(lits.len()),
-                    _ => unreachable!(),
-                }
-            }
+            Ty::Int(ref ty) => match self.int_type_width(ty) {
+                Some(width) => llhd::int_ty(width),
+                None => llhd::void_ty(),
+            },
+            Ty::Enum(ref ty) => llhd::int_ty(self.enum_code_width(ty.decl)?),
             Ty::Physical(ref ty) => {
                 self.emit(DiagBuilder2::error(format!(
                     "cannot generate code for physical type `{}`",
@@ -75,57 +304,18 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 return Err(());
             }
             Ty::Access(ref ty) => llhd::pointer_ty(self.map_type(ty)?),
+            // Multi-dimensional arrays are flattened into a single LLHD array
+            // whose elements are laid out in row-major order.
             Ty::Array(ref ty) => {
-                let mut llty = self.map_type(&ty.element)?;
-                for index in ty.indices.iter().rev() {
-                    match *index {
-                        ArrayIndex::Unbounded(_) => {
-                            self.emit(
-                                DiagBuilder2::error(format!("type `{}` is unbounded", ty)), // TODO: What span should we use here?
-                            );
-                            return Err(());
-                        }
-                        ArrayIndex::Constrained(ref ty) => {
-                            let num = match **ty {
-                                Ty::Int(ref ty) => {
-                                    let l = ty.len();
-                                    if l.is_negative() || l.is_zero() {
-                                        return Ok(llhd::void_ty());
-                                    }
-                                    match l.to_usize() {
-                                        Some(l) => l,
-                                        None => {
-                                            self.emit(
-                                                DiagBuilder2::error(format!(
-                                                    "array index `{}` is too large; {} elements",
-                                                    ty, l
-                                                )), // TODO: What span should we use here?
-                                            );
-                                            return Err(());
-                                        }
-                                    }
-                                }
-                                Ty::Enum(ref ty) => {
-                                    match self.lazy_hir(ty.decl)?.data.as_ref().unwrap().value {
-                                        hir::TypeData::Enum(ref lits) => lits.len(),
-                                        _ => unreachable!(),
-                                    }
-                                }
-                                _ => {
-                                    self.emit(
-                                        DiagBuilder2::error(format!(
-                                            "`{}` is an invalid array index type",
-                                            ty
-                                        )), // TODO: What span should we use here?
-                                    );
-                                    return Err(());
-                                }
-                            };
-                            llty = llhd::array_ty(num, llty);
-                        }
-                    }
+                let element = self.map_type(&ty.element)?;
+                let mut num = 1;
+                for index in &ty.indices {
+                    num *= self.array_index_len(index, ty)?;
                 }
-                llty
+                if num == 0 {
+                    return Ok(llhd::void_ty());
+                }
+                llhd::array_ty(num, element)
             }
             Ty::File(ref _ty) => llhd::int_ty(32),
             Ty::Record(ref ty) => {
@@ -137,13 +327,41 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 llhd::struct_ty(fields)
             }
             Ty::Subprog(..) => unimplemented!(),
+            Ty::Protected(decl) => self.protected_state_type(decl)?,
             // Unbounded integers cannot be mapped to LLHD. All cases where
             // such an int can leak through to codegen should actually be caught
             // beforehand in the type check.
-            Ty::UnboundedInt | Ty::UniversalInt => unreachable!(),
+            Ty::UnboundedInt | Ty::UniversalInt => {
+                self.emit(DiagBuilder2::bug(format!(
+                    "type `{}` leaked through type checking and cannot be mapped to LLHD",
+                    ty
+                )));
+                return Err(());
+            }
         })
     }
 
+    /// Determine the number of bits used to represent an integer type, as
+    /// prescribed by the scoreboard's integer width policy.
+    ///
+    /// Returns `None` for null ranges.
+    pub fn int_type_width(&self, ty: &IntTy) -> Option<usize> {
+        let diff = match ty.dir {
+            hir::Dir::To => &ty.right_bound - &ty.left_bound,
+            hir::Dir::Downto => &ty.left_bound - &ty.right_bound,
+        };
+        if diff.is_negative() {
+            return None;
+        }
+        let bits = diff.bits() as usize;
+        let policy = self.sb.int_width;
+        if policy.minimal {
+            Some(bits)
+        } else {
+            Some(std::cmp::max(bits, policy.default_width))
+        }
+    }
+
     /// Map a constant value to the LLHD counterpart.
     pub fn map_const(
         &self,
@@ -153,191 +371,4264 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         Ok(match *konst {
             // TODO: Map this to llhd::const_void once available.
             Const::Null => builder.ins().const_int((0, 0)),
-            Const::Int(ref k) => builder.ins().const_int((999, k.value.clone())),
-            Const::Enum(ref k) => {
-                let size = match self.lazy_hir(k.decl)?.data.as_ref().unwrap().value {
-                    hir::TypeData::Enum(ref lits) => lits.len(),
-                    _ => unreachable!(),
-                };
-                builder.ins().const_int((size, k.index))
+            Const::Int(ref k) => {
+                let width =
+                    k.ty.as_ref()
+                        .and_then(|ty| self.int_type_width(ty))
+                        .unwrap_or(self.sb.int_width.default_width);
+                builder.ins().const_int((width, k.value.clone()))
             }
+            Const::Enum(ref k) => builder.ins().const_int(self.enum_code(k.decl, k.index)?),
             Const::Float(ref _k) => panic!("cannot map float constant"),
             Const::IntRange(_) | Const::FloatRange(_) => panic!("cannot map range constant"),
+            Const::Array(ref k) => {
+                if k.elements.is_empty() {
+                    self.emit(DiagBuilder2::bug("cannot map empty array constant"));
+                    return Err(());
+                }
+                let elements = k
+                    .elements
+                    .iter()
+                    .map(|k| self.map_const(builder, k))
+                    .collect::<Result<Vec<_>>>()?;
+                builder.ins().array(elements)
+            }
+            Const::Record(ref k) => {
+                let fields = k
+                    .fields
+                    .iter()
+                    .map(|k| self.map_const(builder, k))
+                    .collect::<Result<Vec<_>>>()?;
+                builder.ins().strukt(fields)
+            }
         }
         .into())
     }
-}
 
-impl_codegen!(self, id: DeclInBlockRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    match id {
-        DeclInBlockRef::Subprog(id)     => self.codegen(id, &mut ()),
-        DeclInBlockRef::SubprogBody(id) => self.codegen(id, &mut ()),
-        DeclInBlockRef::SubprogInst(id) => self.codegen(id, &mut ()),
-        DeclInBlockRef::Pkg(id)         => self.codegen(id, &mut ()),
-        DeclInBlockRef::PkgBody(id)     => self.codegen(id, &mut ()),
-        DeclInBlockRef::PkgInst(id)     => self.codegen(id, &mut ()),
-        DeclInBlockRef::Type(_id)       => Ok(()),
-        DeclInBlockRef::Subtype(_id)    => Ok(()),
-        DeclInBlockRef::Const(id)       => self.codegen(id, ctx),
-        DeclInBlockRef::Signal(id)      => self.codegen(id, ctx),
-        DeclInBlockRef::Var(id)         => self.codegen(id, ctx),
-        DeclInBlockRef::File(id)        => self.codegen(id, ctx),
-        DeclInBlockRef::Alias(_id)      => Ok(()),
-        DeclInBlockRef::Comp(id)        => self.codegen(id, &mut ()),
-        DeclInBlockRef::Attr(_id)       => Ok(()),
-        DeclInBlockRef::AttrSpec(_id)   => Ok(()),
-        DeclInBlockRef::CfgSpec(_id)    => Ok(()),
-        DeclInBlockRef::Discon(_id)     => Ok(()),
-        DeclInBlockRef::GroupTemp(_id)  => Ok(()),
-        DeclInBlockRef::Group(_id)      => Ok(()),
+    /// Generate the default value of a type.
+    ///
+    /// Arrays and records are built from the default values of their elements
+    /// and fields, since these cannot be represented as a constant yet.
+    pub fn codegen_default_value(
+        &self,
+        builder: &mut llhd::ir::UnitBuilder,
+        ty: &Ty,
+    ) -> Result<llhd::ir::Value> {
+        match *self.deref_named_type(ty)? {
+            Ty::Array(ref array_ty) => {
+                let value = self.codegen_default_value(builder, &array_ty.element)?;
+                match *self.map_type(ty)? {
+                    llhd::ArrayType(len, _) => Ok(builder.ins().array_uniform(len, value)),
+                    // TODO: Map this to llhd::const_void once available.
+                    _ => Ok(builder.ins().const_int((0, 0))),
+                }
+            }
+            Ty::Record(ref record_ty) => {
+                let fields = record_ty
+                    .fields
+                    .iter()
+                    .map(|(_, ty)| self.codegen_default_value(builder, ty))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(builder.ins().strukt(fields))
+            }
+            // The state of a protected object starts out with the initial
+            // values of the variables in the body of its type.
+            Ty::Protected(decl) => {
+                let fields = self
+                    .protected_state(decl)?
+                    .into_iter()
+                    .map(|var| match self.lazy_hir(var)?.decl.init {
+                        Some(init) => {
+                            let k = self.const_value(init)?;
+                            let ty = self.lazy_typeval(var)?;
+                            let k = self.adapt_init(k, ty, self.lazy_hir(init)?.span)?;
+                            self.map_const(builder, k)
+                        }
+                        None => self.codegen_default_value(builder, self.lazy_typeval(var)?),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(builder.ins().strukt(fields))
+            }
+            _ => {
+                let k = self.default_value_for_type(ty)?;
+                self.map_const(builder, k)
+            }
+        }
     }
-});
-
-impl_codegen!(self, id: ConstDeclRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
 
-impl_codegen!(self, id: VarDeclRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Convert an initial value to the type of the signal or variable it
+    /// initializes.
+    ///
+    /// Untyped integers, such as literals and overridden values, are assigned
+    /// the object's integer type, or used as the literal index for enumeration
+    /// types.
+    fn adapt_init(&self, init: &'ctx Const, ty: &Ty, span: Span) -> Result<&'ctx Const> {
+        let value = match *init {
+            Const::Int(ConstInt {
+                ty: None,
+                ref value,
+            }) => value,
+            _ => return Ok(init),
+        };
+        match *ty {
+            Ty::Named(_, ty) => self.adapt_init(init, self.ty(ty)?, span),
+            Ty::Int(ref ty) => {
+                let (lo, hi) = match ty.dir {
+                    Dir::To => (&ty.left_bound, &ty.right_bound),
+                    Dir::Downto => (&ty.right_bound, &ty.left_bound),
+                };
+                if value < lo || value > hi {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "initial value {} is out of range for type `{}`",
+                            value, ty
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+                Ok(self.intern_const(ConstInt::new(Some(ty.clone()), value.clone())))
+            }
+            Ty::Enum(ref ty) => {
+                let len = self.enum_len(ty.decl)?;
+                match value.to_usize() {
+                    Some(index) if index < len => {
+                        Ok(self.intern_const(ConstEnum::new(ty.decl, index)))
+                    }
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "initial value {} is out of range for type `{}`",
+                                value, ty
+                            ))
+                            .span(span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "cannot use integer {} as initial value of type `{}`",
+                        value, ty
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
 
-impl_codegen!(self, id: SignalDeclRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    // Determine the type of the signal.
-    let hir = self.lazy_hir(id)?;
-    let ty = self.lazy_typeval(id)?;
+    /// Determine the values the parameter of a for-generate statement takes,
+    /// in the order of iteration.
+    pub fn gen_param_values(&self, id: ForGenStmtRef) -> Result<Vec<&'ctx Const>> {
+        let hir = self.hir(id)?;
+        let int_ty = match *self.deref_named_type(self.ty(id)?)? {
+            Ty::Int(ref ty) => Some(ty.clone()),
+            _ => None,
+        };
+        let (dir, left, right) = self.const_discrete_range(hir.range.as_ref())?;
+        Ok(match (left, right) {
+            (Const::Int(left), Const::Int(right)) => {
+                let mut values = vec![];
+                let mut value = left.value.clone();
+                while match dir {
+                    Dir::To => value <= right.value,
+                    Dir::Downto => value >= right.value,
+                } {
+                    values.push(self.intern_const(ConstInt::new(int_ty.clone(), value.clone())));
+                    value = match dir {
+                        Dir::To => value + 1,
+                        Dir::Downto => value - 1,
+                    };
+                }
+                values
+            }
+            (Const::Enum(left), Const::Enum(right)) if left.decl == right.decl => {
+                let indices: Vec<usize> = match dir {
+                    Dir::To => (left.index..=right.index).collect(),
+                    Dir::Downto => (right.index..=left.index).rev().collect(),
+                };
+                indices
+                    .into_iter()
+                    .map(|index| self.intern_const(ConstEnum::new(left.decl, index)))
+                    .collect()
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a constant discrete range",
+                        hir.range.span.extract()
+                    ))
+                    .span(hir.range.span),
+                );
+                return Err(());
+            }
+        })
+    }
 
-    // Calculate the initial value for the signal, either from the provided
-    // expression or implicitly.
-    let init = if let Some(init_id) = hir.decl.init {
-        self.const_value(init_id)?
-    } else {
-        self.default_value_for_type(&ty)?
-    };
+    /// Determine the value of a generate parameter in the iteration of its
+    /// for-generate statement that is currently being generated.
+    fn gen_param_value(&self, id: ForGenStmtRef, span: Span) -> Result<&'ctx Const> {
+        match self.sb.gen_params.borrow().get(&id) {
+            Some(&value) => Ok(value),
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "generate parameter `{}` used outside its generate statement",
+                        span.extract()
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
 
-    debugln!("signal {:?}, type {:?}, init {:?}", id, ty, init);
-    // Create the signal instance.
-    // let inst = llhd::Inst::new(
-    //     Some(hir.name.value.into()),
-    //     llhd::SignalInst(self.map_type(ty)?, Some(self.map_const(init)?))
-    // );
-    // ctx.add_inst(inst, llhd::InstPosition::End);
-    let k = self.map_const(ctx, init)?;
-    ctx.ins().sig(k);
-    Ok(())
-});
+    /// Generate one iteration of a for-generate statement.
+    fn codegen_gen_iteration(&self, hir: &hir::ForGenStmt, ctx: &mut UnitContext) -> Result<()> {
+        for &decl in &hir.decls {
+            self.codegen(decl, ctx)?;
+        }
+        for &stmt in &hir.stmts {
+            self.codegen(stmt, ctx)?;
+        }
+        Ok(())
+    }
 
-impl_codegen!(self, id: FileDeclRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Determine the number of elements along one index of an array type.
+    ///
+    /// Null ranges have zero elements.
+    fn array_index_len(&self, index: &ArrayIndex, array_ty: &ArrayTy) -> Result<usize> {
+        let ty = match *index {
+            ArrayIndex::Unbounded(_) => {
+                self.emit(
+                    DiagBuilder2::error(format!("type `{}` is unbounded", array_ty)), // TODO: What span should we use here?
+                );
+                return Err(());
+            }
+            ArrayIndex::Constrained(ref ty) => ty,
+        };
+        match **ty {
+            Ty::Int(ref ty) => {
+                let l = ty.len();
+                if l.is_negative() || l.is_zero() {
+                    return Ok(0);
+                }
+                match l.to_usize() {
+                    Some(l) => Ok(l),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "array index `{}` is too large; {} elements",
+                                ty, l
+                            )), // TODO: What span should we use here?
+                        );
+                        Err(())
+                    }
+                }
+            }
+            Ty::Enum(ref ty) => self.enum_len(ty.decl),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is an invalid array index type", ty)), // TODO: What span should we use here?
+                );
+                Err(())
+            }
+        }
+    }
 
-impl_codegen!(self, id: ConcStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    match id {
-        ConcStmtRef::Block(id)         => self.codegen(id, ctx),
-        ConcStmtRef::Process(id)       => self.codegen(id, ctx),
-        ConcStmtRef::ConcProcCall(id)  => self.codegen(id, ctx),
-        ConcStmtRef::ConcAssert(id)    => self.codegen(id, ctx),
-        ConcStmtRef::ConcSigAssign(id) => self.codegen(id, ctx),
-        ConcStmtRef::CompInst(id)      => self.codegen(id, ctx),
-        ConcStmtRef::ForGen(id)        => self.codegen(id, ctx),
-        ConcStmtRef::IfGen(id)         => self.codegen(id, ctx),
-        ConcStmtRef::CaseGen(id)       => self.codegen(id, ctx),
+    /// Determine the number of bits an enum type is encoded with.
+    pub fn enum_code_width(&self, decl: TypeDeclRef) -> Result<usize> {
+        match self.enum_encoding(decl)? {
+            Some(ref encoding) => Ok(encoding.width),
+            None => Ok(enum_width(self.enum_len(decl)?)),
+        }
     }
-});
 
-impl_codegen!(self, id: BlockStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Determine the width and the bit pattern of an enum literal.
+    ///
+    /// This honors the `enum_encoding` attribute of the enum type, if any.
+    pub fn enum_code(&self, decl: TypeDeclRef, index: usize) -> Result<(usize, BigInt)> {
+        match self.enum_encoding(decl)? {
+            Some(ref encoding) => Ok((encoding.width, encoding.codes[index].clone())),
+            None => Ok((enum_width(self.enum_len(decl)?), BigInt::from(index))),
+        }
+    }
 
-impl_codegen!(self, id: ProcessStmtRef, ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    let hir = self.hir(id)?;
-    let name = match hir.label {
-        Some(n) => format!("{}_{}", ctx.name(), n.value),
-        None => format!("{}_proc", ctx.name()),
-    };
-    let name = llhd::ir::UnitName::Global(name);
-    debugln!("generating process `{}`", name);
-    // TODO: Check which signals are actually read and written.
-    // let ty = llhd::entity_ty(vec![], vec![]);
-    let sig = llhd::ir::Signature::new();
-    let mut prok = llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name.clone(), sig.clone());
-    // let mut prok = llhd::Process::new(name, ty.clone());
-    let mut prok_builder = llhd::ir::UnitBuilder::new_anonymous(&mut prok);
-    // TODO: define the process as a local name
-    // TOOD: codegen declarations
-    // TOOD: codegen statements
-    let entry_bb = prok_builder.named_block("entry");
-    prok_builder.append_to(entry_bb);
-    for &stmt in &hir.stmts {
-        self.codegen(stmt, &mut prok_builder)?;
+    /// Determine the encoding an `enum_encoding` attribute specifies for an
+    /// enum type.
+    ///
+    /// The attribute must be specified in the scope that declares the type, as
+    /// a string of one bit pattern per literal, separated by spaces, such as
+    /// `"001 010 100"`.
+    pub fn enum_encoding(&self, decl: TypeDeclRef) -> Result<Option<EnumEncoding>> {
+        if let Some(encoding) = self.sb.enum_encodings.borrow().get(&decl) {
+            return Ok(encoding.clone());
+        }
+        let encoding = self.find_enum_encoding(decl)?;
+        self.sb
+            .enum_encodings
+            .borrow_mut()
+            .insert(decl, encoding.clone());
+        Ok(encoding)
     }
-    // TODO: codegen wait statements implied by sensitivity list
 
-    // TODO: wire instantiation with signals in the process' port.
-    let ext_unit = ctx.add_extern(
-        prok_builder.name().clone(),
-        prok_builder.sig().clone(),
-    );
-    ctx.ins().inst(ext_unit, vec![], vec![]);
-    self.sb.llmod.borrow_mut().add_unit(prok);
-    // ctx.add_inst(
-    //     llhd::Inst::new(hir.label.map(|l| l.value.into()), llhd::InstKind::InstanceInst(
-    //         ty, prok_ref.into(), vec![], vec![]
-    //     )),
-    //     llhd::InstPosition::End
-    // );
-    Ok(())
-});
+    fn find_enum_encoding(&self, decl: TypeDeclRef) -> Result<Option<EnumEncoding>> {
+        // The types of the standard library have no attributes.
+        if builtin_enum_len(decl).is_some() {
+            return Ok(None);
+        }
+        let hir = self.lazy_hir(decl)?;
+        let names_type = |name: &ast::CompoundName| match name.primary.kind {
+            ast::PrimaryNameKind::Ident(ident) if name.parts.is_empty() => ident
+                .as_str()
+                .eq_ignore_ascii_case(&hir.name.value.as_str()),
+            _ => false,
+        };
+        let mut expr = None;
+        for spec in self.attr_specs_in_scope(hir.parent) {
+            let (_, spec_ast) = self.ast(spec);
+            let name = spec_ast.name.value.as_str();
+            if !name.eq_ignore_ascii_case("enum_encoding") {
+                continue;
+            }
+            if let ast::AttrData::Spec {
+                target: ast::AttrTarget::List(ref names),
+                cls: ast::EntityClass::Type,
+                expr: ref value,
+            } = spec_ast.data
+            {
+                if names.iter().any(|(name, _)| names_type(name)) {
+                    expr = Some(value);
+                    break;
+                }
+            }
+        }
+        let expr = match expr {
+            Some(expr) => expr,
+            None => return Ok(None),
+        };
 
-impl_codegen!(self, id: ConcCallStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+        // Parse the bit patterns. The parser yields string literals as names,
+        // since they may also name an operator.
+        let value = match expr.data {
+            ast::ExprData::LitExpr(Literal::String(value), None) => value,
+            ast::ExprData::NameExpr(ast::CompoundName {
+                primary:
+                    ast::PrimaryName {
+                        kind: ast::PrimaryNameKind::String(value),
+                        ..
+                    },
+                ref parts,
+                ..
+            }) if parts.is_empty() => value,
+            _ => {
+                self.emit(
+                    DiagBuilder2::error("`enum_encoding` must be a string literal").span(expr.span),
+                );
+                return Err(());
+            }
+        };
+        let value = value.as_str();
+        let patterns: Vec<&str> = value.split_whitespace().collect();
+        let len = self.enum_len(decl)?;
+        if patterns.len() != len {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "`enum_encoding` specifies {} patterns, but type `{}` has {} literals",
+                    patterns.len(),
+                    hir.name.value,
+                    len
+                ))
+                .span(expr.span)
+                .add_note("Type declared here:")
+                .span(hir.name.span),
+            );
+            return Err(());
+        }
+        let width = patterns.first().map(|p| p.len()).unwrap_or(0);
+        let mut codes = Vec::with_capacity(len);
+        for pattern in patterns {
+            let is_binary = pattern.bytes().all(|b| b == b'0' || b == b'1');
+            let code = if pattern.len() == width && is_binary {
+                BigInt::parse_bytes(pattern.as_bytes(), 2)
+            } else {
+                None
+            };
+            match code {
+                Some(code) => codes.push(code),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a bit pattern of width {}",
+                            pattern, width
+                        ))
+                        .span(expr.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Ok(Some(EnumEncoding { width, codes }))
+    }
 
-impl_codegen!(self, id: ConcAssertStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Determine the number of literals of an enum type.
+    pub fn enum_len(&self, decl: TypeDeclRef) -> Result<usize> {
+        if let Some(len) = builtin_enum_len(decl) {
+            return Ok(len);
+        }
+        match self.lazy_hir(decl)?.data.as_ref().unwrap().value {
+            hir::TypeData::Enum(ref lits) => Ok(lits.len()),
+            _ => unreachable!(),
+        }
+    }
 
-impl_codegen!(self, id: ConcSigAssignStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Determine the name of a signal.
+    pub fn signal_name(&self, id: SignalRef) -> Result<Name> {
+        Ok(match id {
+            SignalRef::Intf(id) => self.hir(id)?.name.value,
+            SignalRef::Decl(id) => self.lazy_hir(id)?.name.value,
+            SignalRef::Guard(_) => get_name_table().intern("GUARD", false),
+        })
+    }
 
-impl_codegen!(self, id: CompInstStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Record the signals a process unit waits on in the scoreboard.
+    fn record_sensitivity<'a>(
+        &self,
+        name: &llhd::ir::UnitName,
+        signals: impl IntoIterator<Item = &'a SignalRef>,
+    ) -> Result<()> {
+        let names = signals
+            .into_iter()
+            .map(|&s| self.signal_name(s))
+            .collect::<Result<Vec<_>>>()?;
+        self.sb
+            .sensitivities
+            .borrow_mut()
+            .insert(name.to_string(), names);
+        Ok(())
+    }
 
-impl_codegen!(self, id: ForGenStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Determine the signature of a process that accesses a set of signals.
+    ///
+    /// Signals that are only read become inputs, all driven signals become
+    /// outputs. Returns the input and output signals in the order of the
+    /// signature.
+    fn process_signature(
+        &self,
+        accesses: &SignalAccesses,
+    ) -> Result<(Vec<SignalRef>, Vec<SignalRef>, llhd::ir::Signature)> {
+        let inputs: Vec<SignalRef> = accesses
+            .read
+            .difference(&accesses.written)
+            .cloned()
+            .collect();
+        let outputs: Vec<SignalRef> = accesses.written.iter().cloned().collect();
+        let mut sig = llhd::ir::Signature::new();
+        for &s in &inputs {
+            sig.add_input(llhd::signal_ty(self.map_type(self.ty(s)?)?));
+        }
+        for &s in &outputs {
+            sig.add_output(llhd::signal_ty(self.map_type(self.ty(s)?)?));
+        }
+        Ok((inputs, outputs, sig))
+    }
 
-impl_codegen!(self, id: IfGenStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Make the arguments of a process available as the signals they
+    /// correspond to.
+    fn bind_process_args(
+        &self,
+        ctx: &mut UnitContext<'_>,
+        inputs: &[SignalRef],
+        outputs: &[SignalRef],
+    ) -> Result<()> {
+        let args: Vec<_> = ctx
+            .builder
+            .input_args()
+            .zip(inputs.iter())
+            .chain(ctx.builder.output_args().zip(outputs.iter()))
+            .collect();
+        for (arg, &s) in args {
+            ctx.builder
+                .set_name(arg, self.signal_name(s)?.as_str().to_string());
+            ctx.signals.insert(s, arg);
+        }
+        Ok(())
+    }
 
-impl_codegen!(self, id: CaseGenStmtRef, _ctx: &mut llhd::ir::UnitBuilder<'_> => {
-    unimp!(self, id);
-});
+    /// Sample the signals whose previous value a process needs.
+    ///
+    /// This happens once initially, and again each time the process suspends
+    /// by means of `update_samples`.
+    fn sample_signals(&self, accesses: &SignalAccesses, ctx: &mut UnitContext<'_>) {
+        for &s in &accesses.sampled {
+            let value = ctx.builder.ins().prb(ctx.signals[&s]);
+            let slot = ctx.builder.ins().var(value);
+            ctx.prev_values.insert(s, slot);
+        }
+    }
 
-impl_codegen!(self, id: SeqStmtRef, _ctx: &'a mut llhd::ir::UnitBuilder<'a> => {
-    unimp!(self, id);
-});
+    /// Store the current value of the sampled signals before a process
+    /// suspends.
+    fn update_samples(&self, accesses: &SignalAccesses, ctx: &mut UnitContext<'_>) {
+        for s in &accesses.sampled {
+            let value = ctx.builder.ins().prb(ctx.signals[s]);
+            ctx.builder.ins().st(ctx.prev_values[s], value);
+        }
+    }
 
-impl_codegen!(self, id: SubprogDeclRef, _ctx: &mut () => {
-    unimp!(self, id);
-});
+    /// Instantiate a process and connect the signals it accesses.
+    fn inst_process(
+        &self,
+        name: llhd::ir::UnitName,
+        sig: llhd::ir::Signature,
+        inputs: &[SignalRef],
+        outputs: &[SignalRef],
+        ctx: &mut UnitContext<'_>,
+    ) -> Result<()> {
+        let lookup = |s: &SignalRef| match ctx.signals.get(s) {
+            Some(&v) => Ok(v),
+            None => {
+                self.emit(DiagBuilder2::bug(format!(
+                    "signal {:?} not accessible in `{}`",
+                    s,
+                    ctx.builder.name()
+                )));
+                Err(())
+            }
+        };
+        let inputs = inputs.iter().map(&lookup).collect::<Result<Vec<_>>>()?;
+        let outputs = outputs.iter().map(&lookup).collect::<Result<Vec<_>>>()?;
+        let ext_unit = ctx.builder.add_extern(name, sig);
+        ctx.builder.ins().inst(ext_unit, inputs, outputs);
+        Ok(())
+    }
 
-impl_codegen!(self, id: SubprogBodyRef, _ctx: &mut () => {
-    unimp!(self, id);
-});
+    /// Create a context that populates a unit.
+    ///
+    /// The context records the spans of the generated instructions if the
+    /// scoreboard carries a source map, see `finish_unit_context`.
+    fn unit_context<'u>(&self, unit: &'u mut llhd::ir::UnitData) -> UnitContext<'u> {
+        let mut ctx = UnitContext::new(unit);
+        if self.sb.source_map.is_some() {
+            ctx.spans = Some(Vec::new());
+        }
+        ctx
+    }
 
-impl_codegen!(self, id: SubprogInstRef, _ctx: &mut () => {
-    unimp!(self, id);
-});
+    /// Add the spans recorded while populating a unit to the source map.
+    ///
+    /// Every instruction is attributed to the innermost statement it was
+    /// generated for.
+    fn finish_unit_context(&self, name: &llhd::ir::UnitName, ctx: UnitContext) {
+        let (map, stmts) = match (self.sb.source_map, ctx.spans) {
+            (Some(map), Some(stmts)) => (map, stmts),
+            _ => return,
+        };
+        let mut insts: Vec<_> = ctx.builder.all_insts().collect();
+        insts.sort();
+        let position = |inst: Option<llhd::ir::Inst>| match inst {
+            Some(inst) => insts.partition_point(|&i| i <= inst),
+            None => 0,
+        };
+        let mut spans = HashMap::new();
+        for stmt in stmts {
+            let (begin, end) = (position(stmt.after), position(stmt.last));
+            for &inst in insts.get(begin..end).unwrap_or(&[]) {
+                spans.entry(inst).or_insert(stmt.span);
+            }
+        }
+        map.insert(name.to_string(), spans);
+    }
 
-impl_codegen!(self, id: PkgDeclRef, _ctx: &mut () => {
-    unimp!(self, id);
-});
+    /// Generate the process equivalent to a concurrent statement.
+    ///
+    /// The process executes `body` once initially, and again whenever one of
+    /// the signals it reads changes.
+    fn codegen_equivalent_process(
+        &self,
+        name: llhd::ir::UnitName,
+        accesses: &SignalAccesses,
+        span: Span,
+        ctx: &mut UnitContext<'_>,
+        body: &dyn Fn(&mut UnitContext<'_>) -> Result<()>,
+    ) -> Result<()> {
+        debugln!("generating process `{}`", name);
+        let (inputs, outputs, sig) = self.process_signature(accesses)?;
+        let mut prok =
+            llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name.clone(), sig.clone());
+        {
+            let mut prok_ctx = self.unit_context(&mut prok);
+            self.bind_process_args(&mut prok_ctx, &inputs, &outputs)?;
+            let entry_bb = prok_ctx.builder.named_block("entry");
+            prok_ctx.builder.append_to(entry_bb);
+            self.sample_signals(accesses, &mut prok_ctx);
+            let body_bb = if accesses.sampled.is_empty() {
+                entry_bb
+            } else {
+                let bb = prok_ctx.builder.named_block("body");
+                prok_ctx.builder.ins().br(bb);
+                prok_ctx.builder.append_to(bb);
+                bb
+            };
+            body(&mut prok_ctx)?;
+            self.update_samples(accesses, &mut prok_ctx);
+            self.record_sensitivity(&name, &accesses.read)?;
+            let signals: Vec<_> = accesses.read.iter().map(|s| prok_ctx.signals[s]).collect();
+            if signals.is_empty() {
+                prok_ctx.builder.ins().halt();
+            } else {
+                prok_ctx.builder.ins().wait(body_bb, signals);
+            }
+            prune_blocks(&mut prok_ctx.builder);
+            self.finish_unit_context(&name, prok_ctx);
+        }
+        self.inst_process(name.clone(), sig, &inputs, &outputs, ctx)?;
+        self.add_unit_deferred(name.to_string(), prok, span)
+    }
 
-impl_codegen!(self, id: PkgBodyRef, _ctx: &mut () => {
-    unimp!(self, id);
+    /// Lower a single sequential statement into a throwaway process.
+    ///
+    /// The signals the statement accesses become arguments of the process,
+    /// which halts after executing the statement once. This allows testing
+    /// the code generated for a statement without an enclosing architecture.
+    #[cfg(test)]
+    pub(crate) fn lower_seq_stmt(&self, stmt: SeqStmtRef) -> Result<llhd::ir::UnitData> {
+        let accesses = self.signal_accesses(&[stmt])?;
+        let (inputs, outputs, sig) = self.process_signature(&accesses)?;
+        let name = llhd::ir::UnitName::Global(String::from("stmt"));
+        let mut unit = llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name, sig);
+        {
+            let mut ctx = self.unit_context(&mut unit);
+            self.bind_process_args(&mut ctx, &inputs, &outputs)?;
+            let entry_bb = ctx.builder.named_block("entry");
+            ctx.builder.append_to(entry_bb);
+            self.codegen(stmt, &mut ctx)?;
+            ctx.builder.ins().halt();
+            prune_blocks(&mut ctx.builder);
+        }
+        Ok(unit)
+    }
+
+    /// Lower a single concurrent statement into a throwaway entity.
+    ///
+    /// The signals the statement accesses become arguments of the entity. The
+    /// units the statement instantiates, such as the process equivalent to a
+    /// signal assignment, are added to the module as usual.
+    #[cfg(test)]
+    pub(crate) fn lower_conc_stmt(&self, stmt: ConcStmtRef) -> Result<llhd::ir::UnitData> {
+        let mut accesses = SignalAccesses::default();
+        self.collect_conc_accesses(&[], &[stmt], &mut vec![], &mut accesses)?;
+        let (inputs, outputs, sig) = self.process_signature(&accesses)?;
+        let name = llhd::ir::UnitName::Global(String::from("stmt"));
+        let mut unit = llhd::ir::UnitData::new(llhd::ir::UnitKind::Entity, name, sig);
+        {
+            let mut ctx = self.unit_context(&mut unit);
+            self.bind_process_args(&mut ctx, &inputs, &outputs)?;
+            self.codegen(stmt, &mut ctx)?;
+        }
+        Ok(unit)
+    }
+
+    /// Determine which signals a sequence of statements reads and drives.
+    pub fn signal_accesses(&self, stmts: &[SeqStmtRef]) -> Result<SignalAccesses> {
+        let mut accesses = SignalAccesses::default();
+        for &stmt in stmts {
+            self.collect_stmt_accesses(stmt, &mut accesses)?;
+        }
+        Ok(accesses)
+    }
+
+    fn collect_stmt_accesses(&self, id: SeqStmtRef, accesses: &mut SignalAccesses) -> Result<()> {
+        match id {
+            SeqStmtRef::SigAssign(id) => {
+                let hir = self.hir(id)?;
+                match hir.target {
+                    hir::SigAssignTarget::Name(sig) => {
+                        accesses.written.insert(sig);
+                    }
+                    hir::SigAssignTarget::Part(sig, expr) => {
+                        accesses.written.insert(sig);
+                        self.collect_target_accesses(expr, accesses)?;
+                    }
+                    hir::SigAssignTarget::Aggregate => (),
+                }
+                if let hir::SigAssignKind::SimpleWave(_, ref wave) = hir.kind {
+                    for elem in wave {
+                        for &expr in elem.value.iter().chain(elem.after.iter()) {
+                            self.collect_expr_accesses(expr, accesses)?;
+                        }
+                    }
+                }
+            }
+            SeqStmtRef::If(id) => {
+                let hir = self.lazy_hir(id)?;
+                for &(cond, ref stmts) in &hir.stmt.branches {
+                    self.collect_expr_accesses(cond, accesses)?;
+                    for &stmt in stmts {
+                        self.collect_stmt_accesses(stmt, accesses)?;
+                    }
+                }
+                for &stmt in hir.stmt.otherwise.iter().flat_map(|s| s.iter()) {
+                    self.collect_stmt_accesses(stmt, accesses)?;
+                }
+            }
+            SeqStmtRef::Loop(id) => {
+                let hir = self.lazy_hir(id)?;
+                if let hir::LoopScheme::While(cond) = hir.stmt.scheme {
+                    self.collect_expr_accesses(cond, accesses)?;
+                }
+                for &stmt in &hir.stmt.stmts {
+                    self.collect_stmt_accesses(stmt, accesses)?;
+                }
+            }
+            SeqStmtRef::Nexit(id) => {
+                if let Some(cond) = self.lazy_hir(id)?.stmt.cond {
+                    self.collect_expr_accesses(cond, accesses)?;
+                }
+            }
+            SeqStmtRef::VarAssign(id) => {
+                let hir = self.lazy_hir(id)?;
+                if let hir::Target::Name(expr) = hir.stmt.target.value {
+                    self.collect_target_accesses(expr, accesses)?;
+                }
+                self.collect_var_assign_accesses(&hir.stmt.kind, accesses)?;
+            }
+            SeqStmtRef::Return(id) => {
+                if let Some(expr) = self.lazy_hir(id)?.stmt.expr {
+                    self.collect_expr_accesses(expr, accesses)?;
+                }
+            }
+            SeqStmtRef::Wait(id) => {
+                let hir = self.lazy_hir(id)?;
+                if let Some(ref sens) = hir.stmt.sens {
+                    accesses.read.extend(sens.value.iter().map(|s| s.value));
+                }
+                for &expr in hir.stmt.cond.iter().chain(hir.stmt.timeout.iter()) {
+                    self.collect_expr_accesses(expr, accesses)?;
+                }
+            }
+            SeqStmtRef::Assert(id) => {
+                let hir = self.lazy_hir(id)?;
+                self.collect_expr_accesses(hir.stmt.cond, accesses)?;
+                for &expr in hir.stmt.report.iter().chain(hir.stmt.severity.iter()) {
+                    self.collect_expr_accesses(expr, accesses)?;
+                }
+            }
+            SeqStmtRef::Report(id) => {
+                let hir = self.lazy_hir(id)?;
+                self.collect_expr_accesses(hir.stmt.report, accesses)?;
+                if let Some(expr) = hir.stmt.severity {
+                    self.collect_expr_accesses(expr, accesses)?;
+                }
+            }
+            SeqStmtRef::ProcCall(id) => {
+                self.collect_expr_accesses(self.lazy_hir(id)?.stmt.call, accesses)?;
+            }
+            SeqStmtRef::Case(id) => {
+                let hir = self.lazy_hir(id)?;
+                self.collect_expr_accesses(hir.stmt.switch, accesses)?;
+                for (_, stmts) in &hir.stmt.cases {
+                    for &stmt in stmts {
+                        self.collect_stmt_accesses(stmt, accesses)?;
+                    }
+                }
+            }
+            SeqStmtRef::Null(_) => (),
+        }
+        Ok(())
+    }
+
+    fn collect_var_assign_accesses(
+        &self,
+        kind: &hir::VarAssignKind,
+        accesses: &mut SignalAccesses,
+    ) -> Result<()> {
+        match *kind {
+            hir::VarAssignKind::Simple(expr) => self.collect_expr_accesses(expr, accesses)?,
+            hir::VarAssignKind::Cond(ref cond) => {
+                for &(value, cond) in &cond.when {
+                    self.collect_expr_accesses(value, accesses)?;
+                    self.collect_expr_accesses(cond, accesses)?;
+                }
+                if let Some(value) = cond.other {
+                    self.collect_expr_accesses(value, accesses)?;
+                }
+            }
+            hir::VarAssignKind::Sel(..) => (),
+        }
+        Ok(())
+    }
+
+    /// Collect the signals read by the indices of an assignment target.
+    ///
+    /// The signal or variable being assigned is not considered to be read.
+    fn collect_target_accesses(&self, id: ExprRef, accesses: &mut SignalAccesses) -> Result<()> {
+        match self.lazy_hir(id)?.data {
+            hir::ExprData::VarName(var) if self.lazy_hir(var)?.decl.shared => {
+                accesses.shared.insert(var);
+            }
+            hir::ExprData::Select(prefix, _) => self.collect_target_accesses(prefix, accesses)?,
+            hir::ExprData::Deref(prefix) => self.collect_expr_accesses(prefix, accesses)?,
+            hir::ExprData::Call(prefix, ref args) => {
+                self.collect_target_accesses(prefix, accesses)?;
+                self.collect_assoc_accesses(&args.value, accesses)?;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn collect_assoc_accesses(
+        &self,
+        assocs: &[hir::AssocElement],
+        accesses: &mut SignalAccesses,
+    ) -> Result<()> {
+        for assoc in assocs {
+            match assoc.actual.value {
+                hir::AssocActual::Expr(expr) | hir::AssocActual::InertialExpr(expr) => {
+                    self.collect_expr_accesses(expr, accesses)?
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn collect_expr_accesses(&self, id: ExprRef, accesses: &mut SignalAccesses) -> Result<()> {
+        if let Some((object, method, args)) = self.resolve_method(id)? {
+            accesses.read.extend(self.implicit_signals(method)?);
+            self.collect_expr_accesses(object, accesses)?;
+            return self.collect_assoc_accesses(args, accesses);
+        }
+        match self.lazy_hir(id)?.data {
+            hir::ExprData::SignalName(sig) => {
+                accesses.read.insert(sig);
+            }
+            hir::ExprData::SignalAttr(sig, _) => {
+                accesses.read.insert(sig);
+                accesses.sampled.insert(sig);
+            }
+            hir::ExprData::VarName(var) if self.lazy_hir(var)?.decl.shared => {
+                accesses.shared.insert(var);
+            }
+            hir::ExprData::Select(expr, _)
+            | hir::ExprData::Attr(expr, _)
+            | hir::ExprData::Unary(_, _, expr)
+            | hir::ExprData::Qualified(_, expr)
+            | hir::ExprData::Cast(_, expr)
+            | hir::ExprData::ImageAttr(_, expr)
+            | hir::ExprData::Allocator(_, Some(expr))
+            | hir::ExprData::Deref(expr) => self.collect_expr_accesses(expr, accesses)?,
+            hir::ExprData::Binary(_, _, lhs, rhs) | hir::ExprData::Range(_, lhs, rhs) => {
+                self.collect_expr_accesses(lhs, accesses)?;
+                self.collect_expr_accesses(rhs, accesses)?;
+            }
+            hir::ExprData::Call(callee, ref args) => {
+                self.collect_expr_accesses(callee, accesses)?;
+                self.collect_assoc_accesses(&args.value, accesses)?;
+            }
+            hir::ExprData::SubprogCall(_, ref args) => {
+                if let Some((sig, _)) = self.edge_call(id)? {
+                    accesses.sampled.insert(sig);
+                } else if let Def::Subprog(subprog) = self.resolve_call(id)?.value {
+                    accesses.read.extend(self.implicit_signals(subprog)?);
+                }
+                self.collect_assoc_accesses(&args.value, accesses)?;
+            }
+            hir::ExprData::Aggregate(id) => {
+                let hir = self.lazy_hir(id)?;
+                for expr in &hir.positional {
+                    self.collect_expr_accesses(expr.value, accesses)?;
+                }
+                match hir.named {
+                    hir::AggregateKind::Both => (),
+                    hir::AggregateKind::Record(ref fields) => {
+                        for field in fields {
+                            self.collect_expr_accesses((field.value.1).value, accesses)?;
+                        }
+                    }
+                    hir::AggregateKind::Array(ref fields) => {
+                        for field in fields {
+                            for choice in &field.value.0 {
+                                if let hir::ArrayChoice::Expr(expr) = choice.value {
+                                    self.collect_expr_accesses(expr, accesses)?;
+                                }
+                            }
+                            self.collect_expr_accesses((field.value.1).value, accesses)?;
+                        }
+                    }
+                }
+                if let Some(expr) = hir.others {
+                    self.collect_expr_accesses(expr.value, accesses)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Warn about the signals declared in an architecture that are never read
+    /// or never driven.
+    ///
+    /// Ports are exempt, since the environment of the entity reads and drives
+    /// them. Signals connected to the port of an instance count as read or
+    /// driven according to the mode of the port.
+    pub fn check_unused_signals(&self, id: ArchRef) -> Result<()> {
+        let (decls, accesses) = self.arch_signal_accesses(id)?;
+        for decl in decls {
+            let sig = SignalRef::Decl(decl);
+            let name = self.lazy_hir(decl)?.name;
+            if !accesses.read.contains(&sig) && !accesses.sampled.contains(&sig) {
+                self.emit(
+                    DiagBuilder2::warning(format!("signal `{}` is never read", name.value))
+                        .span(name.span),
+                );
+            }
+            if !accesses.written.contains(&sig) {
+                self.emit(
+                    DiagBuilder2::warning(format!("signal `{}` is never driven", name.value))
+                        .span(name.span),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Determine the signals declared in an architecture, including its blocks
+    /// and generate statements, and the signals its statements access.
+    pub fn arch_signal_accesses(
+        &self,
+        id: ArchRef,
+    ) -> Result<(Vec<SignalDeclRef>, SignalAccesses)> {
+        let hir = self.hir(id)?;
+        let mut decls = Vec::new();
+        let mut accesses = SignalAccesses::default();
+        self.collect_conc_accesses(&hir.decls, &hir.stmts, &mut decls, &mut accesses)?;
+        Ok((decls, accesses))
+    }
+
+    /// Collect the signals declared in a block and the signals accessed by its
+    /// concurrent statements.
+    fn collect_conc_accesses(
+        &self,
+        decls: &[DeclInBlockRef],
+        stmts: &[ConcStmtRef],
+        signals: &mut Vec<SignalDeclRef>,
+        accesses: &mut SignalAccesses,
+    ) -> Result<()> {
+        signals.extend(decls.iter().filter_map(|&decl| match decl {
+            DeclInBlockRef::Signal(id) => Some(id),
+            _ => None,
+        }));
+        for &stmt in stmts {
+            match stmt {
+                ConcStmtRef::Block(id) => {
+                    let hir = self.hir(id)?;
+                    if let Some(guard) = hir.guard {
+                        self.collect_expr_accesses(guard, accesses)?;
+                    }
+                    for &(formal, actual) in &hir.port_map {
+                        self.collect_port_accesses(formal, actual, accesses)?;
+                    }
+                    self.collect_conc_accesses(&hir.decls, &hir.stmts, signals, accesses)?;
+                }
+                ConcStmtRef::Process(id) => {
+                    let hir = self.hir(id)?;
+                    for &stmt in &hir.stmts {
+                        self.collect_stmt_accesses(stmt, accesses)?;
+                    }
+                    if let hir::ProcessSensitivity::List(ref list) = hir.sensitivity {
+                        accesses.read.extend(list.iter().map(|s| s.value));
+                    }
+                }
+                ConcStmtRef::ConcSigAssign(id) => {
+                    let hir = self.hir(id)?;
+                    self.collect_stmt_accesses(SeqStmtRef::SigAssign(hir.assign), accesses)?;
+                    accesses.read.extend(hir.guard);
+                }
+                ConcStmtRef::CompInst(id) => {
+                    let hir = self.hir(id)?;
+                    for &(_, actual) in &hir.generic_map {
+                        self.collect_expr_accesses(actual, accesses)?;
+                    }
+                    for &(formal, actual) in &hir.port_map {
+                        self.collect_port_accesses(formal, actual, accesses)?;
+                    }
+                }
+                ConcStmtRef::ForGen(id) => {
+                    let hir = self.hir(id)?;
+                    self.collect_conc_accesses(&hir.decls, &hir.stmts, signals, accesses)?;
+                }
+                // The remaining statements are rejected during code generation.
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Collect the signals connected to a port, which count as read or driven
+    /// according to the mode of the port.
+    fn collect_port_accesses(
+        &self,
+        formal: IntfSignalRef,
+        actual: ExprRef,
+        accesses: &mut SignalAccesses,
+    ) -> Result<()> {
+        let mut connected = SignalAccesses::default();
+        self.collect_expr_accesses(actual, &mut connected)?;
+        match self.hir(formal)?.mode {
+            hir::IntfSignalMode::In => accesses.read.extend(connected.read),
+            hir::IntfSignalMode::Out => accesses.written.extend(connected.read),
+            _ => {
+                accesses.read.extend(connected.read.iter().cloned());
+                accesses.written.extend(connected.read);
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the constructs in an architecture that code generation does not
+    /// support yet.
+    ///
+    /// This only inspects the syntax of the architecture, such that the
+    /// constructs can be listed before lowering to HIR or code generation
+    /// trips over the first of them.
+    pub fn unsupported_constructs(&self, id: ArchRef) -> Vec<Unsupported> {
+        let ast = self.ast(id).2;
+        let mut found = Vec::new();
+        collect_unsupported_decls(&ast.decls, &mut found);
+        collect_unsupported_conc_stmts(&ast.stmts, &mut found);
+        found
+    }
+
+    /// Emit a warning for every construct in an architecture that code
+    /// generation does not support yet.
+    ///
+    /// Returns whether any such constructs were found.
+    pub fn report_unsupported(&self, id: ArchRef) -> bool {
+        let found = self.unsupported_constructs(id);
+        for construct in &found {
+            self.emit(DiagBuilder2::warning(construct.to_string()).span(construct.span));
+        }
+        !found.is_empty()
+    }
+
+    /// Recognize a call to `rising_edge` or `falling_edge` of a signal.
+    ///
+    /// Returns the signal and whether the call detects a rising edge. These
+    /// functions of `std_logic_1164` are lowered through the signal's
+    /// `'event` attribute rather than called, see `codegen_edge`.
+    fn edge_call(&self, id: ExprRef) -> Result<Option<(SignalRef, bool)>> {
+        let args = match self.lazy_hir(id)?.data {
+            hir::ExprData::SubprogCall(_, ref args) => args,
+            _ => return Ok(None),
+        };
+        self.lazy_typeval(id)?;
+        let subprog = match self.resolve_call(id)?.value {
+            Def::Subprog(id) => id,
+            _ => return Ok(None),
+        };
+        let hir = self.hir(subprog)?;
+        let in_std_logic_1164 = match hir.parent {
+            ScopeRef::Pkg(pkg) => self
+                .hir(pkg)?
+                .name
+                .value
+                .as_str()
+                .eq_ignore_ascii_case("std_logic_1164"),
+            _ => false,
+        };
+        let rising = match hir.spec.name.value {
+            ResolvableName::Ident(name) if in_std_logic_1164 => {
+                match name.as_str().to_ascii_lowercase().as_str() {
+                    "rising_edge" => true,
+                    "falling_edge" => false,
+                    _ => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+        let arg = match args.value.as_slice() {
+            [arg] => match arg.actual.value {
+                hir::AssocActual::Expr(arg) => arg,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        Ok(match self.lazy_hir(arg)?.data {
+            hir::ExprData::SignalName(sig) => Some((sig, rising)),
+            _ => None,
+        })
+    }
+
+    /// Determine whether a condition is statically true or false.
+    ///
+    /// Returns `None` if the condition needs to be evaluated at runtime.
+    pub fn static_condition(&self, id: ExprRef) -> Result<Option<bool>> {
+        match self.lazy_hir(id)?.data {
+            hir::ExprData::EnumName(..) => (),
+            _ => return Ok(None),
+        }
+        Ok(match *self.const_value(id)? {
+            Const::Enum(ref k) if k.decl == BOOLEAN_TYPE.id => Some(k.index == 1),
+            _ => None,
+        })
+    }
+
+    /// Reject an integer division whose divisor is statically zero.
+    ///
+    /// Divisors whose value is only known at runtime are accepted.
+    fn check_static_divisor(&self, id: ExprRef) -> Result<()> {
+        let hir = self.lazy_hir(id)?;
+        let value = match hir.data {
+            hir::ExprData::IntegerLiteral(..)
+            | hir::ExprData::ConstName(ConstRef::Intf(_))
+            | hir::ExprData::ConstName(ConstRef::Decl(_)) => self.const_value(id)?,
+            hir::ExprData::ConstName(ConstRef::Gen(gen)) => self.gen_param_value(gen, hir.span)?,
+            _ => return Ok(()),
+        };
+        match *value {
+            Const::Int(ref k) if k.value.is_zero() => {
+                self.emit(
+                    DiagBuilder2::error(format!("division by zero; `{}` is 0", hir.span.extract()))
+                        .span(hir.span),
+                );
+                Err(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Determine the severity level of a report.
+    ///
+    /// Returns the index of the level in `SEVERITY_LEVEL`, or `None` if the
+    /// severity needs to be evaluated at runtime. Reports without an explicit
+    /// severity default to `default`.
+    pub fn static_severity(&self, id: Option<ExprRef>, default: usize) -> Result<Option<usize>> {
+        let id = match id {
+            Some(id) => id,
+            None => return Ok(Some(default)),
+        };
+        match self.lazy_hir(id)?.data {
+            hir::ExprData::EnumName(..) => (),
+            _ => return Ok(None),
+        }
+        Ok(match *self.const_value(id)? {
+            Const::Enum(ref k) if k.decl == SEVERITY_LEVEL_TYPE.id => Some(k.index),
+            _ => None,
+        })
+    }
+
+    /// Determine the severity level of an assertion.
+    ///
+    /// Assertions without an explicit severity default to `error`. See
+    /// `static_severity`.
+    pub fn assert_severity(&self, id: AssertStmtRef) -> Result<Option<usize>> {
+        self.static_severity(self.lazy_hir(id)?.stmt.severity, SEVERITY_ERROR)
+    }
+
+    /// Determine the severity level of a report statement.
+    ///
+    /// Reports without an explicit severity default to `note`. See
+    /// `static_severity`.
+    pub fn report_severity(&self, id: ReportStmtRef) -> Result<Option<usize>> {
+        self.static_severity(self.lazy_hir(id)?.stmt.severity, SEVERITY_NOTE)
+    }
+
+    /// Generate a report with a severity level and an optional message.
+    ///
+    /// Reports are emitted as a call to the `vhdl.report` runtime function,
+    /// which is passed the severity level. The `static_severity` is the level
+    /// as determined by `static_severity`. A report whose severity is at least
+    /// the scoreboard's `halt_severity` additionally halts a process.
+    ///
+    /// The message is passed as a second argument, see `codegen_message`.
+    /// Since LLHD arrays have a fixed length, the runtime function is
+    /// specialized for the length `N` of the message as `vhdl.report.N`.
+    pub fn codegen_report(
+        &self,
+        severity: Option<ExprRef>,
+        static_severity: Option<usize>,
+        message: Option<ExprRef>,
+        ctx: &mut UnitContext,
+    ) -> Result<()> {
+        let level = match (severity, static_severity) {
+            (Some(severity), None) => self.codegen_expr(severity, ctx)?,
+            (_, level) => {
+                let k = Const::Enum(ConstEnum::new(SEVERITY_LEVEL_TYPE.id, level.unwrap()));
+                self.map_const(&mut ctx.builder, &k)?
+            }
+        };
+        let mut sig = llhd::ir::Signature::new();
+        sig.add_input(ctx.builder.value_type(level));
+        sig.set_return_type(llhd::void_ty());
+        let mut args = vec![level];
+        let mut name = String::from("vhdl.report");
+        if let Some(message) = message {
+            let message = self.codegen_message(message, ctx)?;
+            let len = match *ctx.builder.value_type(message) {
+                llhd::ArrayType(len, _) => len,
+                _ => unreachable!(),
+            };
+            sig.add_input(ctx.builder.value_type(message));
+            args.push(message);
+            name = format!("vhdl.report.{}", len);
+        }
+        let report = ctx.extern_unit(llhd::ir::UnitName::Global(name), sig);
+        ctx.builder.ins().call(report, args);
+        if matches!(static_severity, Some(level) if level >= self.sb.halt_severity)
+            && ctx.builder.kind() == llhd::ir::UnitKind::Process
+        {
+            ctx.builder.ins().halt();
+            let cont_bb = ctx.builder.named_block("halt_cont");
+            ctx.builder.append_to(cont_bb);
+        }
+        Ok(())
+    }
+
+    /// Generate the code for the message of a report or assertion.
+    ///
+    /// Strings are arrays of 8 bit characters. The operands of concatenations
+    /// in the message are generated individually and collected into a single
+    /// string, such that messages like `"a=" & integer'image(a)` need not be
+    /// resolved as a whole. Operands that are single characters contribute
+    /// one element.
+    fn codegen_message(&self, id: ExprRef, ctx: &mut UnitContext) -> Result<llhd::ir::Value> {
+        let mut chars = vec![];
+        self.collect_message_chars(id, &mut chars, ctx)?;
+        if chars.is_empty() {
+            let zero = ctx.builder.ins().const_int((8, BigInt::zero()));
+            return Ok(ctx.builder.ins().array_uniform(0, zero));
+        }
+        Ok(ctx.builder.ins().array(chars))
+    }
+
+    /// Collect the characters of a report message, see `codegen_message`.
+    fn collect_message_chars(
+        &self,
+        id: ExprRef,
+        chars: &mut Vec<llhd::ir::Value>,
+        ctx: &mut UnitContext,
+    ) -> Result<()> {
+        if let hir::ExprData::Binary(op, _, lhs, rhs) = self.lazy_hir(id)?.data {
+            if op.value == BinaryOp::Concat {
+                self.collect_message_chars(lhs, chars, ctx)?;
+                return self.collect_message_chars(rhs, chars, ctx);
+            }
+        }
+        self.set_type_context(id, self.builtin_string_type());
+        let value = self.codegen_expr(id, ctx)?;
+        match *ctx.builder.value_type(value) {
+            llhd::ArrayType(len, _) => {
+                for i in 0..len {
+                    chars.push(ctx.builder.ins().ext_field(value, i));
+                }
+            }
+            _ => chars.push(value),
+        }
+        Ok(())
+    }
+
+    /// Generate the code for the `T'image(X)` attribute.
+    ///
+    /// The image of an integer literal, constant, or generic is folded to a
+    /// string.
+    /// Otherwise the image is produced at runtime by the `vhdl.image.iN`
+    /// function, where `N` is the width of the integer. Since LLHD arrays have
+    /// a fixed length, the function pads the image with `NUL` characters to
+    /// the length of the longest image of an `N` bit integer. These are
+    /// dropped when the string is printed.
+    fn codegen_image(&self, id: ExprRef, ctx: &mut UnitContext) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        let (tm, arg) = match hir.data {
+            hir::ExprData::ImageAttr(tm, arg) => (tm, arg),
+            _ => unreachable!(),
+        };
+        match *self.deref_named_type(self.ty(tm.value)?)? {
+            Ty::Int(..) => (),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for `{}` not implemented",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span)
+                    .add_note("Only the image of integers is supported."),
+                );
+                return Err(());
+            }
+        }
+
+        // Fold the image of literals, constants, and generics.
+        let is_const = match self.lazy_hir(arg)?.data {
+            hir::ExprData::IntegerLiteral(..) => true,
+            hir::ExprData::ConstName(ConstRef::Decl(_)) => true,
+            hir::ExprData::ConstName(id @ ConstRef::Intf(_)) => self.is_generic(id),
+            _ => false,
+        };
+        if is_const {
+            if let Const::Int(ref k) = *self.const_value(arg)? {
+                let chars = k
+                    .value
+                    .to_string()
+                    .bytes()
+                    .map(|c| ctx.builder.ins().const_int((8, BigInt::from(c))))
+                    .collect();
+                return Ok(ctx.builder.ins().array(chars));
+            }
+        }
+
+        let value = self.codegen_expr(arg, ctx)?;
+        let width = ctx.builder.value_type(value).unwrap_int();
+        let len = (-(BigInt::one() << (width - 1))).to_string().len();
+        let mut sig = llhd::ir::Signature::new();
+        sig.add_input(llhd::int_ty(width));
+        sig.set_return_type(llhd::array_ty(len, llhd::int_ty(8)));
+        let name = llhd::ir::UnitName::Global(format!("vhdl.image.i{}", width));
+        let image = ctx.extern_unit(name, sig);
+        Ok(ctx.builder.ins().call(image, vec![value]))
+    }
+
+    /// Generate the code for an expression.
+    pub fn codegen_expr(&self, id: ExprRef, ctx: &mut UnitContext) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        match hir.data {
+            hir::ExprData::SignalName(sig) => self.codegen_signal_value(sig, hir.span, ctx),
+            hir::ExprData::VarName(var) => match ctx.variables.get(&var) {
+                Some(&slot) => Ok(ctx.builder.ins().ld(slot)),
+                None => {
+                    self.emit(
+                        DiagBuilder2::bug(format!(
+                            "variable `{}` not accessible during code generation",
+                            hir.span.extract()
+                        ))
+                        .span(hir.span),
+                    );
+                    Err(())
+                }
+            },
+            hir::ExprData::ConstName(ConstRef::Gen(gen)) => {
+                let value = self.gen_param_value(gen, hir.span)?;
+                self.map_const(&mut ctx.builder, value)
+            }
+            hir::ExprData::ConstName(k) => match ctx.constants.get(&k) {
+                Some(&value) => Ok(value),
+                // Generics and constants declared outside the unit, e.g. in a
+                // package, are not part of the unit, but have a constant
+                // value.
+                None => {
+                    let k = self.const_value(id)?;
+                    self.map_const(&mut ctx.builder, k)
+                }
+            },
+            hir::ExprData::IntegerLiteral(ref k) => {
+                let width = match self.type_context_resolved(id)? {
+                    Some(ty) => match *self.deref_named_type(ty)? {
+                        Ty::Int(ref ty) => self.int_type_width(ty),
+                        _ => None,
+                    },
+                    None => None,
+                };
+                match width {
+                    Some(width) => Ok(ctx.builder.ins().const_int((width, k.value.clone()))),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "type of `{}` cannot be inferred from context",
+                                hir.span.extract()
+                            ))
+                            .span(hir.span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+            hir::ExprData::EnumName(..) => {
+                let k = self.const_value(id)?;
+                self.map_const(&mut ctx.builder, k)
+            }
+            hir::ExprData::StringLiteral(ref defs) => {
+                // Pick the literals of the element type that overload
+                // resolution settled on.
+                let decl = match *self.deref_named_type(self.lazy_typeval(id)?)? {
+                    Ty::Array(ref ty) => match *self.deref_named_type(&ty.element)? {
+                        Ty::Enum(ref ty) => ty.decl,
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                };
+                let indices = &defs.iter().find(|&&(d, _)| d == decl).unwrap().1;
+                let elements = indices
+                    .iter()
+                    .map(|&index| {
+                        let k = Const::Enum(ConstEnum::new(decl, index));
+                        self.map_const(&mut ctx.builder, &k)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                if elements.is_empty() {
+                    let k = Const::Enum(ConstEnum::new(decl, 0));
+                    let zero = self.map_const(&mut ctx.builder, &k)?;
+                    return Ok(ctx.builder.ins().array_uniform(0, zero));
+                }
+                Ok(ctx.builder.ins().array(elements))
+            }
+            // Name attributes are folded to a string of 8 bit characters.
+            hir::ExprData::NameAttr(..) => {
+                let value = self.name_attr_value(id)?;
+                let chars = value
+                    .bytes()
+                    .map(|c| ctx.builder.ins().const_int((8, BigInt::from(c))))
+                    .collect();
+                Ok(ctx.builder.ins().array(chars))
+            }
+            hir::ExprData::ImageAttr(..) => self.codegen_image(id, ctx),
+            hir::ExprData::Cast(tm, arg) => {
+                self.lazy_typeval(id)?;
+                let value = self.codegen_expr(arg, ctx)?;
+                self.codegen_cast(tm, arg, value, hir.span, ctx)
+            }
+            // The aggregate inherits the type context of the expression once
+            // the latter is type checked.
+            hir::ExprData::Aggregate(agg) => {
+                self.lazy_typeval(id)?;
+                self.codegen_aggregate(agg, ctx)
+            }
+            // LLHD has no heap, so allocated objects are placed in a variable
+            // of the unit and live as long as it does.
+            hir::ExprData::Allocator(tm, expr) => {
+                self.lazy_typeval(id)?;
+                let value = match expr {
+                    Some(expr) => self.codegen_expr(expr, ctx)?,
+                    None => self.codegen_default_value(&mut ctx.builder, self.ty(tm.value)?)?,
+                };
+                Ok(ctx.builder.ins().var(value))
+            }
+            hir::ExprData::Deref(prefix) => {
+                let ptr = self.codegen_expr(prefix, ctx)?;
+                Ok(ctx.builder.ins().ld(ptr))
+            }
+            hir::ExprData::Select(..) | hir::ExprData::Call(..)
+                if self.resolve_method(id)?.is_some() =>
+            {
+                let (object, method, args) = self.resolve_method(id)?.unwrap();
+                self.codegen_method_call(object, method, args, hir.span, ctx)
+            }
+            hir::ExprData::Select(prefix, name) => {
+                let base = self.codegen_expr(prefix, ctx)?;
+                self.codegen_select(base, prefix, name, ctx)
+            }
+            hir::ExprData::Call(prefix, ref args) => {
+                let base = self.codegen_expr(prefix, ctx)?;
+                self.codegen_index(base, prefix, args, hir.span, ctx)
+            }
+            hir::ExprData::Unary(op, _, arg) => {
+                let def = self.resolve_unary_op(id)?;
+                if let Def::Subprog(subprog) = def.value {
+                    let args = self.codegen_call_args(subprog, &[arg], ctx)?;
+                    return self.codegen_call(subprog, args, hir.span, ctx);
+                }
+                let arg = self.codegen_expr(arg, ctx)?;
+                match def.value {
+                    Def::BuiltinOp(..) => self.codegen_builtin_unary_op(op, arg, ctx),
+                    _ => unreachable!(),
+                }
+            }
+            hir::ExprData::Binary(op, _, lhs_id, rhs_id) => {
+                let def = self.resolve_binary_op(id)?;
+                if let Def::Subprog(subprog) = def.value {
+                    let args = self.codegen_call_args(subprog, &[lhs_id, rhs_id], ctx)?;
+                    return self.codegen_call(subprog, args, hir.span, ctx);
+                }
+                let lhs = self.codegen_expr(lhs_id, ctx)?;
+                let rhs = self.codegen_expr(rhs_id, ctx)?;
+                match def.value {
+                    Def::BuiltinOp(builtin) if op.value == BinaryOp::Concat => {
+                        self.codegen_concat(builtin, lhs, rhs, ctx)
+                    }
+                    Def::BuiltinOp(..) => {
+                        if let BinaryOp::Div | BinaryOp::Mod | BinaryOp::Rem = op.value {
+                            self.check_static_divisor(rhs_id)?;
+                        }
+                        // Operands of different subtypes of the same integer
+                        // type are extended to the wider of the two.
+                        let lhs_ty = ctx.builder.value_type(lhs);
+                        let rhs_ty = ctx.builder.value_type(rhs);
+                        let (lhs, rhs) = match (lhs_ty.is_int(), rhs_ty.is_int()) {
+                            (true, true) if lhs_ty.unwrap_int() < rhs_ty.unwrap_int() => {
+                                (self.codegen_int_cast(lhs, lhs_id, &rhs_ty, ctx)?, rhs)
+                            }
+                            (true, true) => {
+                                (lhs, self.codegen_int_cast(rhs, rhs_id, &lhs_ty, ctx)?)
+                            }
+                            _ => (lhs, rhs),
+                        };
+                        let signed = self.is_signed_int(self.lazy_typeval(lhs_id)?)?;
+                        self.codegen_builtin_binary_op(op, signed, lhs, rhs, ctx)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            hir::ExprData::SignalAttr(sig, attr) => self.codegen_signal_attr(sig, attr, ctx),
+            hir::ExprData::SubprogCall(_, ref args) => {
+                self.lazy_typeval(id)?;
+                if let Some((sig, rising)) = self.edge_call(id)? {
+                    return self.codegen_edge(sig, rising, hir.span, ctx);
+                }
+                let subprog = match self.resolve_call(id)?.value {
+                    Def::Subprog(id) => id,
+                    _ => unreachable!(),
+                };
+                if let ScopeRef::Protected(_) = self.hir(subprog)?.parent {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "calling method `{}` without an object is not supported",
+                            hir.span.extract()
+                        ))
+                        .span(hir.span),
+                    );
+                    return Err(());
+                }
+                let args: Vec<_> = args
+                    .value
+                    .iter()
+                    .map(|arg| match arg.actual.value {
+                        hir::AssocActual::Expr(id) => id,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                if let Some(value) = self.codegen_numeric_std_call(subprog, &args, hir.span, ctx)? {
+                    return Ok(value);
+                }
+                let args = self.codegen_call_args(subprog, &args, ctx)?;
+                self.codegen_call(subprog, args, hir.span, ctx)
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for expression `{}` not implemented",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code for a type conversion.
+    ///
+    /// Closely related array types map to the same LLHD type, such that the
+    /// conversion does not change the value. A constrained target type must
+    /// have the same length as the operand.
+    fn codegen_cast(
+        &self,
+        tm: Spanned<TypeMarkRef>,
+        arg: ExprRef,
+        value: llhd::ir::Value,
+        span: Span,
+        ctx: &UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let into = self.deref_named_type(self.ty(tm.value)?)?;
+        let from = self.deref_named_type(self.lazy_typeval(arg)?)?;
+        match (into, from) {
+            (Ty::Array(into_ty), &Ty::Array(_)) => {
+                let constrained = into_ty.indices.iter().all(|index| match *index {
+                    ArrayIndex::Constrained(_) => true,
+                    ArrayIndex::Unbounded(_) => false,
+                });
+                if constrained {
+                    let into_llty = self.map_type(into)?;
+                    let value_llty = ctx.builder.value_type(value);
+                    if into_llty != value_llty {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "length mismatch in conversion: target is `{}`, but value is `{}`",
+                                into_llty, value_llty
+                            ))
+                            .span(span),
+                        );
+                        return Err(());
+                    }
+                }
+                Ok(value)
+            }
+            (into, from) if into == from => Ok(value),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for conversion from {} to {} not implemented",
+                        from, into
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code for a predefined attribute of a signal.
+    ///
+    /// The attributes compare the current value of the signal to the value
+    /// it had when the enclosing process last suspended, which the process
+    /// keeps in `ctx.prev_values`.
+    fn codegen_signal_attr(
+        &self,
+        sig: SignalRef,
+        attr: Spanned<hir::SignalAttr>,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let (signal, prev) = match (ctx.signals.get(&sig), ctx.prev_values.get(&sig)) {
+            (Some(&signal), Some(&prev)) => (signal, prev),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "attribute `{}` of signal `{}` is only supported within processes",
+                        attr.span.extract(),
+                        self.signal_name(sig)?
+                    ))
+                    .span(attr.span),
+                );
+                return Err(());
+            }
+        };
+        let prev = ctx.builder.ins().ld(prev);
+        let value = match attr.value {
+            hir::SignalAttr::LastValue => return Ok(prev),
+            _ => ctx.builder.ins().prb(signal),
+        };
+        Ok(match attr.value {
+            hir::SignalAttr::Event => ctx.builder.ins().neq(value, prev),
+            _ => ctx.builder.ins().eq(value, prev),
+        })
+    }
+
+    /// Generate the code for a call to `rising_edge` or `falling_edge`.
+    ///
+    /// A rising edge is lowered as `s'event and s = '1'`, a falling edge as
+    /// `s'event and s = '0'`.
+    fn codegen_edge(
+        &self,
+        sig: SignalRef,
+        rising: bool,
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let decl = match *self.deref_named_type(self.ty(sig)?)? {
+            Ty::Enum(ref ty) => ty.decl,
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` requires a signal of an enumeration type",
+                        span.extract()
+                    ))
+                    .span(span),
+                );
+                return Err(());
+            }
+        };
+        let level = if rising { '1' } else { '0' };
+        let index = if decl == BIT_TYPE.id {
+            rising as usize
+        } else {
+            self.required_enum_char_index(decl, level, span)?
+        };
+        let event =
+            self.codegen_signal_attr(sig, Spanned::new(hir::SignalAttr::Event, span), ctx)?;
+        let width = enum_width(self.enum_len(decl)?);
+        let level = ctx.builder.ins().const_int((width, BigInt::from(index)));
+        let value = ctx.builder.ins().prb(ctx.signals[&sig]);
+        let is_level = ctx.builder.ins().eq(value, level);
+        Ok(ctx.builder.ins().and(event, is_level))
+    }
+
+    /// Adapt a value to the width of the signal or variable it is assigned to.
+    ///
+    /// Integer subtypes map to LLHD integers of the width their range
+    /// requires, such that a value of one subtype is resized to the width of
+    /// the target, see `codegen_int_cast`. Arrays of different lengths map to
+    /// LLHD types of different widths. VHDL requires their assignment to
+    /// fail, so the mismatch is reported as an error rather than generating a
+    /// malformed assignment.
+    fn codegen_assign_value(
+        &self,
+        target: llhd::ir::Value,
+        value: llhd::ir::Value,
+        expr: ExprRef,
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let target_ty = ctx.builder.value_type(target);
+        let target_ty = if target_ty.is_signal() {
+            target_ty.unwrap_signal().clone()
+        } else {
+            target_ty.unwrap_pointer().clone()
+        };
+        let value = self.codegen_int_cast(value, expr, &target_ty, ctx)?;
+        let value_ty = ctx.builder.value_type(value);
+        if target_ty == value_ty {
+            return Ok(value);
+        }
+        self.emit(
+            DiagBuilder2::error(format!(
+                "width mismatch in assignment: target is `{}`, but value is `{}`",
+                target_ty, value_ty
+            ))
+            .span(span),
+        );
+        Err(())
+    }
+
+    /// Resize an integer value to the width of another integer subtype.
+    ///
+    /// The value is sign extended if the subtype of the expression it was
+    /// generated from admits negative values. Values that are not integers are
+    /// returned unchanged.
+    ///
+    /// Narrowing a value deliberately drops its upper bits without checking
+    /// it against the range of the target subtype. VHDL requires a value
+    /// outside the range to fail at run time, but LLHD has no means to report
+    /// such a failure, so the value is assumed to be in range.
+    fn codegen_int_cast(
+        &self,
+        value: llhd::ir::Value,
+        expr: ExprRef,
+        ty: &llhd::Type,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let value_ty = ctx.builder.value_type(value);
+        if !ty.is_int() || !value_ty.is_int() || *ty == value_ty {
+            return Ok(value);
+        }
+        let expr_ty = self.lazy_typeval(expr)?;
+        if let Ty::Int(_) = *self.deref_named_type(expr_ty)? {
+            let from = value_ty.unwrap_int();
+            let to = ty.unwrap_int();
+            let signed = self.is_signed_int(expr_ty)?;
+            return Ok(self.codegen_int_resize(value, from, to, signed, ctx));
+        }
+        Ok(value)
+    }
+
+    /// Generate the code for the arguments of a subprogram call.
+    ///
+    /// Integer arguments are resized to the width of their formal, such that a
+    /// subprogram taking an `integer` accepts a value of any of its subtypes.
+    fn codegen_call_args(
+        &self,
+        id: SubprogDeclRef,
+        args: &[ExprRef],
+        ctx: &mut UnitContext,
+    ) -> Result<Vec<llhd::ir::Value>> {
+        let ty = match *self.lazy_typeval(id)? {
+            Ty::Subprog(ref ty) => ty,
+            _ => unreachable!(),
+        };
+        let mut values = Vec::with_capacity(args.len());
+        for (&arg, formal) in args.iter().zip(ty.args.iter()) {
+            let mut value = self.codegen_expr(arg, ctx)?;
+            if ctx.builder.value_type(value).is_int() {
+                let formal_ty = self.map_type(&formal.ty)?;
+                value = self.codegen_int_cast(value, arg, &formal_ty, ctx)?;
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Generate the code for the target of an assignment.
+    ///
+    /// Returns the signal or the pointer to the variable being assigned. Names
+    /// that select a part of a signal or variable, such as `a(i).b`, are
+    /// resolved to the signal or pointer of that part.
+    pub fn codegen_target(&self, id: ExprRef, ctx: &mut UnitContext) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        let value = match hir.data {
+            hir::ExprData::SignalName(sig) => ctx.signals.get(&sig).cloned(),
+            hir::ExprData::VarName(var) => ctx.variables.get(&var).cloned(),
+            hir::ExprData::Deref(prefix) => Some(self.codegen_expr(prefix, ctx)?),
+            hir::ExprData::Select(prefix, name) => {
+                let base = self.codegen_target(prefix, ctx)?;
+                return self.codegen_select(base, prefix, name, ctx);
+            }
+            hir::ExprData::Call(prefix, ref args) => {
+                let base = self.codegen_target(prefix, ctx)?;
+                return self.codegen_index(base, prefix, args, hir.span, ctx);
+            }
+            _ => None,
+        };
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for assignment target `{}` not implemented",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code to select a field of a record.
+    ///
+    /// The `base` may be a value, a signal, or a pointer to a variable.
+    fn codegen_select(
+        &self,
+        base: llhd::ir::Value,
+        prefix: ExprRef,
+        name: Spanned<ResolvableName>,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let ty = self.lazy_typeval(prefix)?;
+        let index = match (self.deref_named_type(ty)?, name.value) {
+            (Ty::Record(ty), ResolvableName::Ident(name)) => ty.lookup.get(&name).cloned(),
+            _ => None,
+        };
+        match index {
+            Some(index) => Ok(ctx.builder.ins().ext_field(base, index)),
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "field `{}` of type {} not accessible during code generation",
+                        name.value, ty
+                    ))
+                    .span(name.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code to index into an array.
+    ///
+    /// The `base` may be a value, a signal, or a pointer to a variable. Static
+    /// indices select the element directly, dynamic ones shift the array such
+    /// that the element ends up at the front.
+    fn codegen_index(
+        &self,
+        base: llhd::ir::Value,
+        prefix: ExprRef,
+        args: &Spanned<hir::AssocList>,
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let ty = self.lazy_typeval(prefix)?;
+        let ty = match *self.deref_named_type(ty)? {
+            Ty::Array(ref ty) => ty,
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for call `{}` not implemented",
+                        span.extract()
+                    ))
+                    .span(span),
+                );
+                return Err(());
+            }
+        };
+        let indices: Vec<ExprRef> = args
+            .value
+            .iter()
+            .map(|arg| match arg.actual.value {
+                hir::AssocActual::Expr(id) => id,
+                _ => unreachable!(),
+            })
+            .collect();
+        if ty.indices.len() > 1 {
+            return self.codegen_multi_index(base, ty, &indices, ctx);
+        }
+        let mut value = base;
+        for (&arg, index) in indices.iter().zip(ty.indices.iter()) {
+            let (dir, left) = self.index_origin(index, arg)?;
+            value = match self.static_index_offset(arg, dir, &left)? {
+                Some(offset) => {
+                    let offset = self.index_offset_to_usize(arg, offset)?;
+                    ctx.builder.ins().ext_field(value, offset)
+                }
+                None => {
+                    let amount = self.codegen_index_offset(arg, dir, left, ctx)?;
+                    let shifted = ctx.builder.ins().shr(value, value, amount);
+                    ctx.builder.ins().ext_field(shifted, 0)
+                }
+            };
+        }
+        Ok(value)
+    }
+
+    /// Generate the code to index into a multi-dimensional array.
+    ///
+    /// The array is flattened in row-major order, see `map_type`, so the
+    /// element at `(i, j)` of an array with `n` columns is element `i * n + j`
+    /// of the flattened array.
+    fn codegen_multi_index(
+        &self,
+        base: llhd::ir::Value,
+        ty: &ArrayTy,
+        indices: &[ExprRef],
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let mut lens = vec![];
+        for index in &ty.indices {
+            lens.push(self.array_index_len(index, ty)?);
+        }
+        let total: usize = lens.iter().product();
+        let width = enum_width(total);
+
+        // Accumulate the flat offset, keeping it static as long as all
+        // indices seen so far are static.
+        let mut static_offset = Some(BigInt::zero());
+        let mut dynamic_offset = None;
+        for ((&arg, index), &len) in indices.iter().zip(ty.indices.iter()).zip(lens.iter()) {
+            let (dir, left) = self.index_origin(index, arg)?;
+            let offset = self.static_index_offset(arg, dir, &left)?;
+            if let Some(ref offset) = offset {
+                if offset.is_negative() || *offset >= BigInt::from(len) {
+                    let span = self.span(arg).unwrap();
+                    self.emit(
+                        DiagBuilder2::error(format!("index `{}` is out of bounds", span.extract()))
+                            .span(span),
+                    );
+                    return Err(());
+                }
+            }
+            match (static_offset.take(), offset) {
+                (Some(acc), Some(offset)) => static_offset = Some(acc * len + offset),
+                (acc, offset) => {
+                    let offset = match offset {
+                        Some(offset) => ctx.builder.ins().const_int((width, offset)),
+                        None => {
+                            let offset = self.codegen_index_offset(arg, dir, left, ctx)?;
+                            let from = ctx.builder.value_type(offset).unwrap_int();
+                            self.codegen_int_resize(offset, from, width, false, ctx)
+                        }
+                    };
+                    let acc = match (acc, dynamic_offset.take()) {
+                        (Some(ref acc), _) if acc.is_zero() => None,
+                        (Some(acc), _) => Some(ctx.builder.ins().const_int((width, acc))),
+                        (None, acc) => acc,
+                    };
+                    dynamic_offset = Some(match acc {
+                        Some(acc) => {
+                            let len = ctx.builder.ins().const_int((width, BigInt::from(len)));
+                            let scaled = ctx.builder.ins().umul(acc, len);
+                            ctx.builder.ins().add(scaled, offset)
+                        }
+                        None => offset,
+                    });
+                }
+            }
+        }
+        match (static_offset, dynamic_offset) {
+            (Some(offset), _) => {
+                let offset = self.index_offset_to_usize(indices[0], offset)?;
+                Ok(ctx.builder.ins().ext_field(base, offset))
+            }
+            (None, Some(amount)) => {
+                let shifted = ctx.builder.ins().shr(base, base, amount);
+                Ok(ctx.builder.ins().ext_field(shifted, 0))
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    /// Determine the direction and left bound of an array index.
+    ///
+    /// Enum indices always count up from their first literal.
+    fn index_origin(&self, index: &ArrayIndex, arg: ExprRef) -> Result<(Dir, BigInt)> {
+        match *self.deref_named_type(index.ty())? {
+            Ty::Int(ref ty) => Ok((ty.dir, ty.left_bound.clone())),
+            Ty::Enum(..) => Ok((Dir::To, BigInt::zero())),
+            ref other => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for index of type {} not implemented",
+                        other
+                    ))
+                    .span(self.span(arg).unwrap()),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the offset of a static index from the left bound of its
+    /// array index, or `None` if the index is only known at runtime.
+    fn static_index_offset(&self, arg: ExprRef, dir: Dir, left: &BigInt) -> Result<Option<BigInt>> {
+        Ok(match self.lazy_hir(arg)?.data {
+            hir::ExprData::IntegerLiteral(ref k) => Some(match dir {
+                Dir::To => &k.value - left,
+                Dir::Downto => left - &k.value,
+            }),
+            hir::ExprData::EnumName(..) => match *self.const_value(arg)? {
+                Const::Enum(ref k) => Some(BigInt::from(k.index)),
+                _ => None,
+            },
+            hir::ExprData::ConstName(ConstRef::Gen(gen)) => {
+                match *self.gen_param_value(gen, self.span(arg).unwrap())? {
+                    Const::Int(ref k) => Some(match dir {
+                        Dir::To => &k.value - left,
+                        Dir::Downto => left - &k.value,
+                    }),
+                    Const::Enum(ref k) => Some(BigInt::from(k.index)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    }
+
+    /// Convert a static index offset to a field index.
+    fn index_offset_to_usize(&self, arg: ExprRef, offset: BigInt) -> Result<usize> {
+        match offset.to_usize() {
+            Some(offset) => Ok(offset),
+            None => {
+                let span = self.span(arg).unwrap();
+                self.emit(
+                    DiagBuilder2::error(format!("index `{}` is out of bounds", span.extract()))
+                        .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code for the offset of a dynamic index from the left bound
+    /// of its array index.
+    fn codegen_index_offset(
+        &self,
+        arg: ExprRef,
+        dir: Dir,
+        left: BigInt,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let mut index = self.codegen_expr(arg, ctx)?;
+        if let Ty::Enum(ref ty) = *self.deref_named_type(self.lazy_typeval(arg)?)? {
+            index = self.codegen_enum_position(ty.decl, index, ctx)?;
+        }
+        Ok(if left.is_zero() && dir == Dir::To {
+            index
+        } else {
+            let width = ctx.builder.value_type(index).unwrap_int();
+            let left = ctx.builder.ins().const_int((width, left));
+            match dir {
+                Dir::To => ctx.builder.ins().sub(index, left),
+                Dir::Downto => ctx.builder.ins().sub(left, index),
+            }
+        })
+    }
+
+    /// Generate the code for the position of an enum literal from its value.
+    ///
+    /// Without an `enum_encoding` attribute, the value of a literal is its
+    /// position already. Otherwise the value is compared against the pattern
+    /// of every literal.
+    fn codegen_enum_position(
+        &self,
+        decl: TypeDeclRef,
+        value: llhd::ir::Value,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let encoding = match self.enum_encoding(decl)? {
+            Some(encoding) => encoding,
+            None => return Ok(value),
+        };
+        let width = enum_width(encoding.codes.len());
+        let mut position = ctx.builder.ins().const_int((width, BigInt::zero()));
+        for (index, code) in encoding.codes.into_iter().enumerate().skip(1) {
+            let code = ctx.builder.ins().const_int((encoding.width, code));
+            let is_literal = ctx.builder.ins().eq(value, code);
+            let index = ctx.builder.ins().const_int((width, BigInt::from(index)));
+            let choices = ctx.builder.ins().array(vec![position, index]);
+            position = ctx.builder.ins().mux(choices, is_literal);
+        }
+        Ok(position)
+    }
+
+    /// Generate the code for an aggregate.
+    ///
+    /// The aggregate has already been checked against its type, so every
+    /// field or element is covered by exactly one of its expressions.
+    fn codegen_aggregate(
+        &self,
+        id: AggregateRef,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        let ty = self.lazy_typeval(id)?;
+        match *self.deref_named_type(ty)? {
+            Ty::Record(ref ty) => {
+                let mut fields = vec![];
+                for expr in self.record_aggregate_fields(id, ty)? {
+                    fields.push(self.codegen_expr(expr.value, ctx)?);
+                }
+                Ok(ctx.builder.ins().strukt(fields))
+            }
+            Ty::Array(..) if hir.named.is_both() && hir.others.is_none() => {
+                let elements = hir
+                    .positional
+                    .iter()
+                    .map(|e| self.codegen_expr(e.value, ctx))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(ctx.builder.ins().array(elements))
+            }
+            Ty::Array(ref ty) if ty.indices.len() == 1 => self.codegen_array_aggregate(id, ty, ctx),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for aggregate `{}` not implemented",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code for an array aggregate with named elements or
+    /// `others`.
+    ///
+    /// Each expression is generated once, and its value used for all the
+    /// elements its choices select. Elements may themselves be aggregates,
+    /// e.g. for arrays of arrays.
+    fn codegen_array_aggregate(
+        &self,
+        id: AggregateRef,
+        ty: &ArrayTy,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let hir = self.lazy_hir(id)?;
+        let index_ty = match ty.indices[0] {
+            ArrayIndex::Constrained(ref index_ty) => Some(self.deref_named_type(index_ty)?),
+            ArrayIndex::Unbounded(_) => None,
+        };
+        let len = match index_ty {
+            Some(Ty::Enum(ty)) => Some(self.enum_len(ty.decl)?),
+            Some(Ty::Int(ty)) => ty.len().to_usize(),
+            _ => None,
+        };
+        let (index_ty, len) = match (index_ty, len) {
+            (Some(index_ty), Some(len)) => (index_ty, len),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for aggregate `{}` not implemented",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+        };
+
+        // Assign the positional elements, then the named ones, and fill the
+        // remaining elements with `others`.
+        let mut elements = vec![None; len];
+        for (index, expr) in hir.positional.iter().enumerate() {
+            if index >= len {
+                self.emit(
+                    DiagBuilder2::error(format!("aggregate has more than {} elements", len))
+                        .span(expr.span),
+                );
+                return Err(());
+            }
+            elements[index] = Some(self.codegen_expr(expr.value, ctx)?);
+        }
+        if let hir::AggregateKind::Array(ref fields) = hir.named {
+            for field in fields {
+                let value = self.codegen_expr(field.value.1.value, ctx)?;
+                for choice in &field.value.0 {
+                    for index in self.const_choice_indices(index_ty, len, choice)? {
+                        elements[index] = Some(value);
+                    }
+                }
+            }
+        }
+        let others = match hir.others {
+            Some(expr) if elements.iter().any(Option::is_none) => {
+                Some(self.codegen_expr(expr.value, ctx)?)
+            }
+            _ => None,
+        };
+        match elements
+            .into_iter()
+            .map(|element| element.or(others))
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(elements) => Ok(ctx.builder.ins().array(elements)),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "aggregate `{}` does not cover all elements",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate the code that reads the current value of a signal.
+    pub fn codegen_signal_value(
+        &self,
+        sig: SignalRef,
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        if let Some(&value) = ctx.signal_values.get(&sig) {
+            return Ok(value);
+        }
+        match ctx.signals.get(&sig) {
+            Some(&signal) => Ok(ctx.builder.ins().prb(signal)),
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "signal `{}` not accessible during code generation",
+                        self.signal_name(sig)?
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Generate a call to a subprogram.
+    ///
+    /// The subprogram is referred to by an external unit declaration in the
+    /// unit being generated. Impure functions additionally receive the values
+    /// of the signals they read, see `implicit_signals`. Procedures yield a
+    /// void value. Methods of protected types expect a pointer to the state of
+    /// the object as their first argument, see `codegen_method_call`.
+    pub fn codegen_call(
+        &self,
+        id: SubprogDeclRef,
+        mut args: Vec<llhd::ir::Value>,
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let ty = match *self.lazy_typeval(id)? {
+            Ty::Subprog(ref ty) => ty,
+            _ => unreachable!(),
+        };
+        let mut sig = llhd::ir::Signature::new();
+        if let ScopeRef::Protected(decl) = self.hir(id)?.parent {
+            sig.add_input(llhd::pointer_ty(self.protected_state_type(decl)?));
+        }
+        for arg in &ty.args {
+            sig.add_input(self.map_type(&arg.ty)?);
+        }
+        for s in self.implicit_signals(id)? {
+            sig.add_input(self.map_type(self.ty(s)?)?);
+            args.push(self.codegen_signal_value(s, span, ctx)?);
+        }
+        sig.set_return_type(match ty.ret {
+            Some(ref ret) => self.map_type(ret)?,
+            None => llhd::void_ty(),
+        });
+        let name = self.subprog_unit_name(id)?;
+        let ext_unit = ctx.extern_unit(name, sig);
+        Ok(ctx.builder.ins().call(ext_unit, args))
+    }
+
+    /// Generate a call to a method of a protected type.
+    ///
+    /// The method receives a pointer to the state of the object, followed by
+    /// the arguments. See `resolve_method`.
+    fn codegen_method_call(
+        &self,
+        object: ExprRef,
+        method: SubprogDeclRef,
+        args: &[hir::AssocElement],
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let mut values = vec![self.codegen_target(object, ctx)?];
+        for arg in args {
+            match arg.actual.value {
+                hir::AssocActual::Expr(id) => values.push(self.codegen_expr(id, ctx)?),
+                _ => unreachable!(),
+            }
+        }
+        self.codegen_call(method, values, span, ctx)
+    }
+
+    /// Generate the code for a conversion or shift function of `numeric_std`.
+    ///
+    /// The conversions between integers and `unsigned`/`signed` vectors, as
+    /// well as the shift and rotate functions, are generated inline, since the
+    /// body of the package is usually not available. Returns `None` if the
+    /// subprogram is not such a function.
+    fn codegen_numeric_std_call(
+        &self,
+        id: SubprogDeclRef,
+        args: &[ExprRef],
+        span: Span,
+        ctx: &mut UnitContext,
+    ) -> Result<Option<llhd::ir::Value>> {
+        let hir = self.hir(id)?;
+        let in_numeric_std = match hir.parent {
+            ScopeRef::Pkg(pkg) => self
+                .hir(pkg)?
+                .name
+                .value
+                .as_str()
+                .eq_ignore_ascii_case("numeric_std"),
+            _ => false,
+        };
+        let name = match hir.spec.name.value {
+            ResolvableName::Ident(name) if in_numeric_std => name.as_str().to_ascii_lowercase(),
+            _ => return Ok(None),
+        };
+        let ty = match *self.lazy_typeval(id)? {
+            Ty::Subprog(ref ty) => ty,
+            _ => unreachable!(),
+        };
+        match (name.as_str(), args, &ty.ret) {
+            // Interpret the vector as a binary number, with the leftmost
+            // element being the most significant bit. Weak ones count as ones.
+            ("to_integer", &[arg], Some(ret)) => {
+                let decl = self.numeric_std_element(&ty.args[0].ty, span)?;
+                let width = enum_width(self.enum_len(decl)?);
+                let one = self.required_enum_char_index(decl, '1', span)?;
+                let weak_one = self.enum_char_index(decl, 'H')?;
+                let value = self.codegen_expr(arg, ctx)?;
+                let len = match *ctx.builder.value_type(value) {
+                    llhd::ArrayType(len, _) => len,
+                    _ => unreachable!(),
+                };
+                let one = ctx.builder.ins().const_int((width, BigInt::from(one)));
+                let weak_one =
+                    weak_one.map(|i| ctx.builder.ins().const_int((width, BigInt::from(i))));
+                let mut bits = ctx.builder.ins().const_int((len, BigInt::zero()));
+                for i in 0..len {
+                    let elem = ctx.builder.ins().ext_field(value, i);
+                    let mut bit = ctx.builder.ins().eq(elem, one);
+                    if let Some(weak_one) = weak_one {
+                        let weak_bit = ctx.builder.ins().eq(elem, weak_one);
+                        bit = ctx.builder.ins().or(bit, weak_bit);
+                    }
+                    bits = ctx.builder.ins().ins_slice(bits, bit, len - 1 - i, 1);
+                }
+                let ret_width = self.map_type(ret)?.unwrap_int();
+                let signed = self.is_signed_int(ret)?;
+                Ok(Some(
+                    self.codegen_int_resize(bits, len, ret_width, signed, ctx),
+                ))
+            }
+
+            // Resize the integer to the requested number of bits and map each
+            // bit to a `'0'` or `'1'` element.
+            ("to_unsigned", &[arg, size], &Some(ref ret))
+            | ("to_signed", &[arg, size], &Some(ref ret)) => {
+                let size_span = self.span(size).unwrap();
+                let size = match *self.const_value(size)? {
+                    Const::Int(ref k) => k.value.to_usize(),
+                    _ => None,
+                };
+                let size = match size {
+                    Some(size) => size,
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "size `{}` must be a constant natural number",
+                                size_span.extract()
+                            ))
+                            .span(size_span),
+                        );
+                        return Err(());
+                    }
+                };
+                let decl = self.numeric_std_element(ret, span)?;
+                let width = enum_width(self.enum_len(decl)?);
+                let zero = self.required_enum_char_index(decl, '0', span)?;
+                let one = self.required_enum_char_index(decl, '1', span)?;
+                let value = self.codegen_expr(arg, ctx)?;
+                let value_width = ctx.builder.value_type(value).unwrap_int();
+                let signed = self.is_signed_int(&ty.args[0].ty)?;
+                let value = self.codegen_int_resize(value, value_width, size, signed, ctx);
+                let zero = ctx.builder.ins().const_int((width, BigInt::from(zero)));
+                let one = ctx.builder.ins().const_int((width, BigInt::from(one)));
+                let choices = ctx.builder.ins().array(vec![zero, one]);
+                let elems = (0..size)
+                    .map(|i| {
+                        let bit = ctx.builder.ins().ext_slice(value, size - 1 - i, 1);
+                        ctx.builder.ins().mux(choices, bit)
+                    })
+                    .collect();
+                Ok(Some(ctx.builder.ins().array(elems)))
+            }
+
+            // The leftmost element of a vector is at index 0 in LLHD, such
+            // that shifting to the right moves elements to higher indices,
+            // which is what LLHD's `shl` does.
+            ("shift_left", &[arg, count], _)
+            | ("shift_right", &[arg, count], _)
+            | ("rotate_left", &[arg, count], _)
+            | ("rotate_right", &[arg, count], _) => {
+                let decl = self.numeric_std_element(&ty.args[0].ty, span)?;
+                let value = self.codegen_expr(arg, ctx)?;
+                let amount = self.codegen_expr(count, ctx)?;
+                let len = match *ctx.builder.value_type(value) {
+                    llhd::ArrayType(len, _) => len,
+                    _ => unreachable!(),
+                };
+                if len == 0 {
+                    return Ok(Some(value));
+                }
+                let left = name.ends_with("_left");
+                let amount_width = ctx.builder.value_type(amount).unwrap_int();
+
+                // Rotations shift in the elements shifted out.
+                if name.starts_with("rotate") {
+                    let limit = ctx
+                        .builder
+                        .ins()
+                        .const_int((amount_width, BigInt::from(len)));
+                    let amount = ctx.builder.ins().urem(amount, limit);
+                    return Ok(Some(if left {
+                        ctx.builder.ins().shr(value, value, amount)
+                    } else {
+                        ctx.builder.ins().shl(value, value, amount)
+                    }));
+                }
+
+                // Shifting a `signed` vector to the right replicates its sign,
+                // all other shifts fill in `'0'`.
+                let signed = match ty.args[0].ty {
+                    Ty::Named(_, TypeMarkRef::Type(decl)) => self
+                        .lazy_hir(decl)?
+                        .name
+                        .value
+                        .as_str()
+                        .eq_ignore_ascii_case("signed"),
+                    _ => false,
+                };
+                let fill = if signed && !left {
+                    ctx.builder.ins().ext_field(value, 0)
+                } else {
+                    let width = enum_width(self.enum_len(decl)?);
+                    let zero = self.required_enum_char_index(decl, '0', span)?;
+                    ctx.builder.ins().const_int((width, BigInt::from(zero)))
+                };
+                let fill = ctx.builder.ins().array_uniform(len, fill);
+                let shifted = if left {
+                    ctx.builder.ins().shr(value, fill, amount)
+                } else {
+                    ctx.builder.ins().shl(value, fill, amount)
+                };
+
+                // Shifting by the length of the vector or more leaves nothing
+                // but the fill.
+                let limit = ctx
+                    .builder
+                    .ins()
+                    .const_int((amount_width, BigInt::from(len)));
+                let in_range = ctx.builder.ins().ult(amount, limit);
+                let choices = ctx.builder.ins().array(vec![fill, shifted]);
+                Ok(Some(ctx.builder.ins().mux(choices, in_range)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Determine the enum type of the elements of a `numeric_std` vector.
+    fn numeric_std_element(&self, ty: &Ty, span: Span) -> Result<TypeDeclRef> {
+        if let Ty::Array(ref ty) = *self.deref_named_type(ty)? {
+            if let Ty::Enum(ref ty) = *self.deref_named_type(&ty.element)? {
+                return Ok(ty.decl);
+            }
+        }
+        self.emit(
+            DiagBuilder2::error(format!("type {} is not a vector of logic values", ty)).span(span),
+        );
+        Err(())
+    }
+
+    /// Determine the index of a character literal of an enum type.
+    fn enum_char_index(&self, decl: TypeDeclRef, chr: char) -> Result<Option<usize>> {
+        Ok(match self.lazy_hir(decl)?.data.as_ref().unwrap().value {
+            hir::TypeData::Enum(ref lits) => lits.iter().position(|lit| match *lit {
+                hir::EnumLit::Char(c) => c.value == chr,
+                _ => false,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Same as `enum_char_index`, but emits an error if there is no such
+    /// literal.
+    fn required_enum_char_index(&self, decl: TypeDeclRef, chr: char, span: Span) -> Result<usize> {
+        match self.enum_char_index(decl, chr)? {
+            Some(index) => Ok(index),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "enum type `{}` has no literal `'{}'`",
+                        self.lazy_hir(decl)?.name.value,
+                        chr
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Check whether an integer type admits negative values.
+    fn is_signed_int(&self, ty: &Ty) -> Result<bool> {
+        Ok(match *self.deref_named_type(ty)? {
+            Ty::Int(ref ty) => ty.left_bound.is_negative() || ty.right_bound.is_negative(),
+            _ => false,
+        })
+    }
+
+    /// Generate the code to resize an integer.
+    ///
+    /// The integer is truncated, or zero or sign extended.
+    fn codegen_int_resize(
+        &self,
+        value: llhd::ir::Value,
+        from: usize,
+        to: usize,
+        signed: bool,
+        ctx: &mut UnitContext,
+    ) -> llhd::ir::Value {
+        if to <= from {
+            return if to == from {
+                value
+            } else {
+                ctx.builder.ins().ext_slice(value, 0, to)
+            };
+        }
+        let zeros = ctx.builder.ins().const_int((to, BigInt::zero()));
+        let base = if signed && from > 0 {
+            let sign = ctx.builder.ins().ext_slice(value, from - 1, 1);
+            let ones = ctx.builder.ins().not(zeros);
+            let choices = ctx.builder.ins().array(vec![zeros, ones]);
+            ctx.builder.ins().mux(choices, sign)
+        } else {
+            zeros
+        };
+        ctx.builder.ins().ins_slice(base, value, 0, from)
+    }
+
+    /// Generate the code for the builtin concatenation operator.
+    ///
+    /// Each operand is either an array or a single element, depending on the
+    /// overload of the operator. The elements of both operands are collected
+    /// into a new array.
+    fn codegen_concat(
+        &self,
+        op: BuiltinOpRef,
+        lhs: llhd::ir::Value,
+        rhs: llhd::ir::Value,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let ty = match *self.lazy_typeval(op)? {
+            Ty::Subprog(ref ty) => ty,
+            _ => unreachable!(),
+        };
+        let mut elements = vec![];
+        for (arg, value) in ty.args.iter().zip(vec![lhs, rhs]) {
+            if Some(&arg.ty) != ty.ret.as_deref() {
+                elements.push(value);
+                continue;
+            }
+            let len = match *ctx.builder.value_type(value) {
+                llhd::ArrayType(len, _) => len,
+                _ => unreachable!(),
+            };
+            for i in 0..len {
+                elements.push(ctx.builder.ins().ext_field(value, i));
+            }
+        }
+
+        // Concatenating two null arrays yields a null array.
+        if elements.is_empty() {
+            return Ok(lhs);
+        }
+        Ok(ctx.builder.ins().array(elements))
+    }
+
+    /// Evaluate a constant time expression to a number of seconds.
+    ///
+    /// The value of a `TIME` constant is expressed in its primary unit, `fs`.
+    fn const_time(&self, id: ExprRef) -> Result<BigRational> {
+        match *self.const_value(id)? {
+            Const::Int(ref k) => Ok(BigRational::new(
+                k.value.clone(),
+                num::pow(BigInt::from(10), 15),
+            )),
+            _ => {
+                let hir = self.lazy_hir(id)?;
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a constant time", hir.span.extract()))
+                        .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Check that a delay mechanism can be encoded in LLHD.
+    ///
+    /// LLHD drives reject no pulses, which corresponds to transport delay.
+    /// Inertial delay with an explicit pulse rejection limit is only supported
+    /// if the limit is zero. Without a limit, the limit is the delay of the
+    /// first waveform element; such inertial delay is approximated as
+    /// transport delay. This approximation is silent if the mechanism is
+    /// implied, since plain `y <= x after 1 ns;` assignments are common, and
+    /// emits a warning if `inertial` is given explicitly.
+    fn check_delay_mechanism(
+        &self,
+        mech: &hir::DelayMechanism,
+        delay: &BigRational,
+        span: Span,
+    ) -> Result<()> {
+        let (limit, span) = match *mech {
+            hir::DelayMechanism::Transport | hir::DelayMechanism::ImplicitInertial => return Ok(()),
+            hir::DelayMechanism::Inertial => {
+                if !delay.is_zero() {
+                    self.emit(
+                        DiagBuilder2::warning("inertial delay approximated as transport delay")
+                            .span(span)
+                            .add_note("LLHD drives do not reject pulses")
+                            .add_note("use `transport` to silence this warning"),
+                    );
+                }
+                return Ok(());
+            }
+            hir::DelayMechanism::RejectInertial(limit) => {
+                (self.const_time(limit)?, self.lazy_hir(limit)?.span)
+            }
+        };
+        if &limit > delay {
+            self.emit(
+                DiagBuilder2::error("pulse rejection limit exceeds the delay of the waveform")
+                    .span(span),
+            );
+            return Err(());
+        }
+        if !limit.is_zero() {
+            self.emit(
+                DiagBuilder2::error("inertial delay cannot be encoded in LLHD")
+                    .span(span)
+                    .add_note("LLHD drives do not reject pulses")
+                    .add_note("use `transport` or `reject 0 ns inertial` instead"),
+            );
+            return Err(());
+        }
+        Ok(())
+    }
+
+    /// Generate the instructions for a builtin binary operator.
+    fn codegen_builtin_unary_op(
+        &self,
+        op: Spanned<UnaryOp>,
+        arg: llhd::ir::Value,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let logical = match op.value {
+            UnaryOp::Pos => return Ok(arg),
+            UnaryOp::Neg => return Ok(ctx.builder.ins().neg(arg)),
+            UnaryOp::Not => return Ok(ctx.builder.ins().not(arg)),
+            UnaryOp::Logical(op) => op,
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for builtin operator `{}` not implemented",
+                        op.value
+                    ))
+                    .span(op.span),
+                );
+                return Err(());
+            }
+        };
+
+        // Reduce the elements of the array with the operator. A null array
+        // reduces to the identity of the operator.
+        let (len, width) = match *ctx.builder.value_type(arg) {
+            llhd::ArrayType(len, ref elem) => (len, elem.unwrap_int()),
+            _ => unreachable!(),
+        };
+        let mut value = match len {
+            0 => {
+                let identity = match logical {
+                    LogicalOp::And | LogicalOp::Nand => BigInt::one(),
+                    _ => BigInt::zero(),
+                };
+                ctx.builder.ins().const_int((width, identity))
+            }
+            _ => ctx.builder.ins().ext_field(arg, 0),
+        };
+        for i in 1..len {
+            let elem = ctx.builder.ins().ext_field(arg, i);
+            value = match logical {
+                LogicalOp::And | LogicalOp::Nand => ctx.builder.ins().and(value, elem),
+                LogicalOp::Or | LogicalOp::Nor => ctx.builder.ins().or(value, elem),
+                LogicalOp::Xor | LogicalOp::Xnor => ctx.builder.ins().xor(value, elem),
+            };
+        }
+        Ok(match logical {
+            LogicalOp::Nand | LogicalOp::Nor | LogicalOp::Xnor => ctx.builder.ins().not(value),
+            _ => value,
+        })
+    }
+
+    fn codegen_builtin_binary_op(
+        &self,
+        op: Spanned<BinaryOp>,
+        signed: bool,
+        lhs: llhd::ir::Value,
+        rhs: llhd::ir::Value,
+        ctx: &mut UnitContext,
+    ) -> Result<llhd::ir::Value> {
+        let mut ins = ctx.builder.ins();
+        Ok(match op.value {
+            BinaryOp::Logical(LogicalOp::And) => ins.and(lhs, rhs),
+            BinaryOp::Logical(LogicalOp::Or) => ins.or(lhs, rhs),
+            BinaryOp::Logical(LogicalOp::Xor) => ins.xor(lhs, rhs),
+            BinaryOp::Logical(LogicalOp::Nand) => {
+                let v = ins.and(lhs, rhs);
+                ins.not(v)
+            }
+            BinaryOp::Logical(LogicalOp::Nor) => {
+                let v = ins.or(lhs, rhs);
+                ins.not(v)
+            }
+            BinaryOp::Logical(LogicalOp::Xnor) => {
+                let v = ins.xor(lhs, rhs);
+                ins.not(v)
+            }
+            BinaryOp::Rel(RelationalOp::Eq) => ins.eq(lhs, rhs),
+            BinaryOp::Rel(RelationalOp::Neq) => ins.neq(lhs, rhs),
+            BinaryOp::Add => ins.add(lhs, rhs),
+            BinaryOp::Sub => ins.sub(lhs, rhs),
+            // LLHD's `mod` takes the sign of the divisor and `rem` the sign
+            // of the dividend, as in VHDL.
+            BinaryOp::Div if signed => ins.sdiv(lhs, rhs),
+            BinaryOp::Div => ins.udiv(lhs, rhs),
+            BinaryOp::Mod if signed => ins.smod(lhs, rhs),
+            BinaryOp::Mod => ins.umod(lhs, rhs),
+            BinaryOp::Rem if signed => ins.srem(lhs, rhs),
+            BinaryOp::Rem => ins.urem(lhs, rhs),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for builtin operator `{}` not implemented",
+                        op.value
+                    ))
+                    .span(op.span),
+                );
+                return Err(());
+            }
+        })
+    }
+
+    /// Determine the name of the unit that implements a subprogram.
+    pub fn subprog_unit_name(&self, id: SubprogDeclRef) -> Result<llhd::ir::UnitName> {
+        Ok(self.mangle_unit_name(&self.subprog_path(id)?))
+    }
+
+    /// Find the body of a subprogram.
+    ///
+    /// The body is looked for in the declarative region of the declaration,
+    /// in the package body if the subprogram is declared in a package, or in
+    /// the protected type body if it is a method. Returns `None` if there is
+    /// no body.
+    pub fn subprog_body(&self, id: SubprogDeclRef) -> Result<Option<SubprogBodyRef>> {
+        let hir = self.hir(id)?;
+        let bodies: Vec<SubprogBodyRef> = match hir.parent {
+            ScopeRef::Arch(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInBlockRef::SubprogBody(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::Block(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInBlockRef::SubprogBody(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::ForGen(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInBlockRef::SubprogBody(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::Process(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInProcRef::SubprogBody(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::SubprogBody(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInSubprogRef::SubprogBody(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::Pkg(pkg) => {
+                let pkg = self.hir(pkg)?;
+                let lib = match pkg.parent {
+                    ScopeRef::CtxItems(id) => match self.ast(id).0 {
+                        ScopeRef::Lib(lib) => self.hir(lib)?,
+                        _ => return Ok(None),
+                    },
+                    _ => return Ok(None),
+                };
+                let mut bodies = vec![];
+                for &body in &lib.pkg_bodies {
+                    let body = self.hir(body)?;
+                    if body.name.value != pkg.name.value {
+                        continue;
+                    }
+                    bodies.extend(body.decls.iter().filter_map(|&d| match d {
+                        DeclInPkgBodyRef::SubprogBody(id) => Some(id),
+                        _ => None,
+                    }));
+                }
+                bodies
+            }
+            ScopeRef::Protected(id) => {
+                let body = match self.protected_body(id)? {
+                    Some(body) => body,
+                    None => return Ok(None),
+                };
+                match self.lazy_hir(body)?.data {
+                    Some(Spanned {
+                        value: hir::TypeData::ProtectedBody(ref decls),
+                        ..
+                    }) => decls
+                        .iter()
+                        .filter_map(|&d| match d {
+                            DeclInSubprogRef::SubprogBody(id) => Some(id),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => unreachable!(),
+                }
+            }
+            _ => return Ok(None),
+        };
+        for body in bodies {
+            if self.hir(body)?.spec.name.value == hir.spec.name.value {
+                return Ok(Some(body));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Determine the signals an impure function reads.
+    ///
+    /// LLHD functions cannot access signals, so impure functions receive the
+    /// values of these signals as implicit arguments following their
+    /// parameters. This includes the signals read by the impure functions
+    /// they call. Returns an empty list for pure functions and procedures.
+    pub fn impure_signals(&self, id: SubprogBodyRef) -> Result<Vec<SignalRef>> {
+        let hir = self.hir(id)?;
+        if hir.spec.kind != hir::SubprogKind::ImpureFunc {
+            return Ok(vec![]);
+        }
+        // A recursive call contributes no signals beyond those of the
+        // function itself.
+        if let Some(signals) = self.sb.impure_signal_table.borrow().get(&id) {
+            return Ok(signals.clone().unwrap_or_default());
+        }
+        self.sb.impure_signal_table.borrow_mut().insert(id, None);
+        let accesses = self.signal_accesses(&hir.stmts)?;
+        let signals: Vec<SignalRef> = accesses.read.into_iter().collect();
+        self.sb
+            .impure_signal_table
+            .borrow_mut()
+            .insert(id, Some(signals.clone()));
+        Ok(signals)
+    }
+
+    /// Determine the signals passed implicitly to a subprogram.
+    ///
+    /// See `impure_signals`.
+    pub fn implicit_signals(&self, id: SubprogDeclRef) -> Result<Vec<SignalRef>> {
+        match self.subprog_body(id)? {
+            Some(body) => self.impure_signals(body),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Place a shared variable in a process.
+    ///
+    /// LLHD has no storage that processes can share, so a shared variable is
+    /// placed in the process that accesses it. Only one process may do so.
+    fn claim_shared_var(&self, var: VarDeclRef, process: ProcessStmtRef) -> Result<()> {
+        let owner = *self
+            .sb
+            .shared_var_owners
+            .borrow_mut()
+            .entry(var)
+            .or_insert(process);
+        if owner != process {
+            let name = self.lazy_hir(var)?.name;
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "shared variable `{}` is accessed by more than one process",
+                    name.value
+                ))
+                .span(name.span)
+                .add_note("Shared variables are only supported in a single process."),
+            );
+            return Err(());
+        }
+        Ok(())
+    }
+
+    /// Find the body of a protected type.
+    ///
+    /// The body is looked for in the declarative region of the type
+    /// declaration, or in the package body if the type is declared in a
+    /// package. Returns `None` if there is no body.
+    pub fn protected_body(&self, id: TypeDeclRef) -> Result<Option<TypeDeclRef>> {
+        let hir = self.lazy_hir(id)?;
+        let types: Vec<TypeDeclRef> = match hir.parent {
+            ScopeRef::Arch(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInBlockRef::Type(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::Block(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInBlockRef::Type(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::ForGen(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInBlockRef::Type(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::Process(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInProcRef::Type(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::SubprogBody(id) => self
+                .hir(id)?
+                .decls
+                .iter()
+                .filter_map(|&d| match d {
+                    DeclInSubprogRef::Type(id) => Some(id),
+                    _ => None,
+                })
+                .collect(),
+            ScopeRef::Pkg(pkg) => {
+                let pkg = self.hir(pkg)?;
+                let lib = match pkg.parent {
+                    ScopeRef::CtxItems(id) => match self.ast(id).0 {
+                        ScopeRef::Lib(lib) => self.hir(lib)?,
+                        _ => return Ok(None),
+                    },
+                    _ => return Ok(None),
+                };
+                let mut types = vec![];
+                for &body in &lib.pkg_bodies {
+                    let body = self.hir(body)?;
+                    if body.name.value != pkg.name.value {
+                        continue;
+                    }
+                    types.extend(body.decls.iter().filter_map(|&d| match d {
+                        DeclInPkgBodyRef::Type(id) => Some(id),
+                        _ => None,
+                    }));
+                }
+                types
+            }
+            _ => return Ok(None),
+        };
+        for ty in types {
+            let ty_hir = self.lazy_hir(ty)?;
+            if let Some(Spanned {
+                value: hir::TypeData::ProtectedBody(..),
+                ..
+            }) = ty_hir.data
+            {
+                if ty_hir.name.value == hir.name.value {
+                    return Ok(Some(ty));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Determine the variables that make up the state of a protected type.
+    ///
+    /// `id` refers to either the declaration or the body of the type. The
+    /// state of an object of the type is a struct with one field for each of
+    /// the variables declared in the body, in order.
+    pub fn protected_state(&self, id: TypeDeclRef) -> Result<Vec<VarDeclRef>> {
+        let hir = self.lazy_hir(id)?;
+        let decls = match hir.data {
+            Some(Spanned {
+                value: hir::TypeData::ProtectedBody(ref decls),
+                ..
+            }) => decls,
+            _ => match self.protected_body(id)? {
+                Some(body) => return self.protected_state(body),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "protected type `{}` has no body",
+                            hir.name.value
+                        ))
+                        .span(hir.name.span),
+                    );
+                    return Err(());
+                }
+            },
+        };
+        Ok(decls
+            .iter()
+            .filter_map(|&d| match d {
+                DeclInSubprogRef::Var(id) => Some(id),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Map the state of a protected type to an LLHD struct type.
+    ///
+    /// See `protected_state`.
+    pub fn protected_state_type(&self, id: TypeDeclRef) -> Result<llhd::Type> {
+        let fields = self
+            .protected_state(id)?
+            .into_iter()
+            .map(|var| self.map_type(self.lazy_typeval(var)?))
+            .collect::<Result<_>>()?;
+        Ok(llhd::struct_ty(fields))
+    }
+}
+
+/// Determine the number of bits needed to represent an enum with `len`
+/// literals.
+fn enum_width(len: usize) -> usize {
+    let bits = (usize::BITS - len.saturating_sub(1).leading_zeros()) as usize;
+    std::cmp::max(bits, 1)
+}
+
+/// Determine the blocks that can be reached from a block.
+pub fn reachable_blocks(
+    builder: &llhd::ir::UnitBuilder,
+    from: llhd::ir::Block,
+) -> HashSet<llhd::ir::Block> {
+    let mut reachable = HashSet::new();
+    let mut todo = vec![from];
+    while let Some(bb) = todo.pop() {
+        if !reachable.insert(bb) {
+            continue;
+        }
+        if let Some(term) = builder.last_inst(bb) {
+            todo.extend(builder[term].blocks().iter().cloned());
+        }
+    }
+    reachable
+}
+
+/// Check a generated unit for consistency.
+///
+/// This catches lowerings that produce invalid LLHD, such as mismatching
+/// operand types or blocks without a terminator.
+pub fn verify_unit(
+    unit: &llhd::ir::UnitData,
+) -> std::result::Result<(), llhd::verifier::VerifierErrors> {
+    let mut verifier = llhd::verifier::Verifier::new();
+    verifier.verify_unit(llhd::ir::Unit::new_anonymous(unit));
+    verifier.finish()
+}
+
+/// Remove unreachable blocks from a unit and merge trivially connected ones.
+///
+/// Lowering sequential statements introduces a lot of blocks, some of which
+/// end up unreachable (e.g. the `else` branch of an `if` whose condition is
+/// statically true) or merely continue the block before them. This deletes
+/// all blocks that cannot be reached from the entry block, and merges a block
+/// into its predecessor if that is its only predecessor and ends in an
+/// unconditional branch to it.
+pub fn prune_blocks(builder: &mut llhd::ir::UnitBuilder) {
+    let entry = match builder.first_block() {
+        Some(bb) => bb,
+        None => return,
+    };
+
+    // Delete the blocks that cannot be reached from the entry block.
+    let reachable = reachable_blocks(builder, entry);
+    let unreachable: Vec<_> = builder
+        .blocks()
+        .filter(|bb| !reachable.contains(bb))
+        .collect();
+    for bb in unreachable {
+        builder.delete_block(bb);
+    }
+
+    // Merge blocks into their only predecessor, one at a time.
+    loop {
+        let mut preds = HashMap::new();
+        for bb in builder.blocks() {
+            if let Some(term) = builder.last_inst(bb) {
+                for &succ in builder[term].blocks() {
+                    *preds.entry(succ).or_insert(0) += 1;
+                }
+            }
+        }
+        let merge = builder.blocks().find_map(|bb| {
+            let term = builder.last_inst(bb)?;
+            match builder[term].opcode() {
+                llhd::ir::Opcode::Br => (),
+                _ => return None,
+            }
+            let succ = builder[term].blocks()[0];
+            if succ != entry && succ != bb && preds[&succ] == 1 {
+                Some((bb, term, succ))
+            } else {
+                None
+            }
+        });
+        let (bb, term, succ) = match merge {
+            Some(m) => m,
+            None => break,
+        };
+        builder.delete_inst(term);
+        let insts: Vec<_> = builder.insts(succ).collect();
+        for inst in insts {
+            builder.remove_inst(inst);
+            builder.append_inst(inst, bb);
+        }
+        builder.delete_block(succ);
+    }
+}
+
+impl_codegen!(self, id: DeclInBlockRef, ctx: &mut UnitContext<'_> => {
+    match id {
+        DeclInBlockRef::Subprog(id)     => self.codegen(id, &mut ()),
+        DeclInBlockRef::SubprogBody(id) => self.codegen(id, &mut ()),
+        DeclInBlockRef::SubprogInst(id) => self.codegen(id, &mut ()),
+        DeclInBlockRef::Pkg(id)         => self.codegen(id, &mut ()),
+        DeclInBlockRef::PkgBody(id)     => self.codegen(id, &mut ()),
+        DeclInBlockRef::PkgInst(id)     => self.codegen(id, &mut ()),
+        DeclInBlockRef::Type(id)        => self.codegen(id, &mut ()),
+        DeclInBlockRef::Subtype(_id)    => Ok(()),
+        DeclInBlockRef::Const(id)       => self.codegen(id, ctx),
+        DeclInBlockRef::Signal(id)      => self.codegen(id, ctx),
+        // Shared variables are placed in the process that accesses them.
+        DeclInBlockRef::Var(_id)        => Ok(()),
+        DeclInBlockRef::File(id)        => self.codegen(id, ctx),
+        DeclInBlockRef::Alias(_id)      => Ok(()),
+        DeclInBlockRef::Comp(id)        => self.codegen(id, &mut ()),
+        DeclInBlockRef::Attr(_id)       => Ok(()),
+        DeclInBlockRef::AttrSpec(_id)   => Ok(()),
+        DeclInBlockRef::CfgSpec(_id)    => Ok(()),
+        DeclInBlockRef::Discon(id)      => self.codegen(id, ctx),
+        DeclInBlockRef::GroupTemp(_id)  => Ok(()),
+        DeclInBlockRef::Group(_id)      => Ok(()),
+    }
+});
+
+impl_codegen!(self, id: DeclInProcRef, ctx: &mut UnitContext<'_> => {
+    match id {
+        DeclInProcRef::Subprog(id)     => self.codegen(id, &mut ()),
+        DeclInProcRef::SubprogBody(id) => self.codegen(id, &mut ()),
+        DeclInProcRef::SubprogInst(id) => self.codegen(id, &mut ()),
+        DeclInProcRef::Pkg(id)         => self.codegen(id, &mut ()),
+        DeclInProcRef::PkgBody(id)     => self.codegen(id, &mut ()),
+        DeclInProcRef::PkgInst(id)     => self.codegen(id, &mut ()),
+        DeclInProcRef::Type(id)        => self.codegen(id, &mut ()),
+        DeclInProcRef::Subtype(_id)    => Ok(()),
+        DeclInProcRef::Const(id)       => self.codegen(id, ctx),
+        DeclInProcRef::Var(id)         => self.codegen(id, ctx),
+        DeclInProcRef::File(id)        => self.codegen(id, ctx),
+        DeclInProcRef::Alias(_id)      => Ok(()),
+        DeclInProcRef::Attr(_id)       => Ok(()),
+        DeclInProcRef::AttrSpec(_id)   => Ok(()),
+        DeclInProcRef::GroupTemp(_id)  => Ok(()),
+        DeclInProcRef::Group(_id)      => Ok(()),
+    }
+});
+
+impl_codegen!(self, id: DeclInSubprogRef, ctx: &mut UnitContext<'_> => {
+    match id {
+        DeclInSubprogRef::Subprog(id)     => self.codegen(id, &mut ()),
+        DeclInSubprogRef::SubprogBody(id) => self.codegen(id, &mut ()),
+        DeclInSubprogRef::SubprogInst(id) => self.codegen(id, &mut ()),
+        DeclInSubprogRef::Pkg(id)         => self.codegen(id, &mut ()),
+        DeclInSubprogRef::PkgBody(id)     => self.codegen(id, &mut ()),
+        DeclInSubprogRef::PkgInst(id)     => self.codegen(id, &mut ()),
+        DeclInSubprogRef::Type(id)        => self.codegen(id, &mut ()),
+        DeclInSubprogRef::Subtype(_id)    => Ok(()),
+        DeclInSubprogRef::Const(id)       => self.codegen(id, ctx),
+        DeclInSubprogRef::Var(id)         => self.codegen(id, ctx),
+        DeclInSubprogRef::File(id)        => self.codegen(id, ctx),
+        DeclInSubprogRef::Alias(_id)      => Ok(()),
+        DeclInSubprogRef::Attr(_id)       => Ok(()),
+        DeclInSubprogRef::AttrSpec(_id)   => Ok(()),
+        DeclInSubprogRef::GroupTemp(_id)  => Ok(()),
+        DeclInSubprogRef::Group(_id)      => Ok(()),
+    }
+});
+
+impl_codegen!(self, id: ConstDeclRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: VarDeclRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let ty = self.lazy_typeval(id)?;
+    let k = if let Some(init_id) = hir.decl.init {
+        // Allocators create a new object every time the declaration is
+        // elaborated and have no constant value.
+        if let hir::ExprData::Allocator(..) = self.lazy_hir(init_id)?.data {
+            self.codegen_expr(init_id, ctx)?
+        } else {
+            let init = self.const_value(init_id)?;
+            let init = self.adapt_init(init, ty, self.lazy_hir(init_id)?.span)?;
+            self.map_const(&mut ctx.builder, init)?
+        }
+    } else {
+        self.codegen_default_value(&mut ctx.builder, ty)?
+    };
+    let slot = ctx.builder.ins().var(k);
+    ctx.builder.set_name(slot, hir.name.value.as_str().to_string());
+    ctx.variables.insert(id, slot);
+    Ok(())
+});
+
+impl_codegen!(self, id: SignalDeclRef, ctx: &mut UnitContext<'_> => {
+    // Determine the type of the signal.
+    let hir = self.lazy_hir(id)?;
+    let ty = self.lazy_typeval(id)?;
+
+    // Calculate the initial value for the signal, either from an override,
+    // the provided expression, or implicitly.
+    let mut path = self.scope_path(hir.parent)?;
+    path.push(hir.name.value.as_str().to_string());
+    let init = if let Some(init) = self.signal_init_override(&path.join(".")) {
+        Some(self.adapt_init(init, ty, hir.name.span)?)
+    } else if let Some(init_id) = hir.decl.init {
+        let init = self.const_value(init_id)?;
+        Some(self.adapt_init(init, ty, self.lazy_hir(init_id)?.span)?)
+    } else {
+        None
+    };
+
+    debugln!("signal {:?}, type {:?}, init {:?}", id, ty, init);
+    // Create the signal instance.
+    let k = match init {
+        Some(init) => self.map_const(&mut ctx.builder, init)?,
+        None => self.codegen_default_value(&mut ctx.builder, ty)?,
+    };
+    let sig = ctx.builder.ins().sig(k);
+    ctx.builder.set_name(sig, hir.name.value.as_str().to_string());
+    ctx.signals.insert(id.into(), sig);
+
+    // An explicit initial value is also established as a transaction at time
+    // zero, such that simulation starts out with the signal driven to it. This
+    // is only done for signals without any other driver, since the drive would
+    // otherwise conflict with the drivers of the statements.
+    if init.is_some() && ctx.undriven.contains(&id) {
+        let zero = llhd::value::TimeValue::new(num::zero(), 0, 0);
+        let zero = ctx.builder.ins().const_time(zero);
+        ctx.builder.ins().drv(sig, k, zero);
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: FileDeclRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: ConcStmtRef, ctx: &mut UnitContext<'_> => {
+    // Processes open a span of their own.
+    let _span = match id {
+        ConcStmtRef::Process(_) => None,
+        _ => Some(LowerSpan::enter(log::Level::Trace, "statement", id)),
+    };
+    match id {
+        ConcStmtRef::Block(id)         => self.codegen(id, ctx),
+        ConcStmtRef::Process(id)       => self.codegen(id, ctx),
+        ConcStmtRef::ConcProcCall(id)  => self.codegen(id, ctx),
+        ConcStmtRef::ConcAssert(id)    => self.codegen(id, ctx),
+        ConcStmtRef::ConcSigAssign(id) => self.codegen(id, ctx),
+        ConcStmtRef::CompInst(id)      => self.codegen(id, ctx),
+        ConcStmtRef::ForGen(id)        => self.codegen(id, ctx),
+        ConcStmtRef::IfGen(id)         => self.codegen(id, ctx),
+        ConcStmtRef::CaseGen(id)       => self.codegen(id, ctx),
+    }
+});
+
+impl_codegen!(self, id: BlockStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.hir(id)?;
+
+    // The ports of the block refer to the signals connected to them, and the
+    // generics have the values they are mapped to.
+    for &port in &hir.ports {
+        let actual = match hir.port_map.iter().find(|&&(formal, _)| formal == port) {
+            Some(&(_, actual)) => self.codegen_target(actual, ctx)?,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "port `{}` of block `{}` is not connected",
+                        self.hir(port)?.name.value,
+                        hir.label.value
+                    ))
+                    .span(hir.label.span),
+                );
+                return Err(());
+            }
+        };
+        ctx.signals.insert(port.into(), actual);
+    }
+    for &(generic, actual) in &hir.generic_map {
+        let value = self.const_value(actual)?;
+        let value = self.map_const(&mut ctx.builder, value)?;
+        ctx.constants.insert(generic.into(), value);
+    }
+
+    // A guard expression implicitly declares the signal `GUARD`, which a
+    // separate process keeps up to date with the expression.
+    if let Some(guard) = hir.guard {
+        let init = ctx.builder.ins().const_int((1, BigInt::zero()));
+        let sig = ctx.builder.ins().sig(init);
+        ctx.builder.set_name(sig, String::from("GUARD"));
+        ctx.signals.insert(SignalRef::Guard(id), sig);
+
+        let mut path = self.scope_path(ScopeRef::Block(id))?;
+        path.push(String::from("guard"));
+        let name = self.mangle_unit_name(&path);
+        let mut accesses = SignalAccesses::default();
+        self.collect_expr_accesses(guard, &mut accesses)?;
+        accesses.written.insert(SignalRef::Guard(id));
+        let expr: &hir::Expr = self.lazy_hir(guard)?;
+        self.codegen_equivalent_process(name, &accesses, expr.span, ctx, &|ctx| {
+            let value = self.codegen_expr(guard, ctx)?;
+            let delay = llhd::value::TimeValue::new(num::zero(), 1, 0);
+            let delay = ctx.builder.ins().const_time(delay);
+            ctx.builder.ins().drv(ctx.signals[&SignalRef::Guard(id)], value, delay);
+            Ok(())
+        })?;
+    }
+
+    // The declarations and statements of the block become part of the
+    // enclosing unit.
+    for &decl in &hir.decls {
+        self.codegen(decl, ctx)?;
+    }
+    for &stmt in &hir.stmts {
+        self.codegen(stmt, ctx)?;
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: ProcessStmtRef, ctx: &mut UnitContext<'_> => {
+    let _span = LowerSpan::enter(log::Level::Debug, "process", id);
+    let hir = self.hir(id)?;
+    let name = self.mangle_unit_name(&self.process_path(id)?);
+    debugln!("generating process `{}`", name);
+
+    // Determine which signals the process reads and drives. Signals that are
+    // only read become inputs, all driven signals become outputs. Signals in
+    // the sensitivity list count as read.
+    let mut accesses = self.signal_accesses(&hir.stmts)?;
+    if let hir::ProcessSensitivity::List(ref list) = hir.sensitivity {
+        accesses.read.extend(list.iter().map(|s| s.value));
+    }
+    let (inputs, outputs, sig) = self.process_signature(&accesses)?;
+
+    let mut prok = llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name.clone(), sig.clone());
+    {
+        let mut prok_ctx = self.unit_context(&mut prok);
+        self.bind_process_args(&mut prok_ctx, &inputs, &outputs)?;
+
+        // Declarations are only elaborated once, so the statements loop back
+        // to a separate block if there are any.
+        let entry_bb = prok_ctx.builder.named_block("entry");
+        prok_ctx.builder.append_to(entry_bb);
+        for &decl in &hir.decls {
+            self.codegen(decl, &mut prok_ctx)?;
+        }
+        for &var in &accesses.shared {
+            self.claim_shared_var(var, id)?;
+            self.codegen(var, &mut prok_ctx)?;
+        }
+        self.sample_signals(&accesses, &mut prok_ctx);
+        let body_bb = if hir.decls.is_empty()
+            && accesses.shared.is_empty()
+            && accesses.sampled.is_empty()
+        {
+            entry_bb
+        } else {
+            let bb = prok_ctx.builder.named_block("body");
+            prok_ctx.builder.ins().br(bb);
+            prok_ctx.builder.append_to(bb);
+            bb
+        };
+        for &stmt in &hir.stmts {
+            self.codegen(stmt, &mut prok_ctx)?;
+        }
+        // A sensitivity list implies a wait statement at the end of the
+        // process. For `all`, the process is sensitive to every signal it
+        // reads.
+        let sensitive: Vec<SignalRef> = match hir.sensitivity {
+            hir::ProcessSensitivity::None => vec![],
+            hir::ProcessSensitivity::All => accesses.read.iter().cloned().collect(),
+            hir::ProcessSensitivity::List(ref list) => list.iter().map(|s| s.value).collect(),
+        };
+        match hir.sensitivity {
+            hir::ProcessSensitivity::None => (),
+            _ => self.record_sensitivity(&name, &sensitive)?,
+        }
+        self.update_samples(&accesses, &mut prok_ctx);
+        if sensitive.is_empty() {
+            prok_ctx.builder.ins().br(body_bb);
+        } else {
+            let signals = sensitive.iter().map(|s| prok_ctx.signals[s]).collect();
+            prok_ctx.builder.ins().wait(body_bb, signals);
+        }
+        prune_blocks(&mut prok_ctx.builder);
+        self.finish_unit_context(&name, prok_ctx);
+    }
+
+    self.inst_process(name.clone(), sig, &inputs, &outputs, ctx)?;
+    self.add_unit_deferred(name.to_string(), prok, self.ast(id).1.span)?;
+    Ok(())
+});
+
+impl_codegen!(self, id: ConcCallStmtRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: ConcAssertStmtRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: ConcSigAssignStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.hir(id)?;
+    let name = self.mangle_unit_name(&self.conc_sig_assign_path(id)?);
+
+    // The equivalent process drives the target whenever a signal read by the
+    // assignment changes. A guarded assignment only drives the target while
+    // `GUARD` is true.
+    let mut accesses = self.signal_accesses(&[SeqStmtRef::SigAssign(hir.assign)])?;
+    accesses.read.extend(hir.guard);
+    self.codegen_equivalent_process(name, &accesses, hir.span, ctx, &|ctx| {
+        if let Some(guard) = hir.guard {
+            let assign_bb = ctx.builder.named_block("assign");
+            let exit_bb = ctx.builder.named_block("exit");
+            let cond = ctx.builder.ins().prb(ctx.signals[&guard]);
+            ctx.builder.ins().br_cond(cond, exit_bb, assign_bb);
+            ctx.builder.append_to(assign_bb);
+            self.codegen(hir.assign, ctx)?;
+            ctx.builder.ins().br(exit_bb);
+            ctx.builder.append_to(exit_bb);
+        } else {
+            self.codegen(hir.assign, ctx)?;
+        }
+        Ok(())
+    })
+});
+
+impl_codegen!(self, id: CompInstStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.hir(id)?;
+    let (name, sig) = self.codegen_inst_arch(id)?;
+
+    // Connect the ports in the order of the entity's signature. The ports of
+    // the specialization are copies of the instantiated entity's ports.
+    let (in_ports, out_ports) = self.entity_unit_ports(self.hir(hir.arch.value)?.entity)?;
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    for (ports, values) in [(in_ports, &mut inputs), (out_ports, &mut outputs)] {
+        for port in ports {
+            let actual = match hir.port_map.iter().find(|&&(formal, _)| formal == port) {
+                Some(&(_, actual)) => self.codegen_target(actual, ctx)?,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "port `{}` of `{}` is not connected",
+                            self.hir(port)?.name.value,
+                            hir.label.value
+                        ))
+                        .span(hir.label.span),
+                    );
+                    return Err(());
+                }
+            };
+            values.push(actual);
+        }
+    }
+    let ext_unit = ctx.extern_unit(name, sig);
+    ctx.builder.ins().inst(ext_unit, inputs, outputs);
+    Ok(())
+});
+
+impl_codegen!(self, id: ForGenStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.hir(id)?;
+
+    // The declarations and statements are generated once for every value of
+    // the generate parameter, which the generated code looks up in
+    // `gen_params`.
+    for value in self.gen_param_values(id)? {
+        self.sb.gen_params.borrow_mut().insert(id, value);
+        let result = self.codegen_gen_iteration(hir, ctx);
+        self.sb.gen_params.borrow_mut().remove(&id);
+        result?;
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: IfGenStmtRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: CaseGenStmtRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: SeqStmtRef, ctx: &mut UnitContext<'_> => {
+    let _span = LowerSpan::enter(log::Level::Trace, "statement", id);
+    let after = ctx.spans.as_ref().map(|_| ctx.last_inst());
+    match id {
+        SeqStmtRef::Wait(id)      => self.codegen(id, ctx),
+        SeqStmtRef::Assert(id)    => self.codegen(id, ctx),
+        SeqStmtRef::Report(id)    => self.codegen(id, ctx),
+        SeqStmtRef::SigAssign(id) => self.codegen(id, ctx),
+        SeqStmtRef::VarAssign(id) => self.codegen(id, ctx),
+        SeqStmtRef::ProcCall(id)  => self.codegen(id, ctx),
+        SeqStmtRef::If(id)        => self.codegen(id, ctx),
+        SeqStmtRef::Case(id)      => self.codegen(id, ctx),
+        SeqStmtRef::Loop(id)      => self.codegen(id, ctx),
+        SeqStmtRef::Nexit(id)     => self.codegen(id, ctx),
+        SeqStmtRef::Return(id)    => self.codegen(id, ctx),
+        SeqStmtRef::Null(id)      => self.codegen(id, ctx),
+    }?;
+
+    // Record the instructions generated for the statement, which are
+    // attributed to its span unless a nested statement claims them.
+    if let (Some(after), Some(span)) = (after, self.span(id)) {
+        let last = ctx.last_inst();
+        if let Some(ref mut spans) = ctx.spans {
+            spans.push(StmtInsts { after, last, span });
+        }
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: WaitStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    if let (Some(_), Some(timeout)) = (hir.stmt.cond, hir.stmt.timeout) {
+        self.emit(
+            DiagBuilder2::bug("code generation for wait with condition and timeout not implemented")
+            .span(self.lazy_hir(timeout)?.span)
+        );
+        return Err(());
+    }
+
+    // Without a sensitivity clause, the statement is sensitive to the signals
+    // read by the condition.
+    let sensitive: Vec<SignalRef> = match (&hir.stmt.sens, hir.stmt.cond) {
+        (Some(sens), _) => sens.value.iter().map(|s| s.value).collect(),
+        (None, Some(cond)) => {
+            let mut accesses = SignalAccesses::default();
+            self.collect_expr_accesses(cond, &mut accesses)?;
+            accesses.read.union(&accesses.sampled).cloned().collect()
+        }
+        (None, None) => vec![],
+    };
+    let mut signals = Vec::new();
+    for s in &sensitive {
+        match ctx.signals.get(s) {
+            Some(&v) => signals.push(v),
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "signal `{}` not accessible during code generation",
+                        self.signal_name(*s)?
+                    ))
+                    .span(hir.span)
+                );
+                return Err(());
+            }
+        }
+    }
+    let timeout = match hir.stmt.timeout {
+        Some(timeout) => Some(self.const_time(timeout)?),
+        None => None,
+    };
+
+    // The sampled signals are updated before the process suspends. A
+    // condition is checked whenever the process resumes, and the process
+    // suspends again if it does not hold.
+    let wait_bb = ctx.builder.named_block("wait");
+    ctx.builder.ins().br(wait_bb);
+    ctx.builder.append_to(wait_bb);
+    let mut sampled: Vec<_> = ctx.prev_values.iter().map(|(&s, &slot)| (s, slot)).collect();
+    sampled.sort_by_key(|&(s, _)| s);
+    for (s, slot) in sampled {
+        let value = ctx.builder.ins().prb(ctx.signals[&s]);
+        ctx.builder.ins().st(slot, value);
+    }
+    let resume_bb = ctx.builder.named_block("resume");
+    match timeout {
+        Some(timeout) => {
+            let time = llhd::value::TimeValue::new(timeout, 0, 0);
+            let time = ctx.builder.ins().const_time(time);
+            ctx.builder.ins().wait_time(resume_bb, time, signals);
+        }
+        None if signals.is_empty() => {
+            ctx.builder.ins().halt();
+        }
+        None => {
+            ctx.builder.ins().wait(resume_bb, signals);
+        }
+    }
+    ctx.builder.append_to(resume_bb);
+    if let Some(cond) = hir.stmt.cond {
+        let exit_bb = ctx.builder.named_block("wait_exit");
+        let value = self.codegen_expr(cond, ctx)?;
+        ctx.builder.ins().br_cond(value, wait_bb, exit_bb);
+        ctx.builder.append_to(exit_bb);
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: AssertStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let fail_bb = ctx.builder.named_block("assert_fail");
+    let exit_bb = ctx.builder.named_block("assert_exit");
+    match self.static_condition(hir.stmt.cond)? {
+        Some(true) => {
+            ctx.builder.ins().br(exit_bb);
+        }
+        Some(false) => {
+            ctx.builder.ins().br(fail_bb);
+        }
+        None => {
+            let cond = self.codegen_expr(hir.stmt.cond, ctx)?;
+            ctx.builder.ins().br_cond(cond, fail_bb, exit_bb);
+        }
+    }
+    ctx.builder.append_to(fail_bb);
+    self.codegen_report(hir.stmt.severity, self.assert_severity(id)?, hir.stmt.report, ctx)?;
+    ctx.builder.ins().br(exit_bb);
+    ctx.builder.append_to(exit_bb);
+    Ok(())
+});
+
+impl_codegen!(self, id: ReportStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let severity = self.report_severity(id)?;
+    self.codegen_report(hir.stmt.severity, severity, Some(hir.stmt.report), ctx)
+});
+
+impl_codegen!(self, id: SigAssignStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.hir(id)?;
+    let target = match hir.target {
+        hir::SigAssignTarget::Name(sig) => match ctx.signals.get(&sig) {
+            Some(&v) => v,
+            None => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "signal `{}` not accessible during code generation",
+                        hir.target_span.extract()
+                    ))
+                    .span(hir.target_span)
+                );
+                return Err(());
+            }
+        },
+        hir::SigAssignTarget::Part(_, expr) => self.codegen_target(expr, ctx)?,
+        hir::SigAssignTarget::Aggregate => {
+            self.emit(
+                DiagBuilder2::bug("code generation for assignment to aggregate not implemented")
+                .span(hir.target_span)
+            );
+            return Err(());
+        }
+    };
+    let (mech, wave) = match hir.kind {
+        hir::SigAssignKind::SimpleWave(ref mech, ref wave) => (mech, wave),
+        _ => {
+            self.emit(
+                DiagBuilder2::bug("code generation for this kind of signal assignment not implemented")
+                .span(hir.kind_span)
+            );
+            return Err(());
+        }
+    };
+    // An empty waveform corresponds to `unaffected`, which does nothing. Every
+    // element schedules its own transaction, with the delay measured from the
+    // execution of the assignment.
+    let mut prev_after: Option<BigRational> = None;
+    for elem in wave {
+        let value = match elem.value {
+            Some(value) => {
+                let llvalue = self.codegen_expr(value, ctx)?;
+                self.codegen_assign_value(target, llvalue, value, hir.span, ctx)?
+            }
+            None => {
+                self.emit(
+                    DiagBuilder2::bug("code generation for this waveform not implemented")
+                    .span(hir.kind_span)
+                );
+                return Err(());
+            }
+        };
+        let after = match elem.after {
+            Some(after) => self.const_time(after)?,
+            None => num::zero(),
+        };
+        match prev_after {
+            // The pulse rejection limit only applies to the first element.
+            None => self.check_delay_mechanism(mech, &after, hir.kind_span)?,
+            Some(ref prev) if &after <= prev => {
+                let span = match elem.after {
+                    Some(after) => self.lazy_hir(after)?.span,
+                    None => hir.kind_span,
+                };
+                self.emit(
+                    DiagBuilder2::error(
+                        "waveform elements must be in ascending order of their delays",
+                    )
+                    .span(span),
+                );
+                return Err(());
+            }
+            Some(_) => (),
+        }
+
+        // A zero delay schedules the transaction for the next delta cycle.
+        let delay = if after.is_zero() {
+            llhd::value::TimeValue::new(after.clone(), 1, 0)
+        } else {
+            llhd::value::TimeValue::new(after.clone(), 0, 0)
+        };
+        let delay = ctx.builder.ins().const_time(delay);
+        ctx.builder.ins().drv(target, value, delay);
+        prev_after = Some(after);
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: VarAssignStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let slot = match hir.stmt.target.value {
+        hir::Target::Name(expr) => self.codegen_target(expr, ctx)?,
+        hir::Target::Aggregate(..) => {
+            self.emit(
+                DiagBuilder2::bug(format!(
+                    "code generation for assignment target `{}` not implemented",
+                    hir.stmt.target.span.extract()
+                ))
+                .span(hir.stmt.target.span),
+            );
+            return Err(());
+        }
+    };
+    match hir.stmt.kind {
+        hir::VarAssignKind::Simple(expr) => {
+            let value = self.codegen_expr(expr, ctx)?;
+            let value = self.codegen_assign_value(slot, value, expr, hir.span, ctx)?;
+            ctx.builder.ins().st(slot, value);
+        }
+
+        // Each condition either jumps to a block that assigns its value, or
+        // falls through to the next condition.
+        hir::VarAssignKind::Cond(ref cond) => {
+            let exit_bb = ctx.builder.named_block("assign_exit");
+            for &(value, cond) in &cond.when {
+                let true_bb = ctx.builder.named_block("assign_true");
+                let false_bb = ctx.builder.named_block("assign_false");
+                match self.static_condition(cond)? {
+                    Some(true) => {
+                        ctx.builder.ins().br(true_bb);
+                    }
+                    Some(false) => {
+                        ctx.builder.ins().br(false_bb);
+                    }
+                    None => {
+                        let cond = self.codegen_expr(cond, ctx)?;
+                        ctx.builder.ins().br_cond(cond, false_bb, true_bb);
+                    }
+                }
+                ctx.builder.append_to(true_bb);
+                let llvalue = self.codegen_expr(value, ctx)?;
+                let value = self.codegen_assign_value(slot, llvalue, value, hir.span, ctx)?;
+                ctx.builder.ins().st(slot, value);
+                ctx.builder.ins().br(exit_bb);
+                ctx.builder.append_to(false_bb);
+            }
+            if let Some(value) = cond.other {
+                let llvalue = self.codegen_expr(value, ctx)?;
+                let value = self.codegen_assign_value(slot, llvalue, value, hir.span, ctx)?;
+                ctx.builder.ins().st(slot, value);
+            }
+            ctx.builder.ins().br(exit_bb);
+            ctx.builder.append_to(exit_bb);
+        }
+        hir::VarAssignKind::Sel(..) => {
+            self.emit(
+                DiagBuilder2::bug("code generation for selected variable assignments not implemented")
+                    .span(hir.span),
+            );
+            return Err(());
+        }
+    }
+    Ok(())
+});
+
+impl_codegen!(self, id: CallStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    match self.resolve_method(hir.stmt.call)? {
+        Some((object, method, args)) => {
+            self.codegen_method_call(object, method, args, hir.span, ctx)?;
+            Ok(())
+        }
+        None => unimp!(self, id),
+    }
+});
+
+impl_codegen!(self, id: IfStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let blocks: Vec<_> = hir.stmt.branches.iter().map(|_| (
+        ctx.builder.named_block("if_true"),
+        ctx.builder.named_block("if_false"),
+    )).collect();
+    let exit_bb = ctx.builder.named_block("if_exit");
+
+    // Each branch either jumps to its body, or falls through to the next
+    // branch. Blocks rendered unreachable by static conditions are removed
+    // once the unit is complete.
+    for (&(cond, ref stmts), &(true_bb, false_bb)) in hir.stmt.branches.iter().zip(blocks.iter()) {
+        match self.static_condition(cond)? {
+            Some(true) => {
+                ctx.builder.ins().br(true_bb);
+            }
+            Some(false) => {
+                ctx.builder.ins().br(false_bb);
+            }
+            None => {
+                let cond = self.codegen_expr(cond, ctx)?;
+                ctx.builder.ins().br_cond(cond, false_bb, true_bb);
+            }
+        }
+        ctx.builder.append_to(true_bb);
+        for &stmt in stmts {
+            self.codegen(stmt, ctx)?;
+        }
+        ctx.builder.ins().br(exit_bb);
+        ctx.builder.append_to(false_bb);
+    }
+    for &stmt in hir.stmt.otherwise.iter().flat_map(|s| s.iter()) {
+        self.codegen(stmt, ctx)?;
+    }
+    ctx.builder.ins().br(exit_bb);
+    ctx.builder.append_to(exit_bb);
+    Ok(())
+});
+
+impl_codegen!(self, id: CaseStmtRef, _ctx: &mut UnitContext<'_> => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: LoopStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let body_bb = ctx.builder.named_block("loop_body");
+    let exit_bb = ctx.builder.named_block("loop_exit");
+
+    // The next iteration starts at the loop condition, if there is one. The
+    // parameter of a `for` loop is kept in a variable.
+    let (next_bb, param) = match hir.stmt.scheme {
+        hir::LoopScheme::Loop => {
+            ctx.builder.ins().br(body_bb);
+            (body_bb, None)
+        }
+        hir::LoopScheme::While(cond) => {
+            let cond_bb = ctx.builder.named_block("loop_cond");
+            ctx.builder.ins().br(cond_bb);
+            ctx.builder.append_to(cond_bb);
+            match self.static_condition(cond)? {
+                Some(true) => {
+                    ctx.builder.ins().br(body_bb);
+                }
+                Some(false) => {
+                    ctx.builder.ins().br(exit_bb);
+                }
+                None => {
+                    let cond = self.codegen_expr(cond, ctx)?;
+                    ctx.builder.ins().br_cond(cond, exit_bb, body_bb);
+                }
+            }
+            (cond_bb, None)
+        }
+        hir::LoopScheme::For(_, ref range) => {
+            let (dir, left, right) = self.const_discrete_range(range.as_ref())?;
+            // Integer bounds assume the type of the parameter, such that they
+            // can be mapped to LLHD.
+            let (left, right) = match (self.deref_named_type(self.ty(id)?)?, left, right) {
+                (Ty::Int(ty), Const::Int(l), Const::Int(r)) => (
+                    self.intern_const(ConstInt::new(Some(ty.clone()), l.value.clone())),
+                    self.intern_const(ConstInt::new(Some(ty.clone()), r.value.clone())),
+                ),
+                _ => (left, right),
+            };
+            let null = match (left, right) {
+                (Const::Int(l), Const::Int(r)) => match dir {
+                    Dir::To => l.value > r.value,
+                    Dir::Downto => l.value < r.value,
+                },
+                (Const::Enum(l), Const::Enum(r)) => match dir {
+                    Dir::To => l.index > r.index,
+                    Dir::Downto => l.index < r.index,
+                },
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a constant discrete range",
+                            range.span.extract()
+                        ))
+                        .span(range.span),
+                    );
+                    return Err(());
+                }
+            };
+            // The parameter of an enum type with an `enum_encoding` attribute
+            // steps through the positions of the literals, and its value is
+            // looked up in the table of their patterns.
+            let positions = match (left, right) {
+                (Const::Enum(l), Const::Enum(r)) => {
+                    self.enum_encoding(l.decl)?.map(|encoding| {
+                        let width = enum_width(encoding.codes.len());
+                        let left = (width, BigInt::from(l.index));
+                        let right = (width, BigInt::from(r.index));
+                        (left, right, encoding)
+                    })
+                }
+                _ => None,
+            };
+            let init = match positions {
+                Some((ref left, _, _)) => ctx.builder.ins().const_int(left.clone()),
+                None => self.map_const(&mut ctx.builder, left)?,
+            };
+            let slot = ctx.builder.ins().var(init);
+            ctx.builder.ins().br(if null { exit_bb } else { body_bb });
+
+            // After each iteration, the loop either ends at the right bound of
+            // the range or advances the parameter towards it.
+            let cond_bb = ctx.builder.named_block("loop_cond");
+            let incr_bb = ctx.builder.named_block("loop_incr");
+            ctx.builder.append_to(cond_bb);
+            let value = ctx.builder.ins().ld(slot);
+            let last = match positions {
+                Some((_, ref right, _)) => ctx.builder.ins().const_int(right.clone()),
+                None => self.map_const(&mut ctx.builder, right)?,
+            };
+            let is_last = ctx.builder.ins().eq(value, last);
+            ctx.builder.ins().br_cond(is_last, incr_bb, exit_bb);
+            ctx.builder.append_to(incr_bb);
+            let value = ctx.builder.ins().ld(slot);
+            let width = ctx.builder.value_type(value).unwrap_int();
+            let one = ctx.builder.ins().const_int((width, BigInt::one()));
+            let value = match dir {
+                Dir::To => ctx.builder.ins().add(value, one),
+                Dir::Downto => ctx.builder.ins().sub(value, one),
+            };
+            ctx.builder.ins().st(slot, value);
+            ctx.builder.ins().br(body_bb);
+            (cond_bb, Some((slot, positions.map(|(_, _, encoding)| encoding))))
+        }
+    };
+
+    // Generate the body with the loop visible to `next` and `exit`.
+    ctx.builder.append_to(body_bb);
+    if let Some((slot, encoding)) = param {
+        let mut value = ctx.builder.ins().ld(slot);
+        if let Some(EnumEncoding { width, codes }) = encoding {
+            let codes = codes
+                .into_iter()
+                .map(|code| ctx.builder.ins().const_int((width, code)))
+                .collect();
+            let table = ctx.builder.ins().array(codes);
+            value = ctx.builder.ins().mux(table, value);
+        }
+        ctx.constants.insert(ConstRef::Loop(id), value);
+    }
+    ctx.loops.push(LoopBlocks {
+        label: hir.label.map(|l| l.value),
+        next: next_bb,
+        exit: exit_bb,
+    });
+    let result = hir
+        .stmt
+        .stmts
+        .iter()
+        .map(|&stmt| self.codegen(stmt, ctx))
+        .collect::<Result<Vec<_>>>();
+    ctx.loops.pop();
+    result?;
+    ctx.builder.ins().br(next_bb);
+    ctx.builder.append_to(exit_bb);
+    Ok(())
+});
+
+impl_codegen!(self, id: NexitStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    let kw = match hir.stmt.mode {
+        hir::NexitMode::Next => "next",
+        hir::NexitMode::Exit => "exit",
+    };
+
+    // Find the loop the statement refers to.
+    let blocks = match hir.stmt.target {
+        Some(label) => ctx.loops.iter().rev().find(|l| l.label == Some(label.value)).cloned(),
+        None => ctx.loops.last().cloned(),
+    };
+    let blocks = match (blocks, hir.stmt.target) {
+        (Some(blocks), _) => blocks,
+        (None, Some(label)) => {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` does not label an enclosing loop",
+                    label.value
+                ))
+                .span(label.span),
+            );
+            return Err(());
+        }
+        (None, None) => {
+            self.emit(
+                DiagBuilder2::error(format!("`{}` outside of a loop", kw)).span(hir.span),
+            );
+            return Err(());
+        }
+    };
+    let target_bb = match hir.stmt.mode {
+        hir::NexitMode::Next => blocks.next,
+        hir::NexitMode::Exit => blocks.exit,
+    };
+
+    // Statements after the branch continue in a separate block. It is
+    // removed once the unit is complete if it turns out to be unreachable.
+    let cont_bb = ctx.builder.named_block(format!("{}_cont", kw));
+    match hir.stmt.cond.map(|cond| self.static_condition(cond)).transpose()? {
+        Some(Some(false)) => {
+            ctx.builder.ins().br(cont_bb);
+        }
+        Some(None) => {
+            let cond = self.codegen_expr(hir.stmt.cond.unwrap(), ctx)?;
+            ctx.builder.ins().br_cond(cond, cont_bb, target_bb);
+        }
+        Some(Some(true)) | None => {
+            ctx.builder.ins().br(target_bb);
+        }
+    }
+    ctx.builder.append_to(cont_bb);
+    Ok(())
+});
+
+impl_codegen!(self, id: ReturnStmtRef, ctx: &mut UnitContext<'_> => {
+    let hir = self.lazy_hir(id)?;
+    match hir.stmt.expr {
+        Some(expr) => {
+            let value = self.codegen_expr(expr, ctx)?;
+            let ty = ctx.builder.sig().return_type();
+            let value = self.codegen_int_cast(value, expr, &ty, ctx)?;
+            ctx.builder.ins().ret_value(value);
+        }
+        None => {
+            ctx.builder.ins().ret();
+        }
+    }
+
+    // Statements after the return continue in a separate block, which is
+    // removed once the unit is complete.
+    let cont_bb = ctx.builder.named_block("return_cont");
+    ctx.builder.append_to(cont_bb);
+    Ok(())
+});
+
+impl_codegen!(self, _id: NullStmtRef, _ctx: &mut UnitContext<'_> => {
+    // A null statement performs no action.
+    Ok(())
+});
+
+impl_codegen!(self, _id: SubprogDeclRef, _ctx: &mut () => {
+    // The calls to the subprogram declare it as an external unit, and its
+    // body generates the definition.
+    Ok(())
+});
+
+impl_codegen!(self, id: SubprogBodyRef, _ctx: &mut () => {
+    let hir = self.hir(id)?;
+    let name = self.mangle_unit_name(&self.scope_path(id.into())?);
+    debugln!("generating subprogram `{}`", name);
+
+    let ret = self.subprog_return_type(&hir.spec)?;
+    let mut params = vec![];
+    for &param in &hir.spec.params {
+        match param {
+            IntfObjRef::Const(id) => params.push(id),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "code generation for parameter {:?} not implemented",
+                        param
+                    ))
+                    .span(hir.spec.name.span),
+                );
+                return Err(());
+            }
+        }
+    }
+    // The signals read by an impure function are passed as additional
+    // arguments. Methods of a protected type receive a pointer to the state of
+    // the object as their first argument.
+    let signals = self.impure_signals(id)?;
+    let state = match hir.parent {
+        ScopeRef::Protected(body) => Some(body),
+        _ => None,
+    };
+    let mut sig = llhd::ir::Signature::new();
+    if let Some(body) = state {
+        sig.add_input(llhd::pointer_ty(self.protected_state_type(body)?));
+    }
+    for &param in &params {
+        sig.add_input(self.map_type(self.ty(param)?)?);
+    }
+    for &s in &signals {
+        sig.add_input(self.map_type(self.ty(s)?)?);
+    }
+    // Procedures are functions that return no value.
+    sig.set_return_type(match ret {
+        Some(ref ret) => self.map_type(ret)?,
+        None => llhd::void_ty(),
+    });
+
+    let mut func = llhd::ir::UnitData::new(llhd::ir::UnitKind::Function, name.clone(), sig);
+    {
+        let mut ctx = self.unit_context(&mut func);
+        let mut args: Vec<_> = ctx.builder.input_args().collect();
+        let state_arg = state.map(|_| args.remove(0));
+        for (&arg, &param) in args.iter().zip(params.iter()) {
+            let param_name = self.ast(param).3.name;
+            ctx.builder.set_name(arg, param_name.as_str().to_string());
+            ctx.constants.insert(param.into(), arg);
+        }
+        for (&arg, &s) in args[params.len()..].iter().zip(signals.iter()) {
+            ctx.builder.set_name(arg, self.signal_name(s)?.as_str().to_string());
+            ctx.signal_values.insert(s, arg);
+        }
+        let entry_bb = ctx.builder.named_block("entry");
+        ctx.builder.append_to(entry_bb);
+        if let (Some(body), Some(state_arg)) = (state, state_arg) {
+            ctx.builder.set_name(state_arg, "state".to_string());
+            for (i, var) in self.protected_state(body)?.into_iter().enumerate() {
+                let field = ctx.builder.ins().ext_field(state_arg, i);
+                ctx.builder.set_name(field, self.lazy_hir(var)?.name.value.as_str().to_string());
+                ctx.variables.insert(var, field);
+            }
+        }
+        for &decl in &hir.decls {
+            self.codegen(decl, &mut ctx)?;
+        }
+        for &stmt in &hir.stmts {
+            self.codegen(stmt, &mut ctx)?;
+        }
+
+        // A procedure returns at the end of its body, but a function must
+        // not run off its end.
+        let end_bb = ctx.builder.named_block("end");
+        ctx.builder.ins().br(end_bb);
+        ctx.builder.append_to(end_bb);
+        if ret.is_none() {
+            ctx.builder.ins().ret();
+        } else if reachable_blocks(&ctx.builder, entry_bb).contains(&end_bb) {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "function `{}` may end without returning a value",
+                    hir.spec.name.span.extract()
+                ))
+                .span(hir.spec.name.span),
+            );
+            return Err(());
+        }
+        prune_blocks(&mut ctx.builder);
+        self.finish_unit_context(&name, ctx);
+    }
+    self.add_unit_deferred(name.to_string(), func, hir.spec.name.span)?;
+    Ok(())
+});
+
+// Only the methods in the body of a protected type generate code. The state of
+// the objects of the type is placed where the objects are declared.
+impl_codegen!(self, id: TypeDeclRef, _ctx: &mut () => {
+    match self.lazy_hir(id)?.data {
+        Some(Spanned {
+            value: hir::TypeData::ProtectedBody(ref decls),
+            ..
+        }) => {
+            for &decl in decls {
+                if let DeclInSubprogRef::SubprogBody(id) = decl {
+                    self.codegen(id, &mut ())?;
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+});
+
+impl_codegen!(self, id: SubprogInstRef, _ctx: &mut () => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: PkgDeclRef, _ctx: &mut () => {
+    unimp!(self, id);
+});
+
+impl_codegen!(self, id: PkgBodyRef, _ctx: &mut () => {
+    unimp!(self, id);
 });
 
 impl_codegen!(self, id: PkgInstRef, _ctx: &mut () => {
@@ -348,6 +4639,16 @@ impl_codegen!(self, id: CompDeclRef, _ctx: &mut () => {
     unimp!(self, id);
 });
 
+impl_codegen!(self, id: DisconSpecRef, _ctx: &mut UnitContext<'_> => {
+    // Disconnection specifications only affect the drivers of guarded signals,
+    // which are not generated yet.
+    self.emit(
+        DiagBuilder2::error("disconnection specification is not yet supported")
+            .span(self.ast(id).1.span),
+    );
+    Err(())
+});
+
 // /// An helper to build sequences of instructions.
 // pub struct InstBuilder<'ctx> {
 //     pub body: &'ctx mut llhd::SeqBody,
@@ -380,3 +4681,1155 @@ impl_codegen!(self, id: CompDeclRef, _ctx: &mut () => {
 //         self.block = block
 //     }
 // }
+
+/// Collect the declarations that code generation does not support yet.
+fn collect_unsupported_decls(decls: &[ast::DeclItem], found: &mut Vec<Unsupported>) {
+    for decl in decls {
+        let desc = match *decl {
+            ast::DeclItem::ObjDecl(ast::ObjDecl {
+                kind: ast::ObjKind::Const,
+                ..
+            }) => "constant declaration",
+            ast::DeclItem::ObjDecl(ast::ObjDecl {
+                kind: ast::ObjKind::File,
+                ..
+            }) => "file declaration",
+            ast::DeclItem::SubprogDecl(ref subprog) => match subprog.data {
+                ast::SubprogData::Body {
+                    ref decls,
+                    ref stmts,
+                } => {
+                    collect_unsupported_decls(decls, found);
+                    collect_unsupported_seq_stmts(stmts, found);
+                    continue;
+                }
+                _ => subprog.data.desc(),
+            },
+            ast::DeclItem::PkgDecl(..)
+            | ast::DeclItem::PkgBody(..)
+            | ast::DeclItem::PkgInst(..)
+            | ast::DeclItem::CompDecl(..) => decl.desc(),
+            ast::DeclItem::DisconDecl(..) => "disconnection specification",
+            _ => continue,
+        };
+        found.push(Unsupported {
+            desc,
+            span: decl.human_span(),
+        });
+    }
+}
+
+/// Collect the concurrent statements that code generation does not support
+/// yet, including the ones nested in blocks, generate statements, and
+/// processes.
+fn collect_unsupported_conc_stmts(stmts: &[ast::Stmt], found: &mut Vec<Unsupported>) {
+    for stmt in stmts {
+        let desc = match stmt.data {
+            ast::BlockStmt {
+                ref decls,
+                ref stmts,
+                ..
+            }
+            | ast::ForGenStmt {
+                body:
+                    ast::GenBody {
+                        ref decls,
+                        ref stmts,
+                        ..
+                    },
+                ..
+            } => {
+                collect_unsupported_decls(decls, found);
+                collect_unsupported_conc_stmts(stmts, found);
+                continue;
+            }
+            ast::ProcStmt {
+                ref decls,
+                ref stmts,
+                ..
+            } => {
+                collect_unsupported_decls(decls, found);
+                collect_unsupported_seq_stmts(stmts, found);
+                continue;
+            }
+            ast::AssignStmt { .. }
+            | ast::InstOrCallStmt {
+                target: Some(ast::InstTarget::Entity),
+                ..
+            } => continue,
+            ast::InstOrCallStmt {
+                target: None,
+                generics: None,
+                ports: None,
+                ..
+            } => "concurrent procedure call",
+            ast::InstOrCallStmt { .. } => "component instantiation",
+            ast::AssertStmt { .. } => "concurrent assertion",
+            ast::SelectAssignStmt { .. } => "selected signal assignment",
+            ast::IfGenStmt { .. } | ast::CaseGenStmt { .. } => stmt.desc(),
+            _ => continue,
+        };
+        found.push(Unsupported {
+            desc,
+            span: stmt.human_span(),
+        });
+    }
+}
+
+/// Collect the sequential statements that code generation does not support
+/// yet, including the ones nested in other statements.
+fn collect_unsupported_seq_stmts(stmts: &[ast::Stmt], found: &mut Vec<Unsupported>) {
+    for stmt in stmts {
+        let desc = match stmt.data {
+            ast::IfStmt { ref conds, ref alt } => {
+                for (_, body) in conds {
+                    collect_unsupported_seq_stmts(&body.stmts, found);
+                }
+                if let Some(ref alt) = *alt {
+                    collect_unsupported_seq_stmts(&alt.stmts, found);
+                }
+                continue;
+            }
+            ast::LoopStmt { ref body, .. } => {
+                collect_unsupported_seq_stmts(&body.stmts, found);
+                continue;
+            }
+            ast::InstOrCallStmt { .. } => "procedure call",
+            ast::CaseStmt { .. } => stmt.desc(),
+            _ => continue,
+        };
+        found.push(Unsupported {
+            desc,
+            span: stmt.human_span(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, first_arch, score_test};
+    use moore_common::Session;
+
+    /// Build a function that returns zero, optionally preceded by an addition
+    /// of mismatching types.
+    fn function(broken: bool) -> llhd::ir::UnitData {
+        let mut sig = llhd::ir::Signature::new();
+        sig.set_return_type(llhd::int_ty(32));
+        let name = llhd::ir::UnitName::Global(String::from("f"));
+        let mut unit = llhd::ir::UnitData::new(llhd::ir::UnitKind::Function, name, sig);
+        {
+            let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut unit);
+            let entry_bb = builder.named_block("entry");
+            builder.append_to(entry_bb);
+            let zero = builder.ins().const_int((32, BigInt::zero()));
+            if broken {
+                let bit = builder.ins().const_int((1, BigInt::zero()));
+                builder.ins().add(bit, zero);
+            }
+            builder.ins().ret_value(zero);
+        }
+        unit
+    }
+
+    #[test]
+    fn verify_valid_unit() {
+        assert!(verify_unit(&function(false)).is_ok());
+    }
+
+    #[test]
+    fn verify_catches_invalid_unit() {
+        assert!(verify_unit(&function(true)).is_err());
+    }
+
+    #[test]
+    fn begin_entity_unit() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is port (a, b : in bit; c : out bit; d : inout bit); end;
+                 architecture bar of foo is begin end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            let data = ctx.begin_entity_unit(arch).unwrap();
+            let unit = llhd::ir::Unit::new_anonymous(&data);
+            assert_eq!(unit.name().to_string(), "@work.foo.bar");
+            assert_eq!(unit.input_args().count(), 3);
+            assert_eq!(unit.output_args().count(), 2);
+        });
+    }
+
+    #[test]
+    fn protected_counter() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is port (y : out integer); end;
+                 architecture bar of foo is
+                     type counter_t is protected
+                         procedure increment (n : integer);
+                         impure function value return integer;
+                     end protected;
+                     type counter_t is protected body
+                         variable count : integer := 0;
+                         procedure increment (n : integer) is
+                         begin
+                             count := count + n;
+                         end;
+                         impure function value return integer is
+                         begin
+                             return count;
+                         end;
+                     end protected body;
+                     shared variable counter : counter_t;
+                 begin
+                     process begin
+                         counter.increment(1);
+                         y <= counter.value;
+                         wait;
+                     end process;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+            ctx.add_pending_units();
+            assert!(!sess.failed());
+
+            // The methods are functions that take a pointer to the state of the
+            // counter, which the process holds in a variable.
+            let module = ctx.sb.llmod.borrow();
+            let mut asm = Vec::new();
+            llhd::assembly::write_module(&mut asm, &module);
+            let asm = String::from_utf8(asm).unwrap();
+            let count = |name: &str, opcode| {
+                let unit = module
+                    .units()
+                    .find(|unit| unit.name().to_string().contains(name))
+                    .unwrap();
+                unit.all_insts()
+                    .filter(|&inst| unit[inst].opcode() == opcode)
+                    .count()
+            };
+            assert!(asm.contains("@work.foo.bar.counter_t.increment ({i32}* %state, i32 %n) void"));
+            assert!(asm.contains("@work.foo.bar.counter_t.value ({i32}* %state) i32"));
+            assert_eq!(count("proc", llhd::ir::Opcode::Var), 1);
+            assert_eq!(count("proc", llhd::ir::Opcode::Call), 2);
+            assert_eq!(count("increment", llhd::ir::Opcode::St), 1);
+        });
+    }
+
+    #[test]
+    fn time_zero_drives() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is port (y : out bit); end;
+                 architecture bar of foo is
+                     signal a, b : bit := '1';
+                     signal c : bit;
+                 begin
+                     b <= not a;
+                     y <= b and c;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+
+            // Only the initialized signal without a driver is driven at time zero.
+            let module = ctx.sb.llmod.borrow();
+            let unit = module
+                .units()
+                .find(|unit| unit.name().to_string() == "@work.foo.bar")
+                .unwrap();
+            let drives: Vec<_> = unit
+                .all_insts()
+                .filter(|&inst| unit[inst].opcode() == llhd::ir::Opcode::Drv)
+                .map(|inst| unit[inst].args()[0])
+                .collect();
+            assert_eq!(drives.len(), 1);
+            assert_eq!(unit.get_name(drives[0]), Some("a"));
+        });
+    }
+
+    /// Generate the process of an architecture and count its drives.
+    ///
+    /// Returns `None` if a warning or error was emitted.
+    fn drives_without_warnings(assign: &str) -> Option<usize> {
+        let src = format!(
+            "entity foo is port (y : out bit); end;
+             architecture bar of foo is
+             begin
+                 p : process begin {} end process;
+             end;",
+            assign
+        );
+        let units = crate::syntax::parse_str(&src).unwrap();
+        let mut sess = Session::new();
+        sess.opts.warnings_as_errors = true;
+        score_context!(ctx, &sess);
+        let lib = add_work(&ctx, &units);
+        let arch = first_arch(&ctx, lib);
+        if ctx.llunit(arch).is_err() || sess.failed() {
+            return None;
+        }
+        let module = ctx.sb.llmod.borrow();
+        let unit = module
+            .units()
+            .find(|unit| unit.name().to_string() == "@work.foo.bar.p")
+            .unwrap();
+        Some(
+            unit.all_insts()
+                .filter(|&inst| unit[inst].opcode() == llhd::ir::Opcode::Drv)
+                .count(),
+        )
+    }
+
+    #[test]
+    fn default_inertial_delay() {
+        score_test(|| {
+            assert_eq!(drives_without_warnings("y <= '1';"), Some(1));
+            assert_eq!(
+                drives_without_warnings("y <= transport '1' after 2 ns, '0' after 4 ns;"),
+                Some(2)
+            );
+            assert_eq!(
+                drives_without_warnings("y <= reject 0 ns inertial '1' after 2 ns;"),
+                Some(1)
+            );
+            // Inertial delay is approximated as transport delay, with a warning
+            // only if it is given explicitly.
+            assert_eq!(
+                drives_without_warnings("y <= '1' after 2 ns, '0' after 4 ns;"),
+                Some(2)
+            );
+            assert_eq!(
+                drives_without_warnings("y <= inertial '1' after 2 ns, '0' after 4 ns;"),
+                None
+            );
+            assert_eq!(
+                drives_without_warnings("y <= inertial '1' after 2 ns;"),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn unsupported_disconnection_spec() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal x : bit;
+                     disconnect x : bit after 1 ns;
+                 begin
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            let found = ctx.unsupported_constructs(arch);
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].span.extract(), "disconnect x : bit after 1 ns;");
+            assert_eq!(
+                found[0].to_string(),
+                "disconnection specification is not yet supported"
+            );
+            assert!(ctx.codegen_arch(arch).is_err());
+            assert!(sess.failed());
+        });
+    }
+
+    /// Lower the first architecture of some source code.
+    ///
+    /// Yields the session, to check for diagnostics, and the generated module
+    /// if lowering succeeded.
+    fn lower_module(src: &str) -> (Session, Option<llhd::ir::Module>) {
+        let units = crate::syntax::parse_str(src).unwrap();
+        let sess = Session::new();
+        let module = {
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).ok().map(|_| {
+                ctx.add_pending_units();
+                std::mem::replace(&mut *ctx.sb.llmod.borrow_mut(), llhd::ir::Module::new())
+            })
+        };
+        (sess, module)
+    }
+
+    /// Print the process of a module with the given name.
+    fn process_asm(module: &llhd::ir::Module, name: &str) -> String {
+        let mut asm = Vec::new();
+        llhd::assembly::write_module(&mut asm, module);
+        let header = format!("proc {} ", name);
+        String::from_utf8(asm)
+            .unwrap()
+            .split("\n\n")
+            .find(|unit| unit.trim_start().starts_with(&header))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn map_unbounded_int() {
+        score_test(|| {
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            assert!(ctx.map_type(&Ty::UniversalInt).is_err());
+            assert!(ctx.map_type(&Ty::UnboundedInt).is_err());
+            assert!(sess.failed());
+        });
+    }
+
+    #[test]
+    fn int_width_policies() {
+        score_test(|| {
+            let widths = |policy: IntWidthPolicy| -> (usize, usize) {
+                let sess = Session::new();
+                score_context!(ctx, &sess, |sb| {
+                    sb.int_width = policy;
+                });
+                let byte = IntTy::new(hir::Dir::To, BigInt::zero(), BigInt::from(255));
+                let ty_width = ctx.map_type(&Ty::Int(byte)).unwrap().unwrap_int();
+
+                let name = llhd::ir::UnitName::Global(String::from("f"));
+                let sig = llhd::ir::Signature::new();
+                let mut unit = llhd::ir::UnitData::new(llhd::ir::UnitKind::Process, name, sig);
+                let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut unit);
+                let entry_bb = builder.named_block("entry");
+                builder.append_to(entry_bb);
+                let k = Const::Int(ConstInt::new(None, BigInt::from(5)));
+                let value = ctx.map_const(&mut builder, &k).unwrap();
+                (ty_width, builder.value_type(value).unwrap_int())
+            };
+            assert_eq!(widths(IntWidthPolicy::default()), (8, 32));
+            let policy = IntWidthPolicy {
+                default_width: 64,
+                minimal: false,
+            };
+            assert_eq!(widths(policy), (64, 64));
+        });
+    }
+
+    #[test]
+    fn assign_width_mismatch() {
+        score_test(|| {
+            let (sess, module) = lower_module(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a : bit_vector(3 downto 0);
+                     signal b : bit_vector(7 downto 0);
+                 begin
+                     p : process (a) begin b <= a; end process;
+                 end;",
+            );
+            assert!(module.is_none());
+            assert!(sess.failed());
+        });
+    }
+
+    #[test]
+    fn assign_int_subtypes() {
+        score_test(|| {
+            let (sess, module) = lower_module(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal n : natural;
+                     signal i : integer;
+                 begin
+                     p : process (i)
+                         variable v : integer range 0 to 7;
+                     begin
+                         n <= i;
+                         v := i;
+                     end process;
+                 end;",
+            );
+            assert!(!sess.failed());
+
+            // The values assigned have the width of their targets.
+            let asm = process_asm(&module.unwrap(), "@work.foo.bar.p");
+            assert!(asm.contains("drv i31$ %n, "), "{}", asm);
+            assert!(asm.contains("st i3* "), "{}", asm);
+        });
+    }
+
+    #[test]
+    fn call_with_subtype_args() {
+        score_test(|| {
+            use llhd::ir::Opcode;
+            let (sess, module) = lower_module(
+                "entity foo is port (y : out integer); end;
+                 architecture bar of foo is
+                     subtype byte is integer range 0 to 255;
+                     function twice (x : integer) return integer is
+                     begin
+                         return x + x;
+                     end;
+                     function low (x : byte) return byte is
+                     begin
+                         return x;
+                     end;
+                     function clip (x : integer) return byte is
+                     begin
+                         return x;
+                     end;
+                     signal i : integer;
+                 begin
+                     p : process (i) begin y <= twice(low(i)) + clip(i); end process;
+                 end;",
+            );
+            assert!(!sess.failed());
+
+            let module = module.unwrap();
+            let opcodes = |name: &str| -> Vec<_> {
+                let unit = module
+                    .units()
+                    .find(|unit| unit.name().to_string() == name)
+                    .unwrap();
+                unit.all_insts().map(|inst| unit[inst].opcode()).collect()
+            };
+
+            // The integer is truncated to a byte, which is then extended back.
+            let process = opcodes("@work.foo.bar.p");
+            assert_eq!(process.iter().filter(|&&op| op == Opcode::Call).count(), 3);
+            assert!(process.contains(&Opcode::ExtSlice));
+            assert!(process.contains(&Opcode::InsSlice));
+
+            // The integer returned is truncated to a byte.
+            assert!(opcodes("@work.foo.bar.clip").contains(&Opcode::ExtSlice));
+        });
+    }
+
+    #[test]
+    fn return_record_aggregate() {
+        score_test(|| {
+            use llhd::ir::Opcode;
+            let (sess, module) = lower_module(
+                "entity foo is end;
+                 architecture bar of foo is
+                     type rec is record
+                         x : boolean;
+                         y : boolean;
+                     end record;
+                     function swap (a, b : boolean) return rec is
+                     begin
+                         return (b, a);
+                     end;
+                     function named (a, b : boolean) return rec is
+                     begin
+                         return (y => a, x => b);
+                     end;
+                 begin end;",
+            );
+            assert!(!sess.failed());
+
+            // Both aggregates take their type from the return type and are
+            // built from the parameters at run time.
+            let module = module.unwrap();
+            for name in &["@work.foo.bar.swap", "@work.foo.bar.named"] {
+                let unit = module
+                    .units()
+                    .find(|unit| unit.name().to_string() == *name)
+                    .unwrap();
+                let opcodes: Vec<_> = unit.all_insts().map(|inst| unit[inst].opcode()).collect();
+                assert_eq!(opcodes, vec![Opcode::Struct, Opcode::RetValue]);
+            }
+        });
+    }
+
+    #[test]
+    fn null_statement() {
+        score_test(|| {
+            let (sess, module) = lower_module(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a, b : bit;
+                 begin
+                     p : process (a) begin
+                         null;
+                         if a = '1' then b <= a; else null; end if;
+                     end process;
+                     q : process (a) begin
+                         if a = '1' then b <= a; end if;
+                     end process;
+                 end;",
+            );
+            assert!(!sess.failed());
+
+            // The null statements generate no instructions, leaving the same
+            // body as the process without them.
+            let module = module.unwrap();
+            let body = |name: &str| {
+                let asm = process_asm(&module, name);
+                asm.trim().lines().skip(1).collect::<Vec<_>>().join("\n")
+            };
+            assert_eq!(body("@work.foo.bar.p"), body("@work.foo.bar.q"));
+        });
+    }
+
+    #[test]
+    fn for_loop_over_range_attr() {
+        score_test(|| {
+            let (sess, module) = lower_module(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal v : bit_vector(3 downto 0);
+                     signal a, b : integer;
+                 begin
+                     p : process (v)
+                         variable n : integer;
+                     begin
+                         for i in v'range loop n := n + 1; end loop;
+                         a <= n;
+                     end process;
+                     q : process (v)
+                         variable n : integer;
+                     begin
+                         for i in v'reverse_range loop n := n + 1; end loop;
+                         b <= n;
+                     end process;
+                 end;",
+            );
+            assert!(!sess.failed());
+            let module = module.unwrap();
+
+            // Describe the loop of a process by the initial and the last value
+            // of its parameter, and the instruction that steps it.
+            let for_loop = |name: &str| -> (String, String, String) {
+                let asm = process_asm(&module, name);
+                let insts: HashMap<&str, &str> = asm
+                    .lines()
+                    .filter_map(|line| line.trim().split_once(" = "))
+                    .collect();
+                let find = |opcode: &str| {
+                    let prefix = format!("{} i2 ", opcode);
+                    insts
+                        .values()
+                        .find(|inst| inst.starts_with(&prefix))
+                        .unwrap_or_else(|| panic!("no `{}` in {}", prefix, asm))
+                        .trim_start_matches(&prefix)
+                        .to_string()
+                };
+                let def = |value: &str| insts[value.trim()].to_string();
+                let step = if asm.contains("= add i2 ") {
+                    "add"
+                } else {
+                    "sub"
+                };
+                let last = find("eq");
+                let last = last.split(", ").nth(1).unwrap();
+                (def(&find("var")), def(last), step.to_string())
+            };
+            let expect = |init: &str, last: &str, step: &str| {
+                (init.to_string(), last.to_string(), step.to_string())
+            };
+            assert_eq!(
+                for_loop("@work.foo.bar.p"),
+                expect("const i2 3", "const i2 0", "sub")
+            );
+            assert_eq!(
+                for_loop("@work.foo.bar.q"),
+                expect("const i2 0", "const i2 3", "add")
+            );
+        });
+    }
+
+    /// Lower an architecture that declares a `state_t` enum with the given
+    /// `enum_encoding`, and iterates over and indexes an array by it. Yields
+    /// the opcodes of all generated units.
+    fn lower_encoded_enum(encoding: &str) -> (Session, Option<Vec<llhd::ir::Opcode>>) {
+        let src = format!(
+            "entity foo is port (y : out integer); end;
+             architecture bar of foo is
+                 type state_t is (idle, run, done);
+                 attribute enum_encoding : string;
+                 attribute enum_encoding of state_t : type is \"{}\";
+                 type table_t is array (state_t) of integer;
+                 signal s : state_t;
+             begin
+                 p : process (s)
+                     variable t : table_t;
+                 begin
+                     for i in state_t loop t(i) := 7; end loop;
+                     y <= t(s);
+                 end process;
+                 s <= run;
+             end;",
+            encoding
+        );
+        let (sess, module) = lower_module(&src);
+        let opcodes = module.map(|module| {
+            module
+                .units()
+                .flat_map(|unit| {
+                    unit.all_insts()
+                        .map(|inst| unit[inst].opcode())
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        });
+        (sess, opcodes)
+    }
+
+    #[test]
+    fn encoded_enum_positions() {
+        score_test(|| {
+            use llhd::ir::Opcode;
+            let (sess, opcodes) = lower_encoded_enum("001 010 100");
+            assert!(!sess.failed());
+            let opcodes = opcodes.unwrap();
+            assert!(opcodes.contains(&Opcode::Mux));
+            assert!(opcodes.contains(&Opcode::Eq));
+
+            let (sess, opcodes) = lower_encoded_enum("-1 10 01");
+            assert!(opcodes.is_none());
+            assert!(sess.failed());
+        });
+    }
+
+    #[test]
+    fn static_division_by_zero() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     constant zero : integer := 0;
+                     signal a, b : integer;
+                 begin
+                     p : process (a) begin b <= a mod zero; end process;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            assert!(ctx.llunit(arch).is_err());
+            assert!(sess.failed());
+        });
+    }
+
+    #[test]
+    fn default_severities() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a : bit;
+                 begin
+                     p : process (a) begin
+                         assert a = '1';
+                         report \"a is set\";
+                         assert a = '1' severity note;
+                     end process;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            let process = match ctx.hir(arch).unwrap().stmts[0] {
+                ConcStmtRef::Process(id) => id,
+                _ => panic!("expected a process"),
+            };
+            let stmts = &ctx.hir(process).unwrap().stmts;
+            let severities: Vec<_> = stmts
+                .iter()
+                .map(|&stmt| match stmt {
+                    SeqStmtRef::Assert(id) => ctx.assert_severity(id).unwrap(),
+                    SeqStmtRef::Report(id) => ctx.report_severity(id).unwrap(),
+                    _ => panic!("expected an assertion or report"),
+                })
+                .collect();
+            assert_eq!(
+                severities,
+                vec![
+                    Some(SEVERITY_ERROR),
+                    Some(SEVERITY_NOTE),
+                    Some(SEVERITY_NOTE)
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn lower_lone_signal_assignment() {
+        score_test(|| {
+            use llhd::ir::Opcode;
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a, b, c : bit;
+                 begin
+                     p : process (a) begin b <= a; end process;
+                     c <= not a;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            let opcodes = |data: &llhd::ir::UnitData| {
+                let unit = llhd::ir::Unit::new_anonymous(data);
+                unit.all_insts()
+                    .map(|inst| unit[inst].opcode())
+                    .collect::<Vec<_>>()
+            };
+
+            let stmts = &ctx.hir(arch).unwrap().stmts;
+            let process = match stmts[0] {
+                ConcStmtRef::Process(id) => id,
+                _ => panic!("expected a process"),
+            };
+            let stmt = ctx.hir(process).unwrap().stmts[0];
+            let data = ctx.lower_seq_stmt(stmt).unwrap();
+            let unit = llhd::ir::Unit::new_anonymous(&data);
+            assert_eq!(unit.input_args().count(), 1);
+            assert_eq!(unit.output_args().count(), 1);
+            assert_eq!(
+                opcodes(&data),
+                vec![Opcode::Prb, Opcode::ConstTime, Opcode::Drv, Opcode::Halt]
+            );
+
+            // LLHD terminates every entity with an implicit `halt`.
+            let data = ctx.lower_conc_stmt(stmts[1]).unwrap();
+            assert_eq!(opcodes(&data), vec![Opcode::Inst, Opcode::Halt]);
+            assert!(!sess.failed());
+        });
+    }
+
+    #[test]
+    fn report_image_message() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal s : integer;
+                 begin
+                     p : process
+                         variable a : integer;
+                     begin
+                         report \"a=\" & integer'image(a) & \" s=\" & integer'image(s);
+                         report \"n=\" & integer'image(42);
+                         wait for 1 ns;
+                     end process;
+                     s <= 1;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+
+            // The signal is read by the report message.
+            let process = match ctx.hir(arch).unwrap().stmts[0] {
+                ConcStmtRef::Process(id) => id,
+                _ => panic!("expected a process"),
+            };
+            let mut accesses = SignalAccesses::default();
+            for &stmt in &ctx.hir(process).unwrap().stmts {
+                ctx.collect_stmt_accesses(stmt, &mut accesses).unwrap();
+            }
+            assert_eq!(accesses.read.len(), 1);
+
+            // The images of the variable and signal are 11 characters long at
+            // most, and the image of the literal is folded.
+            let mut asm = Vec::new();
+            llhd::assembly::write_module(&mut asm, &ctx.sb.llmod.borrow());
+            let asm = String::from_utf8(asm).unwrap();
+            assert_eq!(
+                asm.matches("call [11 x i8] @vhdl.image.i32 (i32 ").count(),
+                2
+            );
+            assert!(asm.contains("call void @vhdl.report.27 (i2 "));
+            assert!(asm.contains("call void @vhdl.report.4 (i2 "));
+            assert!(!sess.failed());
+        });
+    }
+
+    #[test]
+    fn assert_reads_signal() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal s : bit;
+                 begin
+                     p : process begin
+                         assert s = '1' report \"s is low\" severity error;
+                         wait for 1 ns;
+                     end process;
+                     s <= '1';
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+
+            // The signal is read by the condition of the assertion.
+            let process = match ctx.hir(arch).unwrap().stmts[0] {
+                ConcStmtRef::Process(id) => id,
+                _ => panic!("expected a process"),
+            };
+            let mut accesses = SignalAccesses::default();
+            for &stmt in &ctx.hir(process).unwrap().stmts {
+                ctx.collect_stmt_accesses(stmt, &mut accesses).unwrap();
+            }
+            assert_eq!(accesses.read.len(), 1);
+            assert!(accesses.written.is_empty());
+
+            // The process probes the signal to evaluate the condition.
+            let module = ctx.sb.llmod.borrow();
+            let unit = module
+                .units()
+                .find(|unit| unit.name().to_string() == "@work.foo.bar.p")
+                .unwrap();
+            assert!(unit
+                .all_insts()
+                .any(|inst| unit[inst].opcode() == llhd::ir::Opcode::Prb));
+            assert!(unit
+                .all_insts()
+                .any(|inst| unit[inst].opcode() == llhd::ir::Opcode::BrCond));
+            assert!(!sess.failed());
+        });
+    }
+
+    #[test]
+    fn name_attrs() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal S : bit;
+                 begin
+                     p : process (S)
+                         variable v : bit;
+                     begin
+                         report S'simple_name;
+                         report S'path_name;
+                         report S'instance_name;
+                         report v'path_name;
+                     end process;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            assert!(ctx.llunit(arch).is_ok());
+
+            let process = match ctx.hir(arch).unwrap().stmts[0] {
+                ConcStmtRef::Process(id) => id,
+                _ => panic!("expected a process"),
+            };
+            let names: Vec<_> = ctx
+                .hir(process)
+                .unwrap()
+                .stmts
+                .iter()
+                .map(|&stmt| match stmt {
+                    SeqStmtRef::Report(id) => {
+                        let report = ctx.lazy_hir(id).unwrap().stmt.report;
+                        ctx.name_attr_value(report).unwrap()
+                    }
+                    _ => panic!("expected a report"),
+                })
+                .collect();
+            assert_eq!(names, vec!["s", ":foo:s", ":foo(bar):s", ":foo:p:v"]);
+        });
+    }
+
+    #[test]
+    fn process_sensitivity() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a, b, c, d, e : bit;
+                 begin
+                     p : process (b) begin c <= a; end process;
+                     q : process (all) begin d <= a and b; end process;
+                     e <= b;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+
+            let sensitivity = |unit: &str| -> Vec<String> {
+                ctx.sb.sensitivities.borrow()[unit]
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect()
+            };
+            assert_eq!(sensitivity("@work.foo.bar.p"), vec!["b"]);
+            assert_eq!(sensitivity("@work.foo.bar.q"), vec!["a", "b"]);
+            assert_eq!(sensitivity("@work.foo.bar.assign2"), vec!["b"]);
+        });
+    }
+
+    /// Check whether an architecture produces unused signal warnings.
+    fn warns_unused_signals(arch: &str) -> bool {
+        let src = format!(
+            "entity foo is port (a : in bit; b : out bit); end; {}",
+            arch
+        );
+        let units = crate::syntax::parse_str(&src).unwrap();
+        let mut sess = Session::new();
+        sess.opts.warnings_as_errors = true;
+        score_context!(ctx, &sess);
+        let lib = add_work(&ctx, &units);
+        let arch = first_arch(&ctx, lib);
+        ctx.check_unused_signals(arch).unwrap();
+        sess.failed()
+    }
+
+    #[test]
+    fn unused_signals() {
+        score_test(|| {
+            assert!(!warns_unused_signals(
+                "architecture bar of foo is signal x : bit; begin x <= a; b <= x; end;"
+            ));
+            assert!(warns_unused_signals(
+                "architecture bar of foo is signal x, y : bit; begin x <= a; b <= x; end;"
+            ));
+            assert!(warns_unused_signals(
+                "architecture bar of foo is signal x : bit; begin x <= a; end;"
+            ));
+            assert!(warns_unused_signals(
+                "architecture bar of foo is signal x : bit; begin b <= x; end;"
+            ));
+        });
+    }
+
+    #[test]
+    fn unsupported_conc_proc_call() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal x : bit;
+                     procedure toggle (signal s : inout bit) is
+                     begin
+                         s <= not s;
+                     end;
+                 begin
+                     toggle(x);
+                     x <= '1';
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            let found = ctx.unsupported_constructs(arch);
+            assert_eq!(found.len(), 1);
+            assert!(found[0].span.extract().starts_with("toggle(x)"));
+            assert_eq!(
+                found[0].to_string(),
+                "concurrent procedure call is not yet supported"
+            );
+            assert!(ctx.report_unsupported(arch));
+            assert!(!sess.failed());
+        });
+    }
+
+    thread_local! {
+        static CAPTURED: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// A logger that captures the messages logged on the current thread.
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED.with(|c| c.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn lowering_spans() {
+        score_test(|| {
+            static LOGGER: CaptureLogger = CaptureLogger;
+            let _ = log::set_logger(&LOGGER);
+            log::set_max_level(log::LevelFilter::Trace);
+
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal y : bit;
+                 begin
+                     p : process begin y <= '1'; wait; end process;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            CAPTURED.with(|c| c.borrow_mut().clear());
+            assert!(ctx.llunit(arch).is_ok());
+            let msgs = CAPTURED.with(|c| c.borrow_mut().split_off(0));
+
+            // Strip the node ids, which are checked separately.
+            let spans: Vec<_> = msgs
+                .iter()
+                .map(|msg| msg.rsplit_once(' ').unwrap().0)
+                .collect();
+            assert_eq!(
+                spans,
+                vec![
+                    "> entity",
+                    "  > process",
+                    "    > statement",
+                    "    < statement",
+                    "    > statement",
+                    "    < statement",
+                    "  < process",
+                    "< entity",
+                ]
+            );
+            assert!(msgs[0].ends_with(&format!("{:?}", arch)));
+            assert!(msgs[1].contains("ProcessStmtRef("));
+            assert!(msgs[2].contains("SigAssign("));
+            assert!(msgs[4].contains("Wait("));
+        });
+    }
+}
@@ -64,7 +64,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }
         match def {
             // Handle overloadable cases.
-            Def::Enum(_) => {
+            Def::Enum(_) | Def::Subprog(_) | Def::SubprogInst(_) => {
                 self.defs
                     .entry(name.value)
                     .or_insert_with(|| Vec::new())
@@ -109,8 +109,18 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     }
 
     /// Handle type declarations.
+    ///
+    /// The body of a protected type does not declare a name, since it shares
+    /// the name of the type.
     pub fn declare_type(&mut self, id: TypeDeclRef) {
         let ast = self.ctx.ast(id).1;
+        if let Some(Spanned {
+            value: ast::ProtectedBody(..),
+            ..
+        }) = ast.data
+        {
+            return;
+        }
         self.declare(ast.name.map_into(), Def::Type(id));
         // This is a rather hacky way of declaring the variant names for enum
         // literals, but it does not require the HIR to be constructed, which is
@@ -364,15 +374,44 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> DefsContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     ///
     /// These are mainly subprogram parameters and entity ports.
     pub fn declare_intf_objs(&mut self, ids: &[IntfObjRef]) {
-        if !ids.is_empty() {
-            unimplemented!();
+        for &id in ids {
+            match id {
+                IntfObjRef::Signal(id) => {
+                    let hir = match self.ctx.hir(id) {
+                        Ok(h) => h,
+                        Err(()) => {
+                            self.failed = true;
+                            continue;
+                        }
+                    };
+                    self.declare(hir.name.map_into(), Def::Signal(id.into()))
+                }
+                IntfObjRef::Const(id) => {
+                    let name = self.ctx.ast(id).3;
+                    self.declare(
+                        Spanned::new(name.name, name.span).map_into(),
+                        Def::Const(id.into()),
+                    )
+                }
+                // TODO: Declare variable and file parameters once there are
+                // definitions that can refer to them.
+                _ => (),
+            }
         }
     }
 
     /// Handle generics.
     pub fn declare_generics(&mut self, ids: &[GenericRef]) {
-        if !ids.is_empty() {
-            unimplemented!();
+        // TODO: Declare generic types, subprograms, and packages once there are
+        // definitions that can refer to them.
+        for &id in ids {
+            if let GenericRef::Const(id) = id {
+                let name = self.ctx.ast(id).3;
+                self.declare(
+                    Spanned::new(name.name, name.span).map_into(),
+                    Def::Const(id.into()),
+                )
+            }
         }
     }
 }
@@ -0,0 +1,416 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! A driver that runs the phases of the compilation of VHDL sources.
+//!
+//! The sources are lexed, parsed, scored, and finally lowered to LLHD. For
+//! debugging, the driver may be asked to stop after any of these phases, in
+//! which case it returns what the phase produced, similar to the
+//! `-fsyntax-only` flag of C compilers.
+
+#![deny(missing_docs)]
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::common::errors::{DiagBuilder2, DiagEmitter};
+use crate::common::name::Name;
+use crate::common::score::{GenericContext, NodeRef, Result};
+use crate::common::source::Source;
+use crate::common::{NodeId, Session};
+use crate::lazy::LazyPhaseTable;
+use crate::score::*;
+use crate::syntax::ast;
+use crate::syntax::lexer::{dump_tokens, TokenDump};
+use crate::typeck::{Typeck, TypeckContext};
+use llhd;
+use llhd::opt::{Pass, PassContext};
+
+/// A phase of the compilation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    /// Lexical analysis.
+    Lex,
+    /// Parsing into an AST.
+    Parse,
+    /// Lowering to HIR and type checking.
+    Score,
+    /// Generation of LLHD code for the architectures.
+    Codegen,
+}
+
+impl FromStr for Phase {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Phase, ()> {
+        match s {
+            "lex" => Ok(Phase::Lex),
+            "parse" => Ok(Phase::Parse),
+            "score" => Ok(Phase::Score),
+            "codegen" => Ok(Phase::Codegen),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Phase::Lex => write!(f, "lex"),
+            Phase::Parse => write!(f, "parse"),
+            Phase::Score => write!(f, "score"),
+            Phase::Codegen => write!(f, "codegen"),
+        }
+    }
+}
+
+/// The artifacts produced by the last phase of a compilation.
+pub enum Artifacts {
+    /// The tokens of all sources, produced by `Phase::Lex`.
+    Tokens(Vec<TokenDump>),
+    /// The design units of all sources, produced by `Phase::Parse`.
+    Ast(Vec<ast::DesignUnit>),
+    /// The design units of all sources, produced by `Phase::Score`. The HIR
+    /// lives in the scoreboard of the compilation and is discarded; these are
+    /// the design units that were lowered and type checked without errors.
+    Scored(Vec<ast::DesignUnit>),
+    /// The LLHD module produced by `Phase::Codegen`.
+    Module(Box<Design>),
+}
+
+/// An LLHD module generated for a design, together with the unit at the top
+/// of its hierarchy.
+pub struct Design {
+    /// The generated module.
+    pub module: llhd::ir::Module,
+    /// The entity unit of the top architecture, if it is known.
+    pub top: Option<llhd::ir::UnitId>,
+}
+
+impl Design {
+    /// The entity unit at the top of the design hierarchy, if it is known.
+    pub fn top_unit(&self) -> Option<llhd::ir::Unit<'_>> {
+        self.top.map(|id| self.module.unit(id))
+    }
+}
+
+/// The options of a compilation.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The last phase to run.
+    pub stop_after: Phase,
+    /// The optimization level of the generated LLHD module. See `optimize` for
+    /// the passes each level runs.
+    pub opt_level: usize,
+    /// The entity whose unit is the top of the design. If `None`, it is
+    /// inferred as the only entity of the library that no other entity
+    /// instantiates.
+    pub top: Option<Name>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            stop_after: Phase::Codegen,
+            opt_level: 0,
+            top: None,
+        }
+    }
+}
+
+/// Compile a set of sources into library `lib`.
+///
+/// Diagnostics are emitted to `sess`. Returns the artifacts of the phase
+/// `opts.stop_after`, or an error if any phase fails. The most recently
+/// analyzed architecture of the top entity provides the top unit of the
+/// generated design.
+pub fn compile(sess: &Session, sources: &[Source], lib: Name, opts: &Options) -> Result<Artifacts> {
+    if opts.stop_after == Phase::Lex {
+        return Ok(Artifacts::Tokens(
+            sources
+                .iter()
+                .flat_map(|&src| dump_tokens(src, sess))
+                .collect(),
+        ));
+    }
+
+    let mut units = Vec::new();
+    let mut failed = false;
+    for &src in sources {
+        match crate::syntax::parse_with_session(src, sess) {
+            Ok(ast) => units.extend(ast),
+            Err(()) => failed = true,
+        }
+    }
+    if failed {
+        return Err(());
+    }
+    if opts.stop_after == Phase::Parse {
+        return Ok(Artifacts::Ast(units));
+    }
+
+    let design = {
+        let arenas = Arenas::new();
+        let sb = ScoreBoard::new(&arenas);
+        let lazy = LazyPhaseTable::new(&sb);
+        let ctx = ScoreContext {
+            sess,
+            global: &Global,
+            sb: &sb,
+            lazy: &lazy,
+        };
+        let lib_id = LibRef::new(NodeId::alloc());
+        ctx.add_library(lib, lib_id, units.iter().collect());
+        let tyc = TypeckContext::new(&ctx);
+        tyc.typeck(lib_id);
+        if !tyc.finish() || sess.failed() {
+            return Err(());
+        }
+        if opts.stop_after == Phase::Score {
+            None
+        } else {
+            let arch_table = ctx.archs(lib_id)?;
+            let top = match opts.top {
+                Some(name) => {
+                    match arch_table
+                        .by_entity
+                        .keys()
+                        .find(|&&entity| ctx.ast(entity).2.name.value == name)
+                    {
+                        Some(&entity) => Some(entity),
+                        None => {
+                            sess.emit(DiagBuilder2::error(format!(
+                                "top entity `{}` not found in library `{}`",
+                                name, lib
+                            )));
+                            return Err(());
+                        }
+                    }
+                }
+                None => match ctx.top_entities(lib_id)?.as_slice() {
+                    &[entity] => Some(entity),
+                    _ => None,
+                },
+            };
+            let mut archs = Vec::new();
+            let mut top_index = None;
+            for (&entity, entity_archs) in &arch_table.by_entity {
+                for &arch in &entity_archs.ordered {
+                    if Some(entity) == top {
+                        top_index = Some(archs.len());
+                    }
+                    archs.push(arch);
+                }
+            }
+            let units = codegen(&ctx, &archs, opts.opt_level)?;
+            Some(Design {
+                module: sb.llmod.replace(llhd::ir::Module::new()),
+                top: top_index.map(|index| units[index]),
+            })
+        }
+    };
+    Ok(match design {
+        Some(design) => Artifacts::Module(Box::new(design)),
+        None => Artifacts::Scored(units),
+    })
+}
+
+/// Generate the LLHD units of a set of architectures, and optimize the module
+/// of the scoreboard at `opt_level`.
+///
+/// Returns the unit of each architecture. The units are added to the module
+/// at once and in the order of their names, such that the module does not
+/// depend on the order of `archs`. This is the code generation phase of
+/// `compile`, for callers that score the design themselves.
+pub fn codegen(
+    ctx: &ScoreContext,
+    archs: &[ArchRef],
+    opt_level: usize,
+) -> Result<Vec<llhd::ir::UnitId>> {
+    let names = archs
+        .iter()
+        .map(|&arch| ctx.lower_arch(arch))
+        .collect::<Result<Vec<_>>>()?;
+    ctx.add_pending_units();
+    optimize(&mut ctx.sb.llmod.borrow_mut(), opt_level);
+    Ok(names
+        .iter()
+        .map(|name| ctx.unit_id(name).unwrap())
+        .collect())
+}
+
+/// Run the LLHD passes of optimization level `opt_level` over a module.
+///
+/// Level 0 leaves the module as it was lowered. Level 1 and above fold
+/// constants, eliminate common subexpressions, simplify instructions, and
+/// remove dead code.
+pub fn optimize(module: &mut llhd::ir::Module, opt_level: usize) {
+    let ctx = PassContext;
+    if opt_level >= 1 {
+        llhd::pass::ConstFolding::run_on_module(&ctx, module);
+        // llhd::pass::VarToPhiPromotion::run_on_module(&ctx, module); // broken in llhd 0.13
+        llhd::pass::DeadCodeElim::run_on_module(&ctx, module);
+        llhd::pass::GlobalCommonSubexprElim::run_on_module(&ctx, module);
+        llhd::pass::InstSimplification::run_on_module(&ctx, module);
+        llhd::pass::DeadCodeElim::run_on_module(&ctx, module);
+    }
+}
+
+/// The global context of a compilation that involves only VHDL.
+pub(crate) struct Global;
+
+impl GenericContext for Global {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, score_test};
+    use moore_common::name::get_name_table;
+    use moore_common::source::get_source_manager;
+
+    // The signal `b` is not declared, which is only detected by scoring.
+    const SRC: &str = "entity foo is end;
+        architecture bar of foo is signal a : bit; begin a <= b; end;";
+
+    fn run(stop_after: Phase) -> Result<Artifacts> {
+        let sess = Session::new();
+        let src = get_source_manager().add_anonymous(SRC);
+        let work = get_name_table().intern("work", false);
+        let opts = Options {
+            stop_after,
+            ..Default::default()
+        };
+        compile(&sess, &[src], work, &opts)
+    }
+
+    #[test]
+    fn parse_phase_returns_ast() {
+        score_test(|| {
+            match run(Phase::Parse) {
+                Ok(Artifacts::Ast(units)) => assert_eq!(units.len(), 2),
+                _ => panic!("expected the AST"),
+            }
+            assert!(run(Phase::Codegen).is_err());
+        });
+    }
+
+    #[test]
+    fn lex_phase_returns_tokens() {
+        score_test(|| match run(Phase::Lex) {
+            Ok(Artifacts::Tokens(tokens)) => assert_eq!(tokens[0].text, "entity"),
+            _ => panic!("expected the tokens"),
+        });
+    }
+
+    #[test]
+    fn opt_level_removes_dead_code() {
+        score_test(|| {
+            let count_ands = |opt_level| {
+                let sess = Session::new();
+                let src = get_source_manager().add_anonymous(
+                    "entity foo is end;
+                     architecture bar of foo is signal a : bit; begin a <= '1' and '0'; end;",
+                );
+                let work = get_name_table().intern("work", false);
+                let opts = Options {
+                    opt_level,
+                    ..Default::default()
+                };
+                let module = match compile(&sess, &[src], work, &opts) {
+                    Ok(Artifacts::Module(design)) => design.module,
+                    _ => panic!("expected an LLHD module"),
+                };
+                module
+                    .units()
+                    .map(|unit| {
+                        unit.all_insts()
+                            .filter(|&inst| unit[inst].opcode() == llhd::ir::Opcode::And)
+                            .count()
+                    })
+                    .sum::<usize>()
+            };
+            assert_eq!(count_ands(0), 1);
+            assert_eq!(count_ands(1), 0);
+        });
+    }
+
+    #[test]
+    fn top_unit() {
+        score_test(|| {
+            let top_unit_name = |top: Option<&str>| {
+                let sess = Session::new();
+                let src = get_source_manager().add_anonymous(
+                    "entity sub is end;
+                     architecture a of sub is begin end;
+                     entity top is end;
+                     architecture b of top is begin u : entity work.sub; end;",
+                );
+                let work = get_name_table().intern("work", false);
+                let opts = Options {
+                    top: top.map(|name| get_name_table().intern(name, false)),
+                    ..Default::default()
+                };
+                let design = match compile(&sess, &[src], work, &opts) {
+                    Ok(Artifacts::Module(design)) => design,
+                    _ => panic!("expected an LLHD module"),
+                };
+                // Names are case insensitive and may have been interned with a
+                // different case by another test.
+                design
+                    .top_unit()
+                    .map(|unit| unit.name().to_string().to_lowercase())
+            };
+            assert_eq!(top_unit_name(None), Some(String::from("@work.top.b")));
+            assert_eq!(
+                top_unit_name(Some("sub")),
+                Some(String::from("@work.sub.a"))
+            );
+        });
+    }
+
+    #[test]
+    fn codegen_is_deterministic() {
+        score_test(|| {
+            let lower = |reverse: bool| {
+                let units = crate::syntax::parse_str(
+                    "entity foo is end;
+                     architecture a of foo is begin
+                         zz : process begin wait; end process;
+                         aa : process begin wait; end process;
+                     end;
+                     entity bar is end;
+                     architecture b of bar is begin
+                         p : process begin wait; end process;
+                     end;",
+                )
+                .unwrap();
+                let sess = Session::new();
+                score_context!(ctx, &sess);
+                let lib = add_work(&ctx, &units);
+                let mut archs: Vec<_> = ctx
+                    .archs(lib)
+                    .unwrap()
+                    .by_entity
+                    .values()
+                    .flat_map(|archs| archs.ordered.iter().cloned())
+                    .collect();
+                if reverse {
+                    archs.reverse();
+                }
+                codegen(&ctx, &archs, 0).unwrap();
+                let mut asm = Vec::new();
+                llhd::assembly::write_module(&mut asm, &ctx.sb.llmod.borrow());
+                String::from_utf8(asm).unwrap()
+            };
+            assert_eq!(lower(false), lower(false));
+            assert_eq!(lower(false), lower(true));
+        });
+    }
+
+    #[test]
+    fn phase_names() {
+        for &phase in &[Phase::Lex, Phase::Parse, Phase::Score, Phase::Codegen] {
+            assert_eq!(phase.to_string().parse(), Ok(phase));
+        }
+        assert_eq!("elab".parse::<Phase>(), Err(()));
+    }
+}
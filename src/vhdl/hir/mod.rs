@@ -60,7 +60,11 @@ make_arenas!(
         file_decl: Decl<FileDecl>,
         type_decl2: Decl<Option<TypeData>>,
         process_stmt: ProcessStmt,
+        block_stmt: BlockStmt,
+        for_gen_stmt: ForGenStmt,
+        comp_inst_stmt: CompInstStmt,
         sig_assign_stmt: SigAssignStmt,
+        conc_sig_assign_stmt: ConcSigAssignStmt,
         array_type_index: Spanned<ArrayTypeIndex>,
         subprog: Subprog,
         subprog_body: SubprogBody,
@@ -163,10 +167,24 @@ pub struct SubtypeInd {
     pub span: Span,
     /// The type mark.
     pub type_mark: Spanned<TypeMarkRef>,
+    /// The optional resolution indication.
+    pub resol: Option<Spanned<ResolInd>>,
     /// The optional constraint.
     pub constraint: Option<Spanned<Constraint>>,
 }
 
+/// A resolution indication.
+///
+/// See IEEE 1076-2008 section 6.3.
+#[derive(Debug)]
+pub enum ResolInd {
+    /// A resolution function, given as the overloads of its name.
+    Func(Vec<Spanned<Def>>),
+    /// An element resolution, which applies a resolution indication to the
+    /// elements of an array, as in `(resolved) std_ulogic_vector`.
+    Elem(Box<Spanned<ResolInd>>),
+}
+
 /// A constraint.
 ///
 /// See IEEE 1076-2008 section 6.3.
@@ -292,10 +310,34 @@ impl From<Range> for DiscreteRange {
 /// ```
 #[derive(Debug)]
 pub enum Range {
-    // Attr(AttrRef),
+    /// A range attribute of an array object, e.g. `A'range`.
+    Attr(ExprRef, Spanned<RangeAttr>),
     Immediate(Dir, ExprRef, ExprRef),
 }
 
+/// A predefined attribute of an array that denotes a range.
+///
+/// See IEEE 1076-2008 section 16.2.3.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangeAttr {
+    /// `A'range`, the range of the array's index.
+    Range,
+    /// `A'reverse_range`, the range of the array's index in reverse
+    /// direction.
+    ReverseRange,
+}
+
+impl RangeAttr {
+    /// Look up a predefined range attribute by name.
+    pub fn from_name(name: &str) -> Option<RangeAttr> {
+        match name.to_ascii_lowercase().as_str() {
+            "range" => Some(RangeAttr::Range),
+            "reverse_range" => Some(RangeAttr::ReverseRange),
+            _ => None,
+        }
+    }
+}
+
 /// A record constraint as per IEEE 1076-2008 section 5.3.3.
 #[derive(Debug)]
 pub struct RecordConstraint {
@@ -388,6 +430,10 @@ pub enum TypeData {
     File(Spanned<TypeMarkRef>),
     /// A record type.
     Record(Vec<(Spanned<Name>, SubtypeIndRef)>),
+    /// A protected type, with the declarations of its methods.
+    Protected(Vec<SubprogDeclRef>),
+    /// The body of a protected type. This does not declare a type of its own.
+    ProtectedBody(Vec<DeclInSubprogRef>),
 }
 
 /// An enumeration literal as listed in a type declaration.
@@ -447,7 +493,7 @@ pub enum ExprData {
     // #[deprecated]
     Name(Def, Span),
     /// A resolved constant name.
-    ConstName(ConstDeclRef),
+    ConstName(ConstRef),
     /// A resolved signal name.
     SignalName(SignalRef),
     /// A resolved variable name.
@@ -463,12 +509,22 @@ pub enum ExprData {
     Select(ExprRef, Spanned<ResolvableName>),
     /// An attribute selection, e.g. `a'b`.
     Attr(ExprRef, Spanned<ResolvableName>),
+    /// A predefined attribute of a signal, e.g. `clk'event`.
+    SignalAttr(SignalRef, Spanned<SignalAttr>),
+    /// A predefined attribute that names an entity, e.g. `s'path_name`.
+    /// Consists of the simple name of the prefix and the attribute.
+    NameAttr(Spanned<Name>, Spanned<NameAttr>),
+    /// The `T'image(X)` attribute, the string representation of the value
+    /// `X` of scalar type `T`.
+    ImageAttr(Spanned<TypeMarkRef>, ExprRef),
     /// A bit string literal.
     StringLiteral(Vec<(TypeDeclRef, Vec<usize>)>),
     /// An integer literal.
     IntegerLiteral(ConstInt),
     /// A float literal.
     FloatLiteral(ConstFloat),
+    /// A physical literal, e.g. `5 ns`.
+    PhysicalLiteral(BigInt, Spanned<UnitRef>),
     /// A unary operator expression.
     Unary(Spanned<UnaryOp>, Vec<Spanned<Def>>, ExprRef),
     /// A binary operator expression.
@@ -481,10 +537,66 @@ pub enum ExprData {
     Qualified(Spanned<TypeMarkRef>, ExprRef),
     /// An allocator expression, i.e. `new`.
     Allocator(Spanned<TypeMarkRef>, Option<ExprRef>),
+    /// A dereference of an access value, i.e. `.all`.
+    Deref(ExprRef),
     /// A cast expression.
     Cast(Spanned<TypeMarkRef>, ExprRef),
     /// A function call expression.
     Call(ExprRef, Spanned<AssocList>),
+    /// A call to an overloaded subprogram.
+    SubprogCall(Vec<Spanned<Def>>, Spanned<AssocList>),
+}
+
+/// A predefined attribute of a signal.
+///
+/// See IEEE 1076-2008 section 16.2.5.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignalAttr {
+    /// `S'event`, whether the signal changed in the current simulation cycle.
+    Event,
+    /// `S'stable`, whether the signal did not change in the current
+    /// simulation cycle.
+    Stable,
+    /// `S'last_value`, the value of the signal before its last change.
+    LastValue,
+}
+
+impl SignalAttr {
+    /// Look up a predefined signal attribute by name.
+    pub fn from_name(name: &str) -> Option<SignalAttr> {
+        match name.to_ascii_lowercase().as_str() {
+            "event" => Some(SignalAttr::Event),
+            "stable" => Some(SignalAttr::Stable),
+            "last_value" => Some(SignalAttr::LastValue),
+            _ => None,
+        }
+    }
+}
+
+/// A predefined attribute that yields the name of a named entity.
+///
+/// See IEEE 1076-2008 section 16.2.6.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NameAttr {
+    /// `E'simple_name`, the simple name of the entity.
+    SimpleName,
+    /// `E'path_name`, the hierarchical path to the entity.
+    PathName,
+    /// `E'instance_name`, the hierarchical path to the entity, including the
+    /// architectures of the design entities along the way.
+    InstanceName,
+}
+
+impl NameAttr {
+    /// Look up a predefined name attribute by name.
+    pub fn from_name(name: &str) -> Option<NameAttr> {
+        match name.to_ascii_lowercase().as_str() {
+            "simple_name" => Some(NameAttr::SimpleName),
+            "path_name" => Some(NameAttr::PathName),
+            "instance_name" => Some(NameAttr::InstanceName),
+            _ => None,
+        }
+    }
 }
 
 /// An object declaration.
@@ -560,6 +672,51 @@ pub struct FileDecl {
     pub mode: Option<ExprRef>,
 }
 
+/// A block statement.
+///
+/// See IEEE 1076-2008 section 11.2.
+#[derive(Debug)]
+pub struct BlockStmt {
+    /// The scope within which the block is declared.
+    pub parent: ScopeRef,
+    /// The block label.
+    pub label: Spanned<Name>,
+    /// The guard expression. If present, the block implicitly declares a
+    /// signal `GUARD` that holds the value of the expression.
+    pub guard: Option<ExprRef>,
+    /// The generics declared in the block header.
+    pub generics: Vec<GenericRef>,
+    /// The generics of the block, together with their actual values.
+    pub generic_map: Vec<(IntfConstRef, ExprRef)>,
+    /// The ports declared in the block header.
+    pub ports: Vec<IntfSignalRef>,
+    /// The ports of the block, together with the signals connected to them.
+    pub port_map: Vec<(IntfSignalRef, ExprRef)>,
+    /// The declarations made before the `begin` keyword.
+    pub decls: Vec<DeclInBlockRef>,
+    /// The statements inside the block.
+    pub stmts: Vec<ConcStmtRef>,
+}
+
+/// A for-generate statement.
+///
+/// See IEEE 1076-2008 section 11.8.
+#[derive(Debug)]
+pub struct ForGenStmt {
+    /// The scope within which the statement is declared.
+    pub parent: ScopeRef,
+    /// The statement label.
+    pub label: Spanned<Name>,
+    /// The name of the generate parameter.
+    pub param: Spanned<Name>,
+    /// The range of values the generate parameter iterates over.
+    pub range: Spanned<DiscreteRange>,
+    /// The declarations of the generate statement body.
+    pub decls: Vec<DeclInBlockRef>,
+    /// The statements of the generate statement body.
+    pub stmts: Vec<ConcStmtRef>,
+}
+
 /// A process statement.
 ///
 /// See IEEE 1076-2008 section 11.3.
@@ -579,6 +736,23 @@ pub struct ProcessStmt {
     pub stmts: Vec<SeqStmtRef>,
 }
 
+/// An entity instantiation statement.
+///
+/// See IEEE 1076-2008 section 11.7.
+#[derive(Debug)]
+pub struct CompInstStmt {
+    /// The scope within which the instance is declared.
+    pub parent: ScopeRef,
+    /// The instance label.
+    pub label: Spanned<Name>,
+    /// The instantiated architecture.
+    pub arch: Spanned<ArchRef>,
+    /// The generics of the entity, together with their actual values.
+    pub generic_map: Vec<(IntfConstRef, ExprRef)>,
+    /// The ports of the entity, together with the signals connected to them.
+    pub port_map: Vec<(IntfSignalRef, ExprRef)>,
+}
+
 /// A process sensitivity specification.
 ///
 /// See IEEE 1076-2008 section 11.3.
@@ -589,7 +763,7 @@ pub enum ProcessSensitivity {
     /// The `all` sensitivity list.
     All,
     /// Explicitly enumerated signals.
-    List(Vec<Def>),
+    List(SensitivityList),
 }
 
 /// A sequential signal assignment.
@@ -613,10 +787,33 @@ pub struct SigAssignStmt {
     pub kind_span: Span,
 }
 
+/// A concurrent signal assignment statement.
+///
+/// The statement is equivalent to a process that performs the sequential
+/// signal assignment `assign` whenever one of the signals it reads changes.
+///
+/// See IEEE 1076-2008 section 11.6.
+#[derive(Debug)]
+pub struct ConcSigAssignStmt {
+    /// The scope within which the statement has been made.
+    pub parent: ScopeRef,
+    /// The location of the entire statement in the source file.
+    pub span: Span,
+    /// The optional statement label.
+    pub label: Option<Spanned<Name>>,
+    /// The `GUARD` signal that controls a guarded assignment.
+    pub guard: Option<SignalRef>,
+    /// The equivalent sequential signal assignment.
+    pub assign: SigAssignStmtRef,
+}
+
 /// A signal assignment target.
 #[derive(Debug)]
 pub enum SigAssignTarget {
     Name(SignalRef),
+    /// A part of a signal, e.g. `a(i).b`. Consists of the signal and the
+    /// expression that selects the part.
+    Part(SignalRef, ExprRef),
     Aggregate,
 }
 
@@ -683,6 +880,8 @@ pub enum DelayMechanism {
     Transport,
     /// A `inertial` delay mechanism.
     Inertial,
+    /// No delay mechanism, which implies `inertial`.
+    ImplicitInertial,
     /// A `reject <time_expr> inertial` delay mechanism.
     RejectInertial(ExprRef),
 }
@@ -860,10 +1059,10 @@ pub enum VarAssignKind {
 /// See IEEE 1076-2008 section 10.7.
 #[derive(Debug)]
 pub struct CallStmt {
-    /// The target subprogram.
-    pub subprog: SubprogRef,
-    /// The optional call parameters.
-    pub params: (),
+    /// The called procedure with the optional call parameters. Only calls of
+    /// methods of protected types are supported, see
+    /// `ScoreContext::resolve_method`.
+    pub call: ExprRef,
 }
 
 /// An if statement.
@@ -921,9 +1120,10 @@ pub enum LoopScheme {
 pub struct NexitStmt {
     /// Whether this is a next or exit statement.
     pub mode: NexitMode,
-    /// The optional loop the statement operates on. If omitted the statement
-    /// applies to the innermost loop.
-    pub target: Option<Spanned<LoopStmtRef>>,
+    /// The optional label of the loop the statement operates on. If omitted
+    /// the statement applies to the innermost loop. The label is resolved
+    /// against the enclosing loops during code generation.
+    pub target: Option<Spanned<Name>>,
     /// The optional condition.
     pub cond: Option<ExprRef>,
 }
@@ -1052,6 +1252,11 @@ pub enum AggregateKind {
 }
 
 impl AggregateKind {
+    /// Check if the aggregate has no named elements.
+    pub fn is_both(&self) -> bool {
+        matches!(*self, AggregateKind::Both)
+    }
+
     /// Get a reference to a named field.
     ///
     /// Panics if called on `Both`.
@@ -5,7 +5,7 @@
 pub use crate::hir::Dir;
 use crate::score::TypeDeclRef;
 use crate::ty::*;
-use num::BigInt;
+use num::{BigInt, Integer, Signed, Zero};
 use std::fmt;
 
 /// A constant value.
@@ -17,6 +17,8 @@ pub enum Const {
     Enum(ConstEnum),
     IntRange(ConstIntRange),
     FloatRange(ConstFloatRange),
+    Array(ConstArray),
+    Record(ConstRecord),
 }
 
 impl Const {
@@ -28,6 +30,8 @@ impl Const {
             Const::Enum(_) => panic!("cannot negate enumeration literal"),
             Const::IntRange(_) => panic!("cannot negate integer range"),
             Const::FloatRange(_) => panic!("cannot negate float range"),
+            Const::Array(_) => panic!("cannot negate array"),
+            Const::Record(_) => panic!("cannot negate record"),
         }
     }
 
@@ -40,6 +44,27 @@ impl Const {
             Const::Enum(_) => "enumeration literal",
             Const::IntRange(_) => "integer range",
             Const::FloatRange(_) => "float range",
+            Const::Array(_) => "array",
+            Const::Record(_) => "record",
+        }
+    }
+
+    /// Format the constant with integers in a given radix.
+    ///
+    /// Integers are rendered as VHDL based literals, such as `16#FF#` or
+    /// `2#1010#`, unless the radix is 10. Constants other than integers and
+    /// integer ranges are formatted as usual. Panics if the radix is not
+    /// within 2 to 16, as supported by based literals.
+    pub fn format_radix(&self, radix: u32) -> String {
+        match *self {
+            Const::Int(ref k) => k.format_radix(radix),
+            Const::IntRange(ref k) => format!(
+                "{} {} {}",
+                k.left_bound.format_radix(radix),
+                k.dir,
+                k.right_bound.format_radix(radix)
+            ),
+            _ => self.to_string(),
         }
     }
 }
@@ -74,11 +99,24 @@ impl From<ConstFloatRange> for Const {
     }
 }
 
+impl From<ConstArray> for Const {
+    fn from(k: ConstArray) -> Const {
+        Const::Array(k)
+    }
+}
+
+impl From<ConstRecord> for Const {
+    fn from(k: ConstRecord) -> Const {
+        Const::Record(k)
+    }
+}
+
 /// A constant integer value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ConstInt {
     /// The type of the constant. If `None`, the constant is assumed to be an
-    /// unbounded integer which cannot be mapped to LLHD.
+    /// unbounded integer, which is mapped to LLHD with the default width of
+    /// the integer width policy.
     pub ty: Option<IntTy>,
     /// The value of the constant.
     pub value: BigInt,
@@ -96,6 +134,54 @@ impl ConstInt {
     pub fn negate(self) -> ConstInt {
         ConstInt::new(self.ty, -self.value)
     }
+
+    /// Divide by another integer, rounding towards zero.
+    ///
+    /// Returns `None` if `rhs` is zero.
+    pub fn div(&self, rhs: &ConstInt) -> Option<ConstInt> {
+        if rhs.value.is_zero() {
+            return None;
+        }
+        Some(ConstInt::new(self.ty.clone(), &self.value / &rhs.value))
+    }
+
+    /// Compute `self mod rhs`, which has the sign of `rhs`. See IEEE
+    /// 1076-2008 section 9.2.7.
+    ///
+    /// Returns `None` if `rhs` is zero.
+    pub fn modulo(&self, rhs: &ConstInt) -> Option<ConstInt> {
+        if rhs.value.is_zero() {
+            return None;
+        }
+        Some(ConstInt::new(
+            self.ty.clone(),
+            self.value.mod_floor(&rhs.value),
+        ))
+    }
+
+    /// Compute `self rem rhs`, which has the sign of `self`. See IEEE
+    /// 1076-2008 section 9.2.7.
+    ///
+    /// Returns `None` if `rhs` is zero.
+    pub fn rem(&self, rhs: &ConstInt) -> Option<ConstInt> {
+        if rhs.value.is_zero() {
+            return None;
+        }
+        Some(ConstInt::new(self.ty.clone(), &self.value % &rhs.value))
+    }
+
+    /// Format the integer in a given radix.
+    ///
+    /// See `Const::format_radix`.
+    pub fn format_radix(&self, radix: u32) -> String {
+        assert!((2..=16).contains(&radix), "invalid radix {}", radix);
+        if radix == 10 {
+            return self.value.to_string();
+        }
+        let sign = if self.value.is_negative() { "-" } else { "" };
+        let digits = self.value.abs().to_str_radix(radix).to_uppercase();
+        format!("{}{}#{}#", sign, radix, digits)
+    }
 }
 
 /// A constant float value.
@@ -152,6 +238,34 @@ where
 pub type ConstIntRange = ConstRange<ConstInt>;
 pub type ConstFloatRange = ConstRange<ConstFloat>;
 
+/// A constant array value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstArray {
+    /// The elements of the array, from left to right.
+    pub elements: Vec<Const>,
+}
+
+impl ConstArray {
+    /// Create a new constant array.
+    pub fn new(elements: Vec<Const>) -> ConstArray {
+        ConstArray { elements }
+    }
+}
+
+/// A constant record value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstRecord {
+    /// The fields of the record, in the order of the record type.
+    pub fields: Vec<Const>,
+}
+
+impl ConstRecord {
+    /// Create a new constant record.
+    pub fn new(fields: Vec<Const>) -> ConstRecord {
+        ConstRecord { fields }
+    }
+}
+
 // ----- FORMATTING ------------------------------------------------------------
 
 impl fmt::Display for Const {
@@ -163,6 +277,8 @@ impl fmt::Display for Const {
             Const::Enum(ref k) => k.fmt(f),
             Const::IntRange(ref k) => k.fmt(f),
             Const::FloatRange(ref k) => k.fmt(f),
+            Const::Array(ref k) => k.fmt(f),
+            Const::Record(ref k) => k.fmt(f),
         }
     }
 }
@@ -185,6 +301,32 @@ impl fmt::Display for ConstFloat {
     }
 }
 
+impl fmt::Display for ConstArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, element) in self.elements.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            element.fmt(f)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for ConstRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            field.fmt(f)?;
+        }
+        write!(f, ")")
+    }
+}
+
 impl<T> fmt::Display for ConstRange<T>
 where
     T: fmt::Display + fmt::Debug,
@@ -193,3 +335,44 @@ where
         write!(f, "{} {} {}", self.left_bound, self.dir, self.right_bound)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_radix() {
+        let k = Const::Int(ConstInt::new(None, BigInt::from(0xa5)));
+        assert_eq!(k.format_radix(16), "16#A5#");
+        assert_eq!(k.format_radix(2), "2#10100101#");
+        assert_eq!(k.format_radix(10), "165");
+        let k = Const::Int(ConstInt::new(None, BigInt::from(-10)));
+        assert_eq!(k.format_radix(16), "-16#A#");
+        let k = Const::IntRange(ConstRange::new(
+            Dir::Downto,
+            ConstInt::new(None, BigInt::from(255)),
+            ConstInt::new(None, BigInt::from(0)),
+        ));
+        assert_eq!(k.format_radix(16), "16#FF# downto 16#0#");
+    }
+
+    #[test]
+    fn div_mod_rem() {
+        let k = |v: i32| ConstInt::new(None, BigInt::from(v));
+        let eval = |a: i32, b: i32| {
+            (
+                k(a).div(&k(b)).unwrap().value,
+                k(a).modulo(&k(b)).unwrap().value,
+                k(a).rem(&k(b)).unwrap().value,
+            )
+        };
+        let expect = |d: i32, m: i32, r: i32| (BigInt::from(d), BigInt::from(m), BigInt::from(r));
+        assert_eq!(eval(7, 3), expect(2, 1, 1));
+        assert_eq!(eval(-7, 3), expect(-2, 2, -1));
+        assert_eq!(eval(7, -3), expect(-2, -2, 1));
+        assert_eq!(eval(-7, -3), expect(2, -1, -1));
+        assert_eq!(k(7).div(&k(0)), None);
+        assert_eq!(k(7).modulo(&k(0)), None);
+        assert_eq!(k(7).rem(&k(0)), None);
+    }
+}
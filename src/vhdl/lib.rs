@@ -9,6 +9,8 @@
 extern crate lazy_static;
 #[macro_use]
 extern crate moore_common;
+#[macro_use]
+extern crate log;
 pub extern crate moore_vhdl_syntax as _;
 
 pub(crate) use moore_common as common;
@@ -17,22 +19,31 @@ pub use moore_vhdl_syntax as syntax;
 #[macro_use]
 pub mod arenas;
 pub mod symtbl;
+#[cfg(test)]
+#[macro_use]
+mod testing;
 #[macro_use]
 pub mod score;
 pub mod add_ctx;
 pub mod builtin;
+pub mod cache;
 pub mod codegen;
 pub mod debug;
 pub mod defs;
+pub mod driver;
 pub mod hir;
 pub mod konst;
 pub mod konst2;
 pub mod lazy;
 pub mod make_ctx;
+pub mod mangle;
 pub mod op;
 pub mod overload_resolver;
 pub mod scope;
 pub mod scope2;
+pub mod specialize;
+pub mod srcmap;
+pub mod stats;
 pub mod term;
 pub mod ty;
 pub mod ty2;
@@ -0,0 +1,476 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Name mangling for generated LLHD units.
+//!
+//! Units are named after the path of the design unit, process, or subprogram
+//! they are generated from. For example, the process `p` in architecture `bar`
+//! of entity `foo` in library `work` becomes `work.foo.bar.p`. Characters other
+//! than letters, digits, and underscores are escaped as `\` followed by two hex
+//! digits, such that the names are unique and can be demangled again.
+//! Operators are named by a mnemonic with a leading underscore, which cannot
+//! occur in a basic identifier.
+
+#![deny(missing_docs)]
+
+use std::fmt::Write;
+
+use crate::builtin::{ENV_PKG_REF, STANDARD_PKG_REF, TEXTIO_PKG_REF};
+use crate::common::errors::*;
+use crate::common::name::Name;
+use crate::common::score::Result;
+use crate::common::source::Spanned;
+use crate::hir;
+use crate::konst::Const;
+use crate::op::{LogicalOp, Operator, RelationalOp, ShiftOp};
+use crate::score::*;
+use llhd;
+
+/// A scheme to turn hierarchical paths into unit names.
+pub trait Mangler {
+    /// Mangle a path into a unit name.
+    fn mangle(&self, path: &[String]) -> String;
+
+    /// Recover the path from a unit name.
+    ///
+    /// Returns `None` if the name was not produced by `mangle`.
+    fn demangle(&self, name: &str) -> Option<Vec<String>>;
+}
+
+/// The default mangling scheme.
+///
+/// See the module documentation for details.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultMangler;
+
+impl Mangler for DefaultMangler {
+    fn mangle(&self, path: &[String]) -> String {
+        let mut name = String::new();
+        for (i, component) in path.iter().enumerate() {
+            if i > 0 {
+                name.push('.');
+            }
+            for c in component.chars() {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    name.push(c);
+                } else {
+                    for b in c.encode_utf8(&mut [0; 4]).bytes() {
+                        write!(name, "\\{:02x}", b).unwrap();
+                    }
+                }
+            }
+        }
+        name
+    }
+
+    fn demangle(&self, name: &str) -> Option<Vec<String>> {
+        name.split('.').map(unescape).collect()
+    }
+}
+
+/// Undo the escaping of a single path component.
+fn unescape(component: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut iter = component.chars();
+    while let Some(c) = iter.next() {
+        if c == '\\' {
+            let hi = iter.next()?.to_digit(16)?;
+            let lo = iter.next()?.to_digit(16)?;
+            bytes.push((hi * 16 + lo) as u8);
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            bytes.push(c as u8);
+        } else {
+            return None;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Demangle a unit name produced by the default mangler into a human-readable
+/// path.
+///
+/// Operator mnemonics are shown as the quoted operator symbol, for example
+/// `work.pkg._add` becomes `work.pkg."+"`.
+pub fn demangle(name: &str) -> Option<String> {
+    let path = DefaultMangler.demangle(name)?;
+    let path: Vec<_> = path
+        .into_iter()
+        .map(|component| match operator_from_mnemonic(&component) {
+            Some(op) => format!("\"{}\"", op),
+            None => component,
+        })
+        .collect();
+    Some(path.join("."))
+}
+
+/// All operators that can be overloaded by a function.
+static OPERATORS: &[Operator] = &[
+    Operator::Logical(LogicalOp::And),
+    Operator::Logical(LogicalOp::Or),
+    Operator::Logical(LogicalOp::Nand),
+    Operator::Logical(LogicalOp::Nor),
+    Operator::Logical(LogicalOp::Xor),
+    Operator::Logical(LogicalOp::Xnor),
+    Operator::Rel(RelationalOp::Eq),
+    Operator::Rel(RelationalOp::Neq),
+    Operator::Rel(RelationalOp::Lt),
+    Operator::Rel(RelationalOp::Leq),
+    Operator::Rel(RelationalOp::Gt),
+    Operator::Rel(RelationalOp::Geq),
+    Operator::Match(RelationalOp::Eq),
+    Operator::Match(RelationalOp::Neq),
+    Operator::Match(RelationalOp::Lt),
+    Operator::Match(RelationalOp::Leq),
+    Operator::Match(RelationalOp::Gt),
+    Operator::Match(RelationalOp::Geq),
+    Operator::Shift(ShiftOp::Sll),
+    Operator::Shift(ShiftOp::Srl),
+    Operator::Shift(ShiftOp::Sla),
+    Operator::Shift(ShiftOp::Sra),
+    Operator::Shift(ShiftOp::Rol),
+    Operator::Shift(ShiftOp::Ror),
+    Operator::Add,
+    Operator::Sub,
+    Operator::Concat,
+    Operator::Mul,
+    Operator::Div,
+    Operator::Mod,
+    Operator::Rem,
+    Operator::Pow,
+    Operator::Abs,
+    Operator::Not,
+    Operator::Cond,
+];
+
+/// Determine the path component that names an operator.
+pub fn operator_mnemonic(op: Operator) -> String {
+    let rel = |op| match op {
+        RelationalOp::Eq => "eq",
+        RelationalOp::Neq => "neq",
+        RelationalOp::Lt => "lt",
+        RelationalOp::Leq => "leq",
+        RelationalOp::Gt => "gt",
+        RelationalOp::Geq => "geq",
+    };
+    match op {
+        Operator::Logical(op) => format!("_{}", op),
+        Operator::Rel(op) => format!("_{}", rel(op)),
+        Operator::Match(op) => format!("_match_{}", rel(op)),
+        Operator::Shift(op) => format!("_{}", op),
+        Operator::Add => "_add".to_string(),
+        Operator::Sub => "_sub".to_string(),
+        Operator::Concat => "_concat".to_string(),
+        Operator::Mul => "_mul".to_string(),
+        Operator::Div => "_div".to_string(),
+        Operator::Mod => "_mod".to_string(),
+        Operator::Rem => "_rem".to_string(),
+        Operator::Pow => "_pow".to_string(),
+        Operator::Abs => "_abs".to_string(),
+        Operator::Not => "_not".to_string(),
+        Operator::Cond => "_cond".to_string(),
+    }
+}
+
+/// Determine the operator named by a path component.
+fn operator_from_mnemonic(component: &str) -> Option<Operator> {
+    OPERATORS
+        .iter()
+        .cloned()
+        .find(|&op| operator_mnemonic(op) == component)
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Turn a path into a unit name, using the scoreboard's mangler.
+    ///
+    /// The name starts with the scoreboard's unit prefix, if any. A path
+    /// always maps to the same name. If the name is already taken by a
+    /// different path, a counter is appended to keep it unique, rather than
+    /// having one unit silently replace the other in the module.
+    pub fn mangle_unit_name(&self, path: &[String]) -> llhd::ir::UnitName {
+        if let Some(name) = self.sb.unit_names.borrow().get(path) {
+            return llhd::ir::UnitName::Global(name.clone());
+        }
+        let mut base = self.sb.mangler.mangle(path);
+        if let Some(ref prefix) = self.sb.unit_prefix {
+            base = format!("{}.{}", prefix, base);
+        }
+        let mut used = self.sb.used_unit_names.borrow_mut();
+        let mut name = base.clone();
+        let mut counter = 0;
+        while used.contains(&name) {
+            counter += 1;
+            name = format!("{}_{}", base, counter);
+        }
+        used.insert(name.clone());
+        self.sb
+            .unit_names
+            .borrow_mut()
+            .insert(path.to_vec(), name.clone());
+        llhd::ir::UnitName::Global(name)
+    }
+
+    /// Determine the hierarchical path of a scope.
+    pub fn scope_path(&self, scope: ScopeRef) -> Result<Vec<String>> {
+        Ok(match scope {
+            ScopeRef::Lib(id) => match self.lib_name(id) {
+                Some(name) => vec![name.as_str().to_string()],
+                None => {
+                    self.emit(DiagBuilder2::bug(format!("library {:?} has no name", id)));
+                    return Err(());
+                }
+            },
+            ScopeRef::CtxItems(id) => self.scope_path(self.ast(id).0)?,
+            ScopeRef::Entity(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.lib.into())?;
+                path.push(hir.name.value.as_str().to_string());
+                path
+            }
+            ScopeRef::BuiltinPkg(id) => {
+                let name = if id == *STANDARD_PKG_REF {
+                    "standard"
+                } else if id == *TEXTIO_PKG_REF {
+                    "textio"
+                } else if id == *ENV_PKG_REF {
+                    "env"
+                } else {
+                    self.emit(DiagBuilder2::bug(format!(
+                        "unknown builtin package {:?}",
+                        id
+                    )));
+                    return Err(());
+                };
+                vec![String::from("std"), String::from(name)]
+            }
+            ScopeRef::Pkg(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.parent)?;
+                path.push(hir.name.value.as_str().to_string());
+                path
+            }
+            ScopeRef::PkgBody(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.parent)?;
+                path.push(hir.name.value.as_str().to_string());
+                path
+            }
+            ScopeRef::Arch(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.entity.into())?;
+                path.push(hir.name.value.as_str().to_string());
+                if let Some(index) = self.specialization_index(id) {
+                    path.push(format!("param{}", index));
+                }
+                path
+            }
+            ScopeRef::Process(id) => self.process_path(id)?,
+            ScopeRef::Block(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.parent)?;
+                path.push(hir.label.value.as_str().to_string());
+                path
+            }
+            // The iterations of a for-generate statement are named after the
+            // position of the generate parameter's value in its type.
+            ScopeRef::ForGen(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.parent)?;
+                path.push(hir.label.value.as_str().to_string());
+                match self.sb.gen_params.borrow().get(&id).cloned() {
+                    Some(Const::Int(k)) => path.push(k.value.to_string()),
+                    Some(Const::Enum(k)) => path.push(k.index.to_string()),
+                    _ => (),
+                }
+                path
+            }
+            ScopeRef::Loop(_) => self.scope_path(self.skip_loop_scopes(scope)?)?,
+            ScopeRef::Subprog(id) => self.subprog_path(id)?,
+            ScopeRef::SubprogBody(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.scope_path(hir.parent)?;
+                path.push(subprog_name(hir.spec.name.value));
+                path
+            }
+            // A protected type and its body share the path, such that the
+            // methods declared in the former name the units of the latter.
+            ScopeRef::Protected(id) => {
+                let hir = self.lazy_hir(id)?;
+                let mut path = self.scope_path(hir.parent)?;
+                path.push(hir.name.value.as_str().to_string());
+                path
+            }
+        })
+    }
+
+    /// Determine the hierarchical path of a process.
+    ///
+    /// Unlabeled processes are named after their position in the parent's
+    /// statements.
+    pub fn process_path(&self, id: ProcessStmtRef) -> Result<Vec<String>> {
+        let hir = self.hir(id)?;
+        self.conc_stmt_path(hir.parent, id.into(), hir.label, "proc")
+    }
+
+    /// Determine the hierarchical path of a concurrent signal assignment.
+    ///
+    /// Unlabeled assignments are named after their position in the parent's
+    /// statements.
+    pub fn conc_sig_assign_path(&self, id: ConcSigAssignStmtRef) -> Result<Vec<String>> {
+        let hir = self.hir(id)?;
+        self.conc_stmt_path(hir.parent, id.into(), hir.label, "assign")
+    }
+
+    /// Determine the hierarchical path of a concurrent statement.
+    ///
+    /// Unlabeled statements are named `prefix` followed by their position in
+    /// the parent's statements.
+    fn conc_stmt_path(
+        &self,
+        parent: ScopeRef,
+        id: ConcStmtRef,
+        label: Option<Spanned<Name>>,
+        prefix: &str,
+    ) -> Result<Vec<String>> {
+        let mut path = self.scope_path(parent)?;
+        let name = match label {
+            Some(label) => label.value.as_str().to_string(),
+            None => {
+                let stmts = match parent {
+                    ScopeRef::Arch(arch) => Some(&self.hir(arch)?.stmts),
+                    ScopeRef::Block(block) => Some(&self.hir(block)?.stmts),
+                    ScopeRef::ForGen(gen) => Some(&self.hir(gen)?.stmts),
+                    _ => None,
+                };
+                match stmts.and_then(|stmts| stmts.iter().position(|&stmt| stmt == id)) {
+                    Some(index) => format!("{}{}", prefix, index),
+                    None => String::from(prefix),
+                }
+            }
+        };
+        path.push(name);
+        Ok(path)
+    }
+
+    /// Determine the hierarchical path of a subprogram.
+    pub fn subprog_path(&self, id: SubprogDeclRef) -> Result<Vec<String>> {
+        let hir = self.hir(id)?;
+        let mut path = self.scope_path(hir.parent)?;
+        path.push(subprog_name(hir.spec.name.value));
+        Ok(path)
+    }
+
+    /// Determine the value of a `'simple_name`, `'path_name`, or
+    /// `'instance_name` attribute.
+    ///
+    /// Code is generated per architecture rather than per instance, so paths
+    /// start at the design unit that declares the named entity.
+    pub fn name_attr_value(&self, id: ExprRef) -> Result<String> {
+        let hir = self.lazy_hir(id)?;
+        let (name, attr) = match hir.data {
+            hir::ExprData::NameAttr(name, attr) => (name.value, attr.value),
+            _ => unreachable!(),
+        };
+        let instance = match attr {
+            hir::NameAttr::SimpleName => return Ok(simple_name(name)),
+            hir::NameAttr::PathName => false,
+            hir::NameAttr::InstanceName => true,
+        };
+        let scope = self
+            .declaring_scope(name.into(), hir.parent)?
+            .unwrap_or(hir.parent);
+        let mut path = String::new();
+        for component in self.name_attr_path(scope, instance)? {
+            write!(path, ":{}", component).unwrap();
+        }
+        write!(path, ":{}", simple_name(name)).unwrap();
+        Ok(path)
+    }
+
+    /// Determine the path of the named entities declared in a scope, as
+    /// reported by `'path_name` or, if `instance` is set, `'instance_name`.
+    ///
+    /// See IEEE 1076-2008 section 16.2.6.
+    fn name_attr_path(&self, scope: ScopeRef, instance: bool) -> Result<Vec<String>> {
+        Ok(match scope {
+            ScopeRef::Lib(_)
+            | ScopeRef::BuiltinPkg(_)
+            | ScopeRef::Pkg(_)
+            | ScopeRef::PkgBody(_) => self.scope_path(scope)?,
+            ScopeRef::CtxItems(id) => self.name_attr_path(self.ast(id).0, instance)?,
+            ScopeRef::Entity(id) => vec![simple_name(self.hir(id)?.name.value)],
+            // Only the instance name mentions the architecture.
+            ScopeRef::Arch(id) => {
+                let hir = self.hir(id)?;
+                let entity = simple_name(self.hir(hir.entity)?.name.value);
+                if instance {
+                    vec![format!("{}({})", entity, simple_name(hir.name.value))]
+                } else {
+                    vec![entity]
+                }
+            }
+            ScopeRef::Process(id) => {
+                let mut path = self.name_attr_path(self.hir(id)?.parent, instance)?;
+                path.extend(self.process_path(id)?.pop());
+                path
+            }
+            ScopeRef::Block(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.name_attr_path(hir.parent, instance)?;
+                path.push(simple_name(hir.label.value));
+                path
+            }
+            // The iterations of a for-generate statement are distinguished by
+            // the value of the generate parameter.
+            ScopeRef::ForGen(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.name_attr_path(hir.parent, instance)?;
+                let label = simple_name(hir.label.value);
+                path.push(match self.sb.gen_params.borrow().get(&id).cloned() {
+                    Some(Const::Int(k)) => format!("{}({})", label, k.value),
+                    Some(Const::Enum(k)) => format!("{}({})", label, k.index),
+                    _ => label,
+                });
+                path
+            }
+            ScopeRef::Loop(_) => self.name_attr_path(self.skip_loop_scopes(scope)?, instance)?,
+            ScopeRef::Subprog(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.name_attr_path(hir.parent, instance)?;
+                path.push(subprog_name(hir.spec.name.value));
+                path
+            }
+            ScopeRef::SubprogBody(id) => {
+                let hir = self.hir(id)?;
+                let mut path = self.name_attr_path(hir.parent, instance)?;
+                path.push(subprog_name(hir.spec.name.value));
+                path
+            }
+            ScopeRef::Protected(id) => {
+                let hir = self.lazy_hir(id)?;
+                let mut path = self.name_attr_path(hir.parent, instance)?;
+                path.push(simple_name(hir.name.value));
+                path
+            }
+        })
+    }
+}
+
+/// Determine the simple name of a named entity as reported by `'simple_name`.
+///
+/// Basic identifiers are reported in lower case, extended identifiers as they
+/// are written.
+fn simple_name(name: Name) -> String {
+    if name.is_case_sensitive() {
+        name.as_str().to_string()
+    } else {
+        name.as_str().to_lowercase()
+    }
+}
+
+/// Determine the path component that names a subprogram.
+fn subprog_name(name: ResolvableName) -> String {
+    match name {
+        ResolvableName::Ident(name) => name.as_str().to_string(),
+        ResolvableName::Operator(op) => operator_mnemonic(op),
+        ResolvableName::Bit(_) => unreachable!(),
+    }
+}
@@ -23,7 +23,8 @@ use crate::typeck::TypeckContext;
 impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Add an expression.
     pub fn add_expr(&self, expr: &'ast ast::Expr) -> Result<ExprRef> {
-        let (mk, _id, scope) = self.make::<ExprRef>(expr.span);
+        let (mk, id, scope) = self.make::<ExprRef>(expr.span);
+        self.ctx.set_ast(id, (scope, expr));
         mk.lower_to_hir(Box::new(move |sbc| {
             let ctx = TermContext::new(sbc, scope);
             let term = ctx.termify_expr(expr)?;
@@ -178,6 +179,266 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     }
 }
 
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Determine the type of an expression.
+    ///
+    /// Works for any expression, including subexpressions, which allows tools
+    /// to show the inferred type of a piece of code. Only the expression and
+    /// the nodes it depends on are type checked; no code is generated.
+    pub fn type_of(&self, id: ExprRef) -> Result<&'ctx Ty> {
+        self.lazy_typeval(id)
+    }
+
+    /// Resolve the operator of a unary expression.
+    ///
+    /// Picks the overload of the operator that matches the type of the
+    /// operand and the type context of the expression.
+    pub fn resolve_unary_op(&self, id: ExprRef) -> Result<Spanned<Def>> {
+        let hir = self.lazy_hir(id)?;
+        let (defs, arg) = match hir.data {
+            hir::ExprData::Unary(_, ref defs, arg) => (defs, arg),
+            _ => unreachable!(),
+        };
+        let req = OverloadReq::Subprog(SignatureReq {
+            return_type: match self.type_context_resolved(id)? {
+                Some(tyctx) => TypeReq::One(tyctx),
+                None => TypeReq::Any,
+            },
+            positional: vec![self.operand_type_req(arg)?],
+            named: HashMap::new(),
+        });
+        let def = resolve_overloads(self, defs, &req, hir.span)?;
+        self.set_operand_type_contexts(def, &[arg])?;
+        Ok(def)
+    }
+
+    /// Resolve the operator of a binary expression.
+    ///
+    /// Picks the overload of the operator that matches the types of the
+    /// operands and the type context of the expression. This may either be a
+    /// builtin operator or a user-defined function.
+    pub fn resolve_binary_op(&self, id: ExprRef) -> Result<Spanned<Def>> {
+        let hir = self.lazy_hir(id)?;
+        let (defs, lhs, rhs) = match hir.data {
+            hir::ExprData::Binary(_, ref defs, lhs, rhs) => (defs, lhs, rhs),
+            _ => unreachable!(),
+        };
+
+        // Assemble an overload resolution requirement based on the
+        // operator's types.
+        let req = OverloadReq::Subprog(SignatureReq {
+            return_type: match self.type_context_resolved(id)? {
+                Some(tyctx) => TypeReq::One(tyctx),
+                None => TypeReq::Any,
+            },
+            positional: vec![self.operand_type_req(lhs)?, self.operand_type_req(rhs)?],
+            named: HashMap::new(),
+        });
+        let def = resolve_overloads(self, defs, &req, hir.span)?;
+        self.set_operand_type_contexts(def, &[lhs, rhs])?;
+        Ok(def)
+    }
+
+    /// Determine the type requirement an operand imposes on an operator.
+    ///
+    /// Overloaded literals such as `'1'` may have any of the types of their
+    /// definitions, and aggregates may have any type.
+    fn operand_type_req(&self, id: ExprRef) -> Result<TypeReq<'ctx>> {
+        if !self.is_overloaded(id)? {
+            return Ok(TypeReq::One(self.argument_type(id)?));
+        }
+        Ok(match self.lazy_hir(id)?.data {
+            hir::ExprData::EnumName(ref defs) => TypeReq::Many(
+                defs.iter()
+                    .map(|def| self.intern_ty(EnumTy::new(def.value.0)))
+                    .collect(),
+            ),
+            _ => TypeReq::Any,
+        })
+    }
+
+    /// Let the overloaded operands of an operator and its integer literals take
+    /// the type of the corresponding argument of the resolved operator.
+    fn set_operand_type_contexts(&self, def: Spanned<Def>, operands: &[ExprRef]) -> Result<()> {
+        let ty = match def.value {
+            Def::BuiltinOp(id) => self.lazy_typeval(id)?,
+            Def::Subprog(id) => self.lazy_typeval(id)?,
+            _ => unreachable!(),
+        };
+        let args = match *ty {
+            Ty::Subprog(ref ty) => &ty.args,
+            _ => unreachable!(),
+        };
+        for (&operand, arg) in operands.iter().zip(args.iter()) {
+            let is_literal = matches!(
+                self.lazy_hir(operand)?.data,
+                hir::ExprData::IntegerLiteral(..)
+            );
+            if is_literal || self.is_overloaded(operand)? {
+                self.set_type_context(operand, self.intern_ty(arg.ty.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether the type of an expression can only be determined from
+    /// its context.
+    ///
+    /// This is the case for literals that belong to several types, like `'1'`
+    /// which is both a `bit` and a `character`, and for aggregates.
+    fn is_overloaded(&self, id: ExprRef) -> Result<bool> {
+        Ok(match self.lazy_hir(id)?.data {
+            hir::ExprData::EnumName(ref defs) => defs.len() > 1,
+            hir::ExprData::StringLiteral(ref defs) => defs.len() > 1,
+            hir::ExprData::Aggregate(..) => true,
+            _ => false,
+        })
+    }
+
+    /// Resolve the subprogram of a call expression.
+    ///
+    /// Picks the overload of the subprogram that matches the types of the
+    /// arguments. The type context is not considered, since it usually is a
+    /// constrained subtype which does not name the return type of any
+    /// overload.
+    pub fn resolve_call(&self, id: ExprRef) -> Result<Spanned<Def>> {
+        let hir = self.lazy_hir(id)?;
+        let (defs, args) = match hir.data {
+            hir::ExprData::SubprogCall(ref defs, ref args) => (defs, args),
+            _ => unreachable!(),
+        };
+
+        // Assemble an overload resolution requirement based on the types of
+        // the arguments.
+        let mut positional = vec![];
+        for arg in &args.value {
+            if let Some(formal) = arg.formal {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "named argument `{}` not supported",
+                        formal.span.extract()
+                    ))
+                    .span(arg.span),
+                );
+                return Err(());
+            }
+            match arg.actual.value {
+                hir::AssocActual::Expr(id) => {
+                    positional.push(TypeReq::One(self.argument_type(id)?));
+                }
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a valid argument",
+                            arg.actual.span.extract()
+                        ))
+                        .span(arg.actual.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        let req = OverloadReq::Subprog(SignatureReq {
+            return_type: TypeReq::Any,
+            positional,
+            named: HashMap::new(),
+        });
+        resolve_overloads(self, defs, &req, hir.span)
+    }
+
+    /// Resolve the call of a method of a protected type.
+    ///
+    /// Selecting a name from an object of a protected type, as in
+    /// `counter.increment` or `counter.increment(1)`, calls the method of that
+    /// name. Methods are only told apart by their number of parameters.
+    /// Returns the object, the method, and the arguments of the call, or
+    /// `None` if the expression is not a method call.
+    pub fn resolve_method(
+        &self,
+        id: ExprRef,
+    ) -> Result<Option<(ExprRef, SubprogDeclRef, &'ctx [hir::AssocElement])>> {
+        let hir = self.lazy_hir(id)?;
+        let (callee, args): (_, &[_]) = match hir.data {
+            hir::ExprData::Select(..) => (hir, &[]),
+            hir::ExprData::Call(callee, ref args) => (self.lazy_hir(callee)?, &args.value),
+            _ => return Ok(None),
+        };
+        let (object, name) = match callee.data {
+            hir::ExprData::Select(object, name) => (object, name),
+            _ => return Ok(None),
+        };
+        let decl = match *self.deref_named_type(self.lazy_typeval(object)?)? {
+            Ty::Protected(decl) => decl,
+            _ => return Ok(None),
+        };
+        let decl_hir = self.lazy_hir(decl)?;
+        let methods = match decl_hir.data {
+            Some(Spanned {
+                value: hir::TypeData::Protected(ref methods),
+                ..
+            }) => methods,
+            _ => unreachable!(),
+        };
+        let mut found = vec![];
+        for &method in methods {
+            let spec = &self.hir(method)?.spec;
+            if spec.name.value == name.value && spec.params.len() == args.len() {
+                found.push(method);
+            }
+        }
+        match found.len() {
+            1 => Ok(Some((object, found[0], args))),
+            0 => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "protected type `{}` has no method `{}` with {} parameters",
+                        decl_hir.name.value,
+                        name.value,
+                        args.len()
+                    ))
+                    .span(name.span),
+                );
+                Err(())
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("call of method `{}` is ambiguous", name.value))
+                        .span(hir.span)
+                        .add_note(
+                            "Methods of protected types are only told apart by their \
+                             number of parameters.",
+                        ),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the type of an argument for overload resolution.
+    ///
+    /// The type of a signal, variable, or constant is usually an anonymous
+    /// constrained subtype. To match the formals of a subprogram, names of
+    /// such objects rather yield the base type mark of their declaration.
+    fn argument_type(&self, id: ExprRef) -> Result<&'ctx Ty> {
+        let subty = match self.lazy_hir(id)?.data {
+            hir::ExprData::SignalName(SignalRef::Decl(id)) => Some(self.lazy_hir(id)?.decl.ty),
+            hir::ExprData::SignalName(SignalRef::Intf(id)) => Some(self.hir(id)?.ty),
+            hir::ExprData::VarName(id) => Some(self.lazy_hir(id)?.decl.ty),
+            hir::ExprData::ConstName(ConstRef::Intf(id)) => Some(self.ast(id).2),
+            _ => None,
+        };
+        let subty = match subty {
+            Some(subty) => subty,
+            None => return self.lazy_typeval(id),
+        };
+        let mut tm = self.lazy_hir(subty)?.type_mark;
+        while let TypeMarkRef::Subtype(id) = tm.value {
+            tm = self.lazy_hir(self.hir(id)?.subty)?.type_mark;
+        }
+        Ok(self.intern_ty(Ty::Named(tm.span.into(), tm.value)))
+    }
+}
+
 /// Evaluate the type of an expression.
 pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
     tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
@@ -186,10 +447,26 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
     tyctx: Option<&'ctx Ty>,
 ) -> Result<&'ctx Ty> {
     match hir.data {
-        hir::ExprData::ConstName(id) => tyc.ctx.lazy_typeval(id),
+        hir::ExprData::ConstName(ConstRef::Decl(id)) => tyc.ctx.lazy_typeval(id),
+        hir::ExprData::ConstName(ConstRef::Intf(id)) => tyc.ctx.ty(id),
+        hir::ExprData::ConstName(ConstRef::Gen(id)) => tyc.ctx.ty(id),
+        hir::ExprData::ConstName(ConstRef::Loop(id)) => tyc.ctx.ty(id),
         hir::ExprData::SignalName(id) => tyc.ctx.ty(id),
+        hir::ExprData::SignalAttr(id, attr) => match attr.value {
+            hir::SignalAttr::Event | hir::SignalAttr::Stable => {
+                Ok(tyc.ctx.intern_ty(BOOLEAN_TYPE.named_ty()))
+            }
+            hir::SignalAttr::LastValue => tyc.ctx.ty(id),
+        },
         hir::ExprData::VarName(id) => tyc.ctx.lazy_typeval(id),
         hir::ExprData::FileName(id) => tyc.ctx.lazy_typeval(id),
+        hir::ExprData::NameAttr(..) => Ok(tyc.ctx.builtin_string_type()),
+        hir::ExprData::ImageAttr(ref tm, expr) => {
+            let ty = tyc.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value));
+            let expr_ty = tyc.lazy_typeval(expr)?;
+            tyc.must_match(ty, expr_ty, tyc.ctx.span(expr).unwrap());
+            Ok(tyc.ctx.builtin_string_type())
+        }
         hir::ExprData::EnumName(ref defs) => {
             // Enums are generally overloaded. The type context is needed to
             // pick one of the available variants.
@@ -303,6 +580,7 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
             // );
             // Err(())
         }
+        hir::ExprData::PhysicalLiteral(_, unit) => tyc.ctx.ty(unit.value.0),
         hir::ExprData::Qualified(ref tm, expr) => {
             let ty = tyc.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value));
             let expr_ty = tyc.lazy_typeval(expr)?;
@@ -315,73 +593,255 @@ pub fn typeval_expr<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
                 let expr_ty = tyc.lazy_typeval(expr)?;
                 tyc.must_match(ty, expr_ty, tyc.ctx.span(expr).unwrap());
             }
-            Ok(ty)
+            // The allocator yields an access value, the type of which can only
+            // be determined from the context.
+            match tyctx {
+                Some(tyctx) => match *tyc.ctx.deref_named_type(tyctx)? {
+                    Ty::Access(ref designated) => {
+                        tyc.must_match(designated, ty, tm.span);
+                        Ok(tyctx)
+                    }
+                    _ => {
+                        tyc.emit(
+                            DiagBuilder2::error(format!(
+                                "allocator `{}` used where a value of type {} is expected",
+                                hir.span.extract(),
+                                tyctx
+                            ))
+                            .span(hir.span),
+                        );
+                        Err(())
+                    }
+                },
+                None => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "type of allocator `{}` cannot be inferred from context",
+                            hir.span.extract()
+                        ))
+                        .span(hir.span),
+                    );
+                    Err(())
+                }
+            }
+        }
+        hir::ExprData::Deref(prefix) => {
+            let prefix_ty = tyc.lazy_typeval(prefix)?;
+            match *tyc.ctx.deref_named_type(prefix_ty)? {
+                Ty::Access(ref designated) => Ok(&**designated),
+                _ => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not an access value and cannot be dereferenced",
+                            tyc.ctx.span(prefix).unwrap().extract()
+                        ))
+                        .span(hir.span),
+                    );
+                    Err(())
+                }
+            }
         }
         hir::ExprData::Cast(ref tm, expr) => {
             let ty = tyc.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value));
             let expr_ty = tyc.lazy_typeval(expr)?;
-            tyc.must_cast(ty, expr_ty, tyc.ctx.span(expr).unwrap());
+            if !tyc.must_cast(ty, expr_ty, tyc.ctx.span(expr).unwrap()) {
+                return Err(());
+            }
             Ok(ty)
         }
+        hir::ExprData::Select(prefix, name) => {
+            if let Some((_, method, args)) = tyc.ctx.resolve_method(expr_id)? {
+                return typeval_method_call(tyc, hir, method, args);
+            }
+            let prefix_ty = tyc.lazy_typeval(prefix)?;
+            let record_ty = match *tyc.ctx.deref_named_type(prefix_ty)? {
+                Ty::Record(ref ty) => ty,
+                _ => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a record and has no field `{}`",
+                            tyc.ctx.span(prefix).unwrap().extract(),
+                            name.value
+                        ))
+                        .span(name.span),
+                    );
+                    return Err(());
+                }
+            };
+            let index = match name.value {
+                ResolvableName::Ident(name) => record_ty.lookup.get(&name).cloned(),
+                _ => None,
+            };
+            match index {
+                Some(index) => Ok(tyc.ctx.intern_ty((*record_ty.fields[index].1).clone())),
+                None => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "type {} has no field `{}`",
+                            prefix_ty, name.value
+                        ))
+                        .span(name.span),
+                    );
+                    Err(())
+                }
+            }
+        }
+        hir::ExprData::Call(prefix, ref args) => {
+            if let Some((_, method, args)) = tyc.ctx.resolve_method(expr_id)? {
+                return typeval_method_call(tyc, hir, method, args);
+            }
+            // Calls of an array are index operations.
+            let prefix_ty = tyc.lazy_typeval(prefix)?;
+            let array_ty = match *tyc.ctx.deref_named_type(prefix_ty)? {
+                Ty::Array(ref ty) => ty,
+                _ => {
+                    tyc.emit(
+                        DiagBuilder2::bug(format!(
+                            "typeval for call `{}` not implemented",
+                            hir.span.extract()
+                        ))
+                        .span(hir.span),
+                    );
+                    return Err(());
+                }
+            };
+            if args.value.len() != array_ty.indices.len() {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` needs {} indices, but {} were given",
+                        tyc.ctx.span(prefix).unwrap().extract(),
+                        array_ty.indices.len(),
+                        args.value.len()
+                    ))
+                    .span(args.span),
+                );
+                return Err(());
+            }
+            let mut had_fails = false;
+            for (arg, index) in args.value.iter().zip(array_ty.indices.iter()) {
+                let arg_id = match *arg {
+                    hir::AssocElement {
+                        formal: None,
+                        actual:
+                            Spanned {
+                                value: hir::AssocActual::Expr(id),
+                                ..
+                            },
+                        ..
+                    } => id,
+                    _ => {
+                        tyc.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is not a valid index",
+                                arg.span.extract()
+                            ))
+                            .span(arg.span),
+                        );
+                        had_fails = true;
+                        continue;
+                    }
+                };
+                let index_ty = tyc.ctx.intern_ty(index.ty().clone());
+                tyc.ctx.set_type_context(arg_id, index_ty);
+                match tyc.lazy_typeval(arg_id) {
+                    Ok(arg_ty) => had_fails |= !tyc.must_match(index_ty, arg_ty, arg.span),
+                    Err(()) => had_fails = true,
+                }
+            }
+            if had_fails {
+                return Err(());
+            }
+            Ok(tyc.ctx.intern_ty((*array_ty.element).clone()))
+        }
         hir::ExprData::Aggregate(id) => {
             tyc.ctx
                 .set_type_context(id, TypeCtx::Inherit(expr_id.into()));
             tyc.ctx.lazy_typeval(id)
         }
-        hir::ExprData::Unary(op, ref defs, arg) => {
-            // Assemble an overload resolution requirement based on the
-            // operator's types.
-            let req = OverloadReq::Subprog(SignatureReq {
-                return_type: match tyctx {
-                    Some(tyctx) => TypeReq::One(tyctx),
-                    None => TypeReq::Any,
-                },
-                positional: vec![TypeReq::One(tyc.lazy_typeval(arg)?)],
-                named: HashMap::new(),
-            });
-
-            // Resolve the overload.
-            let def = resolve_overloads(tyc.ctx, defs, &req, hir.span)?;
+        hir::ExprData::Unary(op, _, _) => {
+            // Resolve the overload and use the return type of the chosen
+            // operator.
+            let def = tyc.ctx.resolve_unary_op(expr_id)?;
             debugln!("unary operator `{}` resolved to {:?}", op.value, def);
-
-            tyc.emit(
-                DiagBuilder2::bug(format!(
-                    "typeval for unary operation `{}` not implemented",
-                    hir.span.extract()
-                ))
-                .span(hir.span),
-            );
-            debugln!("Defs are {:?}", defs);
-            Err(())
+            let ty = match def.value {
+                Def::BuiltinOp(id) => tyc.lazy_typeval(id)?,
+                Def::Subprog(id) => tyc.lazy_typeval(id)?,
+                _ => unreachable!(),
+            };
+            match *ty {
+                Ty::Subprog(SubprogTy {
+                    ret: Some(ref ret), ..
+                }) => Ok(tyc.ctx.intern_ty((**ret).clone())),
+                _ => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "operator `{}` does not return a value",
+                            op.value
+                        ))
+                        .span(op.span),
+                    );
+                    Err(())
+                }
+            }
         }
-        hir::ExprData::Binary(op, ref defs, lhs, rhs) => {
-            // Assemble an overload resolution requirement based on the
-            // operator's types.
-            let req = OverloadReq::Subprog(SignatureReq {
-                return_type: match tyctx {
-                    Some(tyctx) => TypeReq::One(tyctx),
-                    None => TypeReq::Any,
-                },
-                positional: vec![
-                    TypeReq::One(tyc.lazy_typeval(lhs)?),
-                    TypeReq::One(tyc.lazy_typeval(rhs)?),
-                ],
-                named: HashMap::new(),
-            });
-
-            // Resolve the overload.
-            let def = resolve_overloads(tyc.ctx, defs, &req, hir.span)?;
+        hir::ExprData::SubprogCall(_, ref args) => {
+            // Resolve the overload and use the return type of the chosen
+            // function. The arguments take the types of the formals.
+            let def = tyc.ctx.resolve_call(expr_id)?;
+            debugln!("call `{}` resolved to {:?}", hir.span.extract(), def);
+            let ty = match def.value {
+                Def::Subprog(id) => tyc.lazy_typeval(id)?,
+                _ => unreachable!(),
+            };
+            let ty = match *ty {
+                Ty::Subprog(ref ty) => ty,
+                _ => unreachable!(),
+            };
+            for (arg, formal) in args.value.iter().zip(ty.args.iter()) {
+                if let hir::AssocActual::Expr(id) = arg.actual.value {
+                    tyc.ctx
+                        .set_type_context(id, tyc.ctx.intern_ty(formal.ty.clone()));
+                }
+            }
+            match ty.ret {
+                Some(ref ret) => Ok(tyc.ctx.intern_ty((**ret).clone())),
+                None => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` does not return a value",
+                            hir.span.extract()
+                        ))
+                        .span(hir.span),
+                    );
+                    Err(())
+                }
+            }
+        }
+        hir::ExprData::Binary(op, _, _, _) => {
+            // Resolve the overload and use the return type of the chosen
+            // operator.
+            let def = tyc.ctx.resolve_binary_op(expr_id)?;
             debugln!("binary operator `{}` resolved to {:?}", op.value, def);
-
-            tyc.emit(
-                DiagBuilder2::bug(format!(
-                    "typeval for binary operation `{}` not implemented",
-                    hir.span.extract()
-                ))
-                .span(hir.span),
-            );
-            debugln!("Defs are {:?}", defs);
-            Err(())
+            let ty = match def.value {
+                Def::BuiltinOp(id) => tyc.lazy_typeval(id)?,
+                Def::Subprog(id) => tyc.lazy_typeval(id)?,
+                _ => unreachable!(),
+            };
+            match *ty {
+                Ty::Subprog(SubprogTy {
+                    ret: Some(ref ret), ..
+                }) => Ok(tyc.ctx.intern_ty((**ret).clone())),
+                _ => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "operator `{}` does not return a value",
+                            op.value
+                        ))
+                        .span(op.span),
+                    );
+                    Err(())
+                }
+            }
         }
         _ => {
             tyc.emit(
@@ -489,10 +949,47 @@ pub fn typeval_record_aggregate<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx:
             return Err(());
         }
     }
-    if let Some(_others) = hir.others {
+    if let Some(others) = hir.others {
         let indices: Vec<_> = (0..record_ty.fields.len())
             .filter(|i| !mapping.contains_key(i))
             .collect();
+
+        if indices.is_empty() {
+            tyc.emit(
+                DiagBuilder2::error(format!(
+                    "`others` in aggregate `{}` does not cover any fields",
+                    hir.span.extract()
+                ))
+                .span(others.span),
+            );
+            return Err(());
+        }
+
+        // The fields covered by `others` must all have the same type, such
+        // that the one expression is assignable to each of them.
+        let mut covered: Option<(Name, &Ty)> = None;
+        for &type_index in &indices {
+            let (name, ref ty) = record_ty.fields[type_index];
+            let ty_flat = tyc.ctx.deref_named_type(ty)?;
+            match covered {
+                None => covered = Some((name, ty_flat)),
+                Some((first_name, first_ty)) if first_ty != ty_flat => {
+                    tyc.emit(
+                        DiagBuilder2::error(format!(
+                            "`others` in aggregate `{}` covers fields of different types",
+                            hir.span.extract()
+                        ))
+                        .span(others.span)
+                        .add_note(format!(
+                            "Field `{}` has type {}, but field `{}` has type {}",
+                            first_name, first_ty, name, ty_flat
+                        )),
+                    );
+                    return Err(());
+                }
+                Some(_) => (),
+            }
+        }
         for type_index in indices {
             mapping.insert(type_index, FieldIndex::Others);
         }
@@ -657,3 +1154,116 @@ pub fn typeck_array_aggregate_choice<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, '
     }
     Ok(())
 }
+
+/// Evaluate the type of a call of a method of a protected type.
+fn typeval_method_call<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    hir: &hir::Expr,
+    method: SubprogDeclRef,
+    args: &[hir::AssocElement],
+) -> Result<&'ctx Ty> {
+    match typeck_method_args(tyc, method, args)?.ret {
+        Some(ref ret) => Ok(tyc.ctx.intern_ty((**ret).clone())),
+        None => {
+            tyc.emit(
+                DiagBuilder2::error(format!("`{}` does not return a value", hir.span.extract()))
+                    .span(hir.span),
+            );
+            Err(())
+        }
+    }
+}
+
+/// Check the arguments of a call of a method of a protected type.
+///
+/// The arguments take the types of the method's parameters. Returns the type
+/// of the method.
+pub fn typeck_method_args<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    method: SubprogDeclRef,
+    args: &[hir::AssocElement],
+) -> Result<&'ctx SubprogTy> {
+    let ty = match *tyc.lazy_typeval(method)? {
+        Ty::Subprog(ref ty) => ty,
+        _ => unreachable!(),
+    };
+    let mut had_fails = false;
+    for (arg, formal) in args.iter().zip(ty.args.iter()) {
+        let arg_id = match *arg {
+            hir::AssocElement {
+                formal: None,
+                actual:
+                    Spanned {
+                        value: hir::AssocActual::Expr(id),
+                        ..
+                    },
+                ..
+            } => id,
+            _ => {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a valid method argument",
+                        arg.span.extract()
+                    ))
+                    .span(arg.span)
+                    .add_note("Only positional arguments are supported."),
+                );
+                had_fails = true;
+                continue;
+            }
+        };
+        let formal_ty = tyc.ctx.intern_ty(formal.ty.clone());
+        tyc.ctx.set_type_context(arg_id, formal_ty);
+        match tyc.lazy_typeval(arg_id) {
+            Ok(arg_ty) => had_fails |= !tyc.must_match(formal_ty, arg_ty, arg.span),
+            Err(()) => had_fails = true,
+        }
+    }
+    if had_fails {
+        Err(())
+    } else {
+        Ok(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, first_arch, score_test};
+    use moore_common::Session;
+
+    #[test]
+    fn type_of_binary_expr() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     constant a, b : integer := 1;
+                     constant c : integer := a + b;
+                 begin end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            let id = match *ctx.hir(arch).unwrap().decls.last().unwrap() {
+                DeclInBlockRef::Const(id) => id,
+                _ => panic!("expected a constant declaration"),
+            };
+            let decl: &hir::Decl<hir::ConstDecl> = ctx.lazy_hir(id).unwrap();
+            let expr = decl.decl.init.unwrap();
+            let hir: &hir::Expr = ctx.lazy_hir(expr).unwrap();
+            match hir.data {
+                hir::ExprData::Binary(..) => (),
+                ref data => panic!("expected a binary expression, got {:?}", data),
+            }
+            let ty = ctx.type_of(expr).unwrap();
+            match *ctx.deref_named_type(ty).unwrap() {
+                Ty::Int(_) => (),
+                ref ty => panic!("expected an integer type, got {}", ty),
+            }
+        });
+    }
+}
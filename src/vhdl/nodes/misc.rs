@@ -60,3 +60,121 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }));
     }
 }
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Determine the resolution indication of a subtype indication.
+    ///
+    /// Subtype indications without a resolution indication of their own
+    /// inherit the one of the subtype named by their type mark. Returns `None`
+    /// for unresolved subtypes.
+    pub fn subtype_resolution(
+        &self,
+        id: SubtypeIndRef,
+    ) -> Result<Option<&'ctx Spanned<hir::ResolInd>>> {
+        let mut hir = self.lazy_hir(id)?;
+        loop {
+            if let Some(ref resol) = hir.resol {
+                return Ok(Some(resol));
+            }
+            match hir.type_mark.value {
+                TypeMarkRef::Subtype(id) => hir = self.lazy_hir(self.hir(id)?.subty)?,
+                TypeMarkRef::Type(_) => return Ok(None),
+            }
+        }
+    }
+
+    /// Determine the base type of a type mark.
+    ///
+    /// Subtypes are followed to the type mark of their declaration until a
+    /// type is reached. For example, both `natural` and a declared `subtype
+    /// byte is integer range 0 to 255` yield `integer`.
+    pub fn base_type_mark(&self, tm: TypeMarkRef) -> Result<TypeMarkRef> {
+        let mut tm = tm;
+        loop {
+            tm = match tm {
+                TypeMarkRef::Subtype(id) => self.lazy_hir(self.hir(id)?.subty)?.type_mark.value,
+                TypeMarkRef::Type(id) => match builtin_subtype_base(id) {
+                    Some(base) => base.into(),
+                    None => return Ok(tm),
+                },
+            };
+        }
+    }
+
+    /// Determine the base type of an integer type.
+    ///
+    /// Returns `None` for types that are not integers, and for anonymous
+    /// integer types that do not constrain a named type.
+    pub fn int_base_type(&self, ty: &Ty) -> Result<Option<TypeMarkRef>> {
+        Ok(match *ty {
+            Ty::Named(_, tm) => match *self.deref_named_type(ty)? {
+                Ty::Int(_) => Some(self.base_type_mark(tm)?),
+                _ => None,
+            },
+            Ty::Int(ref ty) => ty.base,
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, first_arch, score_test};
+    use moore_common::Session;
+
+    #[test]
+    fn resolution_functions() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     type std_ulogic is ('U', 'X', '0', '1', 'Z');
+                     type std_ulogic_vector is array (natural range <>) of std_ulogic;
+                     function resolved (s : std_ulogic_vector) return std_ulogic;
+                     subtype rl is resolved std_ulogic;
+                     signal a : rl;
+                     signal b : resolved std_ulogic;
+                     signal c : std_ulogic;
+                 begin end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            // Map each subtype indication to the name of its resolution
+            // function.
+            let resolution = |id: SubtypeIndRef| -> Option<String> {
+                let resol = ctx.subtype_resolution(id).unwrap()?;
+                let defs = match resol.value {
+                    hir::ResolInd::Func(ref defs) => defs,
+                    ref resol => panic!("expected a resolution function, got {:?}", resol),
+                };
+                match defs[0].value {
+                    Def::Subprog(id) => Some(ctx.hir(id).unwrap().spec.name.value.to_string()),
+                    def => panic!("expected a subprogram, got {:?}", def),
+                }
+            };
+            let resolutions: Vec<_> = ctx
+                .hir(arch)
+                .unwrap()
+                .decls
+                .iter()
+                .filter_map(|&decl| match decl {
+                    DeclInBlockRef::Subtype(id) => Some(ctx.hir(id).unwrap().subty),
+                    DeclInBlockRef::Signal(id) => Some(ctx.lazy_hir(id).unwrap().decl.ty),
+                    _ => None,
+                })
+                .map(resolution)
+                .collect();
+            let resolved = Some(String::from("resolved"));
+            assert_eq!(
+                resolutions,
+                vec![resolved.clone(), resolved.clone(), resolved, None]
+            );
+            assert!(!sess.failed());
+        });
+    }
+}
@@ -12,9 +12,11 @@ use crate::common::util::{HasDesc, HasSpan};
 
 use crate::add_ctx::AddContext;
 use crate::hir;
+use crate::nodes::expr::typeck_method_args;
 use crate::score::*;
 use crate::syntax::ast;
 use crate::term::TermContext;
+use crate::typeck::Typeck;
 
 impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Add multiple sequential statements.
@@ -210,8 +212,21 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     }
 
     /// Add a sig_assign statement.
+    ///
+    /// The HIR of signal assignments is still lowered through the scoreboard's
+    /// `SigAssignStmtRef` node maker, so we only register the AST here.
     pub fn add_sig_assign_stmt(&self, stmt: &'ast ast::Stmt) -> Result<SigAssignStmtRef> {
-        self.unimp(stmt)
+        match stmt.data {
+            ast::AssignStmt { .. } => (),
+            _ => return self.unimp(stmt),
+        }
+        let (mk, id, scope) = self.make::<SigAssignStmtRef>(stmt.span);
+        self.ctx.set_ast(id, (scope, stmt));
+        mk.typeck(Box::new(move |tyc| {
+            tyc.typeck(id);
+            Ok(())
+        }));
+        Ok(mk.finish())
     }
 
     /// Add a var_assign statement.
@@ -276,24 +291,44 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         mk.lower_to_hir(Box::new(move |sbc| {
             let ctx = AddContext::new(sbc, scope);
             let target = ctx.add_target(target);
+
+            // The assigned values must match the type of the target.
+            let target_ty: Option<TypedNodeRef> = match target {
+                Ok(Spanned {
+                    value: hir::Target::Name(expr),
+                    ..
+                }) => Some(expr.into()),
+                _ => None,
+            };
+            let add_value = |expr| -> Result<ExprRef> {
+                let expr = ctx.add_expr(expr)?;
+                if let Some(ty) = target_ty {
+                    sbc.set_type_context(expr, ty);
+                }
+                Ok(expr)
+            };
+
             let kind = match kind {
                 Kind::Simple(expr) => {
-                    let expr = ctx.add_expr(expr);
+                    let expr = add_value(expr);
                     hir::VarAssignKind::Simple(expr?)
                 }
                 Kind::Cond(ref conds, ref otherwise) => {
                     let conds = conds
                         .into_iter()
                         .map(|&(e, c)| {
-                            let e = ctx.add_expr(e);
+                            let e = add_value(e);
                             let c = ctx.add_expr(c);
+                            if let Ok(c) = c {
+                                sbc.set_type_context(c, sbc.builtin_boolean_type());
+                            }
                             Ok((e?, c?))
                         })
                         .collect::<Vec<Result<_>>>()
                         .into_iter()
                         .collect::<Result<Vec<_>>>();
                     let otherwise = match *otherwise {
-                        Some(o) => Some(ctx.add_expr(o)?),
+                        Some(o) => Some(add_value(o)?),
                         None => None,
                     };
                     hir::VarAssignKind::Cond(hir::Cond {
@@ -310,7 +345,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                     let exprs = exprs
                         .into_iter()
                         .map(|&(e, ref c)| {
-                            let e = ctx.add_expr(e);
+                            let e = add_value(e);
                             let c = ctx.add_choices(c.as_ref().map(|i| i.iter()));
                             Ok((e?, c?))
                         })
@@ -343,7 +378,57 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
 
     /// Add a call statement.
     pub fn add_call_stmt(&self, stmt: &'ast ast::Stmt) -> Result<CallStmtRef> {
-        self.unimp(stmt)
+        let name = match stmt.data {
+            ast::InstOrCallStmt {
+                target: None,
+                ref name,
+                generics: None,
+                ports: None,
+            } => name,
+            _ => return self.unimp(stmt),
+        };
+        let (mk, id, scope) = self.make::<CallStmtRef>(stmt.span);
+        mk.lower_to_hir(Box::new(move |sbc| {
+            let ctx = TermContext::new(sbc, scope);
+            let term = ctx.termify_compound_name(name)?;
+            Ok(hir::Stmt {
+                parent: scope,
+                span: stmt.span,
+                label: stmt.label,
+                stmt: hir::CallStmt {
+                    call: ctx.term_to_expr(term)?,
+                },
+            })
+        }));
+        mk.typeck(Box::new(move |tyc| {
+            let hir = tyc.ctx.lazy_hir(id)?;
+            let (method, args) = match tyc.ctx.resolve_method(hir.stmt.call)? {
+                Some((_, method, args)) => (method, args),
+                None => {
+                    tyc.emit(
+                        DiagBuilder2::bug(format!(
+                            "call of procedure `{}` not implemented",
+                            name.span.extract()
+                        ))
+                        .span(name.span)
+                        .add_note("Only methods of protected types can be called."),
+                    );
+                    return Err(());
+                }
+            };
+            if typeck_method_args(tyc, method, args)?.ret.is_some() {
+                tyc.emit(
+                    DiagBuilder2::error(format!(
+                        "function `{}` cannot be called as a statement",
+                        name.span.extract()
+                    ))
+                    .span(name.span),
+                );
+                return Err(());
+            }
+            Ok(())
+        }));
+        Ok(mk.finish())
     }
 
     /// Add an if statement.
@@ -450,8 +535,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         Ok(mk.finish())
     }
 
-    /// Add a while loop statement.
-    #[allow(unused_variables)]
+    /// Add a loop statement.
     pub fn add_loop_stmt(&self, stmt: &'ast ast::Stmt) -> Result<LoopStmtRef> {
         let (mk, id, scope) = self.make::<LoopStmtRef>(stmt.span);
         let (scheme, body) = match stmt.data {
@@ -462,17 +546,30 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             _ => unreachable!(),
         };
         mk.lower_to_hir(Box::new(move |sbc| {
-            // TODO: Make a subscope here! No clue how...
+            // The parameter of a `for` loop is declared in a scope of its own,
+            // which encloses the loop body.
+            let body_scope = match *scheme {
+                ast::LoopScheme::For(name, _) => {
+                    sbc.subscope(id.into(), scope);
+                    sbc.define(id.into(), name.map_into(), Def::Const(ConstRef::Loop(id)))?;
+                    id.into()
+                }
+                _ => scope,
+            };
             let ctx = AddContext::new(sbc, scope);
             let scheme = (|| match *scheme {
                 ast::LoopScheme::Loop => Ok(hir::LoopScheme::Loop),
-                ast::LoopScheme::While(ref cond) => Ok(hir::LoopScheme::While(ctx.add_expr(cond)?)),
+                ast::LoopScheme::While(ref cond) => {
+                    let cond = ctx.add_expr(cond)?;
+                    sbc.set_type_context(cond, sbc.builtin_boolean_type());
+                    Ok(hir::LoopScheme::While(cond))
+                }
                 ast::LoopScheme::For(name, ref range) => Ok(hir::LoopScheme::For(
                     name.into(),
                     ctx.add_discrete_range(range)?,
                 )),
             })();
-            let stmts = ctx.add_seq_stmts(&body.stmts, "a loop body");
+            let stmts = AddContext::new(sbc, body_scope).add_seq_stmts(&body.stmts, "a loop body");
             let (scheme, stmts) = (scheme?, stmts?);
             Ok(hir::Stmt {
                 parent: scope,
@@ -486,7 +583,10 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }));
         mk.typeck(Box::new(move |tyc| {
             let hir = tyc.ctx.lazy_hir(id)?;
-            // TODO: Check the types of the loop scheme.
+            // TODO: Check the type of the loop condition.
+            if let hir::LoopScheme::For(..) = hir.stmt.scheme {
+                tyc.ctx.ty(id)?;
+            }
             tyc.typeck_slice(&hir.stmt.stmts);
             Ok(())
         }));
@@ -510,30 +610,17 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                 ast::NexitMode::Next => hir::NexitMode::Next,
                 ast::NexitMode::Exit => hir::NexitMode::Exit,
             };
-            let target = ctx.add_optional(target, AddContext::add_label);
-            let cond = ctx.add_optional(cond, AddContext::add_expr);
-            let (target, cond) = (target?, cond?);
-            let target = match target {
-                Some(Spanned {
-                    value: StmtRef::Seq(SeqStmtRef::Loop(id)),
-                    span,
-                }) => Some(Spanned::new(id, span)),
-                Some(Spanned { span, .. }) => {
-                    sbc.emit(
-                        DiagBuilder2::error(format!("`{}` is not a loop", span.extract()))
-                            .span(span),
-                    );
-                    return Err(());
-                }
-                None => None,
-            };
+            let cond = ctx.add_optional(cond, AddContext::add_expr)?;
+            if let Some(cond) = cond {
+                sbc.set_type_context(cond, sbc.builtin_boolean_type());
+            }
             Ok(hir::Stmt {
                 parent: scope,
                 span: stmt.span,
                 label: stmt.label,
                 stmt: hir::NexitStmt {
                     mode: mode,
-                    target: target,
+                    target: *target,
                     cond: cond,
                 },
             })
@@ -556,8 +643,12 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         mk.lower_to_hir(Box::new(move |sbc| {
             let ctx = AddContext::new(sbc, scope);
             let expr = ctx.add_optional(expr, AddContext::add_expr)?;
-            // TODO: Set a type context for the expression based on the outer
-            // function signature.
+            let subprog = sbc.skip_loop_scopes(scope)?;
+            if let (Some(expr), ScopeRef::SubprogBody(body)) = (expr, subprog) {
+                if let Some(ty) = sbc.subprog_return_type(&sbc.hir(body)?.spec)? {
+                    sbc.set_type_context(expr, sbc.intern_ty(ty));
+                }
+            }
             Ok(hir::Stmt {
                 parent: scope,
                 span: stmt.span,
@@ -653,7 +744,7 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         if any_cond {
             // Cut away the optional trailing else without condition.
             let (slice, otherwise) = match *ast.last().unwrap() {
-                ast::CondWave(ref wave, None) => (&ast[..ast.len() - 2], Some(wave)),
+                ast::CondWave(ref wave, None) => (&ast[..ast.len() - 1], Some(wave)),
                 _ => (&ast[..], None),
             };
             let conds = slice
@@ -0,0 +1,65 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Subprogram declarations
+
+use crate::common::score::Result;
+
+use crate::add_ctx::AddContext;
+use crate::hir;
+use crate::score::*;
+use crate::syntax::ast;
+use crate::ty::*;
+use crate::typeck::TypeckContext;
+
+impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
+    /// Add a subprogram declaration.
+    ///
+    /// The HIR of the declaration is still lowered by the scoreboard's node
+    /// maker. This function only schedules the evaluation of its type, which
+    /// is required for overload resolution.
+    pub fn add_subprog_decl(&self, decl: &'ast ast::Subprog) -> Result<SubprogDeclRef> {
+        let (mk, id, scope) = self.make::<SubprogDeclRef>(decl.span);
+        self.ctx.set_ast(id, (scope, decl));
+        mk.typeval(Box::new(move |tyc| {
+            let hir = tyc.ctx.hir(id)?;
+            typeval_subprog_spec(tyc, &hir.spec)
+        }));
+        Ok(mk.finish())
+    }
+}
+
+/// Evaluate the type of a subprogram specification.
+pub fn typeval_subprog_spec<'sbc, 'lazy: 'sbc, 'sb: 'lazy, 'ast: 'sb, 'ctx: 'sb>(
+    tyc: &TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx>,
+    hir: &hir::SubprogSpec,
+) -> Result<&'ctx Ty> {
+    let args = hir
+        .params
+        .iter()
+        .map(|&param| {
+            let name = match param {
+                IntfObjRef::Const(id) => tyc.ctx.ast(id).3.name,
+                IntfObjRef::Signal(id) => tyc.ctx.hir(id)?.name.value,
+                // Other parameter classes are rejected during lowering.
+                IntfObjRef::Var(..) | IntfObjRef::File(..) => unreachable!(),
+            };
+            Ok(SubprogTyArg::named(tyc.ctx.ty(param)?.clone(), name))
+        })
+        .collect::<Vec<Result<_>>>()
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    let ret = tyc.ctx.subprog_return_type(hir)?;
+    Ok(tyc.ctx.intern_ty(SubprogTy::new(args, ret)))
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Determine the return type of a subprogram specification.
+    ///
+    /// Returns `None` for procedures.
+    pub fn subprog_return_type(&self, hir: &hir::SubprogSpec) -> Result<Option<Ty>> {
+        Ok(match hir.return_type {
+            Some(ref rt) => Some(Ty::Named(rt.span.into(), self.hir(rt.value)?.value)),
+            None => None,
+        })
+    }
+}
@@ -6,11 +6,13 @@ use crate::common::errors::*;
 use crate::common::name::Name;
 use crate::common::score::{NodeRef, Result};
 use crate::common::source::Spanned;
+use crate::common::util::{HasDesc, HasSpan};
 
 use num::BigInt;
 
 use crate::add_ctx::AddContext;
 use crate::hir;
+use crate::make_ctx::MakeContext;
 use crate::score::*;
 use crate::syntax::ast;
 use crate::term::{Term, TermContext};
@@ -19,8 +21,38 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Add a type declaration.
     pub fn add_type_decl(&self, decl: &'ast ast::TypeDecl) -> Result<TypeDeclRef> {
         let (mk, id, scope) = self.make(decl.span);
-        self.ctx
-            .define(scope, decl.name.map_into(), Def::Type(id))?;
+        // The body of a protected type shares the name of the type.
+        match decl.data {
+            Some(Spanned {
+                value: ast::ProtectedBody(..),
+                ..
+            }) => (),
+            _ => self
+                .ctx
+                .define(scope, decl.name.map_into(), Def::Type(id))?,
+        }
+        self.schedule_type_decl(mk, decl)
+    }
+
+    /// Add a type declaration of a block, process, or subprogram.
+    ///
+    /// Unlike `add_type_decl`, the type is not defined in the scope, since the
+    /// definitions of these scopes are gathered from the AST of their
+    /// declarations. See `DefsContext::declare_type`.
+    pub fn add_local_type_decl(&self, decl: &'ast ast::TypeDecl) -> Result<TypeDeclRef> {
+        let (mk, id, scope) = self.make(decl.span);
+        self.ctx.set_ast(id, (scope, decl));
+        self.schedule_type_decl(mk, decl)
+    }
+
+    /// Schedule the lowering and type checking of a type declaration.
+    fn schedule_type_decl(
+        &self,
+        mk: MakeContext<'sbc, 'lazy, 'sb, 'ast, 'ctx, TypeDeclRef>,
+        decl: &'ast ast::TypeDecl,
+    ) -> Result<TypeDeclRef> {
+        let id = mk.id;
+        let scope = self.scope;
         mk.lower_to_hir(Box::new(move |sbc| {
             let ctx = AddContext::new(sbc, scope);
             Ok(hir::TypeDecl {
@@ -272,9 +304,40 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> AddContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                 hir::TypeData::Record(fields)
             }
 
-            ast::ProtectedType(..) => {
-                self.emit(DiagBuilder2::fatal("protected types not implemented").span(name.span));
-                return Err(());
+            // The methods and variables of a protected type are declared in
+            // a scope of their own.
+            ast::ProtectedType(ref items) => {
+                let ctx = self.with_scope(ScopeRef::Protected(id));
+                let mut methods = vec![];
+                let mut had_fails = false;
+                for item in items {
+                    match *item {
+                        ast::DeclItem::SubprogDecl(ref decl)
+                            if decl.data == ast::SubprogData::Decl =>
+                        {
+                            methods.push(ctx.add_subprog_decl(decl)?);
+                        }
+                        ast::DeclItem::UseClause(..) => (),
+                        ref wrong => {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "a {} cannot appear in a protected type declaration",
+                                    wrong.desc()
+                                ))
+                                .span(wrong.human_span()),
+                            );
+                            had_fails = true;
+                        }
+                    }
+                }
+                if had_fails {
+                    return Err(());
+                }
+                hir::TypeData::Protected(methods)
+            }
+
+            ast::ProtectedBody(ref items) => {
+                hir::TypeData::ProtectedBody(self.ctx.unpack_subprog_decls(id.into(), items)?)
             }
         };
         Ok(Spanned::new(td, data.span))
@@ -29,10 +29,10 @@ pub enum OverloadReq<'ctx> {
 
 impl<'ctx> OverloadReq<'ctx> {
     /// Check if a type matches this requirement.
-    pub fn matches(&self, ty: &Ty) -> bool {
+    pub fn matches(&self, ctx: &ScoreContext, ty: &Ty) -> bool {
         match *self {
-            OverloadReq::Enum(ref req) => req.matches(ty),
-            OverloadReq::Subprog(ref req) => req.matches(ty),
+            OverloadReq::Enum(ref req) => req.matches(ctx, ty),
+            OverloadReq::Subprog(ref req) => req.matches(ctx, ty),
         }
     }
 }
@@ -50,13 +50,13 @@ pub struct SignatureReq<'ctx> {
 
 impl<'ctx> SignatureReq<'ctx> {
     /// Check if a type matches this requirement.
-    pub fn matches(&self, ty: &Ty) -> bool {
+    pub fn matches(&self, ctx: &ScoreContext, ty: &Ty) -> bool {
         if let Ty::Subprog(ref ty) = *ty {
             if !self.return_type.is_any()
                 && !ty
                     .ret
                     .as_ref()
-                    .map(|t| self.return_type.matches(t))
+                    .map(|t| self.return_type.matches(ctx, t))
                     .unwrap_or(false)
             {
                 debugln!("return type mismatch: {} vs {:?}", ty, self);
@@ -69,7 +69,7 @@ impl<'ctx> SignatureReq<'ctx> {
             let mut arg_iter = ty.args.iter();
             for req in &self.positional {
                 let arg = arg_iter.next().unwrap(); // never fails due to above check
-                if !req.matches(&arg.ty) {
+                if !req.matches(ctx, &arg.ty) {
                     debugln!(
                         "positional mismatch: {} vs {:?} in {} vs {:?}",
                         arg.ty,
@@ -101,7 +101,7 @@ impl<'ctx> SignatureReq<'ctx> {
                         return false;
                     }
                 };
-                if !req.matches(&arg.ty) {
+                if !req.matches(ctx, &arg.ty) {
                     debugln!(
                         "named mismatch `{}`: {} vs {:?} in {} vs {:?}",
                         name,
@@ -142,11 +142,11 @@ impl<'ctx> TypeReq<'ctx> {
     }
 
     /// Check if a type matches this requirement.
-    pub fn matches(&self, ty: &Ty) -> bool {
+    pub fn matches(&self, ctx: &ScoreContext, ty: &Ty) -> bool {
         match *self {
             TypeReq::Any => true,
-            TypeReq::One(req) => are_types_matching(req, ty),
-            TypeReq::Many(ref reqs) => reqs.iter().any(|&req| are_types_matching(req, ty)),
+            TypeReq::One(req) => are_types_matching(ctx, req, ty),
+            TypeReq::Many(ref reqs) => reqs.iter().any(|&req| are_types_matching(ctx, req, ty)),
         }
     }
 }
@@ -158,13 +158,49 @@ impl<'ctx> Default for TypeReq<'ctx> {
 }
 
 /// Check if two types match.
-fn are_types_matching(a: &Ty, b: &Ty) -> bool {
+///
+/// Universal integers, such as integer literals, match any integer type. A
+/// named type matches the type it refers to, such as `bit` and the type of the
+/// literal `'1'`. Two named types only match if they have the same base type,
+/// such that a subtype like `natural` or a declared `subtype byte is integer
+/// range 0 to 255` matches `integer`. A constrained subtype, such as `integer
+/// range 0 to 7` or `bit_vector(0 to 3)`, matches its base type regardless of
+/// the constraint.
+fn are_types_matching(ctx: &ScoreContext, a: &Ty, b: &Ty) -> bool {
+    if let (Ok(Some(a)), Ok(Some(b))) = (ctx.int_base_type(a), ctx.int_base_type(b)) {
+        return a == b;
+    }
     match (a, b) {
-        (&Ty::Named(_, ia), &Ty::Named(_, ib)) => ia == ib,
+        (&Ty::Named(_, ia), &Ty::Named(_, ib)) => {
+            match (ctx.base_type_mark(ia), ctx.base_type_mark(ib)) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            }
+        }
+        (&Ty::UniversalInt, other) | (other, &Ty::UniversalInt) => is_integer(ctx, other),
+        (&Ty::Named(..), _) | (_, &Ty::Named(..)) => {
+            match (ctx.deref_named_type(a), ctx.deref_named_type(b)) {
+                (Ok(a), Ok(b)) => are_types_matching(ctx, a, b),
+                _ => false,
+            }
+        }
+        (&Ty::Int(_), &Ty::Int(_)) => true,
+        (Ty::Array(a), Ty::Array(b)) => {
+            a.indices.len() == b.indices.len() && are_types_matching(ctx, &a.element, &b.element)
+        }
         (a, b) => a == b,
     }
 }
 
+/// Check if a type is an integer type.
+fn is_integer(ctx: &ScoreContext, ty: &Ty) -> bool {
+    match *ty {
+        Ty::Int(..) | Ty::UniversalInt => true,
+        Ty::Named(_, tm) => ctx.ty(tm).map(|ty| is_integer(ctx, ty)).unwrap_or(false),
+        _ => false,
+    }
+}
+
 /// Reduce overloaded definitions.
 pub fn reduce_overloads(
     ctx: &ScoreContext,
@@ -211,7 +247,13 @@ pub fn reduce_overloads(
     // Match each of the types against the requirement.
     let matched = types
         .into_iter()
-        .filter_map(|(i, ty)| if req.matches(ty) { Some(defs[i]) } else { None })
+        .filter_map(|(i, ty)| {
+            if req.matches(ctx, ty) {
+                Some(defs[i])
+            } else {
+                None
+            }
+        })
         .collect();
 
     Ok(matched)
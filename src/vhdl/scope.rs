@@ -11,6 +11,7 @@ use crate::common::score::Result;
 use crate::common::source::Spanned;
 use crate::common::Verbosity;
 
+use crate::builtin::BUILTIN_SCOPE_REFS;
 use crate::score::{Def, ResolvableName, ScopeRef, ScoreContext};
 
 /// A scope.
@@ -69,6 +70,49 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             .insert(scope, Scope::new(Some(parent)));
     }
 
+    /// Find the innermost scope enclosing `scope` that is not the scope of a
+    /// loop.
+    ///
+    /// A `for` loop declares its parameter in a scope of its own, which is
+    /// irrelevant to statements that look for their enclosing process or
+    /// subprogram.
+    pub fn skip_loop_scopes(&self, mut scope: ScopeRef) -> Result<ScopeRef> {
+        while let ScopeRef::Loop(_) = scope {
+            match self.with_scope(scope, |scope| Ok(scope.parent))? {
+                Some(parent) => scope = parent,
+                None => break,
+            }
+        }
+        Ok(scope)
+    }
+
+    /// Find the scope that declares a name, starting at `scope` and
+    /// escalating to its parents.
+    ///
+    /// Returns `None` if no enclosing scope declares the name itself, for
+    /// example because the name is made visible by a `use` clause.
+    pub fn declaring_scope(
+        &self,
+        name: ResolvableName,
+        mut scope: ScopeRef,
+    ) -> Result<Option<ScopeRef>> {
+        while !BUILTIN_SCOPE_REFS.contains(&scope) {
+            let declared = self.defs(scope)?.contains_key(&name)
+                || matches!(
+                    self.sb.scope2_table.borrow().get(&scope),
+                    Some(s) if s.defs.contains_key(&name)
+                );
+            if declared {
+                return Ok(Some(scope));
+            }
+            match self.scope(scope)?.parent {
+                Some(parent) => scope = parent,
+                None => break,
+            }
+        }
+        Ok(None)
+    }
+
     /// Define a new name in a scope.
     pub fn define(&self, scope: ScopeRef, name: Spanned<ResolvableName>, def: Def) -> Result<()> {
         if self.sess.opts.verbosity.contains(Verbosity::NAMES) {
@@ -2,69 +2,1322 @@
 
 //! This module implements constant value computation.
 
+use std::cmp::Ordering;
+
+use crate::add_ctx::AddContext;
 use crate::score::*;
+use num::{BigInt, ToPrimitive};
+
+/// The maximum number of nested calls when evaluating a function call at
+/// elaboration time.
+const MAX_CONST_CALL_DEPTH: usize = 64;
+
+/// The state of a function call that is evaluated at elaboration time.
+struct ConstFrame<'a, 'ctx> {
+    /// The values of the parameters, constants, variables, and loop
+    /// parameters of the function.
+    values: HashMap<NodeId, &'ctx Const>,
+    /// The number of statements that may still be executed, shared by all
+    /// nested calls.
+    budget: &'a Cell<usize>,
+    /// The number of calls being evaluated, including this one.
+    depth: usize,
+}
+
+/// How execution continues after a statement evaluated at elaboration time.
+enum ConstFlow<'ctx> {
+    /// With the next statement.
+    Normal,
+    /// With the next iteration of the loop with the given label, or of the
+    /// innermost loop.
+    Next(Option<Name>),
+    /// After the loop with the given label, or the innermost loop.
+    Exit(Option<Name>),
+    /// By returning a value from the function.
+    Return(&'ctx Const),
+}
 
 // Calculate the constant value of an expression.
 impl_make!(self, id: ExprRef => &Const {
+    self.const_expr(id, None)
+});
+
+// Calculate the value of a constant declaration.
+impl_make!(self, id: ConstDeclRef => &Const {
     let hir = self.lazy_hir(id)?;
-    Ok(match hir.data {
-        // Integer literals.
-        hir::ExprData::IntegerLiteral(ref c) => self.intern_const(c.clone()),
-
-        // Float literals.
-        hir::ExprData::FloatLiteral(ref c) => self.intern_const(c.clone()),
-
-        // Unary operators.
-        hir::ExprData::Unary(op, _, arg_id) => {
-            let arg = self.const_value(arg_id)?;
-            // TODO: Lookup the type of the current expression and perform
-            // the operation accordingly.
-            match op.value {
-                UnaryOp::Pos => arg,
-                UnaryOp::Neg => self.intern_const(arg.clone().negate()),
+    let init = match hir.decl.init {
+        Some(init) => init,
+        None => {
+            self.emit(
+                DiagBuilder2::error(format!("deferred constant `{}` has no value", hir.name.value))
+                .span(hir.name.span)
+            );
+            return Err(());
+        }
+    };
+    // Integers assume the type of the constant, such that they can be mapped
+    // to LLHD, and must lie within the range of that type.
+    let value = self.const_value(init)?;
+    Ok(match (value, self.deref_named_type(self.lazy_typeval(id)?)?) {
+        (Const::Int(k), Ty::Int(ty)) => {
+            let (lo, hi) = match ty.dir {
+                Dir::To => (&ty.left_bound, &ty.right_bound),
+                Dir::Downto => (&ty.right_bound, &ty.left_bound),
+            };
+            if k.value < *lo || k.value > *hi {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "value {} is out of range for constant `{}` of type `{}`",
+                        k.value, hir.name.value, ty
+                    ))
+                    .span(self.lazy_hir(init)?.span)
+                );
+                return Err(());
+            }
+            self.intern_const(ConstInt::new(Some(ty.clone()), k.value.clone()))
+        }
+        _ => value,
+    })
+});
+
+// Calculate the default value of a generic.
+impl_make!(self, id: IntfConstRef => &Const {
+    let (scope_id, decl, _, ident) = self.ast(id);
+    match decl.default {
+        Some(ref default) => {
+            let expr = AddContext::new(self, scope_id).add_expr(default)?;
+            self.set_type_context(expr, self.ty(id)?);
+            let value = self.const_value(expr)?;
+            self.adapt_generic_value(id, value, default.span)
+        }
+        None => {
+            self.emit(
+                DiagBuilder2::error(format!("generic `{}` has no value", ident.name))
+                .span(ident.span)
+            );
+            Err(())
+        }
+    }
+});
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Calculate the constant value of an expression.
+    ///
+    /// Within a function call evaluated at elaboration time, `frame` holds the
+    /// values of the parameters and variables of the function. Expressions
+    /// are then evaluated anew for every call, rather than being memoized by
+    /// `const_value`.
+    fn const_expr(&self, id: ExprRef, frame: Option<&ConstFrame<'_, 'ctx>>) -> Result<&'ctx Const> {
+        let hir = self.lazy_hir(id)?;
+        if let Some(frame) = frame {
+            let key: Option<NodeId> = match hir.data {
+                hir::ExprData::ConstName(konst) => Some(konst.into()),
+                hir::ExprData::VarName(var) => Some(var.into()),
+                _ => None,
+            };
+            if let Some(&value) = key.and_then(|key| frame.values.get(&key)) {
+                return Ok(value);
+            }
+        }
+        Ok(match hir.data {
+            // Integer literals.
+            hir::ExprData::IntegerLiteral(ref c) => self.intern_const(c.clone()),
+
+            // Float literals.
+            hir::ExprData::FloatLiteral(ref c) => self.intern_const(c.clone()),
+
+            // Physical literals. The value is scaled to the primary unit and
+            // carries the physical type's underlying integer type.
+            hir::ExprData::PhysicalLiteral(ref value, unit) => {
+                let ty = match *self.deref_named_type(self.ty(unit.value.0)?)? {
+                    Ty::Physical(ref ty) => ty,
+                    _ => unreachable!(),
+                };
+                let scale = &ty.units[unit.value.1].abs;
+                self.intern_const(ConstInt::new(Some(ty.base.clone()), value * scale))
+            }
+
+            // Unary operators.
+            hir::ExprData::Unary(op, _, arg_id) => {
+                let arg = self.const_operand(arg_id, frame)?;
+                // TODO: Lookup the type of the current expression and perform
+                // the operation accordingly.
+                match op.value {
+                    UnaryOp::Pos => arg,
+                    UnaryOp::Neg => self.intern_const(arg.clone().negate()),
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::bug(format!(
+                                "constant unary operator {:?} not yet implemented",
+                                op.value
+                            ))
+                            .span(op.span),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+
+            // Binary operators on integers. Relational operators also compare
+            // enumeration literals.
+            hir::ExprData::Binary(op, _, lhs_id, rhs_id) => {
+                let lhs = self.const_operand(lhs_id, frame)?;
+                let rhs = self.const_operand(rhs_id, frame)?;
+                if let BinaryOp::Rel(rel) = op.value {
+                    return self.const_relational(Spanned::new(rel, op.span), lhs, rhs);
+                }
+                let (lhs, rhs) = match (lhs, rhs) {
+                    (Const::Int(lhs), Const::Int(rhs)) => (lhs, rhs),
+                    _ => {
+                        self.emit(
+                        DiagBuilder2::bug(format!("constant binary operator `{}` not yet implemented for non-integers", op.value))
+                        .span(op.span)
+                    );
+                        return Err(());
+                    }
+                };
+                let ty = lhs.ty.clone().or_else(|| rhs.ty.clone());
+                let value = match op.value {
+                    BinaryOp::Add => Some(ConstInt::new(ty, &lhs.value + &rhs.value)),
+                    BinaryOp::Sub => Some(ConstInt::new(ty, &lhs.value - &rhs.value)),
+                    BinaryOp::Mul => Some(ConstInt::new(ty, &lhs.value * &rhs.value)),
+                    BinaryOp::Div => lhs.div(rhs),
+                    BinaryOp::Mod => lhs.modulo(rhs),
+                    BinaryOp::Rem => lhs.rem(rhs),
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::bug(format!(
+                                "constant binary operator `{}` not yet implemented",
+                                op.value
+                            ))
+                            .span(op.span),
+                        );
+                        return Err(());
+                    }
+                };
+                match value {
+                    Some(value) => self.intern_const(value),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "division by zero in `{}`",
+                                hir.span.extract()
+                            ))
+                            .span(self.lazy_hir(rhs_id)?.span),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+
+            // Ranges.
+            hir::ExprData::Range(dir, lb_id, rb_id) => {
+                // TODO: Determine the type of ourself, then make sure the const
+                // values are all cast appropriately.
+                let lb = self.const_operand(lb_id, frame)?;
+                let rb = self.const_operand(rb_id, frame)?;
+                match (lb, rb) {
+                    (Const::Int(lb), Const::Int(rb)) => {
+                        self.intern_const(ConstIntRange::new(dir, lb.clone(), rb.clone()))
+                    }
+                    (Const::Float(lb), Const::Float(rb)) => {
+                        self.intern_const(ConstFloatRange::new(dir, lb.clone(), rb.clone()))
+                    }
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(
+                                "left and right bound of range must both be integer or float",
+                            )
+                            .span(hir.span),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+
+            // Aggregates. The type of the expression provides the type context
+            // of the aggregate.
+            hir::ExprData::Aggregate(agg) => {
+                match *self.deref_named_type(self.lazy_typeval(id)?)? {
+                    Ty::Record(ref ty) => self.const_record_aggregate(agg, ty)?,
+                    _ => self.const_array_aggregate(agg)?,
+                }
+            }
+
+            // Generics. These are bound to a value upon specialization, see
+            // `specialize_arch`, and otherwise assume their default value.
+            hir::ExprData::ConstName(ConstRef::Intf(id)) => self.const_value(id)?,
+
+            // Constants, e.g. ones made visible by a `use` clause.
+            hir::ExprData::ConstName(ConstRef::Decl(id)) => self.const_value(id)?,
+
+            // Generate parameters take a different value in every iteration of
+            // their generate statement, see `ForGenStmtRef`.
+            hir::ExprData::ConstName(ConstRef::Gen(_)) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "generate parameter `{}` cannot be used in a constant expression",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+
+            // Loop parameters take a different value in every iteration of their
+            // loop.
+            hir::ExprData::ConstName(ConstRef::Loop(_)) => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "loop parameter `{}` cannot be used in a constant expression",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+
+            // Calls to pure functions, which are evaluated at elaboration time.
+            hir::ExprData::SubprogCall(_, ref args) => {
+                self.const_call(id, &args.value, hir.span, frame)?
+            }
+
+            // Names.
+            hir::ExprData::Name(Def::Enum(EnumRef(decl, index)), _) => {
+                self.intern_const(ConstEnum::new(decl, index))
+            }
+
+            // Enum literals. These may be overloaded, in which case the type of
+            // the expression determines which literal is meant.
+            hir::ExprData::EnumName(ref defs) => {
+                let def = if defs.len() == 1 {
+                    defs[0].value
+                } else {
+                    let ty = self.lazy_typeval(id)?;
+                    let decl = match *self.deref_named_type(ty)? {
+                        Ty::Enum(ref et) => et.decl,
+                        _ => unreachable!(),
+                    };
+                    match defs.iter().find(|def| def.value.0 == decl) {
+                        Some(def) => def.value,
+                        None => unreachable!(),
+                    }
+                };
+                self.intern_const(ConstEnum::new(def.0, def.1))
+            }
+
+            // All other expressions cannot be turned into a constant value.
+            _ => {
+                self.emit(
+                    DiagBuilder2::error("expression does not have a constant value").span(hir.span),
+                );
+                return Err(());
+            }
+        })
+    }
+
+    /// Calculate the constant value of an operand of an expression.
+    ///
+    /// See `const_expr`.
+    fn const_operand(
+        &self,
+        id: ExprRef,
+        frame: Option<&ConstFrame<'_, 'ctx>>,
+    ) -> Result<&'ctx Const> {
+        match frame {
+            Some(_) => self.const_expr(id, frame),
+            None => self.const_value(id),
+        }
+    }
+
+    /// Compare two constants with a relational operator.
+    fn const_relational(
+        &self,
+        op: Spanned<RelationalOp>,
+        lhs: &Const,
+        rhs: &Const,
+    ) -> Result<&'ctx Const> {
+        let ord = match (lhs, rhs) {
+            (Const::Int(lhs), Const::Int(rhs)) => lhs.value.cmp(&rhs.value),
+            (Const::Enum(lhs), Const::Enum(rhs)) if lhs.decl == rhs.decl => {
+                lhs.index.cmp(&rhs.index)
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "constant relational operator `{}` not yet implemented for `{}` and `{}`",
+                        op.value, lhs, rhs
+                    ))
+                    .span(op.span),
+                );
+                return Err(());
+            }
+        };
+        let result = match op.value {
+            RelationalOp::Eq => ord == Ordering::Equal,
+            RelationalOp::Neq => ord != Ordering::Equal,
+            RelationalOp::Lt => ord == Ordering::Less,
+            RelationalOp::Leq => ord != Ordering::Greater,
+            RelationalOp::Gt => ord == Ordering::Greater,
+            RelationalOp::Geq => ord != Ordering::Less,
+        };
+        Ok(self.intern_const(ConstEnum::new(BOOLEAN_TYPE.id, result as usize)))
+    }
+
+    /// Evaluate a call to a pure function at elaboration time.
+    ///
+    /// The arguments must be constant. The body of the function is
+    /// interpreted, with at most `MAX_CONST_CALL_DEPTH` nested calls, and at
+    /// most `const_eval_budget` statements executed across all of them.
+    fn const_call(
+        &self,
+        id: ExprRef,
+        args: &hir::AssocList,
+        span: Span,
+        frame: Option<&ConstFrame<'_, 'ctx>>,
+    ) -> Result<&'ctx Const> {
+        let subprog = match self.resolve_call(id)?.value {
+            Def::Subprog(id) => id,
+            _ => unreachable!(),
+        };
+        let name = self.hir(subprog)?.spec.name.value;
+        let body = match self.subprog_body(subprog)? {
+            Some(body) => self.hir(body)?,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "function `{}` has no body and cannot be evaluated at elaboration time",
+                        name
+                    ))
+                    .span(span),
+                );
+                return Err(());
+            }
+        };
+        if body.spec.kind != hir::SubprogKind::PureFunc {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "`{}` is not a pure function and cannot be evaluated at elaboration time",
+                    name
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+        let fresh_budget = Cell::new(self.sb.const_eval_budget);
+        let (budget, depth) = match frame {
+            Some(frame) => (frame.budget, frame.depth + 1),
+            None => (&fresh_budget, 1),
+        };
+        if depth > MAX_CONST_CALL_DEPTH {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "evaluation of `{}` exceeds {} nested calls",
+                    span.extract(),
+                    MAX_CONST_CALL_DEPTH
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+        let mut callee = ConstFrame {
+            values: HashMap::new(),
+            budget,
+            depth,
+        };
+
+        // Bind the parameters to the arguments, or to their default value.
+        for (index, &param) in body.spec.params.iter().enumerate() {
+            let param = match param {
+                IntfObjRef::Const(id) => id,
                 _ => {
                     self.emit(
-                        DiagBuilder2::bug(format!("constant unary operator {:?} not yet implemented", op.value))
-                        .span(op.span)
+                        DiagBuilder2::error(format!(
+                            "function `{}` has a parameter that is not a constant, and cannot be evaluated at elaboration time",
+                            name
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            };
+            let value = match args.get(index).map(|arg| &arg.actual.value) {
+                Some(&hir::AssocActual::Expr(arg)) => {
+                    let value = self.const_operand(arg, frame)?;
+                    self.const_adapt(value, self.ty(param)?, self.lazy_hir(arg)?.span)?
+                }
+                Some(_) => unreachable!(),
+                None => self.const_value(param)?,
+            };
+            callee.values.insert(param.into(), value);
+        }
+
+        // Initialize the constants and variables declared in the function.
+        for &decl in &body.decls {
+            let (decl_id, init, ty): (NodeId, _, _) = match decl {
+                DeclInSubprogRef::Const(id) => (
+                    id.into(),
+                    self.lazy_hir(id)?.decl.init,
+                    self.lazy_typeval(id)?,
+                ),
+                DeclInSubprogRef::Var(id) => (
+                    id.into(),
+                    self.lazy_hir(id)?.decl.init,
+                    self.lazy_typeval(id)?,
+                ),
+                DeclInSubprogRef::Type(_) | DeclInSubprogRef::Subtype(_) => continue,
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "declarations in function `{}` other than constants and variables cannot be evaluated at elaboration time",
+                            name
+                        ))
+                        .span(span),
                     );
                     return Err(());
                 }
+            };
+            let value = match init {
+                Some(init) => {
+                    let value = self.const_expr(init, Some(&callee))?;
+                    self.const_adapt(value, ty, self.lazy_hir(init)?.span)?
+                }
+                None => self.default_value_for_type(ty)?,
+            };
+            callee.values.insert(decl_id, value);
+        }
+
+        // Execute the statements until the function returns.
+        match self.const_exec(&body.stmts, &mut callee)? {
+            ConstFlow::Return(value) => {
+                let ty = self.subprog_return_type(&body.spec)?.unwrap();
+                self.const_adapt(value, &ty, span)
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "function `{}` ended without returning a value",
+                        name
+                    ))
+                    .span(span),
+                );
+                Err(())
             }
         }
+    }
 
-        // Ranges.
-        hir::ExprData::Range(dir, lb_id, rb_id) => {
-            // TODO: Determine the type of ourself, then make sure the const
-            // values are all cast appropriately.
-            let lb = self.const_value(lb_id)?;
-            let rb = self.const_value(rb_id)?;
-            match (lb, rb) {
-                (&Const::Int(ref lb), &Const::Int(ref rb)) => {
-                    self.intern_const(ConstIntRange::new(dir, lb.clone(), rb.clone()))
+    /// Convert a value to the type of the object it is assigned to.
+    ///
+    /// Integers assume the type of the object, and must lie within its range.
+    fn const_adapt(&self, value: &'ctx Const, ty: &Ty, span: Span) -> Result<&'ctx Const> {
+        match (value, self.deref_named_type(ty)?) {
+            (Const::Int(k), Ty::Int(ty)) => {
+                let (lo, hi) = match ty.dir {
+                    Dir::To => (&ty.left_bound, &ty.right_bound),
+                    Dir::Downto => (&ty.right_bound, &ty.left_bound),
+                };
+                if k.value < *lo || k.value > *hi {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "value {} is out of range of type `{}`",
+                            k.value, ty
+                        ))
+                        .span(span),
+                    );
+                    return Err(());
                 }
-                (&Const::Float(ref lb), &Const::Float(ref rb)) => {
-                    self.intern_const(ConstFloatRange::new(dir, lb.clone(), rb.clone()))
+                Ok(self.intern_const(ConstInt::new(Some(ty.clone()), k.value.clone())))
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// Execute a sequence of statements at elaboration time.
+    fn const_exec(
+        &self,
+        stmts: &[SeqStmtRef],
+        frame: &mut ConstFrame<'_, 'ctx>,
+    ) -> Result<ConstFlow<'ctx>> {
+        for &stmt in stmts {
+            match self.const_exec_stmt(stmt, frame)? {
+                ConstFlow::Normal => (),
+                flow => return Ok(flow),
+            }
+        }
+        Ok(ConstFlow::Normal)
+    }
+
+    /// Execute a single statement at elaboration time.
+    fn const_exec_stmt(
+        &self,
+        stmt: SeqStmtRef,
+        frame: &mut ConstFrame<'_, 'ctx>,
+    ) -> Result<ConstFlow<'ctx>> {
+        let span = self.span(stmt).unwrap_or(INVALID_SPAN);
+        self.const_step(span, frame)?;
+        match stmt {
+            SeqStmtRef::VarAssign(id) => {
+                let hir = self.lazy_hir(id)?;
+                let var = match hir.stmt.target.value {
+                    hir::Target::Name(expr) => match self.lazy_hir(expr)?.data {
+                        hir::ExprData::VarName(var) => Some(var),
+                        _ => None,
+                    },
+                    hir::Target::Aggregate(..) => None,
+                };
+                let var = match var {
+                    Some(var) if frame.values.contains_key(&Into::<NodeId>::into(var)) => var,
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "assignment to `{}` cannot be evaluated at elaboration time",
+                                hir.stmt.target.span.extract()
+                            ))
+                            .span(hir.stmt.target.span),
+                        );
+                        return Err(());
+                    }
+                };
+                let expr = match hir.stmt.kind {
+                    hir::VarAssignKind::Simple(expr) => Some(expr),
+                    hir::VarAssignKind::Cond(ref cond) => {
+                        let mut chosen = cond.other;
+                        for &(value, cond) in &cond.when {
+                            if self.const_cond(cond, frame)? {
+                                chosen = Some(value);
+                                break;
+                            }
+                        }
+                        chosen
+                    }
+                    hir::VarAssignKind::Sel(..) => {
+                        self.emit(
+                            DiagBuilder2::error(
+                                "selected variable assignments cannot be evaluated at elaboration time",
+                            )
+                            .span(hir.span),
+                        );
+                        return Err(());
+                    }
+                };
+                if let Some(expr) = expr {
+                    let value = self.const_expr(expr, Some(&*frame))?;
+                    let value = self.const_adapt(
+                        value,
+                        self.lazy_typeval(var)?,
+                        self.lazy_hir(expr)?.span,
+                    )?;
+                    frame.values.insert(var.into(), value);
                 }
-                _ => {
+                Ok(ConstFlow::Normal)
+            }
+            SeqStmtRef::If(id) => {
+                let hir = self.lazy_hir(id)?;
+                for &(cond, ref stmts) in &hir.stmt.branches {
+                    if self.const_cond(cond, frame)? {
+                        return self.const_exec(stmts, frame);
+                    }
+                }
+                match hir.stmt.otherwise {
+                    Some(ref stmts) => self.const_exec(stmts, frame),
+                    None => Ok(ConstFlow::Normal),
+                }
+            }
+            SeqStmtRef::Loop(id) => self.const_exec_loop(id, frame),
+            SeqStmtRef::Nexit(id) => {
+                let hir = self.lazy_hir(id)?;
+                if let Some(cond) = hir.stmt.cond {
+                    if !self.const_cond(cond, frame)? {
+                        return Ok(ConstFlow::Normal);
+                    }
+                }
+                let target = hir.stmt.target.map(|t| t.value);
+                Ok(match hir.stmt.mode {
+                    hir::NexitMode::Next => ConstFlow::Next(target),
+                    hir::NexitMode::Exit => ConstFlow::Exit(target),
+                })
+            }
+            SeqStmtRef::Return(id) => {
+                let hir = self.lazy_hir(id)?;
+                match hir.stmt.expr {
+                    Some(expr) => Ok(ConstFlow::Return(self.const_expr(expr, Some(&*frame))?)),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error("return without a value in a function")
+                                .span(hir.span),
+                        );
+                        Err(())
+                    }
+                }
+            }
+            SeqStmtRef::Null(_) => Ok(ConstFlow::Normal),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be evaluated at elaboration time",
+                        span.extract()
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Execute a loop statement at elaboration time.
+    fn const_exec_loop(
+        &self,
+        id: LoopStmtRef,
+        frame: &mut ConstFrame<'_, 'ctx>,
+    ) -> Result<ConstFlow<'ctx>> {
+        let hir = self.lazy_hir(id)?;
+        let label = hir.label.map(|l| l.value);
+
+        // Determine the values the parameter of a `for` loop assumes.
+        let mut param = match hir.stmt.scheme {
+            hir::LoopScheme::For(_, ref range) => {
+                let (dir, left, right) = match range.value {
+                    hir::DiscreteRange::Range(hir::Range::Immediate(dir, left, right)) => (
+                        dir,
+                        self.const_expr(left, Some(&*frame))?,
+                        self.const_expr(right, Some(&*frame))?,
+                    ),
+                    _ => self.const_discrete_range(range.as_ref())?,
+                };
+                match (left, right) {
+                    (Const::Int(l), Const::Int(r)) => Some((dir, l.value.clone(), r.value.clone())),
+                    _ => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "loop over `{}` cannot be evaluated at elaboration time",
+                                range.span.extract()
+                            ))
+                            .span(range.span),
+                        );
+                        return Err(());
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        loop {
+            self.const_step(hir.span, frame)?;
+            match hir.stmt.scheme {
+                hir::LoopScheme::Loop => (),
+                hir::LoopScheme::While(cond) => {
+                    if !self.const_cond(cond, frame)? {
+                        break;
+                    }
+                }
+                hir::LoopScheme::For(..) => {
+                    let (dir, ref mut value, ref last) = *param.as_mut().unwrap();
+                    let done = match dir {
+                        Dir::To => *value > *last,
+                        Dir::Downto => *value < *last,
+                    };
+                    if done {
+                        break;
+                    }
+                    let ty = match *self.deref_named_type(self.ty(id)?)? {
+                        Ty::Int(ref ty) => Some(ty.clone()),
+                        _ => None,
+                    };
+                    let k = self.intern_const(ConstInt::new(ty, value.clone()));
+                    frame.values.insert(ConstRef::Loop(id).into(), k);
+                    match dir {
+                        Dir::To => *value += BigInt::from(1),
+                        Dir::Downto => *value -= BigInt::from(1),
+                    }
+                }
+            }
+            match self.const_exec(&hir.stmt.stmts, frame)? {
+                ConstFlow::Normal | ConstFlow::Next(None) => (),
+                ConstFlow::Next(Some(l)) if Some(l) == label => (),
+                ConstFlow::Exit(None) => break,
+                ConstFlow::Exit(Some(l)) if Some(l) == label => break,
+                flow => return Ok(flow),
+            }
+        }
+        Ok(ConstFlow::Normal)
+    }
+
+    /// Evaluate a condition at elaboration time.
+    fn const_cond(&self, id: ExprRef, frame: &ConstFrame<'_, 'ctx>) -> Result<bool> {
+        match *self.const_expr(id, Some(frame))? {
+            Const::Enum(ref k) if k.decl == BOOLEAN_TYPE.id => Ok(k.index == 1),
+            ref k => {
+                let span = self.lazy_hir(id)?.span;
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "condition `{}` evaluates to `{}`, which is not a boolean",
+                        span.extract(),
+                        k
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Account for the execution of a statement at elaboration time.
+    ///
+    /// Fails once the scoreboard's `const_eval_budget` is exhausted.
+    fn const_step(&self, span: Span, frame: &ConstFrame<'_, 'ctx>) -> Result<()> {
+        match frame.budget.get() {
+            0 => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "evaluation at elaboration time exceeds {} steps",
+                        self.sb.const_eval_budget
+                    ))
+                    .span(span),
+                );
+                Err(())
+            }
+            n => {
+                frame.budget.set(n - 1);
+                Ok(())
+            }
+        }
+    }
+
+    /// Calculate the constant value of an array aggregate.
+    ///
+    /// The array must have a single index of enumeration or integer type.
+    /// Elements are assigned by position, by choices that are constant
+    /// enumeration literals or integers, and by `others`.
+    fn const_array_aggregate(&self, id: AggregateRef) -> Result<&'ctx Const> {
+        let hir = self.lazy_hir(id)?;
+        let ty = self.lazy_typeval(id)?;
+        let (index_ty, element_ty) = match *self.deref_named_type(ty)? {
+            Ty::Array(ref ty) if ty.indices.len() == 1 => match ty.indices[0] {
+                ArrayIndex::Constrained(ref index_ty) => (
+                    Some(self.deref_named_type(index_ty)?),
+                    self.deref_named_type(&ty.element)?,
+                ),
+                ArrayIndex::Unbounded(_) => (None, &*ty.element),
+            },
+            _ => (None, ty),
+        };
+        let len = match index_ty {
+            Some(Ty::Enum(ty)) => Some(self.enum_len(ty.decl)?),
+            Some(Ty::Int(ty)) => ty.len().to_usize(),
+            _ => None,
+        };
+        let (index_ty, len) = match (index_ty, len) {
+            (Some(index_ty), Some(len)) => (index_ty, len),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "constant value of aggregate `{}` not implemented",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                return Err(());
+            }
+        };
+
+        // Assign the positional elements, then the named ones.
+        let mut elements: Vec<Option<&Const>> = vec![None; len];
+        for (index, expr) in hir.positional.iter().enumerate() {
+            let value = self.const_value(expr.value)?;
+            match elements.get_mut(index) {
+                Some(element) => *element = Some(value),
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!("aggregate has more than {} elements", len))
+                            .span(expr.span),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        if let hir::AggregateKind::Array(ref fields) = hir.named {
+            for field in fields {
+                let value = self.const_value(field.value.1.value)?;
+                for choice in &field.value.0 {
+                    for index in self.const_choice_indices(index_ty, len, choice)? {
+                        if elements[index].is_some() {
+                            self.emit(
+                                DiagBuilder2::error(format!(
+                                    "choice `{}` overlaps with another choice of the aggregate",
+                                    choice.span.extract()
+                                ))
+                                .span(choice.span),
+                            );
+                            return Err(());
+                        }
+                        elements[index] = Some(value);
+                    }
+                }
+            }
+        }
+
+        // Fill the remaining elements with `others`.
+        let others = match hir.others {
+            Some(expr) => Some(self.const_value(expr.value)?),
+            None => None,
+        };
+        // Integers assume the element type, such that they can be mapped to
+        // LLHD.
+        let elements = elements.into_iter().map(|element| {
+            element.or(others).map(|value| match (value, element_ty) {
+                (Const::Int(k), Ty::Int(ty)) => {
+                    ConstInt::new(Some(ty.clone()), k.value.clone()).into()
+                }
+                _ => value.clone(),
+            })
+        });
+        match elements.collect::<Option<Vec<_>>>() {
+            Some(elements) => Ok(self.intern_const(ConstArray::new(elements))),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "aggregate `{}` does not cover all elements",
+                        hir.span.extract()
+                    ))
+                    .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Calculate the constant value of a record aggregate.
+    fn const_record_aggregate(&self, id: AggregateRef, ty: &RecordTy) -> Result<&'ctx Const> {
+        let exprs = self.record_aggregate_fields(id, ty)?;
+        let mut fields = Vec::with_capacity(exprs.len());
+        for (expr, (_, field_ty)) in exprs.into_iter().zip(&ty.fields) {
+            let value = self.const_value(expr.value)?;
+            // Integers assume the field type, such that they can be mapped to
+            // LLHD.
+            fields.push(match (value, self.deref_named_type(field_ty)?) {
+                (Const::Int(k), Ty::Int(ty)) => {
+                    ConstInt::new(Some(ty.clone()), k.value.clone()).into()
+                }
+                _ => value.clone(),
+            });
+        }
+        Ok(self.intern_const(ConstRecord::new(fields)))
+    }
+
+    /// Determine the expression a record aggregate assigns to each field.
+    ///
+    /// Fields are assigned by position, then by name, and finally by `others`.
+    /// Returns the expressions in the order of the fields of `ty`, which is
+    /// the type of the aggregate.
+    pub fn record_aggregate_fields(
+        &self,
+        id: AggregateRef,
+        ty: &RecordTy,
+    ) -> Result<Vec<Spanned<ExprRef>>> {
+        let hir = self.lazy_hir(id)?;
+        let mut fields = vec![None; ty.fields.len()];
+        for (field, &expr) in fields.iter_mut().zip(&hir.positional) {
+            *field = Some(expr);
+        }
+        if let hir::AggregateKind::Record(ref named) = hir.named {
+            for elem in named {
+                for choice in &elem.value.0 {
+                    if let Some(&index) = ty.lookup.get(&choice.value) {
+                        fields[index] = Some(elem.value.1);
+                    }
+                }
+            }
+        }
+        let mut exprs = Vec::with_capacity(fields.len());
+        for (field, &(name, _)) in fields.into_iter().zip(&ty.fields) {
+            match field.or(hir.others) {
+                Some(expr) => exprs.push(expr),
+                None => {
                     self.emit(
-                        DiagBuilder2::error("left and right bound of range must both be integer or float")
-                        .span(hir.span)
+                        DiagBuilder2::error(format!(
+                            "aggregate `{}` does not assign field `{}`",
+                            hir.span.extract(),
+                            name
+                        ))
+                        .span(hir.span),
                     );
                     return Err(());
                 }
             }
         }
+        Ok(exprs)
+    }
 
-        // Names.
-        hir::ExprData::Name(Def::Enum(EnumRef(decl, index)), _) => self.intern_const(ConstEnum::new(decl, index)),
+    /// Determine the positions of the elements an aggregate choice selects.
+    ///
+    /// The choice is either a single constant index, or a discrete range that
+    /// selects every index it covers. Null ranges select no elements.
+    pub fn const_choice_indices(
+        &self,
+        index_ty: &Ty,
+        len: usize,
+        choice: &Spanned<hir::ArrayChoice>,
+    ) -> Result<std::ops::Range<usize>> {
+        let (dir, left, right) = match choice.value {
+            hir::ArrayChoice::Expr(expr) => {
+                let value = self.const_value(expr)?;
+                (Dir::To, value, value)
+            }
+            hir::ArrayChoice::DiscreteRange(ref range) => match *range {
+                hir::DiscreteRange::Range(ref range) => {
+                    self.const_range(Spanned::new(range, choice.span))?
+                }
+                hir::DiscreteRange::Subtype(id) => match *self.deref_named_type(self.ty(id)?)? {
+                    Ty::Int(ref ty) => (
+                        ty.dir,
+                        self.intern_const(ConstInt::new(None, ty.left_bound.clone())),
+                        self.intern_const(ConstInt::new(None, ty.right_bound.clone())),
+                    ),
+                    // Enumeration subtypes cover all literals of the type.
+                    _ => return Ok(0..len),
+                },
+            },
+        };
+        let (left_pos, right_pos) = match (
+            self.const_array_index(index_ty, left),
+            self.const_array_index(index_ty, right),
+        ) {
+            (Some(l), Some(r)) => (l, r),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a constant index of type `{}`",
+                        choice.span.extract(),
+                        index_ty
+                    ))
+                    .span(choice.span),
+                );
+                return Err(());
+            }
+        };
 
-        // All other expressions cannot be turned into a constant value.
-        _ => {
+        // The positions run in the direction of the index type, so the range
+        // is null if its direction disagrees with the order of positions.
+        let ascending = match *index_ty {
+            Ty::Int(ref ty) => ty.dir == Dir::To,
+            _ => true,
+        };
+        let (first, last) = if (dir == Dir::To) == ascending {
+            (left_pos, right_pos)
+        } else {
+            (right_pos, left_pos)
+        };
+        if first > last {
+            return Ok(0..0);
+        }
+        if last >= len {
             self.emit(
-                DiagBuilder2::error("expression does not have a constant value")
-                .span(hir.span)
+                DiagBuilder2::error(format!(
+                    "choice `{}` is out of bounds of index type `{}`",
+                    choice.span.extract(),
+                    index_ty
+                ))
+                .span(choice.span),
             );
             return Err(());
         }
-    })
-});
+        Ok(first..last + 1)
+    }
+
+    /// Determine the position of a constant index within an index type.
+    ///
+    /// Returns `None` if the constant is not a value of the type.
+    fn const_array_index(&self, index_ty: &Ty, index: &Const) -> Option<usize> {
+        match (index_ty, index) {
+            (Ty::Enum(ty), Const::Enum(k)) if ty.decl == k.decl => Some(k.index),
+            (Ty::Int(ty), Const::Int(k)) => match ty.dir {
+                Dir::To => &k.value - &ty.left_bound,
+                Dir::Downto => &ty.left_bound - &k.value,
+            }
+            .to_usize(),
+            _ => None,
+        }
+    }
+
+    /// Determine the direction and the constant bounds of a discrete range.
+    pub fn const_discrete_range(
+        &self,
+        range: Spanned<&hir::DiscreteRange>,
+    ) -> Result<(Dir, &'ctx Const, &'ctx Const)> {
+        match *range.value {
+            hir::DiscreteRange::Subtype(id) => {
+                let ty = self.deref_named_type(self.ty(id)?)?;
+                self.const_type_range(ty, range.span)
+            }
+            hir::DiscreteRange::Range(ref r) => self.const_range(Spanned::new(r, range.span)),
+        }
+    }
+
+    /// Determine the direction and the constant bounds of a range.
+    ///
+    /// A range attribute covers the index of its prefix, in the opposite
+    /// direction in case of `'reverse_range`.
+    pub fn const_range(
+        &self,
+        range: Spanned<&hir::Range>,
+    ) -> Result<(Dir, &'ctx Const, &'ctx Const)> {
+        match *range.value {
+            hir::Range::Immediate(dir, left, right) => {
+                Ok((dir, self.const_value(left)?, self.const_value(right)?))
+            }
+            hir::Range::Attr(prefix, attr) => {
+                let index_ty = self.range_attr_index_ty(prefix, range.span)?;
+                let (dir, left, right) = self.const_type_range(index_ty, range.span)?;
+                Ok(match attr.value {
+                    hir::RangeAttr::Range => (dir, left, right),
+                    hir::RangeAttr::ReverseRange => match dir {
+                        Dir::To => (Dir::Downto, right, left),
+                        Dir::Downto => (Dir::To, right, left),
+                    },
+                })
+            }
+        }
+    }
+
+    /// Determine the direction and the constant bounds of the values of a
+    /// scalar type.
+    fn const_type_range(&self, ty: &Ty, span: Span) -> Result<(Dir, &'ctx Const, &'ctx Const)> {
+        match *ty {
+            Ty::Int(ref ty) => Ok((
+                ty.dir,
+                self.intern_const(ConstInt::new(Some(ty.clone()), ty.left_bound.clone())),
+                self.intern_const(ConstInt::new(Some(ty.clone()), ty.right_bound.clone())),
+            )),
+            // Enumeration types cover all their literals.
+            Ty::Enum(ref ty) => Ok((
+                Dir::To,
+                self.intern_const(ConstEnum::new(ty.decl, 0)),
+                self.intern_const(ConstEnum::new(ty.decl, self.enum_len(ty.decl)? - 1)),
+            )),
+            _ => {
+                self.emit(
+                    DiagBuilder2::bug(format!("range of type `{}` not implemented", ty)).span(span),
+                );
+                Err(())
+            }
+        }
+    }
+
+    /// Determine the index type of the prefix of a range attribute, e.g. `A`
+    /// in `A'range`.
+    ///
+    /// The prefix must be an array with a single, constrained index.
+    pub fn range_attr_index_ty(&self, prefix: ExprRef, span: Span) -> Result<&'ctx Ty> {
+        let ty = self.lazy_typeval(prefix)?;
+        match *self.deref_named_type(ty)? {
+            Ty::Array(ref array) if array.indices.len() == 1 => match array.indices[0] {
+                ArrayIndex::Constrained(ref index_ty) => return self.deref_named_type(index_ty),
+                ArrayIndex::Unbounded(_) => (),
+            },
+            _ => (),
+        }
+        self.emit(
+            DiagBuilder2::error(format!(
+                "`{}` is not a constrained one-dimensional array; its type is `{}`",
+                self.span(prefix).unwrap_or(span).extract(),
+                ty
+            ))
+            .span(span),
+        );
+        Err(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, first_arch, score_test};
+    use moore_common::Session;
+    use num::BigInt;
+
+    /// Calculate the value of the last constant declared in an architecture.
+    fn last_const_value(src: &str) -> Result<Const> {
+        let units = crate::syntax::parse_str(src).unwrap();
+        let sess = Session::new();
+        score_context!(ctx, &sess);
+        let lib = add_work(&ctx, &units);
+        let arch = first_arch(&ctx, lib);
+
+        let id = match *ctx.hir(arch).unwrap().decls.last().unwrap() {
+            DeclInBlockRef::Const(id) => id,
+            _ => panic!("expected a constant declaration"),
+        };
+        ctx.const_value(id).cloned()
+    }
+
+    /// Extract the integer elements of an array constant.
+    fn int_elements(k: &Const) -> Vec<BigInt> {
+        let elements = match *k {
+            Const::Array(ref k) => &k.elements,
+            ref k => panic!("expected an array constant, got {}", k),
+        };
+        elements
+            .iter()
+            .map(|k| match *k {
+                Const::Int(ref k) => k.value.clone(),
+                ref k => panic!("expected an integer constant, got {}", k),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn const_out_of_subtype_range() {
+        score_test(|| {
+            let value = |init: &str| {
+                last_const_value(&format!(
+                    "entity foo is end;
+                     architecture bar of foo is
+                         subtype byte is integer range 0 to 255;
+                         constant c : byte := {};
+                     begin end;",
+                    init
+                ))
+            };
+            assert_eq!(value("255").unwrap().to_string(), "255");
+            assert!(value("300").is_err());
+            assert!(value("-1").is_err());
+        });
+    }
+
+    #[test]
+    fn enum_indexed_lookup_table() {
+        score_test(|| {
+            let k = last_const_value(
+                "entity foo is end;
+                 architecture bar of foo is
+                     type color is (red, green, blue);
+                     type arr_t is array (color) of integer;
+                     constant lut : arr_t := (blue => 3, red => 1, green => 2);
+                 begin end;",
+            )
+            .unwrap();
+            assert_eq!(int_elements(&k), vec![1.into(), 2.into(), 3.into()]);
+        });
+    }
+
+    #[test]
+    fn record_aggregate() {
+        score_test(|| {
+            let value = |init: &str| {
+                last_const_value(&format!(
+                    "entity foo is end;
+                     architecture bar of foo is
+                         type pair is record
+                             a : integer;
+                             b : bit;
+                             c : integer;
+                         end record;
+                         constant k : pair := {};
+                     begin end;",
+                    init
+                ))
+            };
+            let fields = |init: &str| match value(init).unwrap() {
+                Const::Record(k) => k
+                    .fields
+                    .iter()
+                    .map(|k| match *k {
+                        Const::Int(ref k) => k.value.clone(),
+                        Const::Enum(ref k) => BigInt::from(k.index),
+                        ref k => panic!("unexpected field {}", k),
+                    })
+                    .collect::<Vec<_>>(),
+                k => panic!("expected a record constant, got {}", k),
+            };
+            let expected: Vec<BigInt> = vec![3, 1, 5].into_iter().map(BigInt::from).collect();
+            assert_eq!(fields("(c => 5, b => '1', a => 3)"), expected);
+            assert_eq!(fields("(3, '1', 5)"), expected);
+            assert_eq!(fields("(3, c => 5, others => '1')"), expected);
+            assert!(value("(a => 3, b => '1')").is_err());
+        });
+    }
+
+    #[test]
+    fn range_choices() {
+        score_test(|| {
+            let k = last_const_value(
+                "entity foo is end;
+                 architecture bar of foo is
+                     type arr_t is array (0 to 7) of integer;
+                     constant k : arr_t := (0 to 2 => 1, 6 downto 5 => 2, others => 0);
+                 begin end;",
+            )
+            .unwrap();
+            let expected: Vec<BigInt> = vec![1, 1, 1, 0, 0, 2, 2, 0]
+                .into_iter()
+                .map(BigInt::from)
+                .collect();
+            assert_eq!(int_elements(&k), expected);
+        });
+    }
+
+    #[test]
+    fn overlapping_range_choices() {
+        score_test(|| {
+            assert!(last_const_value(
+                "entity foo is end;
+                 architecture bar of foo is
+                     type arr_t is array (0 to 7) of integer;
+                     constant k : arr_t := (0 to 4 => 1, 3 to 7 => 2);
+                 begin end;",
+            )
+            .is_err());
+            assert!(last_const_value(
+                "entity foo is end;
+                 architecture bar of foo is
+                     type arr_t is array (0 to 7) of integer;
+                     constant k : arr_t := (0 to 8 => 1);
+                 begin end;",
+            )
+            .is_err());
+        });
+    }
+
+    #[test]
+    fn const_function_call() {
+        score_test(|| {
+            let k = last_const_value(
+                "entity foo is end;
+                 architecture bar of foo is
+                     function clog2 (n : natural) return natural;
+                     function clog2 (n : natural) return natural is
+                         variable r : natural := 0;
+                         variable v : natural := 1;
+                     begin
+                         while v < n loop
+                             v := v * 2;
+                             r := r + 1;
+                         end loop;
+                         return r;
+                     end;
+                     constant N : natural := clog2(256);
+                 begin end;",
+            )
+            .unwrap();
+            assert_eq!(k.to_string(), "8");
+        });
+    }
+
+    #[test]
+    fn const_function_runaway_recursion() {
+        score_test(|| {
+            assert!(last_const_value(
+                "entity foo is end;
+                 architecture bar of foo is
+                     function f (n : natural) return natural;
+                     function f (n : natural) return natural is
+                     begin
+                         return f(n + 1);
+                     end;
+                     constant N : integer := f(0);
+                 begin end;",
+            )
+            .is_err());
+        });
+    }
+}
@@ -50,9 +50,11 @@ macro_rules! unimp_msg {
 impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
     /// Unpack an AST expression.
     pub fn unpack_expr(&self, ast: &'ast ast::Expr, scope_id: ScopeRef) -> Result<ExprRef> {
-        let id = ExprRef::new(NodeId::alloc());
-        self.set_ast(id, (scope_id, ast));
-        Ok(id)
+        // let id = ExprRef::new(NodeId::alloc());
+        // self.set_ast(id, (scope_id, ast));
+        // Ok(id)
+        let ctx = AddContext::new(self, scope_id);
+        ctx.add_expr(ast)
     }
 
     /// Unpack an AST subtype indication.
@@ -123,11 +125,12 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             match *decl {
                 ast::DeclItem::SubprogDecl(ref decl) => match decl.data {
                     ast::SubprogData::Decl => {
-                        let subid = SubprogDeclRef(NodeId::alloc());
-                        self.set_ast(subid, (scope_id, decl));
-                        refs.push(subid.into());
+                        refs.push(ctx.add_subprog_decl(decl)?.into());
                     }
                     ast::SubprogData::Body { .. } => {
+                        if !is_subprog_declared(decls, decl) {
+                            refs.push(ctx.add_subprog_decl(decl)?.into());
+                        }
                         let subid = SubprogBodyRef(NodeId::alloc());
                         self.set_ast(subid, (scope_id, decl));
                         refs.push(subid.into());
@@ -154,9 +157,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     refs.push(subid.into());
                 }
                 ast::DeclItem::TypeDecl(ref decl) => {
-                    let subid = TypeDeclRef(NodeId::alloc());
-                    self.set_ast(subid, (scope_id, decl));
-                    refs.push(subid.into());
+                    refs.push(ctx.add_local_type_decl(decl)?.into());
                 }
                 ast::DeclItem::SubtypeDecl(ref decl) => {
                     let subid = SubtypeDeclRef(NodeId::alloc());
@@ -271,11 +272,12 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             match *decl {
                 ast::DeclItem::SubprogDecl(ref decl) => match decl.data {
                     ast::SubprogData::Decl => {
-                        let subid = SubprogDeclRef(NodeId::alloc());
-                        self.set_ast(subid, (scope_id, decl));
-                        refs.push(subid.into());
+                        refs.push(ctx.add_subprog_decl(decl)?.into());
                     }
                     ast::SubprogData::Body { .. } => {
+                        if !is_subprog_declared(decls, decl) {
+                            refs.push(ctx.add_subprog_decl(decl)?.into());
+                        }
                         let subid = SubprogBodyRef(NodeId::alloc());
                         self.set_ast(subid, (scope_id, decl));
                         refs.push(subid.into());
@@ -302,9 +304,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     refs.push(subid.into());
                 }
                 ast::DeclItem::TypeDecl(ref decl) => {
-                    let subid = TypeDeclRef(NodeId::alloc());
-                    self.set_ast(subid, (scope_id, decl));
-                    refs.push(subid.into());
+                    refs.push(ctx.add_local_type_decl(decl)?.into());
                 }
                 ast::DeclItem::SubtypeDecl(ref decl) => {
                     let subid = SubtypeDeclRef(NodeId::alloc());
@@ -410,11 +410,12 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             match *decl {
                 ast::DeclItem::SubprogDecl(ref decl) => match decl.data {
                     ast::SubprogData::Decl => {
-                        let subid = SubprogDeclRef(NodeId::alloc());
-                        self.set_ast(subid, (scope_id, decl));
-                        refs.push(subid.into());
+                        refs.push(ctx.add_subprog_decl(decl)?.into());
                     }
                     ast::SubprogData::Body { .. } => {
+                        if !is_subprog_declared(decls, decl) {
+                            refs.push(ctx.add_subprog_decl(decl)?.into());
+                        }
                         let subid = SubprogBodyRef(NodeId::alloc());
                         self.set_ast(subid, (scope_id, decl));
                         refs.push(subid.into());
@@ -441,9 +442,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     refs.push(subid.into());
                 }
                 ast::DeclItem::TypeDecl(ref decl) => {
-                    let subid = TypeDeclRef(NodeId::alloc());
-                    self.set_ast(subid, (scope_id, decl));
-                    refs.push(subid.into());
+                    refs.push(ctx.add_local_type_decl(decl)?.into());
                 }
                 ast::DeclItem::SubtypeDecl(ref decl) => {
                     let subid = SubtypeDeclRef(NodeId::alloc());
@@ -548,8 +547,17 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         for stmt in stmts {
             match stmt.data {
                 ast::BlockStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = BlockStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
+                }
+                ast::InstOrCallStmt {
+                    target: Some(ast::InstTarget::Entity),
+                    ..
+                } => {
+                    let id = CompInstStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
                 ast::InstOrCallStmt { .. } => {
                     unimp(stmt);
@@ -560,8 +568,9 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     had_fails = true;
                 }
                 ast::AssignStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = ConcSigAssignStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
                 ast::SelectAssignStmt { .. } => {
                     unimp(stmt);
@@ -576,8 +585,9 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                     had_fails = true;
                 }
                 ast::ForGenStmt { .. } => {
-                    unimp(stmt);
-                    had_fails = true;
+                    let id = ForGenStmtRef(NodeId::alloc());
+                    self.set_ast(id, (scope_id, stmt));
+                    refs.push(id.into());
                 }
 
                 ast::ProcStmt { .. } => {
@@ -637,13 +647,13 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 let (_res_name, mut defs, res_span, tail) =
                     self.resolve_compound_name(name, scope_id, false)?;
                 if !tail.is_empty() {
-                    self.emit(
-                        DiagBuilder2::bug(
-                            "handling of non-name signal assignment targets not implemented",
-                        )
-                        .span(name.span),
-                    );
-                    return Err(());
+                    let ctx = TermContext::new(self, scope_id);
+                    let term = ctx.termify_compound_name(name)?;
+                    let expr = ctx.term_to_expr(term)?;
+                    return Ok(hir::SigAssignTarget::Part(
+                        self.signal_of_target(expr)?,
+                        expr,
+                    ));
                 }
                 let sig = match defs.pop() {
                     Some(Spanned {
@@ -681,6 +691,26 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Determine the signal a part of which is selected by an expression.
+    ///
+    /// Walks the chain of selected and indexed names down to the signal.
+    fn signal_of_target(&self, id: ExprRef) -> Result<SignalRef> {
+        let hir = self.lazy_hir(id)?;
+        match hir.data {
+            hir::ExprData::SignalName(sig) => Ok(sig),
+            hir::ExprData::Select(prefix, _) | hir::ExprData::Call(prefix, _) => {
+                self.signal_of_target(prefix)
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a signal", hir.span.extract()))
+                        .span(hir.span),
+                );
+                Err(())
+            }
+        }
+    }
+
     /// Unpack a signal assignment mode.
     ///
     /// See IEEE 1076-2008 section 10.5.
@@ -742,7 +772,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 }
             })
         } else {
-            Ok(hir::DelayMechanism::Inertial)
+            Ok(hir::DelayMechanism::ImplicitInertial)
         }
     }
 
@@ -873,12 +903,9 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             );
         }
         let generic_map = vec![];
-        if ast.params.is_some() {
-            unimp_msg!(
-                self,
-                "lowering of parameters in subprogram specifications",
-                name.span
-            );
+        let mut params = Vec::new();
+        if let Some(ref decls) = ast.params {
+            self.unpack_params(scope_id, decls, &mut params)?;
         }
         let return_type = match ast.retty {
             Some(ref name) => Some(self.unpack_type_mark(name.into(), scope_id)?),
@@ -900,7 +927,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             kind: kind,
             generics: generics,
             generic_map: generic_map,
-            params: vec![],
+            params,
             return_type: return_type,
         })
     }
@@ -1002,6 +1029,109 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Unpack ports from a list of interface declarations.
+    ///
+    /// For ports only signal interface declarations are allowed. See IEEE
+    /// 1076-2008 section 6.5.6.3.
+    pub fn unpack_ports(
+        &self,
+        scope_id: ScopeRef,
+        decls: &'ast [ast::IntfDecl],
+        into: &mut Vec<IntfSignalRef>,
+    ) -> Result<()> {
+        let ctx = AddContext::new(self, scope_id);
+        let mut had_fails = false;
+        for decl in decls {
+            match *decl {
+                ast::IntfDecl::ObjDecl(
+                    ref decl @ ast::IntfObjDecl {
+                        kind: ast::IntfObjKind::Signal,
+                        ..
+                    },
+                ) => {
+                    let ty = ctx.add_subtype_ind(&decl.ty)?;
+                    for name in &decl.names {
+                        let id = IntfSignalRef(NodeId::alloc());
+                        self.set_ast(id, (scope_id, decl, ty, name));
+                        into.push(id);
+                    }
+                }
+                ref wrong => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "a {} cannot appear in a port clause",
+                            wrong.desc()
+                        ))
+                        .span(wrong.human_span()),
+                    );
+                    had_fails = true;
+                }
+            }
+        }
+        if had_fails {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Unpack subprogram parameters from a list of interface declarations.
+    ///
+    /// Parameters of mode `in` without an explicit class are constants. See
+    /// IEEE 1076-2008 section 4.2.2.1.
+    pub fn unpack_params(
+        &self,
+        scope_id: ScopeRef,
+        decls: &'ast [ast::IntfDecl],
+        into: &mut Vec<IntfObjRef>,
+    ) -> Result<()> {
+        let ctx = AddContext::new(self, scope_id);
+        let mut had_fails = false;
+        for decl in decls {
+            match *decl {
+                ast::IntfDecl::ObjDecl(ref decl) => match (decl.kind, decl.mode) {
+                    (ast::IntfObjKind::Const, None)
+                    | (ast::IntfObjKind::Const, Some(ast::IntfMode::In))
+                    | (ast::IntfObjKind::Var, None)
+                    | (ast::IntfObjKind::Var, Some(ast::IntfMode::In)) => {
+                        let ty = ctx.add_subtype_ind(&decl.ty)?;
+                        for name in &decl.names {
+                            let id = IntfConstRef(NodeId::alloc());
+                            self.set_ast(id, (scope_id, decl, ty, name));
+                            into.push(id.into());
+                        }
+                    }
+                    (ast::IntfObjKind::Signal, _) => {
+                        let ty = ctx.add_subtype_ind(&decl.ty)?;
+                        for name in &decl.names {
+                            let id = IntfSignalRef(NodeId::alloc());
+                            self.set_ast(id, (scope_id, decl, ty, name));
+                            into.push(id.into());
+                        }
+                    }
+                    _ => {
+                        unimp_msg!(self, format!("{} as parameter", decl.desc()), decl.span);
+                    }
+                },
+                ref wrong => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "a {} cannot appear in a parameter list",
+                            wrong.desc()
+                        ))
+                        .span(wrong.human_span()),
+                    );
+                    had_fails = true;
+                }
+            }
+        }
+        if had_fails {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
     /// Unpack a generic map from a parenthesized list of elements.
     ///
     /// See IEEE 1076-2008 section 6.5.7.2.
@@ -1015,6 +1145,185 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
         Ok(Vec::new())
     }
+
+    /// Unpack the entity aspect of an entity instantiation.
+    ///
+    /// Returns the architecture given in parentheses after the entity name, or
+    /// the most recently analyzed architecture of the entity if there is none.
+    /// See IEEE 1076-2008 section 7.3.2.2.
+    pub fn unpack_entity_aspect(
+        &self,
+        scope_id: ScopeRef,
+        name: &'ast ast::CompoundName,
+    ) -> Result<Spanned<ArchRef>> {
+        let (_, defs, entity_span, tail) = self.resolve_compound_name(name, scope_id, false)?;
+        let entity = match defs.last() {
+            Some(&Spanned {
+                value: Def::Entity(id),
+                ..
+            }) if defs.len() == 1 => id,
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not an entity", entity_span.extract()))
+                        .span(entity_span),
+                );
+                return Err(());
+            }
+        };
+        let arch_name = match *tail {
+            [] => None,
+            [ast::NamePart::Call(ref elems)] => match elems.value.as_slice() {
+                [ref elem] if elem.choices.value.is_empty() => match simple_ident(&elem.expr) {
+                    Some(name) => Some(name),
+                    None => {
+                        self.emit(
+                            DiagBuilder2::error(format!(
+                                "`{}` is not a valid architecture name",
+                                elem.span.extract()
+                            ))
+                            .span(elem.span),
+                        );
+                        return Err(());
+                    }
+                },
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error("expected a single architecture name").span(elems.span),
+                    );
+                    return Err(());
+                }
+            },
+            _ => {
+                let span = Span::union(entity_span.end(), name.span.end());
+                self.emit(DiagBuilder2::error("invalid name suffix").span(span));
+                return Err(());
+            }
+        };
+        let lib = self.hir(entity)?.lib;
+        let archs = &self.archs(lib)?.by_entity[&entity];
+        let arch = match arch_name {
+            Some(arch_name) => match archs.by_name.get(&arch_name.value) {
+                Some(&id) => id,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not an architecture of entity `{}`",
+                            arch_name.value,
+                            entity_span.extract()
+                        ))
+                        .span(arch_name.span),
+                    );
+                    return Err(());
+                }
+            },
+            None => match archs.ordered.last() {
+                Some(&id) => id,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "entity `{}` has no architecture",
+                            entity_span.extract()
+                        ))
+                        .span(entity_span),
+                    );
+                    return Err(());
+                }
+            },
+        };
+        Ok(Spanned::new(arch, name.span))
+    }
+
+    /// Unpack the generic or port map of an instantiation.
+    ///
+    /// Elements with a formal part are associated by name, all others by
+    /// position. Returns each formal together with its actual. See IEEE
+    /// 1076-2008 section 6.5.7.
+    pub fn unpack_assoc_map<T>(
+        &self,
+        scope_id: ScopeRef,
+        elems: &'ast ast::ParenElems,
+        formals: &[(Name, T)],
+        kind: &str,
+    ) -> Result<Vec<(T, ExprRef)>>
+    where
+        T: Copy + PartialEq,
+    {
+        let ctx = AddContext::new(self, scope_id);
+        let mut assocs = Vec::new();
+        let mut had_fails = false;
+        for (index, elem) in elems.value.iter().enumerate() {
+            let formal = if elem.choices.value.is_empty() {
+                formals.get(index).map(|&(_, formal)| formal)
+            } else {
+                let name = match elem.choices.value.as_slice() {
+                    [ref choice] => simple_ident(choice),
+                    _ => None,
+                };
+                name.and_then(|name| {
+                    formals
+                        .iter()
+                        .find(|&&(formal_name, _)| formal_name == name.value)
+                        .map(|&(_, formal)| formal)
+                })
+            };
+            let formal = match formal {
+                Some(formal) => formal,
+                None if elem.choices.value.is_empty() => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "no {} left to associate `{}` with",
+                            kind,
+                            elem.span.extract()
+                        ))
+                        .span(elem.span),
+                    );
+                    had_fails = true;
+                    continue;
+                }
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a {} of the entity",
+                            elem.choices.span.extract(),
+                            kind
+                        ))
+                        .span(elem.choices.span),
+                    );
+                    had_fails = true;
+                    continue;
+                }
+            };
+            if assocs.iter().any(|&(f, _)| f == formal) {
+                self.emit(
+                    DiagBuilder2::error(format!("{} associated more than once", kind))
+                        .span(elem.span),
+                );
+                had_fails = true;
+                continue;
+            }
+            assocs.push((formal, ctx.add_expr(&elem.expr)?));
+        }
+        if had_fails {
+            Err(())
+        } else {
+            Ok(assocs)
+        }
+    }
+}
+
+/// Extract the identifier from an expression that consists of a single name.
+fn simple_ident(expr: &ast::Expr) -> Option<Spanned<Name>> {
+    match expr.data {
+        ast::NameExpr(ast::CompoundName {
+            ref primary,
+            ref parts,
+            ..
+        }) if parts.is_empty() => match primary.kind {
+            ast::PrimaryNameKind::Ident(name) => Some(Spanned::new(name, primary.span)),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 // Lower an entity to HIR.
@@ -1029,34 +1338,12 @@ impl_make!(self, id: EntityRef => &hir::Entity {
     };
     let mut port_spans = Vec::new();
     let mut generic_spans = Vec::new();
-    let ctx = AddContext::new(self, id.into());
     for decl in &ast.decls {
         match *decl {
             // Port clauses
             ast::DeclItem::PortgenClause(_, Spanned{ value: ast::PortgenKind::Port, span }, ref decls) => {
-                // For ports only signal interface declarations are allowed.
                 port_spans.push(span);
-                for decl in &decls.value {
-                    match *decl {
-                        ast::IntfDecl::ObjDecl(ref decl @ ast::IntfObjDecl{ kind: ast::IntfObjKind::Signal, .. }) => {
-                            let ty = ctx.add_subtype_ind(&decl.ty)?;
-                            // let ty = SubtypeIndRef(NodeId::alloc());
-                            // self.set_ast(ty, (id.into(), &decl.ty));
-                            for name in &decl.names {
-                                let subid = IntfSignalRef(NodeId::alloc());
-                                self.set_ast(subid, (id.into(), decl, ty, name));
-                                entity.ports.push(subid);
-                            }
-                        }
-                        ref wrong => {
-                            self.emit(
-                                DiagBuilder2::error(format!("a {} cannot appear in a port clause", wrong.desc()))
-                                .span(wrong.human_span())
-                            );
-                            continue;
-                        }
-                    }
-                }
+                self.unpack_ports(id.into(), &decls.value, &mut entity.ports)?;
             }
 
             // Generic clauses
@@ -1112,10 +1399,13 @@ impl_make!(self, id: PkgDeclRef => &hir::Package {
     let (outer_scope, ast) = self.ast(id);
     let scope = id.into();
     self.subscope(scope, outer_scope);
-    let generics = Vec::new();
+    let mut generics = Vec::new();
     // let generic_maps = Vec::new();
     let mut decls = Vec::new();
     let mut had_fails = false;
+    if let Some(ref gc) = ast.generics {
+        self.unpack_generics(scope, &gc.value, &mut generics)?;
+    }
 
     // Filter the declarations in the package to only those that we actually
     // support, and separate the generic clauses and maps.
@@ -1125,9 +1415,7 @@ impl_make!(self, id: PkgDeclRef => &hir::Package {
             ast::DeclItem::SubprogDecl(ref decl) => {
                 match decl.data {
                     ast::SubprogData::Decl => {
-                        let subid = SubprogDeclRef(NodeId::alloc());
-                        self.set_ast(subid, (scope, decl));
-                        decls.push(subid.into());
+                        decls.push(ctx.add_subprog_decl(decl)?.into());
                     }
                     ast::SubprogData::Body{..} => {
                         self.emit(
@@ -1164,6 +1452,7 @@ impl_make!(self, id: PkgDeclRef => &hir::Package {
             ast::DeclItem::SubtypeDecl(ref decl) => {
                 let subid = SubtypeDeclRef(NodeId::alloc());
                 self.set_ast(subid, (scope, decl));
+                self.define(scope, decl.name.map_into(), Def::Subtype(subid))?;
                 decls.push(subid.into());
             }
             ast::DeclItem::ObjDecl(ref decl) => {
@@ -1268,9 +1557,7 @@ impl_make!(self, id: PkgBodyRef => &hir::PackageBody {
             ast::DeclItem::SubprogDecl(ref decl) => {
                 match decl.data {
                     ast::SubprogData::Decl => {
-                        let subid = SubprogDeclRef(NodeId::alloc());
-                        self.set_ast(subid, (id.into(), decl));
-                        decls.push(subid.into());
+                        decls.push(ctx.with_scope(id.into()).add_subprog_decl(decl)?.into());
                     }
                     ast::SubprogData::Body{..} => {
                         let subid = SubprogBodyRef(NodeId::alloc());
@@ -1300,9 +1587,7 @@ impl_make!(self, id: PkgBodyRef => &hir::PackageBody {
                 decls.push(subid.into());
             }
             ast::DeclItem::TypeDecl(ref decl) => {
-                let subid = TypeDeclRef(NodeId::alloc());
-                self.set_ast(subid, (id.into(), decl));
-                decls.push(subid.into());
+                decls.push(ctx.with_scope(id.into()).add_local_type_decl(decl)?.into());
             }
             ast::DeclItem::SubtypeDecl(ref decl) => {
                 let subid = SubtypeDeclRef(NodeId::alloc());
@@ -1827,7 +2112,10 @@ impl_make!(self, id: ArchRef => &hir::Arch {
     let (lib_id, ctx_id, ast) = self.ast(id);
     let decls = self.unpack_block_decls(id.into(), &ast.decls, "an architecture")?;
     let stmts = self.unpack_concurrent_stmts(id.into(), &ast.stmts, "an architecture")?;
-    let entity_id = *self.archs(lib_id)?.by_arch.get(&id).unwrap();
+    let entity_id = match self.specialized_entity(id) {
+        Some(entity_id) => entity_id,
+        None => *self.archs(lib_id)?.by_arch.get(&id).unwrap(),
+    };
     Ok(self.sb.arenas.hir.arch.alloc(hir::Arch{
         ctx_items: ctx_id,
         entity: entity_id,
@@ -1837,24 +2125,76 @@ impl_make!(self, id: ArchRef => &hir::Arch {
     }))
 });
 
+// Lower an entity instantiation statement to HIR.
+impl_make!(self, id: CompInstStmtRef => &hir::CompInstStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (name, generics, ports) = match ast.data {
+        ast::InstOrCallStmt { ref name, ref generics, ref ports, .. } => (name, generics, ports),
+        _ => unreachable!(),
+    };
+    let label = match ast.label {
+        Some(label) => label,
+        None => {
+            self.emit(
+                DiagBuilder2::error("entity instantiation requires a label")
+                .span(ast.human_span())
+                .add_note("see IEEE 1076-2008 section 11.7.1")
+            );
+            return Err(());
+        }
+    };
+    let arch = self.unpack_entity_aspect(scope_id, name)?;
+    let entity = self.hir(self.hir(arch.value)?.entity)?;
+    let generic_formals: Vec<_> = entity.generics.iter().filter_map(|&generic| match generic {
+        GenericRef::Const(id) => Some((self.ast(id).3.name, id)),
+        _ => None,
+    }).collect();
+    let port_formals = entity.ports.iter()
+        .map(|&port| Ok((self.hir(port)?.name.value, port)))
+        .collect::<Result<Vec<_>>>()?;
+    let generic_map = match *generics {
+        Some(ref elems) => self.unpack_assoc_map(scope_id, elems, &generic_formals, "generic")?,
+        None => vec![],
+    };
+    let port_map = match *ports {
+        Some(ref elems) => self.unpack_assoc_map(scope_id, elems, &port_formals, "port")?,
+        None => vec![],
+    };
+    Ok(self.sb.arenas.hir.comp_inst_stmt.alloc(hir::CompInstStmt {
+        parent: scope_id,
+        label,
+        arch,
+        generic_map,
+        port_map,
+    }))
+});
+
 impl_make!(self, id: ProcessStmtRef => &hir::ProcessStmt {
     let (scope_id, ast) = self.ast(id);
     match ast.data {
         ast::ProcStmt {
-            // ref sensitivity,
+            ref sensitivity,
             ref decls,
             ref stmts,
             postponed,
             ..
         } => {
-            // TODO: map sensititivty
+            let sensitivity = match *sensitivity {
+                None => hir::ProcessSensitivity::None,
+                Some(ast::Sensitivity::All) => hir::ProcessSensitivity::All,
+                Some(ast::Sensitivity::List(ref names)) => {
+                    let ctx = AddContext::new(self, scope_id);
+                    let list = ctx.add_sensitivity_list(Spanned::new(names.iter(), ast.span))?;
+                    hir::ProcessSensitivity::List(list.value)
+                }
+            };
             let decls = self.unpack_process_decls(id.into(), decls, "a process")?;
             let stmts = self.unpack_sequential_stmts(id.into(), stmts, "a process")?;
             Ok(self.sb.arenas.hir.process_stmt.alloc(hir::ProcessStmt {
                 parent: scope_id,
                 label: ast.label,
                 postponed: postponed,
-                sensitivity: hir::ProcessSensitivity::None,
+                sensitivity,
                 decls: decls,
                 stmts: stmts,
             }))
@@ -1863,6 +2203,154 @@ impl_make!(self, id: ProcessStmtRef => &hir::ProcessStmt {
     }
 });
 
+// Lower a block statement to HIR.
+impl_make!(self, id: BlockStmtRef => &hir::BlockStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (guard, header, decls, stmts) = match ast.data {
+        ast::BlockStmt {
+            ref guard,
+            ref generics,
+            ref generic_map,
+            ref ports,
+            ref port_map,
+            ref decls,
+            ref stmts,
+        } => (guard, (generics, generic_map, ports, port_map), decls, stmts),
+        _ => unreachable!(),
+    };
+    let label = match ast.label {
+        Some(label) => label,
+        None => {
+            self.emit(
+                DiagBuilder2::error("block statement requires a label")
+                .span(ast.human_span())
+                .add_note("see IEEE 1076-2008 section 11.2")
+            );
+            return Err(());
+        }
+    };
+    let guard = match *guard {
+        Some(ref expr) => {
+            let expr = self.unpack_expr(expr, id.into())?;
+            self.set_type_context(expr, self.intern_ty(BOOLEAN_TYPE.named_ty()));
+            Some(expr)
+        }
+        None => None,
+    };
+
+    // The generics and ports declared in the block header act as the block's
+    // interface. The actuals in the maps are evaluated in the enclosing scope.
+    let (generic_decls, generic_elems, port_decls, port_elems) = header;
+    let mut generics = Vec::new();
+    if let Some(ref decls) = *generic_decls {
+        self.unpack_generics(id.into(), &decls.value, &mut generics)?;
+    }
+    let mut ports = Vec::new();
+    if let Some(ref decls) = *port_decls {
+        self.unpack_ports(id.into(), &decls.value, &mut ports)?;
+    }
+    let generic_formals: Vec<_> = generics.iter().filter_map(|&generic| match generic {
+        GenericRef::Const(id) => Some((self.ast(id).3.name, id)),
+        _ => None,
+    }).collect();
+    let port_formals = ports.iter()
+        .map(|&port| Ok((self.hir(port)?.name.value, port)))
+        .collect::<Result<Vec<_>>>()?;
+    let generic_map = match *generic_elems {
+        Some(ref elems) => self.unpack_assoc_map(scope_id, elems, &generic_formals, "generic")?,
+        None => vec![],
+    };
+    let port_map = match *port_elems {
+        Some(ref elems) => self.unpack_assoc_map(scope_id, elems, &port_formals, "port")?,
+        None => vec![],
+    };
+
+    let decls = self.unpack_block_decls(id.into(), decls, "a block")?;
+    let stmts = self.unpack_concurrent_stmts(id.into(), stmts, "a block")?;
+    Ok(self.sb.arenas.hir.block_stmt.alloc(hir::BlockStmt {
+        parent: scope_id,
+        label,
+        guard,
+        generics,
+        generic_map,
+        ports,
+        port_map,
+        decls,
+        stmts,
+    }))
+});
+
+// Lower a for-generate statement to HIR.
+impl_make!(self, id: ForGenStmtRef => &hir::ForGenStmt {
+    let (scope_id, ast) = self.ast(id);
+    let (param, range, body) = match ast.data {
+        ast::ForGenStmt { param, ref range, ref body } => (param, range, body),
+        _ => unreachable!(),
+    };
+    let label = match ast.label {
+        Some(label) => label,
+        None => {
+            self.emit(
+                DiagBuilder2::error("generate statement requires a label")
+                .span(ast.human_span())
+                .add_note("see IEEE 1076-2008 section 11.8")
+            );
+            return Err(());
+        }
+    };
+    let range = AddContext::new(self, scope_id).add_discrete_range(range)?;
+    let decls = self.unpack_block_decls(id.into(), &body.decls, "a generate statement")?;
+    let stmts = self.unpack_concurrent_stmts(id.into(), &body.stmts, "a generate statement")?;
+    Ok(self.sb.arenas.hir.for_gen_stmt.alloc(hir::ForGenStmt {
+        parent: scope_id,
+        label,
+        param,
+        range,
+        decls,
+        stmts,
+    }))
+});
+
+// Lower a concurrent signal assignment to HIR.
+impl_make!(self, id: ConcSigAssignStmtRef => &hir::ConcSigAssignStmt {
+    let (scope_id, ast) = self.ast(id);
+    let guarded = match ast.data {
+        ast::AssignStmt { guarded, .. } => guarded,
+        _ => unreachable!(),
+    };
+    let assign = SigAssignStmtRef(NodeId::alloc());
+    self.set_ast(assign, (scope_id, ast));
+
+    // A guarded assignment is controlled by the `GUARD` signal visible at the
+    // statement, which is usually declared implicitly by a guarded block.
+    let guard = if guarded {
+        let name = Spanned::new(
+            ResolvableName::Ident(get_name_table().intern("GUARD", false)),
+            ast.span,
+        );
+        match self.resolve_name(name, scope_id, false, true)?.first() {
+            Some(&Spanned { value: Def::Signal(sig), .. }) => Some(sig),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error("guarded signal assignment requires a signal `GUARD`")
+                    .span(ast.human_span())
+                    .add_note("Guarded assignments may only appear in blocks with a guard expression. See IEEE 1076-2008 section 11.6.")
+                );
+                return Err(());
+            }
+        }
+    } else {
+        None
+    };
+    Ok(self.sb.arenas.hir.conc_sig_assign_stmt.alloc(hir::ConcSigAssignStmt {
+        parent: scope_id,
+        span: ast.span,
+        label: ast.label,
+        guard,
+        assign,
+    }))
+});
+
 impl_make!(self, id: SigAssignStmtRef => &hir::SigAssignStmt {
     let (scope_id, ast) = self.ast(id);
     match ast.data {
@@ -1875,10 +2363,17 @@ impl_make!(self, id: SigAssignStmtRef => &hir::SigAssignStmt {
             let target = self.unpack_signal_assign_target(scope_id, target)?;
             let tyctx = match target {
                 hir::SigAssignTarget::Name(id) => TypeCtx::TypeOf(id.into()),
+                hir::SigAssignTarget::Part(_, expr) => TypeCtx::TypeOf(expr.into()),
                 hir::SigAssignTarget::Aggregate => unimplemented!(),
             };
             let kind = self.unpack_signal_assign_mode(scope_id, mode, &tyctx)?;
-            if guarded {
+            // Concurrent signal assignments are lowered to a sequential one
+            // as well, see `ConcSigAssignStmtRef`.
+            let concurrent = matches!(
+                scope_id,
+                ScopeRef::Arch(_) | ScopeRef::Block(_) | ScopeRef::ForGen(_)
+            );
+            if guarded && !concurrent {
                 self.emit(
                     DiagBuilder2::warning("sequential signal assignment cannot be guarded")
                     .span(ast.human_span())
@@ -2008,3 +2503,27 @@ impl_make!(self, id: LatentTypeMarkRef => Spanned<TypeMarkRef> {
     let term = ctx.termify_latent_name(ast)?;
     ctx.term_to_type_mark(term)
 });
+
+/// Check whether a declarative part declares the subprogram of a body.
+///
+/// A body without such a declaration declares the subprogram itself. The
+/// specifications of the declaration and the body must conform, that is,
+/// consist of the same lexical elements. See IEEE 1076-2008 sections 4.3 and
+/// 4.10.
+fn is_subprog_declared(decls: &[ast::DeclItem], body: &ast::Subprog) -> bool {
+    let lexemes = |spec: &ast::SubprogSpec| -> String {
+        spec.span
+            .extract()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let spec = lexemes(&body.spec);
+    decls.iter().any(|decl| match *decl {
+        ast::DeclItem::SubprogDecl(ref decl) => {
+            decl.data == ast::SubprogData::Decl && lexemes(&decl.spec) == spec
+        }
+        _ => false,
+    })
+}
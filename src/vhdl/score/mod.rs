@@ -7,7 +7,7 @@
 
 use std;
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 
 use moore_common::errors::*;
@@ -25,11 +25,15 @@ use typed_arena::Arena;
 use crate::arenas::Alloc;
 use crate::builtin;
 pub use crate::builtin::*;
-use crate::codegen::Codegen;
+use crate::cache::{CacheEntry, UnitCache};
+use crate::codegen::{Codegen, EnumEncoding, IntWidthPolicy, LowerSpan, UnitContext};
 use crate::hir;
 use crate::konst::*;
 use crate::lazy::*;
+use crate::mangle::{DefaultMangler, Mangler};
 use crate::op::*;
+use crate::specialize::Specialization;
+use crate::srcmap::SourceMap;
 use crate::syntax::ast;
 use crate::ty::*;
 use crate::typeck::{Typeck, TypeckContext};
@@ -91,6 +95,11 @@ pub struct ScoreBoard<'ast, 'ctx> {
     arch_table: RefCell<HashMap<LibRef, &'ctx ArchTable>>,
     /// The LLHD module into which code is emitted.
     pub llmod: RefCell<llhd::ir::Module>,
+    /// Generated LLHD units that have not been added to `llmod` yet, together
+    /// with their names.
+    pending_units: RefCell<Vec<(String, llhd::ir::UnitData)>>,
+    /// The IDs of the units added to `llmod`, by name.
+    unit_ids: RefCell<HashMap<String, llhd::ir::UnitId>>,
     /// A table of LLHD declarations (i.e. prototypes). These are useful for
     /// example when an entity needs so be instantiated, for which only the
     /// signature of the entity is required, but not its full definition with
@@ -102,6 +111,12 @@ pub struct ScoreBoard<'ast, 'ctx> {
     llunit_table: RefCell<HashMap<NodeId, llhd::ir::UnitId>>,
     /// A table of types.
     pub ty_table: RefCell<HashMap<NodeId, &'ctx Ty>>,
+    /// The canonical types, indexed by their `TyId`.
+    pub canon_tys: RefCell<Vec<&'ctx Ty>>,
+    /// The LLHD type each canonical type maps to.
+    pub llty_table: RefCell<HashMap<TyId, llhd::Type>>,
+    /// The encodings of enum types given by `enum_encoding` attributes.
+    pub enum_encodings: RefCell<HashMap<TypeDeclRef, Option<EnumEncoding>>>,
     /// A table of scopes.
     scope_table: RefCell<HashMap<ScopeRef, &'ctx Scope>>,
     /// A table of nodes' constant values.
@@ -114,6 +129,55 @@ pub struct ScoreBoard<'ast, 'ctx> {
     pub typeval_table: RefCell<HashMap<NodeId, Result<&'ctx Ty>>>,
     /// A table of scopes. Revised; will replace `scope_table` and `def_table`.
     pub scope2_table: RefCell<HashMap<ScopeRef, crate::scope::Scope>>,
+    /// The scheme used to name generated LLHD units.
+    pub mangler: Box<dyn Mangler>,
+    /// A prefix for the names of all generated units, to keep them apart
+    /// from other units in the same LLHD module.
+    pub unit_prefix: Option<String>,
+    /// The unit name assigned to each mangled path.
+    pub unit_names: RefCell<HashMap<Vec<String>, String>>,
+    /// The unit names assigned so far, to keep them unique.
+    pub used_unit_names: RefCell<HashSet<String>>,
+    /// Initial values of signals that override their declaration, keyed by
+    /// the signal's fully-qualified name.
+    signal_init_table: RefCell<HashMap<String, &'ctx Const>>,
+    /// The architectures specialized for the values of their generics, in the
+    /// order in which they were created.
+    pub specializations: RefCell<Vec<Specialization<'ctx>>>,
+    /// The names and signatures of the units generated for architectures.
+    pub arch_units: RefCell<HashMap<ArchRef, (llhd::ir::UnitName, llhd::ir::Signature)>>,
+    /// The cache from which units generated in an earlier compilation are
+    /// reused.
+    pub unit_cache: Option<&'ctx UnitCache>,
+    /// The units generated for each architecture that is currently being
+    /// generated, innermost last. Only maintained if a unit cache is present.
+    unit_frames: RefCell<Vec<Vec<String>>>,
+    /// The map in which the source spans of the generated instructions are
+    /// recorded, if any.
+    pub source_map: Option<&'ctx SourceMap>,
+    /// The signals read by impure functions, which are passed to the
+    /// functions as implicit arguments. `None` while being determined.
+    pub impure_signal_table: RefCell<HashMap<SubprogBodyRef, Option<Vec<SignalRef>>>>,
+    /// The process that holds each shared variable. A shared variable is
+    /// placed in the only process that accesses it.
+    pub shared_var_owners: RefCell<HashMap<VarDeclRef, ProcessStmtRef>>,
+    /// The values of the parameters of the for-generate statements whose
+    /// iterations are currently being generated.
+    pub gen_params: RefCell<HashMap<ForGenStmtRef, &'ctx Const>>,
+    /// The severity level from which reports and failed assertions halt the
+    /// process that issues them, as an index into `SEVERITY_LEVEL`.
+    pub halt_severity: usize,
+    /// The signals each generated process waits on, keyed by the name of the
+    /// process unit. Processes reused from the unit cache are not recorded.
+    pub sensitivities: RefCell<HashMap<String, Vec<Name>>>,
+    /// The policy by which integer types and constants are given a width.
+    pub int_width: IntWidthPolicy,
+    /// The maximum number of statements executed when evaluating a function
+    /// call at elaboration time, including the statements of nested calls.
+    pub const_eval_budget: usize,
+    /// The names that failed to resolve, in the order in which they were
+    /// encountered. Each name is recorded once per occurrence in the source.
+    pub unresolved_names: RefCell<Vec<Spanned<ResolvableName>>>,
 }
 
 impl<'ast, 'ctx> ScoreBoard<'ast, 'ctx> {
@@ -129,16 +193,39 @@ impl<'ast, 'ctx> ScoreBoard<'ast, 'ctx> {
             def_table: RefCell::new(HashMap::new()),
             arch_table: RefCell::new(HashMap::new()),
             llmod: RefCell::new(llhd::ir::Module::new()),
+            pending_units: RefCell::new(Vec::new()),
+            unit_ids: RefCell::new(HashMap::new()),
             lldecl_table: RefCell::new(HashMap::new()),
             lldef_table: RefCell::new(HashMap::new()),
             llunit_table: RefCell::new(HashMap::new()),
             ty_table: RefCell::new(HashMap::new()),
+            canon_tys: RefCell::new(Vec::new()),
+            llty_table: RefCell::new(HashMap::new()),
+            enum_encodings: RefCell::new(HashMap::new()),
             scope_table: RefCell::new(HashMap::new()),
             const_table: RefCell::new(HashMap::new()),
             tyctx_table: RefCell::new(HashMap::new()),
             typeck_table: RefCell::new(HashMap::new()),
             typeval_table: RefCell::new(HashMap::new()),
             scope2_table: RefCell::new(HashMap::new()),
+            mangler: Box::new(DefaultMangler),
+            unit_prefix: None,
+            unit_names: RefCell::new(HashMap::new()),
+            used_unit_names: RefCell::new(HashSet::new()),
+            signal_init_table: RefCell::new(HashMap::new()),
+            specializations: RefCell::new(Vec::new()),
+            arch_units: RefCell::new(HashMap::new()),
+            unit_cache: None,
+            unit_frames: RefCell::new(Vec::new()),
+            source_map: None,
+            impure_signal_table: RefCell::new(HashMap::new()),
+            shared_var_owners: RefCell::new(HashMap::new()),
+            gen_params: RefCell::new(HashMap::new()),
+            halt_severity: SEVERITY_FAILURE,
+            sensitivities: RefCell::new(HashMap::new()),
+            int_width: IntWidthPolicy::default(),
+            const_eval_budget: 100_000,
+            unresolved_names: RefCell::new(Vec::new()),
         };
         builtin::register_builtins(&sb);
         sb
@@ -181,6 +268,16 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         self.sb.lib_names.borrow_mut().insert(name, id);
     }
 
+    /// Obtain the name of a library.
+    pub fn lib_name(&self, id: LibRef) -> Option<Name> {
+        self.sb
+            .lib_names
+            .borrow()
+            .iter()
+            .find(|&(_, &lib)| lib == id)
+            .map(|(&name, _)| name)
+    }
+
     /// Obtain the span associated with a node ID.
     pub fn span<I>(&self, id: I) -> Option<Span>
     where
@@ -224,6 +321,22 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Find the attribute specifications declared in a scope, in the order of
+    /// their declaration.
+    pub fn attr_specs_in_scope(&self, scope: ScopeRef) -> Vec<AttrSpecRef> {
+        let mut specs: Vec<AttrSpecRef> = self
+            .sb
+            .ast_table
+            .borrow()
+            .attr_specs
+            .iter()
+            .filter(|&(_, &(spec_scope, _))| spec_scope == scope)
+            .map(|(&id, _)| id)
+            .collect();
+        specs.sort();
+        specs
+    }
+
     /// Store an AST node in the scoreboard.
     pub fn set_ast<I>(&self, id: I, ast: <AstTable<'ast> as NodeStorage<I>>::Node)
     where
@@ -348,6 +461,51 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         Ok(node)
     }
 
+    /// Find the entities of a library that no architecture of the library
+    /// instantiates.
+    ///
+    /// These are the candidates for the top of the design hierarchy.
+    pub fn top_entities(&self, id: LibRef) -> Result<Vec<EntityRef>> {
+        let archs = self.archs(id)?;
+        let mut instantiated = HashSet::new();
+        for &arch in archs.by_arch.keys() {
+            self.collect_instantiated_entities(&self.hir(arch)?.stmts, &mut instantiated)?;
+        }
+        let mut entities: Vec<_> = archs
+            .by_entity
+            .keys()
+            .cloned()
+            .filter(|entity| !instantiated.contains(entity))
+            .collect();
+        entities.sort();
+        Ok(entities)
+    }
+
+    /// Collect the entities instantiated by a sequence of concurrent
+    /// statements, including the ones nested in blocks and generate
+    /// statements.
+    fn collect_instantiated_entities(
+        &self,
+        stmts: &[ConcStmtRef],
+        entities: &mut HashSet<EntityRef>,
+    ) -> Result<()> {
+        for &stmt in stmts {
+            match stmt {
+                ConcStmtRef::CompInst(id) => {
+                    entities.insert(self.hir(self.hir(id)?.arch.value)?.entity);
+                }
+                ConcStmtRef::Block(id) => {
+                    self.collect_instantiated_entities(&self.hir(id)?.stmts, entities)?
+                }
+                ConcStmtRef::ForGen(id) => {
+                    self.collect_instantiated_entities(&self.hir(id)?.stmts, entities)?
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
     pub fn lldecl<I>(&self, id: I) -> Result<llhd::ir::Value>
     where
         I: 'ctx + Copy + Debug + Into<NodeId>,
@@ -549,6 +707,17 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         Ok(node)
     }
 
+    /// Store the constant value of a node.
+    ///
+    /// This overrides the value that would otherwise be calculated for the
+    /// node, for example to bind a generic to the value of an instantiation.
+    pub fn set_const<I>(&self, id: I, konst: &'ctx Const)
+    where
+        I: Copy + Debug + Into<NodeId>,
+    {
+        self.sb.const_table.borrow_mut().insert(id.into(), konst);
+    }
+
     /// Obtain the type context for an expression.
     ///
     /// Returns `None` if no context information is available.
@@ -752,9 +921,11 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 self.emit(
                     DiagBuilder2::error(format!("`{}` is not known", name.value)).span(name.span),
                 );
+                self.record_unresolved_name(name);
                 Err(())
             }
         } else {
+            let found_defs = self.expand_aliases(found_defs)?;
             if self.sess.opts.trace_scoreboard {
                 debugln!("[SB][VHDL] resolved {:?} to {:?}", name.value, found_defs);
             }
@@ -768,6 +939,14 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Record a name that failed to resolve.
+    pub fn record_unresolved_name(&self, name: Spanned<ResolvableName>) {
+        let mut unresolved = self.sb.unresolved_names.borrow_mut();
+        if !unresolved.contains(&name) {
+            unresolved.push(name);
+        }
+    }
+
     /// Resolve a compound name within a scope.
     pub fn resolve_compound_name<'a>(
         &self,
@@ -848,24 +1027,157 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         Ok((res_name.value, defs, seen_span, &[]))
     }
 
+    /// Find the span of the declaration a name expression refers to.
+    ///
+    /// The name is resolved anew in the scope the expression appears in, which
+    /// does not require the expression to be type checked. If the name is
+    /// overloaded, as is common for subprograms and enumeration literals, the
+    /// span of the first declaration found is returned. Returns `None` if the
+    /// expression is not a name or the name does not resolve.
+    pub fn definition_span(&self, id: ExprRef) -> Option<Span> {
+        let (scope_id, ast) = match self.sb.ast_table.borrow().get(&id) {
+            Some(&node) => node,
+            None => return None,
+        };
+        let name = match ast.data {
+            ast::NameExpr(ref name) => name,
+            _ => return None,
+        };
+        let res_name = self.resolvable_from_primary_name(&name.primary).ok()?;
+        let mut defs = self.resolve_name(res_name, scope_id, false, true).ok()?;
+
+        // Follow selections into libraries and packages, similar to
+        // `resolve_compound_name`. Anything else is part of the object named.
+        for part in &name.parts {
+            let (def, pn) = match (defs.as_slice(), part) {
+                ([def], ast::NamePart::Select(pn)) => (def.value, pn),
+                _ => break,
+            };
+            let scope = match def {
+                Def::Lib(id) => id.into(),
+                Def::Pkg(id) => id.into(),
+                Def::BuiltinPkg(id) => id.into(),
+                _ => break,
+            };
+            let res_name = self.resolvable_from_primary_name(pn).ok()?;
+            defs = self.resolve_name(res_name, scope, true, true).ok()?;
+        }
+        defs.first().map(|def| def.span)
+    }
+
+    /// List the declarations a package makes visible to the design units
+    /// that use it.
+    ///
+    /// Returns the name, kind, and span of each declaration in the order in
+    /// which they appear in the source. Enumeration literals and physical
+    /// units are part of their type and are not listed separately.
+    pub fn package_exports(
+        &self,
+        id: PkgDeclRef,
+    ) -> Result<Vec<(ResolvableName, ExportKind, Span)>> {
+        let mut exports = Vec::new();
+        for (&name, defs) in self.defs(ScopeRef::Pkg(id))? {
+            for def in defs {
+                let kind = match def.value {
+                    Def::Type(_) => ExportKind::Type,
+                    Def::Subtype(_) => ExportKind::Subtype,
+                    Def::Const(_) => ExportKind::Const,
+                    Def::Signal(_) => ExportKind::Signal,
+                    Def::Var(_) => ExportKind::Var,
+                    Def::File(_) => ExportKind::File,
+                    Def::Alias(_) => ExportKind::Alias,
+                    Def::Subprog(_) | Def::SubprogInst(_) => ExportKind::Subprog,
+                    Def::Comp(_) => ExportKind::Comp,
+                    Def::Attr(_) => ExportKind::Attr,
+                    Def::GroupTemp(_) | Def::Group(_) => ExportKind::Group,
+                    Def::Pkg(_) | Def::PkgInst(_) => ExportKind::Pkg,
+                    _ => continue,
+                };
+                exports.push((name, kind, def.span));
+            }
+        }
+        exports.sort_by_key(|&(_, _, span)| span.begin);
+        Ok(exports)
+    }
+
+    /// Replace aliases among a set of definitions by the definitions they
+    /// stand for.
+    fn expand_aliases(&self, defs: Vec<Spanned<Def>>) -> Result<Vec<Spanned<Def>>> {
+        let mut expanded = Vec::with_capacity(defs.len());
+        for def in defs {
+            match def.value {
+                Def::Alias(id) => expanded.extend(self.alias_defs(id)?),
+                _ => expanded.push(def),
+            }
+        }
+        Ok(expanded)
+    }
+
+    /// Determine the definitions an alias stands for.
+    ///
+    /// Only non-object aliases are supported, i.e. aliases of subprograms,
+    /// enumeration literals, and types. See IEEE 1076-2008 section 6.6.3. The
+    /// signature of the alias is not checked, such that an alias of an
+    /// overloaded name stands for all overloads.
+    pub fn alias_defs(&self, id: AliasDeclRef) -> Result<Vec<Spanned<Def>>> {
+        let (scope_id, ast) = self.ast(id);
+        let (_, defs, _, parts) = self.resolve_compound_name(&ast.target, scope_id, false)?;
+        match *parts {
+            [] | [ast::NamePart::Signature(_)] => (),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` cannot be aliased",
+                        ast.target.span.extract()
+                    ))
+                    .span(ast.target.span),
+                );
+                return Err(());
+            }
+        }
+        for def in &defs {
+            match def.value {
+                Def::Subprog(_)
+                | Def::SubprogInst(_)
+                | Def::Enum(_)
+                | Def::Type(_)
+                | Def::Subtype(_) => (),
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "alias of `{}` not supported",
+                            ast.target.span.extract()
+                        ))
+                        .span(ast.target.span)
+                        .add_note(
+                            "Only subprograms, enumeration literals, and types can be aliased.",
+                        ),
+                    );
+                    return Err(());
+                }
+            }
+        }
+        Ok(defs)
+    }
+
     /// Get the builtin type `standard.boolean`.
     pub fn builtin_boolean_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(builtin::BOOLEAN_TYPE.named_ty())
     }
 
     /// Get the builtin type `standard.time`.
     pub fn builtin_time_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(builtin::TIME_TYPE.named_ty())
     }
 
     /// Get the builtin type `standard.string`.
     pub fn builtin_string_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(builtin::STRING_TYPE.named_ty())
     }
 
     /// Get the builtin type `standard.severity`.
     pub fn builtin_severity_type(&self) -> &'ctx Ty {
-        self.intern_ty(Ty::Null)
+        self.intern_ty(builtin::SEVERITY_LEVEL_TYPE.named_ty())
     }
 }
 
@@ -967,6 +1279,20 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ArchRef, llhd::ir::UnitId>
     for ScoreContext<'lazy, 'sb, 'ast, 'ctx>
 {
     fn make(&self, id: ArchRef) -> Result<llhd::ir::UnitId> {
+        // Generate the entity and add it and the units generated for it to
+        // the module.
+        let name = self.lower_arch(id)?;
+        self.add_pending_units();
+        Ok(self.unit_id(&name).unwrap())
+    }
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Type check an architecture and generate its entity.
+    ///
+    /// The units are scheduled to be added to the module, see
+    /// `add_pending_units`. Returns the name of the entity.
+    pub fn lower_arch(&self, id: ArchRef) -> Result<llhd::ir::UnitName> {
         // Type check the entire library where the architecture is defined in.
         let typeck_ctx = TypeckContext::new(self);
         typeck_ctx.typeck(self.ast(id).0); // typeck the entire library
@@ -975,86 +1301,319 @@ impl<'lazy, 'sb, 'ast, 'ctx> NodeMaker<ArchRef, llhd::ir::UnitId>
         }
         // self.typeck(id)?;
         // self.typeck(self.ast(id).0)?; // typeck the entire library
+        self.check_unused_signals(id)?;
+        Ok(self.codegen_arch(id)?.0)
+    }
 
+    /// Generate the entity for an architecture.
+    ///
+    /// The entity and the units generated for it are scheduled to be added to
+    /// the module, see `add_unit_deferred`. Architectures are only generated
+    /// once; later calls return the name and signature of the earlier entity.
+    /// If the scoreboard has a unit cache, units generated by an earlier
+    /// compilation from the same source are reused instead.
+    pub fn codegen_arch(&self, id: ArchRef) -> Result<(llhd::ir::UnitName, llhd::ir::Signature)> {
+        if let Some(unit) = self.sb.arch_units.borrow().get(&id) {
+            return Ok(unit.clone());
+        }
+        let cache = match self.sb.unit_cache {
+            Some(cache) => cache,
+            None => return self.generate_arch(id),
+        };
+
+        // Reuse the units from the cache if the architecture's source did not
+        // change since they were generated.
+        let key = self.unit_cache_key(id)?;
+        let name = self.mangle_unit_name(&self.scope_path(id.into())?);
+        if let Some(entry) = cache.lookup(&name.to_string(), key) {
+            debugln!("reusing cached units for {:?}", id);
+            self.sb
+                .arch_units
+                .borrow_mut()
+                .insert(id, (entry.name.clone(), entry.sig.clone()));
+            self.sb
+                .pending_units
+                .borrow_mut()
+                .extend(crate::cache::read_units(&entry.units));
+            return Ok((entry.name, entry.sig));
+        }
+
+        // Otherwise generate the units and record them in the cache.
+        self.sb.unit_frames.borrow_mut().push(Vec::new());
+        let result = self.generate_arch(id);
+        let units = self.sb.unit_frames.borrow_mut().pop().unwrap();
+        let (name, sig) = result?;
+        cache.insert(CacheEntry {
+            key,
+            name: name.clone(),
+            sig: sig.clone(),
+            units: units.join("\n"),
+        });
+        Ok((name, sig))
+    }
+
+    /// Compute the key under which the units generated for an architecture
+    /// are cached.
+    ///
+    /// See the `cache` module for the source text that is hashed. The
+    /// instantiated architectures are generated first, such that their names
+    /// and signatures are known.
+    fn unit_cache_key(&self, id: ArchRef) -> Result<u64> {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
         let hir = self.hir(id)?;
-        let entity = self.hir(hir.entity)?;
-
-        // Assemble the types and names for the entity.
-        debugln!("entity ports: {:?}", entity.ports);
-        let mut in_tys = Vec::new();
-        let mut out_tys = Vec::new();
-        let mut in_names = Vec::new();
-        let mut out_names = Vec::new();
-        let mut sig = llhd::ir::Signature::new();
-        for &port in &entity.ports {
-            let hir = self.hir(port)?;
-            let ty = self.map_type(self.ty(hir.ty)?)?;
-            // let ty = llhd::void_ty();
-            match hir.mode {
-                hir::IntfSignalMode::In
-                | hir::IntfSignalMode::Inout
-                | hir::IntfSignalMode::Linkage => {
-                    sig.add_input(ty.clone());
-                    in_tys.push(ty.clone());
-                    in_names.push(hir.name.value);
-                }
-                _ => (),
+        let (lib, arch_ctx, arch) = self.ast(id);
+        let (_, entity_ctx, entity) = self.ast(hir.entity);
+        arch.span.extract().hash(&mut hasher);
+        entity.span.extract().hash(&mut hasher);
+        for &ctx_id in &[arch_ctx, entity_ctx] {
+            for item in self.ast(ctx_id).1 {
+                let span = match *item {
+                    ast::CtxItem::LibClause(ref x) => x.span,
+                    ast::CtxItem::UseClause(ref x) => x.span,
+                    ast::CtxItem::CtxRef(ref x) => x.span,
+                };
+                span.extract().hash(&mut hasher);
             }
-            match hir.mode {
-                hir::IntfSignalMode::Out
-                | hir::IntfSignalMode::Inout
-                | hir::IntfSignalMode::Buffer => {
-                    sig.add_output(ty.clone());
-                    out_tys.push(ty.clone());
-                    out_names.push(hir.name.value);
-                }
-                _ => (),
+        }
+
+        // Any package or context in the library may be used by the
+        // architecture.
+        for du in &self.sb.libs.borrow()[&lib] {
+            let span = match du.data {
+                ast::DesignUnitData::PkgDecl(ref x) => x.span,
+                ast::DesignUnitData::PkgBody(ref x) => x.span,
+                ast::DesignUnitData::PkgInst(ref x) => x.span,
+                ast::DesignUnitData::CtxDecl(ref x) => x.span,
+                _ => continue,
+            };
+            span.extract().hash(&mut hasher);
+        }
+
+        // The values bound to the generics of a specialization.
+        if let Some(spec) = self
+            .sb
+            .specializations
+            .borrow()
+            .iter()
+            .find(|spec| spec.spec_arch == id)
+        {
+            for value in &spec.values {
+                value.to_string().hash(&mut hasher);
             }
         }
 
-        // Create a new entity into which we will generate all the code.
-        let name = format!("{}_{}", entity.name.value, hir.name.value);
-        let mut entity = llhd::ir::UnitData::new(
-            llhd::ir::UnitKind::Entity,
-            llhd::ir::UnitName::Global(name),
-            sig,
-        );
-        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut entity);
-
-        // Assign names to the arguments. This is merely cosmetic, but makes the
-        // emitted LLHD easier to read.
-        for (arg, &name) in builder.input_args().zip(in_names.iter()) {
-            builder.set_name(arg, name.as_str().to_string());
+        // The initial values of signals overridden for this compilation.
+        let mut inits: Vec<_> = self
+            .sb
+            .signal_init_table
+            .borrow()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        inits.sort();
+        inits.hash(&mut hasher);
+
+        // The units of the instantiated architectures.
+        for &stmt in &hir.stmts {
+            if let ConcStmtRef::CompInst(stmt) = stmt {
+                let (name, sig) = self.codegen_inst_arch(stmt)?;
+                name.to_string().hash(&mut hasher);
+                sig.to_string().hash(&mut hasher);
+            }
         }
-        for (arg, &name) in builder.output_args().zip(out_names.iter()) {
-            builder.set_name(arg, name.as_str().to_string());
+        Ok(hasher.finish())
+    }
+
+    /// Generate the units for an architecture, bypassing the cache.
+    fn generate_arch(&self, id: ArchRef) -> Result<(llhd::ir::UnitName, llhd::ir::Signature)> {
+        let _span = LowerSpan::enter(log::Level::Debug, "entity", id);
+        let hir = self.hir(id)?;
+
+        // Create a new entity into which we will generate all the code.
+        let mut entity = self.begin_entity_unit(id)?;
+        let (inputs, outputs) = self.entity_unit_ports(hir.entity)?;
+        let mut ctx = UnitContext::new(&mut entity);
+        let name = ctx.builder.name().clone();
+        let sig = ctx.builder.sig().clone();
+        self.sb
+            .arch_units
+            .borrow_mut()
+            .insert(id, (name.clone(), sig.clone()));
+
+        // Make the ports available as signals. An inout port is driven
+        // through its output argument.
+        let args: Vec<_> = ctx
+            .builder
+            .input_args()
+            .zip(inputs)
+            .chain(ctx.builder.output_args().zip(outputs))
+            .collect();
+        for (arg, port) in args {
+            ctx.signals.insert(port.into(), arg);
         }
 
+        // Determine the signals which no statement drives.
+        let (decls, accesses) = self.arch_signal_accesses(id)?;
+        ctx.undriven = decls
+            .into_iter()
+            .filter(|&decl| !accesses.written.contains(&decl.into()))
+            .collect();
+
         // Generate the code for the declarations in the architecture.
         for &decl_id in &hir.decls {
-            self.codegen(decl_id, &mut builder)?;
+            self.codegen(decl_id, &mut ctx)?;
         }
 
         // Generate the code for the statements in the architecture.
         for &stmt_id in &hir.stmts {
-            self.codegen(stmt_id, &mut builder)?;
+            self.codegen(stmt_id, &mut ctx)?;
         }
+        drop(ctx);
+        self.add_unit_deferred(name.to_string(), entity, hir.name.span)?;
+        Ok((name, sig))
+    }
 
-        // Add the entity to the module and return a reference to it.
-        Ok(self.sb.llmod.borrow_mut().add_unit(entity))
+    /// Generate the entity for the architecture instantiated by a component
+    /// instantiation.
+    ///
+    /// The architecture is specialized for the instantiation's generic map
+    /// first, see `specialize_arch`.
+    pub fn codegen_inst_arch(
+        &self,
+        id: CompInstStmtRef,
+    ) -> Result<(llhd::ir::UnitName, llhd::ir::Signature)> {
+        let hir = self.hir(id)?;
+        let arch = self.specialize_arch(hir.arch.value, &hir.generic_map, hir.label.span)?;
+
+        // The library is type checked before code generation, but
+        // specializations are created on the fly and need to be checked
+        // separately.
+        if arch != hir.arch.value {
+            let typeck_ctx = TypeckContext::new(self);
+            typeck_ctx.typeck(arch);
+            if !typeck_ctx.finish() {
+                return Err(());
+            }
+        }
+        self.codegen_arch(arch)
+    }
+
+    /// Create the entity unit for an architecture.
+    ///
+    /// The signature of the unit is mapped from the ports of the entity, with
+    /// the arguments named after the ports. The unit is empty; code is
+    /// generated into it by `codegen_arch`.
+    pub fn begin_entity_unit(&self, id: ArchRef) -> Result<llhd::ir::UnitData> {
+        let hir = self.hir(id)?;
+        let (inputs, outputs) = self.entity_unit_ports(hir.entity)?;
+        debugln!("entity inputs: {:?}, outputs: {:?}", inputs, outputs);
+        let mut sig = llhd::ir::Signature::new();
+        for &port in &inputs {
+            sig.add_input(llhd::signal_ty(self.map_type(self.ty(port)?)?));
+        }
+        for &port in &outputs {
+            sig.add_output(llhd::signal_ty(self.map_type(self.ty(port)?)?));
+        }
+        let name = self.mangle_unit_name(&self.scope_path(id.into())?);
+        let mut unit = llhd::ir::UnitData::new(llhd::ir::UnitKind::Entity, name, sig);
+        let mut builder = llhd::ir::UnitBuilder::new_anonymous(&mut unit);
+        let args: Vec<_> = builder
+            .input_args()
+            .zip(inputs)
+            .chain(builder.output_args().zip(outputs))
+            .collect();
+        for (arg, port) in args {
+            let name = self.hir(port)?.name.value;
+            builder.set_name(arg, name.as_str().to_string());
+        }
+        Ok(unit)
+    }
+
+    /// Determine the ports of an entity that are inputs and outputs of its
+    /// unit, in the order of their declaration.
+    ///
+    /// Inout ports are both an input and an output.
+    pub fn entity_unit_ports(
+        &self,
+        id: EntityRef,
+    ) -> Result<(Vec<IntfSignalRef>, Vec<IntfSignalRef>)> {
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for &port in &self.hir(id)?.ports {
+            match self.hir(port)?.mode {
+                hir::IntfSignalMode::In | hir::IntfSignalMode::Linkage => inputs.push(port),
+                hir::IntfSignalMode::Out | hir::IntfSignalMode::Buffer => outputs.push(port),
+                hir::IntfSignalMode::Inout => {
+                    inputs.push(port);
+                    outputs.push(port);
+                }
+            }
+        }
+        Ok((inputs, outputs))
     }
 }
 
 impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Schedule a generated unit to be added to the LLHD module.
+    ///
+    /// The unit is added upon the next call to `add_pending_units`. In debug
+    /// builds, the unit is verified first, such that an invalid lowering is
+    /// reported at `span`, the construct which produced the unit.
+    pub fn add_unit_deferred(
+        &self,
+        name: String,
+        unit: llhd::ir::UnitData,
+        span: Span,
+    ) -> Result<()> {
+        if cfg!(debug_assertions) {
+            if let Err(errors) = crate::codegen::verify_unit(&unit) {
+                self.emit(
+                    DiagBuilder2::bug(format!("generated LLHD unit `{}` is invalid", name))
+                        .span(span)
+                        .add_note(errors.to_string()),
+                );
+                return Err(());
+            }
+        }
+        let unit = match self.sb.unit_frames.borrow_mut().last_mut() {
+            Some(frame) => {
+                let (unit, asm) = crate::cache::write_unit(unit);
+                frame.push(asm);
+                unit
+            }
+            None => unit,
+        };
+        self.sb.pending_units.borrow_mut().push((name, unit));
+        Ok(())
+    }
+
+    /// Add all pending units to the LLHD module.
+    ///
+    /// The units are added in the order of their names, such that the module
+    /// does not depend on the order in which code is generated, as long as
+    /// all units are added at once. See `driver::codegen`.
+    pub fn add_pending_units(&self) {
+        let mut units = std::mem::take(&mut *self.sb.pending_units.borrow_mut());
+        units.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut llmod = self.sb.llmod.borrow_mut();
+        let mut unit_ids = self.sb.unit_ids.borrow_mut();
+        for (name, unit) in units {
+            unit_ids.insert(name, llmod.add_unit(unit));
+        }
+    }
+
+    /// Obtain the ID of a unit that was added to the LLHD module.
+    pub fn unit_id(&self, name: &llhd::ir::UnitName) -> Option<llhd::ir::UnitId> {
+        self.sb.unit_ids.borrow().get(&name.to_string()).cloned()
+    }
+
     /// Calculate the implicit default value for a type.
     pub fn default_value_for_type(&self, ty: &Ty) -> Result<&'ctx Const> {
         match *ty {
             Ty::Named(_, ty) => self.default_value_for_type(self.ty(ty)?),
             Ty::Null => Ok(self.intern_const(Const::Null)),
-            Ty::Enum(ref _ty) => {
-                // TODO: Replace with the first literal in the enum.
-                Ok(self.intern_const(Const::Null))
-            }
+            Ty::Enum(ref ty) => Ok(self.intern_const(ConstEnum::new(ty.decl, 0))),
             Ty::Physical(ref ty) => Ok(self.intern_const(ConstInt::new(
                 Some(ty.base.clone()),
                 ty.base.left_bound.clone(),
@@ -1090,9 +1649,51 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
                 // TODO: Use the correct default value.
                 Ok(self.intern_const(Const::Null))
             }
+            Ty::Protected(_) => panic!("protected type has no constant default value"),
         }
     }
 
+    /// Override the initial value of a signal.
+    ///
+    /// The signal is identified by its fully-qualified name, for example
+    /// `work.foo.bar.a` for signal `a` in architecture `bar` of entity `foo`.
+    /// Code generation uses the override instead of the declared or default
+    /// initial value of the signal. Untyped integers are converted to the
+    /// signal's type.
+    pub fn override_signal_init<T>(&self, name: &str, konst: T)
+    where
+        T: Into<Const>,
+    {
+        let konst = self.intern_const(konst);
+        self.sb
+            .signal_init_table
+            .borrow_mut()
+            .insert(name.to_lowercase(), konst);
+    }
+
+    /// Override the initial values of multiple signals.
+    ///
+    /// See `override_signal_init`.
+    pub fn override_signal_inits<I, S, T>(&self, inits: I)
+    where
+        I: IntoIterator<Item = (S, T)>,
+        S: AsRef<str>,
+        T: Into<Const>,
+    {
+        for (name, konst) in inits {
+            self.override_signal_init(name.as_ref(), konst);
+        }
+    }
+
+    /// Look up the initial value override for a signal, if any.
+    pub fn signal_init_override(&self, name: &str) -> Option<&'ctx Const> {
+        self.sb
+            .signal_init_table
+            .borrow()
+            .get(&name.to_lowercase())
+            .cloned()
+    }
+
     /// Internalize a constant.
     ///
     /// See `ScoreBoard::intern_const`.
@@ -1174,6 +1775,25 @@ impl EntityArchTable {
 /// A set of names and definitions.
 pub type Defs = HashMap<ResolvableName, Vec<Spanned<Def>>>;
 
+/// The kind of a declaration exported by a package.
+///
+/// See `ScoreContext::package_exports`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExportKind {
+    Type,
+    Subtype,
+    Const,
+    Signal,
+    Var,
+    File,
+    Alias,
+    Subprog,
+    Comp,
+    Attr,
+    Group,
+    Pkg,
+}
+
 /// A scope.
 #[derive(Debug)]
 pub struct Scope {
@@ -1414,7 +2034,7 @@ node_ref_group!(
     Subtype(SubtypeDeclRef),
     Enum(EnumRef),
     Unit(UnitRef),
-    Const(ConstDeclRef),
+    Const(ConstRef),
     Signal(SignalRef),
     File(FileDeclRef),
     Var(VarDeclRef),
@@ -1437,8 +2057,12 @@ node_ref_group!(
     PkgBody(PkgBodyRef),
     Arch(ArchRef),
     Process(ProcessStmtRef),
+    Block(BlockStmtRef),
+    ForGen(ForGenStmtRef),
+    Loop(LoopStmtRef),
     Subprog(SubprogDeclRef),
     SubprogBody(SubprogBodyRef),
+    Protected(TypeDeclRef),
 );
 
 node_ref_group!(
@@ -1467,7 +2091,18 @@ impl From<TypeMarkRef> for Def {
     }
 }
 
-node_ref_group!(SignalRef: Intf(IntfSignalRef), Decl(SignalDeclRef),);
+node_ref_group!(
+    ConstRef: Intf(IntfConstRef),
+    Decl(ConstDeclRef),
+    Gen(ForGenStmtRef),
+    Loop(LoopStmtRef),
+);
+
+node_ref_group!(
+    SignalRef: Intf(IntfSignalRef),
+    Decl(SignalDeclRef),
+    Guard(BlockStmtRef),
+);
 
 node_ref_group!(PkgRef: Decl(PkgDeclRef), Inst(PkgInstRef),);
 
@@ -1747,7 +2382,11 @@ node_ref_group!(
 node_ref_group!(StmtRef: Conc(ConcStmtRef), Seq(SeqStmtRef),);
 
 /// A reference to a node which has a type.
-node_ref_group!(TypedNodeRef: SubtypeInd(SubtypeIndRef), Signal(SignalRef),);
+node_ref_group!(
+    TypedNodeRef: SubtypeInd(SubtypeIndRef),
+    Signal(SignalRef),
+    Expr(ExprRef),
+);
 
 // Declare the node tables.
 node_storage!(AstTable<'ast>:
@@ -1790,8 +2429,12 @@ node_storage!(AstTable<'ast>:
 
     // Statements
     proc_stmts:       ProcessStmtRef   => (ScopeRef, &'ast ast::Stmt),
+    block_stmts:      BlockStmtRef     => (ScopeRef, &'ast ast::Stmt),
+    for_gen_stmts:    ForGenStmtRef    => (ScopeRef, &'ast ast::Stmt),
+    conc_sig_assign_stmts: ConcSigAssignStmtRef => (ScopeRef, &'ast ast::Stmt),
     sig_assign_stmts: SigAssignStmtRef => (ScopeRef, &'ast ast::Stmt),
     var_assign_stmts: VarAssignStmtRef => (ScopeRef, &'ast ast::Stmt),
+    comp_inst_stmts:  CompInstStmtRef  => (ScopeRef, &'ast ast::Stmt),
 
     array_type_indices: ArrayTypeIndexRef => (ScopeRef, &'ast ast::Expr),
     type_marks:         LatentTypeMarkRef => (ScopeRef, LatentName<'ast>),
@@ -1819,7 +2462,11 @@ node_storage!(HirTable<'ctx>:
     variable_decls:        VarDeclRef            => &'ctx hir::Decl<hir::VarDecl>,
     file_decls:            FileDeclRef           => &'ctx hir::Decl<hir::FileDecl>,
     process_stmts:         ProcessStmtRef        => &'ctx hir::ProcessStmt,
+    block_stmts:           BlockStmtRef          => &'ctx hir::BlockStmt,
+    for_gen_stmts:         ForGenStmtRef         => &'ctx hir::ForGenStmt,
+    conc_sig_assign_stmts: ConcSigAssignStmtRef  => &'ctx hir::ConcSigAssignStmt,
     sig_assign_stmts:      SigAssignStmtRef      => &'ctx hir::SigAssignStmt,
+    comp_inst_stmts:       CompInstStmtRef       => &'ctx hir::CompInstStmt,
     array_type_indices:    ArrayTypeIndexRef     => &'ctx Spanned<hir::ArrayTypeIndex>,
     subprogs:              SubprogDeclRef        => &'ctx hir::Subprog,
     subprog_bodies:        SubprogBodyRef        => &'ctx hir::SubprogBody,
@@ -1920,3 +2567,225 @@ impl<'ast> HasDesc for LatentName<'ast> {
         "name"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, first_arch, score_test};
+
+    #[test]
+    fn unresolved_names() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a : bit := b;
+                 begin end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let tyc = TypeckContext::new(&ctx);
+            tyc.typeck(lib);
+            assert!(!tyc.finish());
+
+            let unresolved = ctx.sb.unresolved_names.borrow();
+            assert_eq!(unresolved.len(), 1);
+            assert_eq!(unresolved[0].value.to_string(), "b");
+            assert_eq!(unresolved[0].span.extract(), "b");
+        });
+    }
+
+    #[test]
+    fn definition_spans() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a, b : bit;
+                 begin
+                     p : process (a) begin
+                         b <= a;
+                     end process;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+
+            let process = match ctx.hir(arch).unwrap().stmts[0] {
+                ConcStmtRef::Process(id) => id,
+                _ => panic!("expected a process"),
+            };
+            let assign = match ctx.hir(process).unwrap().stmts[0] {
+                SeqStmtRef::SigAssign(id) => ctx.hir(id).unwrap(),
+                _ => panic!("expected a signal assignment"),
+            };
+            let value = match assign.kind {
+                hir::SigAssignKind::SimpleWave(_, ref wave) => wave[0].value.unwrap(),
+                _ => panic!("expected a simple waveform"),
+            };
+            let span = ctx.definition_span(value).unwrap();
+            assert_eq!(span.extract(), "a");
+            assert_eq!(span.begin().human_line(), 3);
+        });
+    }
+
+    #[test]
+    fn elaborate_ports() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity reg is
+                     generic (W : natural := 1);
+                     port (d : in bit_vector(1 to W); q : out bit);
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let entity = *ctx.archs(lib).unwrap().by_entity.keys().next().unwrap();
+
+            let width = get_name_table().intern("W", false);
+            let value = ctx.intern_const(ConstInt::new(None, BigInt::from(8)));
+            let ports = ctx.elaborate_ports(entity, &[(width, value)]).unwrap();
+            assert_eq!(ports.len(), 2);
+            assert_eq!(ports[0].0.to_string(), "d");
+            match *ctx.deref_named_type(ports[0].1).unwrap() {
+                Ty::Array(ref ty) => assert_eq!(ty.indices[0].to_string(), "1 to 8"),
+                ref ty => panic!("expected an array type, got {}", ty),
+            }
+            assert_eq!(ports[1].0.to_string(), "q");
+
+            // The entity itself retains the default value of the generic.
+            let ports = ctx.elaborate_ports(entity, &[]).unwrap();
+            match *ctx.deref_named_type(ports[0].1).unwrap() {
+                Ty::Array(ref ty) => assert_eq!(ty.indices[0].to_string(), "1 to 1"),
+                ref ty => panic!("expected an array type, got {}", ty),
+            }
+        });
+    }
+
+    /// A mangler that names units after the last component of their path.
+    struct LastComponent;
+
+    impl Mangler for LastComponent {
+        fn mangle(&self, path: &[String]) -> String {
+            path.last().cloned().unwrap_or_default()
+        }
+
+        fn demangle(&self, name: &str) -> Option<Vec<String>> {
+            Some(vec![name.to_string()])
+        }
+    }
+
+    #[test]
+    fn unique_unit_names() {
+        score_test(|| {
+            let sess = Session::new();
+            score_context!(ctx, &sess, |sb| {
+                sb.mangler = Box::new(LastComponent);
+            });
+            let path = |a: &str, b: &str| vec![a.to_string(), b.to_string()];
+            let name = |path: &[String]| ctx.mangle_unit_name(path).to_string();
+            assert_eq!(name(&path("a", "p")), "@p");
+            assert_eq!(name(&path("b", "p")), "@p_1");
+            assert_eq!(name(&path("c", "p")), "@p_2");
+            assert_eq!(name(&path("a", "p")), "@p");
+            assert_eq!(name(&path("a", "p_1")), "@p_1_1");
+        });
+    }
+
+    #[test]
+    fn unit_prefix() {
+        score_test(|| {
+            let sess = Session::new();
+            score_context!(ctx, &sess, |sb| {
+                sb.unit_prefix = Some(String::from("top"));
+            });
+            let path = vec![String::from("work"), String::from("foo")];
+            assert_eq!(ctx.mangle_unit_name(&path).to_string(), "@top.work.foo");
+        });
+    }
+
+    #[test]
+    fn package_exports() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "package p is
+                     type color is (red, green);
+                     constant width : natural := 8;
+                     function f (x : natural) return natural;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let name = get_name_table().intern("p", false);
+            let pkg = match ctx.defs(ScopeRef::Lib(lib)).unwrap()[&name.into()][0].value {
+                Def::Pkg(id) => id,
+                _ => panic!("expected a package"),
+            };
+
+            let exports: Vec<_> = ctx
+                .package_exports(pkg)
+                .unwrap()
+                .into_iter()
+                .map(|(name, kind, span)| (name.to_string(), kind, span.extract()))
+                .collect();
+            assert_eq!(
+                exports,
+                vec![
+                    ("color".to_string(), ExportKind::Type, "color".to_string()),
+                    ("width".to_string(), ExportKind::Const, "width".to_string()),
+                    ("f".to_string(), ExportKind::Subprog, "f".to_string()),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn canonical_types() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "package p is
+                     subtype a is integer range 0 to 7;
+                     subtype b is natural range 0 to 7;
+                     subtype c is a;
+                     subtype d is integer range 0 to 8;
+                 end;",
+            )
+            .unwrap();
+            let sess = Session::new();
+            score_context!(ctx, &sess);
+            let lib = add_work(&ctx, &units);
+            let name = get_name_table().intern("p", false);
+            let pkg = match ctx.defs(ScopeRef::Lib(lib)).unwrap()[&name.into()][0].value {
+                Def::Pkg(id) => id,
+                _ => panic!("expected a package"),
+            };
+            let defs = ctx.defs(ScopeRef::Pkg(pkg)).unwrap();
+            let canon = |name: &str| {
+                let name = get_name_table().intern(name, false);
+                let ty = match defs[&name.into()][0].value {
+                    Def::Subtype(id) => ctx.ty(id).unwrap(),
+                    _ => panic!("expected a subtype"),
+                };
+                ctx.canonical_ty(ty).unwrap()
+            };
+
+            let a = canon("a");
+            assert_eq!(canon("b"), a);
+            assert_eq!(canon("c"), a);
+            assert_ne!(canon("d"), a);
+            assert_eq!(
+                ctx.canonical_ty_def(a),
+                &Ty::Int(IntTy::new(Dir::To, 0.into(), 7.into()))
+            );
+        });
+    }
+}
@@ -27,8 +27,12 @@ impl_make_defs!(self, id: ScopeRef => {
         ScopeRef::PkgBody(id)     => self.make(id),
         ScopeRef::Arch(id)        => self.make(id),
         ScopeRef::Process(id)     => self.make(id),
+        ScopeRef::Block(id)       => self.make(id),
+        ScopeRef::ForGen(id)      => self.make(id),
+        ScopeRef::Loop(id)        => self.make(id),
         ScopeRef::Subprog(id)     => self.make(id),
         ScopeRef::SubprogBody(id) => self.make(id),
+        ScopeRef::Protected(id)   => self.make(id),
     }
 });
 
@@ -42,8 +46,12 @@ impl_make_scope!(self, id: ScopeRef => {
         ScopeRef::PkgBody(id)     => self.make(id),
         ScopeRef::Arch(id)        => self.make(id),
         ScopeRef::Process(id)     => self.make(id),
+        ScopeRef::Block(id)       => self.make(id),
+        ScopeRef::ForGen(id)      => self.make(id),
+        ScopeRef::Loop(id)        => self.make(id),
         ScopeRef::Subprog(id)     => self.make(id),
         ScopeRef::SubprogBody(id) => self.make(id),
+        ScopeRef::Protected(id)   => self.make(id),
     }
 });
 
@@ -97,10 +105,10 @@ impl_make_defs!(self, id: LibRef => {
 
 // Definitions made by the context items that appear before design units.
 impl_make_defs!(self, id: CtxItemsRef => {
-    let (_, ast) = self.ast(id);
+    let (parent, ast) = self.ast(id);
     let mut defs = HashMap::new();
     let mut has_fails = false;
-    for item in ast {
+    for item in self.expand_ctx_items(ast)? {
         // For each name in a library clause, find the corresponding library
         // and create a definition for it.
         match *item {
@@ -131,6 +139,14 @@ impl_make_defs!(self, id: CtxItemsRef => {
             _ => ()
         }
     }
+
+    // The library that contains the design unit is implicitly visible as
+    // `WORK`, unless a library clause names a different library `WORK`.
+    if let ScopeRef::Lib(lib_id) = parent {
+        defs.entry(get_name_table().intern("WORK", false).into())
+            .or_insert_with(|| vec![Spanned::new(Def::Lib(lib_id), INVALID_SPAN)]);
+    }
+
     if has_fails {
         Err(())
     } else {
@@ -147,9 +163,13 @@ impl_make_scope!(self, _id: CtxItemsRef => {
 });
 
 // Definitions in an entity.
-impl_make_defs!(self, _id: EntityRef => {
-    // TODO: Implement this.
-    Ok(self.sb.arenas.defs.alloc(HashMap::new()))
+impl_make_defs!(self, id: EntityRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    ctx.declare_generics(&hir.generics);
+    let ports: Vec<_> = hir.ports.iter().map(|&port| IntfObjRef::Signal(port)).collect();
+    ctx.declare_intf_objs(&ports);
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
 });
 
 // Definitions in an architecture.
@@ -162,14 +182,30 @@ impl_make_defs!(self, id: ArchRef => {
     Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
 });
 
-// Definitions in a package declaration.
-impl_make_defs!(self, _id: PkgDeclRef => {
-    let ctx = DefsContext::new(self);
-    // let hir = self.hir(id)?;
-    // for &decl in &hir.decls {
-    //     ctx.declare_any_in_pkg(decl);
-    // }
-    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+// Definitions in a package declaration. These are visible to design units
+// that `use` the package. Types and subtypes are defined in the package's scope
+// as they are added during lowering to HIR, so only the other declarations are
+// gathered.
+impl_make_defs!(self, id: PkgDeclRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    ctx.declare_generics(&hir.generics);
+    for &decl in &hir.decls {
+        match decl {
+            DeclInPkgRef::Type(_) | DeclInPkgRef::Subtype(_) => (),
+            _ => ctx.declare_any_in_pkg(decl),
+        }
+    }
+    let mut defs = ctx.finish()?;
+    self.with_scope(id.into(), |scope| {
+        for (name, scope_defs) in &scope.defs {
+            defs.entry(*name)
+                .or_insert_with(Vec::new)
+                .extend(scope_defs.iter().cloned());
+        }
+        Ok(())
+    })?;
+    Ok(self.sb.arenas.defs.alloc(defs))
 });
 
 // Definitions in a package body.
@@ -224,7 +260,7 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         let mut defs = Vec::new();
         let mut explicit_defs = HashMap::new();
         defs.push(id.into());
-        for item in items {
+        for item in self.expand_ctx_items(items)? {
             if let &ast::CtxItem::UseClause(Spanned {
                 value: ref names, ..
             }) = item
@@ -285,6 +321,126 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
         );
         Ok(id)
     }
+
+    /// Expand the context references among a list of context items.
+    ///
+    /// Each reference to a context declaration is replaced by the library
+    /// clauses, use clauses, and context references of that declaration, which
+    /// are expanded recursively. The result is equivalent to writing out the
+    /// contents of the referenced contexts in place of the reference.
+    pub fn expand_ctx_items(&self, items: &'ast [ast::CtxItem]) -> Result<Vec<&'ast ast::CtxItem>> {
+        let mut expanded = Vec::new();
+        self.expand_ctx_items_into(items, &mut Vec::new(), &mut expanded)?;
+        Ok(expanded)
+    }
+
+    fn expand_ctx_items_into(
+        &self,
+        items: &'ast [ast::CtxItem],
+        stack: &mut Vec<CtxRef>,
+        into: &mut Vec<&'ast ast::CtxItem>,
+    ) -> Result<()> {
+        let mut has_fails = false;
+        for item in items {
+            let names = match *item {
+                ast::CtxItem::CtxRef(Spanned {
+                    value: ref names, ..
+                }) => names,
+                _ => {
+                    into.push(item);
+                    continue;
+                }
+            };
+            for name in names {
+                let id = match self.resolve_ctx_ref(name) {
+                    Ok(id) => id,
+                    Err(()) => {
+                        has_fails = true;
+                        continue;
+                    }
+                };
+                if stack.contains(&id) {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "context `{}` references itself",
+                            name.span.extract()
+                        ))
+                        .span(name.span),
+                    );
+                    has_fails = true;
+                    continue;
+                }
+                stack.push(id);
+                let (_, _, decl) = self.ast(id);
+                has_fails |= self
+                    .expand_ctx_items_into(&decl.items, stack, into)
+                    .is_err();
+                stack.pop();
+            }
+        }
+        if has_fails {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Resolve the name in a context reference to a context declaration.
+    ///
+    /// The name must be of the form `lib.ctx`. It is resolved directly in the
+    /// libraries, since the scope of the context items is not yet available.
+    fn resolve_ctx_ref(&self, name: &ast::CompoundName) -> Result<CtxRef> {
+        let (lib_name, ctx_name) = match (name.primary.kind, name.parts.as_slice()) {
+            (
+                ast::PrimaryNameKind::Ident(lib),
+                &[ast::NamePart::Select(ast::PrimaryName {
+                    kind: ast::PrimaryNameKind::Ident(ctx),
+                    ..
+                })],
+            ) => (lib, ctx),
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a valid context name",
+                        name.span.extract()
+                    ))
+                    .span(name.span)
+                    .add_note("A context reference must be of the form `<library>.<context>`"),
+                );
+                return Err(());
+            }
+        };
+        let lib_id = match self.sb.lib_names.borrow().get(&lib_name) {
+            Some(&id) => id,
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!("no library named `{}` found", lib_name))
+                        .span(name.primary.span),
+                );
+                return Err(());
+            }
+        };
+        let defs = self.defs(lib_id.into())?;
+        let ctx = defs
+            .get(&ResolvableName::Ident(ctx_name))
+            .into_iter()
+            .flat_map(|defs| defs.iter())
+            .filter_map(|def| match def.value {
+                Def::Ctx(id) => Some(id),
+                _ => None,
+            })
+            .next();
+        match ctx {
+            Some(id) => Ok(id),
+            None => {
+                self.emit(
+                    DiagBuilder2::error(format!("`{}` is not a context", name.span.extract()))
+                        .span(name.span),
+                );
+                Err(())
+            }
+        }
+    }
 }
 
 // Populate the scope of an entity.
@@ -392,7 +548,102 @@ impl_make_scope!(self, id: ProcessStmtRef => {
     defs.push(id.into());
     Ok(self.sb.arenas.scope.alloc(Scope {
         parent: Some(hir.parent),
-        defs: defs,
+        defs,
+        explicit_defs: HashMap::new(),
+    }))
+});
+
+// Definitions in a block, including the generics and ports of its header and
+// the implicit `GUARD` signal of a guarded block.
+impl_make_defs!(self, id: BlockStmtRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    if hir.guard.is_some() {
+        let name = get_name_table().intern("GUARD", false);
+        ctx.declare(
+            Spanned::new(ResolvableName::Ident(name), hir.label.span),
+            Def::Signal(SignalRef::Guard(id)),
+        );
+    }
+    ctx.declare_generics(&hir.generics);
+    let ports: Vec<_> = hir.ports.iter().map(|&port| IntfObjRef::Signal(port)).collect();
+    ctx.declare_intf_objs(&ports);
+    for &decl in &hir.decls {
+        ctx.declare_any_in_block(decl);
+    }
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+});
+
+impl_make_scope!(self, id: BlockStmtRef => {
+    let hir = self.hir(id)?;
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent: Some(hir.parent),
+        defs: vec![id.into()],
+        explicit_defs: HashMap::new(),
+    }))
+});
+
+// Definitions in a for-generate statement, including the generate parameter.
+impl_make_defs!(self, id: ForGenStmtRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.hir(id)?;
+    ctx.declare(hir.param.map_into(), Def::Const(ConstRef::Gen(id)));
+    for &decl in &hir.decls {
+        ctx.declare_any_in_block(decl);
+    }
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+});
+
+impl_make_scope!(self, id: ForGenStmtRef => {
+    let hir = self.hir(id)?;
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent: Some(hir.parent),
+        defs: vec![id.into()],
+        explicit_defs: HashMap::new(),
+    }))
+});
+
+// Definitions in a `for` loop. The loop parameter is defined in the revised
+// scope of the loop as the loop is lowered to HIR, see `add_loop_stmt`.
+impl_make_defs!(self, _id: LoopStmtRef => {
+    Ok(self.sb.arenas.defs.alloc(HashMap::new()))
+});
+
+impl_make_scope!(self, id: LoopStmtRef => {
+    let parent = self.with_scope(id.into(), |scope| Ok(scope.parent))?;
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent,
+        defs: Vec::new(),
+        explicit_defs: HashMap::new(),
+    }))
+});
+
+// Definitions in a protected type declaration or body. The variables declared
+// in the body are visible in the bodies of its methods.
+impl_make_defs!(self, id: TypeDeclRef => {
+    let mut ctx = DefsContext::new(self);
+    let hir = self.lazy_hir(id)?;
+    match hir.data.as_ref().map(|data| &data.value) {
+        Some(hir::TypeData::Protected(methods)) => {
+            for &method in methods {
+                ctx.declare_subprog(method);
+            }
+        }
+        Some(hir::TypeData::ProtectedBody(decls)) => {
+            for &decl in decls {
+                ctx.declare_any_in_subprog(decl);
+            }
+        }
+        _ => (),
+    }
+    Ok(self.sb.arenas.defs.alloc(ctx.finish()?))
+});
+
+impl_make_scope!(self, id: TypeDeclRef => {
+    let hir = self.lazy_hir(id)?;
+    Ok(self.sb.arenas.scope.alloc(Scope {
+        parent: Some(hir.parent),
+        defs: vec![id.into()],
         explicit_defs: HashMap::new(),
     }))
 });
@@ -0,0 +1,256 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Specialization of entities for the values of their generics.
+//!
+//! The generics of an entity may affect the types of its ports and signals,
+//! such that instantiations with different generic values cannot share one
+//! LLHD unit. Instead, each distinct set of generic values produces a copy of
+//! the entity and architecture. The copies are lowered to HIR anew from the
+//! same AST, with their generics bound to the values, and are generated as
+//! separate units. These are named after the original architecture, followed
+//! by `paramN` where `N` is the index of the specialization.
+
+#![deny(missing_docs)]
+
+use crate::common::errors::*;
+use crate::common::name::Name;
+use crate::common::score::{NodeRef, Result};
+use crate::common::source::Span;
+use crate::common::NodeId;
+use crate::hir;
+use crate::konst::*;
+use crate::score::*;
+use crate::ty::*;
+
+/// An architecture specialized for the values of its entity's generics.
+#[derive(Debug, Clone)]
+pub struct Specialization<'ctx> {
+    /// The architecture that was specialized.
+    pub arch: ArchRef,
+    /// The values of the generics, in the order of their declaration.
+    pub values: Vec<&'ctx Const>,
+    /// The copy of the architecture.
+    pub spec_arch: ArchRef,
+    /// The copy of the entity, whose generics are bound to the values.
+    pub spec_entity: EntityRef,
+}
+
+impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
+    /// Obtain the architecture specialized for the generic map of an
+    /// instantiation.
+    ///
+    /// Generics without an actual assume their default value. Returns `arch`
+    /// itself if its entity has no generics. Instantiations that assign the
+    /// same values to the generics share the same specialization.
+    pub fn specialize_arch(
+        &self,
+        arch: ArchRef,
+        generic_map: &[(IntfConstRef, ExprRef)],
+        span: Span,
+    ) -> Result<ArchRef> {
+        let entity_id = self.hir(arch)?.entity;
+        let entity = self.hir(entity_id)?;
+        if entity.generics.is_empty() {
+            return Ok(arch);
+        }
+
+        // Determine the value of each generic.
+        let mut values = Vec::new();
+        for &generic in &entity.generics {
+            let id = match generic {
+                GenericRef::Const(id) => id,
+                _ => {
+                    self.emit(
+                        DiagBuilder2::bug(
+                            "specialization for generic types, subprograms, and packages \
+                             not implemented",
+                        )
+                        .span(span),
+                    );
+                    return Err(());
+                }
+            };
+            let value = match generic_map.iter().find(|&&(formal, _)| formal == id) {
+                Some(&(_, actual)) => {
+                    let hir: &hir::Expr = self.lazy_hir(actual)?;
+                    let value = self.const_value(actual)?;
+                    self.adapt_generic_value(id, value, hir.span)?
+                }
+                None => self.const_value(id)?,
+            };
+            values.push(value);
+        }
+
+        // Reuse an earlier specialization with the same values.
+        if let Some(spec) = self
+            .sb
+            .specializations
+            .borrow()
+            .iter()
+            .find(|spec| spec.arch == arch && spec.values == values)
+        {
+            return Ok(spec.spec_arch);
+        }
+
+        // Copy the entity and the architecture. The context items are copied
+        // as well, since their scope refers to the design unit they precede.
+        let (lib, ctx_items, ast) = self.ast(entity_id);
+        let spec_entity = EntityRef::new(NodeId::alloc());
+        self.set_ast(spec_entity, (lib, self.copy_ctx_items(ctx_items), ast));
+        let (lib, ctx_items, ast) = self.ast(arch);
+        let spec_arch = ArchRef::new(NodeId::alloc());
+        self.set_ast(spec_arch, (lib, self.copy_ctx_items(ctx_items), ast));
+        self.sb.specializations.borrow_mut().push(Specialization {
+            arch,
+            values: values.clone(),
+            spec_arch,
+            spec_entity,
+        });
+
+        // Bind the generics of the copy to the values.
+        for (&generic, &value) in self.hir(spec_entity)?.generics.iter().zip(&values) {
+            if let GenericRef::Const(id) = generic {
+                self.set_const(id, value);
+            }
+        }
+        debugln!("specialized {:?} as {:?} for {:?}", arch, spec_arch, values);
+        Ok(spec_arch)
+    }
+
+    /// Determine the types of an entity's ports for a set of generic values.
+    ///
+    /// The generics are bound to the values by name on a copy of the entity,
+    /// as done by `specialize_arch`, and generics without a value assume their
+    /// default value. This allows the widths of the ports of a top-level
+    /// entity to be determined without generating code for it, for example to
+    /// build a testbench harness around it.
+    pub fn elaborate_ports(
+        &self,
+        entity: EntityRef,
+        generics: &[(Name, &'ctx Const)],
+    ) -> Result<Vec<(Name, &'ctx Ty)>> {
+        let hir = self.hir(entity)?;
+
+        // Determine the generic each value is assigned to.
+        let mut bound = Vec::new();
+        for &(name, value) in generics {
+            let id = hir.generics.iter().find_map(|&generic| match generic {
+                GenericRef::Const(id) if self.ast(id).3.name == name => Some(id),
+                _ => None,
+            });
+            let id = match id {
+                Some(id) => id,
+                None => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "entity `{}` has no generic `{}`",
+                            hir.name.value, name
+                        ))
+                        .span(hir.name.span),
+                    );
+                    return Err(());
+                }
+            };
+            let value = self.adapt_generic_value(id, value, hir.name.span)?;
+            bound.push((id, value));
+        }
+
+        // Copy the entity and bind its generics to the values. The copy
+        // declares its generics in the same order as the original.
+        let spec_entity = if bound.is_empty() {
+            entity
+        } else {
+            let (lib, ctx_items, ast) = self.ast(entity);
+            let spec_entity = EntityRef::new(NodeId::alloc());
+            self.set_ast(spec_entity, (lib, self.copy_ctx_items(ctx_items), ast));
+            let spec = self.hir(spec_entity)?;
+            for (&generic, &spec_generic) in hir.generics.iter().zip(&spec.generics) {
+                let (id, spec_id) = match (generic, spec_generic) {
+                    (GenericRef::Const(id), GenericRef::Const(spec_id)) => (id, spec_id),
+                    _ => continue,
+                };
+                if let Some(&(_, value)) = bound.iter().find(|&&(bound_id, _)| bound_id == id) {
+                    self.set_const(spec_id, value);
+                }
+            }
+            spec_entity
+        };
+
+        let mut ports = Vec::new();
+        for &port in &self.hir(spec_entity)?.ports {
+            ports.push((self.hir(port)?.name.value, self.ty(port)?));
+        }
+        Ok(ports)
+    }
+
+    /// Determine the entity that a specialized architecture belongs to.
+    ///
+    /// Returns `None` if the architecture is not a specialization.
+    pub fn specialized_entity(&self, arch: ArchRef) -> Option<EntityRef> {
+        self.sb
+            .specializations
+            .borrow()
+            .iter()
+            .find(|spec| spec.spec_arch == arch)
+            .map(|spec| spec.spec_entity)
+    }
+
+    /// Determine the index of a specialized architecture.
+    ///
+    /// Returns `None` if the architecture is not a specialization.
+    pub fn specialization_index(&self, arch: ArchRef) -> Option<usize> {
+        self.sb
+            .specializations
+            .borrow()
+            .iter()
+            .position(|spec| spec.spec_arch == arch)
+    }
+
+    /// Check whether a constant is a generic of an entity.
+    pub fn is_generic(&self, id: ConstRef) -> bool {
+        match id {
+            ConstRef::Intf(id) => matches!(self.ast(id).0, ScopeRef::Entity(_)),
+            ConstRef::Decl(_) | ConstRef::Gen(_) | ConstRef::Loop(_) => false,
+        }
+    }
+
+    /// Convert the value of a generic to the generic's type.
+    ///
+    /// Integers are assigned the generic's integer type, such that they can be
+    /// mapped to LLHD, and must lie within its range.
+    pub fn adapt_generic_value(
+        &self,
+        id: IntfConstRef,
+        value: &'ctx Const,
+        span: Span,
+    ) -> Result<&'ctx Const> {
+        let (value, ty) = match (value, self.deref_named_type(self.ty(id)?)?) {
+            (Const::Int(k), Ty::Int(ty)) => (&k.value, ty),
+            _ => return Ok(value),
+        };
+        let (lo, hi) = match ty.dir {
+            Dir::To => (&ty.left_bound, &ty.right_bound),
+            Dir::Downto => (&ty.right_bound, &ty.left_bound),
+        };
+        if value < lo || value > hi {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "value {} is out of range for generic `{}` of type `{}`",
+                    value,
+                    self.ast(id).3.name,
+                    ty
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+        Ok(self.intern_const(ConstInt::new(Some(ty.clone()), value.clone())))
+    }
+
+    /// Copy a list of context items under a new ID.
+    fn copy_ctx_items(&self, id: CtxItemsRef) -> CtxItemsRef {
+        let copy = CtxItemsRef::new(NodeId::alloc());
+        self.set_ast(copy, self.ast(id));
+        copy
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Mapping of generated LLHD instructions back to the VHDL source.
+//!
+//! When debugging code generation it helps to see which VHDL construct
+//! produced which LLHD instruction. If the scoreboard carries a `SourceMap`,
+//! code generation records for every instruction the span of the innermost
+//! sequential statement it was generated for. `print_module` then prints the
+//! LLHD assembly of a module with the originating source lines interleaved.
+
+#![deny(missing_docs)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use llhd;
+use moore_common::source::Span;
+
+/// The source spans of the instructions in generated LLHD units.
+#[derive(Default)]
+pub struct SourceMap {
+    /// The spans of the instructions, by the name of their unit.
+    units: RefCell<HashMap<String, HashMap<llhd::ir::Inst, Span>>>,
+}
+
+impl SourceMap {
+    /// Create a new empty source map.
+    pub fn new() -> SourceMap {
+        Default::default()
+    }
+
+    /// Record the spans of the instructions of a unit.
+    ///
+    /// Replaces the spans recorded for an earlier unit of the same name.
+    pub fn insert(&self, unit: String, spans: HashMap<llhd::ir::Inst, Span>) {
+        self.units.borrow_mut().insert(unit, spans);
+    }
+
+    /// Find the span of the source an instruction was generated for.
+    ///
+    /// Returns `None` for instructions that do not belong to a statement, for
+    /// example the ones probing the signals at the start of a process.
+    pub fn span(&self, unit: &str, inst: llhd::ir::Inst) -> Option<Span> {
+        self.units
+            .borrow()
+            .get(unit)
+            .and_then(|spans| spans.get(&inst))
+            .cloned()
+    }
+}
+
+/// Print a module as LLHD assembly, interleaved with the source lines the
+/// instructions were generated for.
+///
+/// Each run of instructions generated for the same statement is preceded by a
+/// comment with the location and the first line of the statement.
+pub fn print_module(module: &llhd::ir::Module, map: &SourceMap) -> String {
+    let units: HashMap<String, llhd::ir::Unit> = module
+        .units()
+        .map(|unit| (unit.name().to_string(), unit))
+        .collect();
+    let mut asm = Vec::new();
+    llhd::assembly::write_module(&mut asm, module);
+    let asm = String::from_utf8_lossy(&asm);
+
+    // The assembly lists the instructions of each unit in layout order, one
+    // per indented line.
+    let mut output = String::new();
+    let mut unit_name = "";
+    let mut insts = Vec::new();
+    let mut last_span = None;
+    for line in asm.lines() {
+        if line.ends_with('{') {
+            unit_name = line.split(' ').nth(1).unwrap_or("");
+            insts = match units.get(unit_name) {
+                Some(unit) => unit.blocks().flat_map(move |bb| unit.insts(bb)).collect(),
+                None => vec![],
+            };
+            insts.reverse();
+            last_span = None;
+        } else if line.starts_with(' ') {
+            let span = insts.pop().and_then(|inst| map.span(unit_name, inst));
+            if let Some(span) = span.filter(|&span| Some(span) != last_span) {
+                output.push_str(&format!(
+                    "    ; {}:{}: {}\n",
+                    span.source.get_path(),
+                    span.begin().human_line(),
+                    span.extract().lines().next().unwrap_or("").trim()
+                ));
+            }
+            last_span = span;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{add_work, first_arch, score_test};
+    use moore_common::Session;
+
+    #[test]
+    fn assignment_carries_span() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a, b : bit;
+                 begin
+                     p : process (a) begin
+                         b <= a;
+                     end process;
+                 end;",
+            )
+            .unwrap();
+            let map = SourceMap::new();
+            let sess = Session::new();
+            score_context!(ctx, &sess, |sb| {
+                sb.source_map = Some(&map);
+            });
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+
+            let module = ctx.sb.llmod.borrow();
+            let unit = module
+                .units()
+                .find(|unit| unit.name().to_string() == "@work.foo.bar.p")
+                .unwrap();
+            let drv = unit
+                .all_insts()
+                .find(|&inst| unit[inst].opcode() == llhd::ir::Opcode::Drv)
+                .unwrap();
+            let span = map.span("@work.foo.bar.p", drv).unwrap();
+            assert!(span.extract().starts_with("b <= a"));
+            assert!(print_module(&module, &map).contains(": b <= a;\n"));
+        });
+    }
+
+    #[test]
+    fn nested_statement_claims_instructions() {
+        score_test(|| {
+            let units = crate::syntax::parse_str(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a, b : bit;
+                 begin
+                     p : process (a) begin
+                         if a = '1' then
+                             b <= a;
+                         end if;
+                     end process;
+                 end;",
+            )
+            .unwrap();
+            let map = SourceMap::new();
+            let sess = Session::new();
+            score_context!(ctx, &sess, |sb| {
+                sb.source_map = Some(&map);
+            });
+            let lib = add_work(&ctx, &units);
+            let arch = first_arch(&ctx, lib);
+            ctx.llunit(arch).unwrap();
+
+            let module = ctx.sb.llmod.borrow();
+            let unit = module
+                .units()
+                .find(|unit| unit.name().to_string() == "@work.foo.bar.p")
+                .unwrap();
+            let span_of = |opcode| {
+                let inst = unit
+                    .all_insts()
+                    .find(|&inst| unit[inst].opcode() == opcode)
+                    .unwrap();
+                map.span("@work.foo.bar.p", inst).unwrap()
+            };
+            assert!(span_of(llhd::ir::Opcode::Drv)
+                .extract()
+                .starts_with("b <= a"));
+            assert!(span_of(llhd::ir::Opcode::BrCond)
+                .extract()
+                .starts_with("if a"));
+        });
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Size statistics of generated LLHD code.
+//!
+//! After lowering, the size of the generated units gives a quick impression of
+//! how much of a design was lowered, and reveals units that grew unexpectedly,
+//! for example due to the unrolling of generate statements.
+
+#![deny(missing_docs)]
+
+use llhd;
+
+/// The size statistics of an LLHD unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitStats {
+    /// The name of the unit.
+    pub name: String,
+    /// The number of instructions in the unit, including terminators.
+    pub insts: usize,
+    /// The number of basic blocks in the unit.
+    pub blocks: usize,
+    /// The number of signals declared in the unit.
+    pub signals: usize,
+}
+
+/// Compute the size statistics of each unit in a module, in the order in which
+/// the units appear in the module.
+pub fn module_stats(module: &llhd::ir::Module) -> Vec<UnitStats> {
+    module.units().map(unit_stats).collect()
+}
+
+/// Compute the size statistics of a unit.
+pub fn unit_stats(unit: llhd::ir::Unit<'_>) -> UnitStats {
+    let signals = unit
+        .all_insts()
+        .filter(|&inst| unit[inst].opcode() == llhd::ir::Opcode::Sig)
+        .count();
+    UnitStats {
+        name: unit.name().to_string(),
+        insts: unit.all_insts().count(),
+        blocks: unit.blocks().count(),
+        signals,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{compile, Artifacts, Options};
+    use crate::testing::score_test;
+    use moore_common::name::get_name_table;
+    use moore_common::source::get_source_manager;
+    use moore_common::Session;
+
+    #[test]
+    fn small_entity() {
+        score_test(|| {
+            let sess = Session::new();
+            let src = get_source_manager().add_anonymous(
+                "entity foo is end;
+                 architecture bar of foo is
+                     signal a : boolean;
+                 begin
+                     p : process begin a <= true; end process;
+                 end;",
+            );
+            let work = get_name_table().intern("work", false);
+            let module = match compile(&sess, &[src], work, &Options::default()) {
+                Ok(Artifacts::Module(design)) => design.module,
+                _ => panic!("expected an LLHD module"),
+            };
+            let stats = |name: &str, insts, blocks, signals| UnitStats {
+                name: name.to_string(),
+                insts,
+                blocks,
+                signals,
+            };
+            // The entity counts the `halt` LLHD implicitly terminates it with.
+            assert_eq!(
+                module_stats(&module),
+                vec![
+                    stats("@work.foo.bar", 4, 1, 1),
+                    stats("@work.foo.bar.p", 4, 1, 0),
+                ]
+            );
+        });
+    }
+}
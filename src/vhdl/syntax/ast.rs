@@ -218,6 +218,8 @@ pub struct PkgDecl {
     pub id: NodeId,
     pub span: Span,
     pub name: Spanned<Name>,
+    pub generics: Option<Spanned<Vec<IntfDecl>>>,
+    pub generic_map: Option<ParenElems>,
     pub decls: Vec<DeclItem>,
 }
 
@@ -973,6 +975,7 @@ pub enum TypeData {
     AccessType(SubtypeInd),
     FileType(CompoundName),
     ProtectedType(Vec<DeclItem>),
+    ProtectedBody(Vec<DeclItem>),
 }
 
 impl HasDesc for TypeData {
@@ -985,6 +988,7 @@ impl HasDesc for TypeData {
             TypeData::AccessType(..) => "access type declaration",
             TypeData::FileType(..) => "file type declaration",
             TypeData::ProtectedType(..) => "protected type declaration",
+            TypeData::ProtectedBody(..) => "protected type body",
         }
     }
 }
@@ -1067,6 +1071,10 @@ pub enum StmtData {
     },
     BlockStmt {
         guard: Option<Expr>,
+        generics: Option<Spanned<Vec<IntfDecl>>>,
+        generic_map: Option<ParenElems>,
+        ports: Option<Spanned<Vec<IntfDecl>>>,
+        port_map: Option<ParenElems>,
         decls: Vec<DeclItem>,
         stmts: Vec<Stmt>,
     },
@@ -0,0 +1,113 @@
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+package fifo_pkg is
+	constant DEPTH : natural := 16;
+	constant WIDTH : natural := 8;
+	subtype word_t is std_logic_vector(WIDTH-1 downto 0);
+	type mem_t is array (0 to DEPTH-1) of word_t;
+	type state_t is (IDLE, FILL, DRAIN, FULL);
+	function next_index (index : natural) return natural;
+end package fifo_pkg;
+
+package body fifo_pkg is
+	function next_index (index : natural) return natural is
+	begin
+		if index = DEPTH-1 then
+			return 0;
+		else
+			return index + 1;
+		end if;
+	end function next_index;
+end package body fifo_pkg;
+
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+use work.fifo_pkg.all;
+
+entity fifo is
+	generic (
+		ALMOST_FULL : natural := DEPTH - 2
+	);
+	port (
+		clk     : in  std_logic;
+		rst     : in  std_logic;
+		wr_en   : in  std_logic;
+		wr_data : in  word_t;
+		rd_en   : in  std_logic;
+		rd_data : out word_t;
+		empty   : out std_logic;
+		full    : out std_logic;
+		almost  : out std_logic
+	);
+end entity fifo;
+
+architecture rtl of fifo is
+	signal mem    : mem_t;
+	signal wr_ptr : natural range 0 to DEPTH-1 := 0;
+	signal rd_ptr : natural range 0 to DEPTH-1 := 0;
+	signal count  : natural range 0 to DEPTH := 0;
+	signal state  : state_t := IDLE;
+begin
+	write_proc : process (clk)
+	begin
+		if rising_edge(clk) then
+			if rst = '1' then
+				wr_ptr <= 0;
+			elsif wr_en = '1' and count < DEPTH then
+				mem(wr_ptr) <= wr_data;
+				wr_ptr <= next_index(wr_ptr);
+			end if;
+		end if;
+	end process write_proc;
+
+	read_proc : process (clk)
+	begin
+		if rising_edge(clk) then
+			if rst = '1' then
+				rd_ptr <= 0;
+				rd_data <= (others => '0');
+			elsif rd_en = '1' and count > 0 then
+				rd_data <= mem(rd_ptr);
+				rd_ptr <= next_index(rd_ptr);
+			end if;
+		end if;
+	end process read_proc;
+
+	count_proc : process (clk)
+		variable delta : integer range -1 to 1;
+	begin
+		if rising_edge(clk) then
+			delta := 0;
+			if wr_en = '1' and count < DEPTH then
+				delta := delta + 1;
+			end if;
+			if rd_en = '1' and count > 0 then
+				delta := delta - 1;
+			end if;
+			if rst = '1' then
+				count <= 0;
+			else
+				count <= count + delta;
+			end if;
+		end if;
+	end process count_proc;
+
+	state_proc : process (count)
+	begin
+		case count is
+			when 0 => state <= IDLE;
+			when DEPTH => state <= FULL;
+			when others =>
+				if state = IDLE then
+					state <= FILL;
+				end if;
+		end case;
+	end process state_proc;
+
+	empty  <= '1' when count = 0 else '0';
+	full   <= '1' when count = DEPTH else '0';
+	almost <= '1' when count >= ALMOST_FULL else '0';
+end architecture rtl;
@@ -0,0 +1,47 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Throughput benchmarks for the VHDL lexer and parser.
+//!
+//! The fixtures in `benches/fixtures` are parsed as they are, and the medium
+//! one is repeated to obtain a large input.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use moore_common::source::{get_source_manager, Source};
+use moore_common::Session;
+use moore_vhdl_syntax::{lexer, parse};
+
+/// The inputs to benchmark on, by name.
+fn corpus() -> Vec<(&'static str, String)> {
+    let small = include_str!("fixtures/small.vhd");
+    let medium = include_str!("fixtures/medium.vhd");
+    vec![
+        ("small", small.to_string()),
+        ("medium", medium.to_string()),
+        ("large", medium.repeat(64)),
+    ]
+}
+
+/// Benchmark a function on each input of the corpus.
+fn bench_corpus<R>(c: &mut Criterion, group: &str, f: impl Fn(Source) -> R) {
+    let mut group = c.benchmark_group(group);
+    for (name, text) in corpus() {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        let src = get_source_manager().add_anonymous(text);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &src, |b, &src| {
+            b.iter(|| f(src))
+        });
+    }
+    group.finish();
+}
+
+fn bench_lexer(c: &mut Criterion) {
+    let sess = Session::new();
+    bench_corpus(c, "lexer", |src| lexer::tokenize(src, &sess));
+}
+
+fn bench_parser(c: &mut Criterion) {
+    bench_corpus(c, "parse", |src| parse(src).unwrap());
+}
+
+criterion_group!(benches, bench_lexer, bench_parser);
+criterion_main!(benches);
@@ -60,21 +60,28 @@ where
             }
         }
 
-        // Handle multi-line comments.
+        // Handle multi-line comments. An unterminated comment extends up to
+        // the end of the file.
         if c == '/' {
             if let Some((_, '*', _, _)) = *self.inner.lookahead(0) {
                 self.inner.next();
-                let mut p0 = None;
-                let mut p1 = None;
-                while let Some((offset, d, sz, _)) = *self.inner.lookahead(0) {
-                    if p0 == Some('*') && p1 == Some('/') {
+                let opening = Span::new(self.src, begin, begin + 2);
+                let mut prev = None;
+                let mut closed = false;
+                while let Some((offset, d, sz, _)) = self.inner.next() {
+                    sp.end = offset + sz as usize;
+                    if prev == Some('*') && d == '/' {
+                        closed = true;
                         break;
-                    } else {
-                        p0 = p1;
-                        p1 = Some(d);
-                        sp.end = offset + sz as usize;
-                        self.inner.next();
                     }
+                    prev = Some(d);
+                }
+                if !closed {
+                    self.emit(
+                        DiagBuilder2::error("Unterminated block comment.")
+                            .span(opening)
+                            .add_note("Block comments must be closed with `*/`"),
+                    );
                 }
                 return Some(Spanned::new(Bundle::Comment, sp));
             }
@@ -94,31 +101,42 @@ where
             }
         }
 
-        // Handle string literals.
+        // Handle string literals. An unterminated literal ends at the end of
+        // the line, such that lexing can resume on the next line.
         if c == '"' {
+            let opening = sp;
             let mut s = String::new();
-            while let Some((offset, d, sz, _)) = self.inner.next() {
+            let mut closed = false;
+            while let Some((offset, d, sz, _)) = *self.inner.lookahead(0) {
+                if d == '\n' {
+                    break;
+                }
+                self.inner.next();
                 sp.end = offset + sz as usize;
                 if d == '"' {
-                    if let Some((_, '"', _, _)) = *self.inner.lookahead(0) {
+                    if let Some((offset, '"', sz, _)) = *self.inner.lookahead(0) {
                         s.push('"');
+                        sp.end = offset + sz as usize;
                         self.inner.next();
                     } else {
+                        closed = true;
                         break;
                     }
-                } else if d == '\n' {
-                    self.emit(
-                        DiagBuilder2::error("String literal must not contain line breaks.")
-                            .span(sp.end())
-                            .add_note(
-                                "Use string concatenation (e.g. \"abc\" & \"def\") to break \
-                                 strings across lines",
-                            ),
-                    );
                 } else {
                     s.push(d);
                 }
             }
+            if !closed {
+                self.emit(
+                    DiagBuilder2::error("Unterminated string literal.")
+                        .span(opening)
+                        .add_note(
+                            "String literals must not contain line breaks. Use string \
+                             concatenation (e.g. \"abc\" & \"def\") to break strings across \
+                             lines",
+                        ),
+                );
+            }
             return Some(Spanned::new(Bundle::StringLiteral(s), sp));
         }
 
@@ -6,6 +6,7 @@
 
 pub mod bundler;
 pub mod categorizer;
+pub mod number;
 pub mod token;
 pub mod tokenizer;
 
@@ -15,8 +16,50 @@ use self::token::*;
 use self::tokenizer::Tokenizer;
 use moore_common::errors::*;
 use moore_common::grind::utf8::Utf8;
-use moore_common::grind::Grinder;
+use moore_common::grind::{self, Grinder};
 use moore_common::source::*;
+use moore_common::Session;
+use std::fmt;
+use std::str::FromStr;
+
+/// A revision of the VHDL standard.
+///
+/// Constructs introduced by a later revision than the selected one are
+/// rejected by the lexer and parser.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Revision {
+    /// IEEE 1076-1993.
+    Vhdl1993,
+    /// IEEE 1076-2002.
+    Vhdl2002,
+    /// IEEE 1076-2008.
+    #[default]
+    Vhdl2008,
+}
+
+impl fmt::Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Revision::Vhdl1993 => write!(f, "VHDL-1993"),
+            Revision::Vhdl2002 => write!(f, "VHDL-2002"),
+            Revision::Vhdl2008 => write!(f, "VHDL-2008"),
+        }
+    }
+}
+
+impl FromStr for Revision {
+    type Err = String;
+
+    /// Parse a revision given as a year, e.g. `1993` or `93`.
+    fn from_str(s: &str) -> Result<Revision, String> {
+        match s {
+            "1993" | "93" => Ok(Revision::Vhdl1993),
+            "2002" | "02" => Ok(Revision::Vhdl2002),
+            "2008" | "08" => Ok(Revision::Vhdl2008),
+            _ => Err(format!("unknown VHDL revision `{}`", s)),
+        }
+    }
+}
 
 /// A VHDL lexer. Converts a stream of bytes to VHDL tokens. Emits errors
 /// backwards up the pipeline.
@@ -36,6 +79,22 @@ where
         let tokens = Tokenizer::new(bundles);
         Lexer { inner: tokens }
     }
+
+    /// Select the revision of the standard to accept. Defaults to the latest.
+    pub fn set_revision(&mut self, revision: Revision) {
+        self.inner.set_revision(revision);
+    }
+
+    /// Get the revision of the standard being accepted.
+    pub fn revision(&self) -> Revision {
+        self.inner.revision()
+    }
+
+    /// Select the number of bits above which integer literals are reported as
+    /// unreasonably large. Defaults to `number::DEFAULT_MAX_BITS`.
+    pub fn set_max_literal_bits(&mut self, bits: u64) {
+        self.inner.set_max_literal_bits(bits);
+    }
 }
 
 impl<T> Grinder for Lexer<T>
@@ -54,6 +113,91 @@ where
     }
 }
 
+/// Get a grinder on the bytes of a source file.
+///
+/// The diagnostics emitted by the stages of lexical analysis and parsing built
+/// on top of the grinder are emitted to `sess`. The grinder holds on to the
+/// content of the file, such that it may outlive the caller's borrow.
+pub fn source_bytes<'a>(
+    src: Source,
+    sess: &'a Session,
+) -> impl Grinder<Item = Option<u8>, Error = DiagBuilder2> + 'a {
+    let content = src.get_content();
+    grind::from_iter((0..content.bytes().len()).map(move |i| content.bytes()[i]))
+        .vent(move |err: DiagBuilder2| sess.emit(err))
+}
+
+/// Create a lexer for a source file, configured as per the options of `sess`.
+pub fn lex_source<'a>(
+    src: Source,
+    sess: &'a Session,
+) -> Lexer<impl Grinder<Item = Option<u8>, Error = DiagBuilder2> + 'a> {
+    let mut lexer = Lexer::new(source_bytes(src, sess), src);
+    if let Some(bits) = sess.opts.max_literal_bits {
+        lexer.set_max_literal_bits(bits);
+    }
+    lexer
+}
+
+/// Tokenize a source file.
+///
+/// Diagnostics are emitted to `sess` as they occur.
+pub fn tokenize(src: Source, sess: &Session) -> Vec<Spanned<Token>> {
+    let mut lexer = lex_source(src, sess);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// A token in a stable, comparable form.
+///
+/// Unlike `Token`, this does not refer to any interned names, such that token
+/// streams can be compared across runs and against golden files. The `Display`
+/// implementation produces one line per token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenDump {
+    /// The kind of token, e.g. `identifier`, `string literal`, or `;`.
+    pub kind: String,
+    /// The byte offset where the token begins.
+    pub begin: usize,
+    /// The byte offset where the token ends.
+    pub end: usize,
+    /// The source text of the token.
+    pub text: String,
+}
+
+impl TokenDump {
+    /// Create a dump of a token.
+    pub fn new(token: Spanned<Token>) -> TokenDump {
+        TokenDump {
+            kind: token.value.as_str().to_string(),
+            begin: token.span.begin,
+            end: token.span.end,
+            text: token.span.extract(),
+        }
+    }
+}
+
+impl fmt::Display for TokenDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}..{} {:?} {:?}",
+            self.begin, self.end, self.kind, self.text
+        )
+    }
+}
+
+/// Tokenize a source file into a stable, comparable form.
+pub fn dump_tokens(src: Source, sess: &Session) -> Vec<TokenDump> {
+    tokenize(src, sess)
+        .into_iter()
+        .map(TokenDump::new)
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::Lexer;
@@ -62,21 +206,29 @@ mod test {
     use moore_common::grind::{self, Grinder};
     use moore_common::name::*;
     use moore_common::source::*;
+    use std::cell::Cell;
 
-    fn lex(src: Source) -> Vec<Token> {
+    fn lex(src: Source) -> (Vec<Token>, usize) {
         let content = src.get_content();
-        let bytes = grind::from_iter(content.bytes().iter().map(|x| *x))
-            .vent(|err: DiagBuilder2| eprintln!("{}", err));
+        let num_diags = Cell::new(0);
+        let bytes =
+            grind::from_iter(content.bytes().iter().map(|x| *x)).vent(|err: DiagBuilder2| {
+                eprintln!("{}", err);
+                num_diags.set(num_diags.get() + 1);
+            });
         let mut tokens = Lexer::new(bytes, src);
         let mut v = Vec::new();
         while let Some(Spanned { value, .. }) = tokens.next() {
             v.push(value);
         }
-        v
+        (v, num_diags.get())
     }
 
     fn check(input: &str, expected: &[Token]) {
-        use std::cell::Cell;
+        check_with_diags(input, expected, 0);
+    }
+
+    fn check_with_diags(input: &str, expected: &[Token], expected_diags: usize) {
         thread_local!(static INDEX: Cell<usize> = Cell::new(0));
         let sm = get_source_manager();
         let idx = INDEX.with(|i| {
@@ -85,11 +237,12 @@ mod test {
             v
         });
         let source = sm.add(&format!("test_{}.vhd", idx), input);
-        let actual = lex(source);
+        let (actual, diags) = lex(source);
         assert_eq!(actual.len(), expected.len());
         for (a, &e) in actual.into_iter().zip(expected.into_iter()) {
             assert_eq!(a, e);
         }
+        assert_eq!(diags, expected_diags);
     }
 
     fn name(n: &str) -> Name {
@@ -395,6 +548,61 @@ mod test {
         );
     }
 
+    #[test]
+    fn unterminated_string_literal() {
+        check_with_diags(
+            "
+            x := \"Setup time is
+            too short;
+        ",
+            &[
+                Ident(name("x")),
+                VarAssign,
+                Lit(Literal::String(name_case("Setup time is"))),
+                Ident(name("too")),
+                Ident(name("short")),
+                Semicolon,
+            ],
+            1,
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment() {
+        check_with_diags(
+            "
+            x /* comment */ y;
+            z; /* unterminated
+            comment
+        ",
+            &[
+                Ident(name("x")),
+                Ident(name("y")),
+                Semicolon,
+                Ident(name("z")),
+                Semicolon,
+            ],
+            1,
+        );
+    }
+
+    #[test]
+    fn block_comments_do_not_nest() {
+        check(
+            "
+            x /* outer /* inner */ y;
+            /* -- */ z; -- /* line comment
+        ",
+            &[
+                Ident(name("x")),
+                Ident(name("y")),
+                Semicolon,
+                Ident(name("z")),
+                Semicolon,
+            ],
+        );
+    }
+
     #[test]
     fn symbols() {
         check(
@@ -442,6 +650,14 @@ mod test {
         );
     }
 
+    #[test]
+    fn adjacent_symbols() {
+        check(
+            "<=> ?/a **= ?<>",
+            &[Leq, Gt, Qmark, Div, Ident(name("a")), Pow, Eq, MatchLt, Gt],
+        );
+    }
+
     #[test]
     fn keywords() {
         check(
@@ -578,4 +794,79 @@ mod test {
             ],
         );
     }
+
+    #[test]
+    fn token_dump() {
+        let source = get_source_manager().add(
+            "test_token_dump.vhd",
+            "entity foo is\n\tport (a : in bit := '0');\nend;\n",
+        );
+        let actual: Vec<String> = super::dump_tokens(source, &moore_common::Session::new())
+            .into_iter()
+            .map(|t| t.to_string())
+            .collect();
+        let expected = vec![
+            r#"0..6 "entity" "entity""#,
+            r#"7..10 "identifier" "foo""#,
+            r#"11..13 "is" "is""#,
+            r#"15..19 "port" "port""#,
+            r#"20..21 "(" "(""#,
+            r#"21..22 "identifier" "a""#,
+            r#"23..24 ":" ":""#,
+            r#"25..27 "in" "in""#,
+            r#"28..31 "identifier" "bit""#,
+            r#"32..34 ":=" ":=""#,
+            r#"35..38 "character literal" "'0'""#,
+            r#"38..39 ")" ")""#,
+            r#"39..40 ";" ";""#,
+            r#"41..44 "end" "end""#,
+            r#"44..45 ";" ";""#,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn large_integer_literal() {
+        use super::number::{parse_integer, DEFAULT_MAX_BITS};
+        use num::BigInt;
+        let digits = "123456789012345678901234567890123456789";
+        check_with_diags(
+            digits,
+            &[Lit(Literal::Abstract(None, name_case(digits), None, None))],
+            1,
+        );
+        let int = parse_integer(digits, 10, DEFAULT_MAX_BITS).unwrap();
+        assert_eq!(int.value, digits.parse::<BigInt>().unwrap());
+        assert_eq!(int.value.to_string(), digits);
+        assert!(int.oversized);
+        assert!(
+            !parse_integer("18446744073709551615", 10, DEFAULT_MAX_BITS)
+                .unwrap()
+                .oversized
+        );
+        assert!(parse_integer("12a", 10, DEFAULT_MAX_BITS).is_none());
+    }
+
+    #[test]
+    fn max_literal_bits() {
+        let source = get_source_manager().add(
+            "test_max_literal_bits.vhd",
+            "255 256 16#FF# 16#100# 2#1_0000_0000# 256.5",
+        );
+        let count = |bits| {
+            let num_diags = Cell::new(0);
+            let content = source.get_content();
+            let bytes = grind::from_iter(content.bytes().iter().copied())
+                .vent(|_: DiagBuilder2| num_diags.set(num_diags.get() + 1));
+            let mut tokens = Lexer::new(bytes, source);
+            if let Some(bits) = bits {
+                tokens.set_max_literal_bits(bits);
+            }
+            while tokens.next().is_some() {}
+            num_diags.get()
+        };
+        assert_eq!(count(None), 0);
+        assert_eq!(count(Some(9)), 0);
+        assert_eq!(count(Some(8)), 4);
+    }
 }
@@ -0,0 +1,37 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Parsing of the digits in abstract literals.
+//!
+//! The tokenizer keeps the digits of a literal as an interned name. This
+//! module converts them to an arbitrary precision integer, such that large
+//! literals are never truncated.
+
+use num::{BigInt, Num};
+
+/// The number of bits above which an integer literal is considered
+/// unreasonably large by default.
+pub const DEFAULT_MAX_BITS: u64 = 64;
+
+/// The value of the digits of a literal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntLiteral {
+    /// The full value.
+    pub value: BigInt,
+    /// Whether the value needs more bits than the limit passed to
+    /// `parse_integer`.
+    pub oversized: bool,
+}
+
+/// Parse the digits of a literal in the given base.
+///
+/// Returns `None` if the digits are not valid in the base. Values that need
+/// more than `max_bits` bits are still parsed in full, but flagged as
+/// oversized.
+pub fn parse_integer(digits: &str, base: u32, max_bits: u64) -> Option<IntLiteral> {
+    if digits.is_empty() || !(2..=36).contains(&base) {
+        return None;
+    }
+    let value = BigInt::from_str_radix(digits, base).ok()?;
+    let oversized = value.bits() > max_bits;
+    Some(IntLiteral { value, oversized })
+}
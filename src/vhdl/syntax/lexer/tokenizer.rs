@@ -1,16 +1,20 @@
 // Copyright (c) 2016-2021 Fabian Schuiki
 
 use crate::lexer::bundler::Bundle;
+use crate::lexer::number::{self, DEFAULT_MAX_BITS};
 use crate::lexer::token::*;
+use crate::lexer::Revision;
 use moore_common::errors::*;
-use moore_common::grind::{Grinder, Lookahead};
+use moore_common::grind::{Grinder, Rewind};
 use moore_common::name::*;
 use moore_common::source::*;
 
 /// A grinder that combines character bundles into lexical tokens. This is the
 /// last stage of lexical analysis.
 pub struct Tokenizer<T: Grinder> {
-    inner: Lookahead<T>,
+    inner: Rewind<T>,
+    revision: Revision,
+    max_literal_bits: u64,
 }
 
 impl<T: Grinder> Tokenizer<T>
@@ -20,10 +24,58 @@ where
     /// Create a new bundler.
     pub fn new<I>(inner: I) -> Tokenizer<T>
     where
-        I: Into<Lookahead<T>>,
+        I: Into<Rewind<T>>,
     {
         Tokenizer {
             inner: inner.into(),
+            revision: Revision::default(),
+            max_literal_bits: DEFAULT_MAX_BITS,
+        }
+    }
+
+    /// Select the revision of the standard to accept.
+    pub fn set_revision(&mut self, revision: Revision) {
+        self.revision = revision;
+    }
+
+    /// Get the revision of the standard being accepted.
+    pub fn revision(&self) -> Revision {
+        self.revision
+    }
+
+    /// Select the number of bits above which integer literals are reported as
+    /// unreasonably large.
+    pub fn set_max_literal_bits(&mut self, bits: u64) {
+        self.max_literal_bits = bits;
+    }
+
+    /// Warn if the integer part of an abstract literal needs more bits than
+    /// the configured limit.
+    ///
+    /// The digits are parsed in full, such that the value of an oversized
+    /// literal is preserved. Digits that are invalid in the base are reported
+    /// once the literal is evaluated.
+    fn check_literal_size(&mut self, base: Option<Name>, int: Name, span: Span) {
+        let base = match base {
+            Some(base) => match base.as_str().parse() {
+                Ok(base) => base,
+                Err(_) => return,
+            },
+            None => 10,
+        };
+        let oversized = match number::parse_integer(&int.as_str(), base, self.max_literal_bits) {
+            Some(int) => int.oversized,
+            None => false,
+        };
+        if oversized {
+            self.emit(
+                DiagBuilder2::warning(format!(
+                    "literal `{}` needs more than {} bits",
+                    span.extract(),
+                    self.max_literal_bits
+                ))
+                .span(span),
+            );
         }
     }
 
@@ -76,6 +128,24 @@ where
             }
         };
 
+        // Sizes and the bases other than B, O, and X were introduced in
+        // VHDL-2008.
+        let extended = match base {
+            BitStringBase::B | BitStringBase::O | BitStringBase::X => int.is_some(),
+            _ => true,
+        };
+        if extended && self.revision < Revision::Vhdl2008 {
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "bit string literal `{}` requires {}",
+                    span.extract(),
+                    Revision::Vhdl2008
+                ))
+                .span(span)
+                .add_note(format!("selected revision is {}", self.revision)),
+            );
+        }
+
         // Parse the value.
         let mut parsed_value = String::new();
         for c in value.value.drain(..) {
@@ -224,42 +294,38 @@ where
         }
     }
 
+    /// Peeks at the next bundle and returns its character and span if it is a
+    /// special character.
+    fn peek_special(&mut self) -> Option<(char, Span)> {
+        match *self.inner.peek() {
+            Some(Spanned {
+                value: Bundle::Special(c),
+                span,
+            }) => Some((c, span)),
+            _ => None,
+        }
+    }
+
     /// Parse any of the symbols in the language. `c0` comes from a
     /// `Bundle::Special` that has already been parsed.
     fn parse_symbol(&mut self, c0: char, mut span: Span) -> Option<Spanned<Token>> {
-        let n1 = self.inner.next();
-        let n2 = self.inner.next();
-
         // Try to parse a three-character symbol.
-        if let (
-            &Some(Spanned {
-                value: Bundle::Special(c1),
-                ..
-            }),
-            &Some(Spanned {
-                value: Bundle::Special(c2),
-                span: sp,
-            }),
-        ) = (&n1, &n2)
-        {
+        if let (Some((c1, _)), Some((c2, sp))) = (self.peek_special(), self.peek_special()) {
             if let Some(tkn) = match (c0, c1, c2) {
                 ('?', '/', '=') => Some(MatchNeq),
                 ('?', '<', '=') => Some(MatchLeq),
                 ('?', '>', '=') => Some(MatchGeq),
                 _ => None,
             } {
+                self.inner.commit();
                 span.expand(sp);
                 return Some(Spanned::new(tkn, span));
             }
         }
-        self.inner.undo(n2);
+        self.inner.rewind();
 
         // Try to parse a two-character symbol.
-        if let &Some(Spanned {
-            value: Bundle::Special(c1),
-            span: sp,
-        }) = &n1
-        {
+        if let Some((c1, sp)) = self.peek_special() {
             if let Some(tkn) = match (c0, c1) {
                 ('=', '>') => Some(Arrow),
                 ('?', '?') => Some(Condition),
@@ -276,11 +342,12 @@ where
                 ('*', '*') => Some(Pow),
                 _ => None,
             } {
+                self.inner.commit();
                 span.expand(sp);
                 return Some(Spanned::new(tkn, span));
             }
         }
-        self.inner.undo(n1);
+        self.inner.rewind();
 
         // Try to parse a one-character symbol.
         if let Some(tkn) = match c0 {
@@ -442,6 +509,7 @@ where
                                 None
                             }
                         };
+                        self.check_literal_size(None, int.value, sp);
                         Some(Spanned::new(
                             Lit(Literal::Abstract(None, int.value, Some(frac.value), exp)),
                             sp,
@@ -505,6 +573,7 @@ where
                             _ => None,
                         };
 
+                        self.check_literal_size(Some(base.value), int.value, sp);
                         Some(Spanned::new(
                             Lit(Literal::Abstract(Some(base.value), int.value, frac, exp)),
                             sp,
@@ -527,6 +596,7 @@ where
                                 None
                             }
                         };
+                        self.check_literal_size(None, int.value, sp);
                         Some(Spanned::new(
                             Lit(Literal::Abstract(None, int.value, None, exp)),
                             sp,
@@ -10,27 +10,169 @@ pub mod lexer;
 pub mod parser;
 
 use moore_common::errors::*;
-use moore_common::grind::{self, Grinder};
+use moore_common::grind::Grinder;
 use moore_common::source::*;
+use moore_common::Session;
 
+/// Parse a source file with the default session options.
 pub fn parse(src: Source) -> Result<Vec<ast::DesignUnit>, ()> {
-    use self::parser::token_stream::TokenStream;
+    parse_with_session(src, &Session::new())
+}
+
+/// Parse a string with the default session options.
+///
+/// The text is added to the source manager as an anonymous source, such that
+/// the spans in the resulting AST and diagnostics refer to it.
+pub fn parse_str(text: &str) -> Result<Vec<ast::DesignUnit>, ()> {
+    parse(get_source_manager().add_anonymous(text))
+}
 
-    // Get a grinder on the bytes of the source file.
-    let content = src.get_content();
-    let bytes = grind::from_iter(content.bytes().iter().map(|x| *x))
-        .vent(|err: DiagBuilder2| eprintln!("{}", err));
+/// Parse a string that is named `name` in diagnostics, with the default
+/// session options.
+///
+/// This is useful to parse generated code, which has no file on disk. See
+/// `SourceManager::add_virtual`.
+pub fn parse_named_str(name: &str, text: &str) -> Result<Vec<ast::DesignUnit>, ()> {
+    parse(get_source_manager().add_virtual(name, text))
+}
 
-    // Perform lexical analysis on the bytes.
-    let tokens = lexer::Lexer::new(bytes, src);
+/// Parse a source file, emitting diagnostics to `sess`.
+///
+/// Fails if any errors are emitted, which includes warnings if the session
+/// treats warnings as errors.
+pub fn parse_with_session(src: Source, sess: &Session) -> Result<Vec<ast::DesignUnit>, ()> {
+    parse_with_revision(src, sess, Default::default())
+}
 
-    // Parse the file.
-    let mut parser = parser::basic::BasicParser::new(tokens);
+/// Parse a source file as per a revision of the standard.
+///
+/// Constructs introduced in later revisions are reported as errors.
+pub fn parse_with_revision(
+    src: Source,
+    sess: &Session,
+    revision: lexer::Revision,
+) -> Result<Vec<ast::DesignUnit>, ()> {
+    let errors = sess.num_errors();
+    let mut parser = make_parser(src, sess, revision);
     let ast = parser::rules::parse_design_file(&mut parser);
 
-    if parser.is_error() {
+    if sess.num_errors() > errors {
         Err(())
     } else {
         Ok(ast)
     }
 }
+
+/// Parse a source file one design unit at a time.
+///
+/// Unlike `parse`, which collects all design units of the file, this yields
+/// each unit as soon as it has been parsed, such that the caller can process
+/// and drop it before the next one is parsed. A unit for which errors were
+/// reported is yielded as `Err(())`, and parsing continues with the next unit.
+/// Diagnostics are emitted to `sess`.
+pub fn parse_iter<'a>(
+    src: Source,
+    sess: &'a Session,
+) -> impl Iterator<Item = Result<ast::DesignUnit, ()>> + 'a {
+    use self::parser::token_stream::TokenStream;
+
+    let mut parser = make_parser(src, sess, Default::default());
+    std::iter::from_fn(move || {
+        if parser.is_fatal() || parser.peek(0).value == lexer::token::Eof {
+            return None;
+        }
+        let errors = sess.num_errors();
+        match parser::rules::parse_design_unit(&mut parser) {
+            Ok(unit) if sess.num_errors() == errors => Some(Ok(unit)),
+            _ => Some(Err(())),
+        }
+    })
+}
+
+/// Combine the design units of several source files into one library.
+///
+/// Primary units share a namespace within a library, such that two entities,
+/// configurations, packages, or contexts of the same name are duplicates.
+/// Architectures are duplicates if they have the same name and entity, and
+/// package bodies if they belong to the same package. Each duplicate is
+/// reported as an error that points at both definitions.
+///
+/// Returns the units of all files in order, or an error if any duplicates
+/// were found.
+pub fn merge_design_units<C: DiagEmitter>(
+    files: Vec<Vec<ast::DesignUnit>>,
+    ctx: C,
+) -> Result<Vec<ast::DesignUnit>, ()> {
+    use moore_common::name::Name;
+    use std::collections::HashMap;
+
+    #[derive(PartialEq, Eq, Hash)]
+    enum Key {
+        Primary(Name),
+        Arch(Name, Name),
+        PkgBody(Name),
+    }
+
+    let mut seen: HashMap<Key, Span> = HashMap::new();
+    let mut failed = false;
+    let units: Vec<_> = files.into_iter().flatten().collect();
+    for unit in &units {
+        let (key, name, desc) = match unit.data {
+            ast::DesignUnitData::EntityDecl(ref d) => {
+                (Key::Primary(d.name.value), d.name, "entity")
+            }
+            ast::DesignUnitData::CfgDecl(ref d) => {
+                (Key::Primary(d.name.value), d.name, "configuration")
+            }
+            ast::DesignUnitData::PkgDecl(ref d) => (Key::Primary(d.name.value), d.name, "package"),
+            ast::DesignUnitData::PkgInst(ref d) => (Key::Primary(d.name.value), d.name, "package"),
+            ast::DesignUnitData::CtxDecl(ref d) => (Key::Primary(d.name.value), d.name, "context"),
+            ast::DesignUnitData::ArchBody(ref d) => {
+                match (d.target.primary.kind, d.target.parts.is_empty()) {
+                    (ast::PrimaryNameKind::Ident(entity), true) => {
+                        (Key::Arch(entity, d.name.value), d.name, "architecture")
+                    }
+                    _ => continue,
+                }
+            }
+            ast::DesignUnitData::PkgBody(ref d) => {
+                (Key::PkgBody(d.name.value), d.name, "package body")
+            }
+        };
+        match seen.get(&key) {
+            Some(&prev) => {
+                ctx.emit(
+                    DiagBuilder2::error(format!(
+                        "{} `{}` declared multiple times",
+                        desc, name.value
+                    ))
+                    .span(name.span)
+                    .add_note("previous declaration was here:")
+                    .span(prev),
+                );
+                failed = true;
+            }
+            None => {
+                seen.insert(key, name.span);
+            }
+        }
+    }
+    if failed {
+        Err(())
+    } else {
+        Ok(units)
+    }
+}
+
+/// Create a parser for a source file.
+///
+/// The diagnostics of the lexer and the parser are emitted to `sess`.
+fn make_parser<'a>(
+    src: Source,
+    sess: &'a Session,
+    revision: lexer::Revision,
+) -> parser::basic::BasicParser<impl Grinder<Item = Option<u8>, Error = DiagBuilder2> + 'a> {
+    let mut tokens = lexer::lex_source(src, sess);
+    tokens.set_revision(revision);
+    parser::basic::BasicParser::new(tokens)
+}
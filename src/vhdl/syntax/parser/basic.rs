@@ -4,13 +4,16 @@
 //! lexer and emits errors back to it.
 
 use crate::lexer::token::Token;
-use crate::lexer::Lexer;
+use crate::lexer::{Lexer, Revision};
 use crate::parser::TokenStream;
 use moore_common::errors::*;
 use moore_common::grind::Grinder;
 use moore_common::source::*;
 use std::collections::VecDeque;
 
+/// The maximum nesting depth of expressions accepted by default.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
 pub struct BasicParser<T>
 where
     T: Grinder<Item = Option<u8>, Error = DiagBuilder2>,
@@ -19,7 +22,10 @@ where
     queue: VecDeque<Spanned<Token>>,
     last_span: Span,
     severity: Severity,
+    errors: usize,
     consumed: usize,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<T> TokenStream<Token> for BasicParser<T>
@@ -60,12 +66,31 @@ where
     fn emit(&mut self, diag: DiagBuilder2) {
         use std::cmp::max;
         self.severity = max(self.severity, diag.get_severity());
+        if diag.get_severity() >= Severity::Error {
+            self.errors += 1;
+        }
         self.input.emit(diag);
     }
 
     fn severity(&self) -> Severity {
         self.severity
     }
+
+    fn revision(&self) -> Revision {
+        self.input.revision()
+    }
+
+    fn enter_nested(&mut self) -> bool {
+        if self.depth >= self.max_depth {
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    fn leave_nested(&mut self) {
+        self.depth -= 1;
+    }
 }
 
 impl<T> BasicParser<T>
@@ -79,10 +104,29 @@ where
             queue: VecDeque::new(),
             last_span: INVALID_SPAN,
             severity: Severity::Note,
+            errors: 0,
             consumed: 0,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
         }
     }
 
+    /// Limit how deeply expressions may be nested.
+    ///
+    /// The parser descends recursively into nested expressions. Expressions
+    /// nested deeper than `depth` are rejected with a diagnostic rather than
+    /// risking an overflow of the call stack. The operands of a chain of binary
+    /// operators nest as well, since the parser descends into each of them.
+    /// Defaults to `DEFAULT_MAX_DEPTH`.
+    pub fn set_max_nesting_depth(&mut self, depth: usize) {
+        self.max_depth = depth;
+    }
+
+    /// The number of errors emitted so far.
+    pub fn num_errors(&self) -> usize {
+        self.errors
+    }
+
     /// Ensure that either the end of file has been reached, or at least
     /// `min_tokens` tokens are in the queue.
     fn ensure_queue_filled(&mut self, min_tokens: usize) {
@@ -263,6 +263,39 @@ where
     Ok(v)
 }
 
+/// The tokens that delimit statements and declarations, to which the parser
+/// synchronizes after an error.
+pub const SYNC_TOKENS: &[Token] = &[Semicolon, Keyword(Kw::End), Keyword(Kw::Begin)];
+
+/// Repeatedly apply a parser until a certain predicate matches, recovering
+/// from errors.
+///
+/// If an item fails to parse, the parser synchronizes to the end of the item,
+/// see `Parser::recover_until`, and continues with the next one. This keeps a
+/// single erroneous statement from discarding the statements around it.
+pub fn repeat_sync_until<P: Parser, R, F, T>(p: &mut P, mut term: T, mut parse: F) -> Vec<R>
+where
+    F: FnMut(&mut P) -> Result<R, Reported>,
+    T: Predicate<P>,
+{
+    let mut v = Vec::new();
+    while !p.is_fatal() && p.peek(0).value != Eof && !term.matches(p) {
+        let consumed = p.consumed();
+        match parse(p) {
+            Ok(x) => v.push(x),
+            Err(_) => {
+                p.recover_until(SYNC_TOKENS);
+                // Skip a delimiter that does not end the list, such as a
+                // stray `begin`, to guarantee progress.
+                if p.consumed() == consumed && p.peek(0).value != Eof && !term.matches(p) {
+                    p.bump();
+                }
+            }
+        }
+    }
+    v
+}
+
 /// Parse a list of items separated with a specific token, until a terminator
 /// oktne has been reached. The terminator is not consumed.
 pub fn separated<P: Parser, M, R, F, T>(
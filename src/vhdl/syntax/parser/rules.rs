@@ -47,6 +47,7 @@
 
 use crate::ast;
 use crate::lexer::token::*;
+use crate::lexer::Revision;
 use crate::parser::core::*;
 use crate::parser::TokenStream;
 use moore_common::errors::*;
@@ -54,7 +55,22 @@ use moore_common::name::*;
 use moore_common::source::*;
 use std::fmt::Display;
 
-pub trait Parser: TokenStream<Token> {}
+pub trait Parser: TokenStream<Token> {
+    /// Skip tokens until one of `sync` is found outside of any parentheses.
+    ///
+    /// A semicolon is consumed, such that parsing resumes with the next
+    /// statement or declaration. Other tokens, such as `end` and `begin`, are
+    /// left to the enclosing construct. See `SYNC_TOKENS` for the usual set.
+    fn recover_until(&mut self, sync: &[Token])
+    where
+        Self: Sized,
+    {
+        recover(self, sync, false);
+        if self.peek(0).value == Semicolon {
+            self.bump();
+        }
+    }
+}
 impl<T> Parser for T where T: TokenStream<Token> {}
 
 #[derive(Debug)]
@@ -79,6 +95,19 @@ macro_rules! unimp {
     }};
 }
 
+/// Emit an error if a construct was introduced after the selected revision of
+/// the standard.
+fn require_revision<P: Parser>(p: &mut P, revision: Revision, construct: &str, span: Span) {
+    if p.revision() < revision {
+        let selected = p.revision();
+        p.emit(
+            DiagBuilder2::error(format!("{} requires {}", construct, revision))
+                .span(span)
+                .add_note(format!("selected revision is {}", selected)),
+        );
+    }
+}
+
 /// Parse an entire design file. IEEE 1076-2008 section 13.1.
 ///
 /// ```text
@@ -225,6 +254,7 @@ pub fn parse_context_ref<P: Parser>(p: &mut P) -> ReportedResult<Spanned<Vec<ast
     let names = separated_nonempty(p, Comma, Semicolon, "selected name", parse_name)?;
     require(p, Semicolon)?;
     span.expand(p.last_span());
+    require_revision(p, Revision::Vhdl2008, "context reference", span);
     Ok(Spanned::new(names, span))
 }
 
@@ -374,6 +404,8 @@ pub fn parse_name_suffix<P: Parser>(
 
 /// Parse the optional trailing name after an entity, configuration, etc., which
 /// must match the name at the beginning of the declaration.
+///
+/// A trailing name that differs from the name at the beginning is an error.
 fn parse_optional_matching_ident<P, M1, M2, T>(p: &mut P, name: T, msg: M1, sec: M2)
 where
     P: Parser,
@@ -385,11 +417,13 @@ where
         if let Some(name) = name.into() {
             if n.value != name.value {
                 p.emit(
-                    DiagBuilder2::warning(format!(
+                    DiagBuilder2::error(format!(
                         "`{}` does not match {} name `{}`",
                         n.value, msg, name.value
                     ))
                     .span(n.span)
+                    .add_note(format!("{} name given here:", msg))
+                    .span(name.span)
                     .add_note(format!("see IEEE 1076-2008 {}", sec)),
                 );
             }
@@ -425,6 +459,7 @@ pub fn parse_context_decl<P: Parser>(p: &mut P) -> ReportedResult<ast::CtxDecl>
     parse_optional_matching_ident(p, name, "context", "section 13.3");
     require(p, Semicolon)?;
     span.expand(p.last_span());
+    require_revision(p, Revision::Vhdl2008, "context declaration", span);
     Ok(ast::CtxDecl {
         id: Default::default(),
         span: span,
@@ -456,7 +491,7 @@ pub fn parse_entity_decl<P: Parser>(p: &mut P) -> ReportedResult<ast::EntityDecl
 
     // Parse the optional statement part.
     let stmts = if accept(p, Keyword(Kw::Begin)) {
-        Some(repeat_until(p, Keyword(Kw::End), parse_stmt)?)
+        Some(repeat_sync_until(p, Keyword(Kw::End), parse_stmt))
     } else {
         None
     };
@@ -535,7 +570,7 @@ pub fn parse_arch_body<P: Parser>(p: &mut P) -> ReportedResult<ast::ArchBody> {
     // Parse the declarative and statement parts.
     let decl_items = repeat(p, try_decl_item)?;
     require(p, Keyword(Kw::Begin))?;
-    let stmts = repeat_until(p, Keyword(Kw::End), parse_stmt)?;
+    let stmts = repeat_sync_until(p, Keyword(Kw::End), parse_stmt);
 
     // Parse the tail of the body.
     require(p, Keyword(Kw::End))?;
@@ -887,7 +922,7 @@ pub fn parse_subprog_decl_item<P: Parser>(p: &mut P) -> ReportedResult<ast::Subp
         } else {
             let decl_items = repeat(p, try_decl_item)?;
             require(p, Keyword(Kw::Begin))?;
-            let stmts = repeat_until(p, Keyword(Kw::End), parse_stmt)?;
+            let stmts = repeat_sync_until(p, Keyword(Kw::End), parse_stmt);
             require(p, Keyword(Kw::End))?;
             // TODO: Check if things match once the subprog_spec returns
             // something useful.
@@ -1253,6 +1288,19 @@ impl ExprPrec {
 /// expr[10] := "inertial" expr[9]
 /// ```
 pub fn parse_expr_prec<P: Parser>(p: &mut P, prec: ExprPrec) -> ReportedResult<ast::Expr> {
+    // Every nested expression passes through here, so this is where the
+    // nesting depth is limited.
+    if !p.enter_nested() {
+        let span = p.peek(0).span;
+        p.emit(DiagBuilder2::error("expression too deeply nested").span(span));
+        return Err(Reported);
+    }
+    let result = parse_expr_prec_nested(p, prec);
+    p.leave_nested();
+    result
+}
+
+fn parse_expr_prec_nested<P: Parser>(p: &mut P, prec: ExprPrec) -> ReportedResult<ast::Expr> {
     let tkn = p.peek(0);
     let mut span = tkn.span;
 
@@ -1261,6 +1309,9 @@ pub fn parse_expr_prec<P: Parser>(p: &mut P, prec: ExprPrec) -> ReportedResult<a
         let op_prec = unary_prec(op);
         if prec <= op_prec {
             p.bump();
+            if let ast::UnaryOp::Logical(_) = op {
+                require_revision(p, Revision::Vhdl2008, "unary logical operator", tkn.span);
+            }
             let arg = parse_expr_prec(p, op_prec)?;
             span.expand(p.last_span());
             return parse_expr_suffix(
@@ -1435,6 +1486,25 @@ pub fn parse_package_decl<P: Parser>(p: &mut P) -> ReportedResult<ast::PkgDecl>
     let name = parse_ident(p, "package name")?;
     require(p, Keyword(Kw::Is))?;
 
+    // Parse the optional package header.
+    let generics = try_generic_clause(p)?;
+    let generic_map = match generics {
+        Some(ref generics) => {
+            require_revision(
+                p,
+                Revision::Vhdl2008,
+                "package generic clause",
+                generics.span,
+            );
+            let gm = try_map_aspect(p, Kw::Generic)?;
+            if gm.is_some() {
+                require(p, Semicolon)?;
+            }
+            gm
+        }
+        None => None,
+    };
+
     // Parse the declarative part.
     let decl_items = repeat(p, try_decl_item)?;
 
@@ -1448,6 +1518,8 @@ pub fn parse_package_decl<P: Parser>(p: &mut P) -> ReportedResult<ast::PkgDecl>
         id: Default::default(),
         span: span,
         name: name,
+        generics,
+        generic_map,
         decls: decl_items,
     })
 }
@@ -1583,6 +1655,7 @@ pub fn parse_type_decl<P: Parser>(
                 } else {
                     None
                 };
+                sp.expand(p.last_span());
                 ast::RangeType(Box::new(range), units)
             }
 
@@ -1643,7 +1716,11 @@ pub fn parse_type_decl<P: Parser>(
                     require(p, Keyword(Kw::Body))?;
                 }
                 parse_optional_matching_ident(p, name, "type", "section 5.6");
-                ast::ProtectedType(decl_items)
+                if body {
+                    ast::ProtectedBody(decl_items)
+                } else {
+                    ast::ProtectedType(decl_items)
+                }
             }
 
             // Emit an error for anything else.
@@ -2745,11 +2822,11 @@ pub fn parse_if_stmt<P: Parser>(
         |p| {
             let cond = parse_expr(p)?;
             require(p, Keyword(Kw::Then))?;
-            let stmts = repeat_until(
+            let stmts = repeat_sync_until(
                 p,
                 token_predicate!(Keyword(Kw::Elsif), Keyword(Kw::Else), Keyword(Kw::End)),
                 parse_stmt,
-            )?;
+            );
             Ok((
                 cond,
                 ast::StmtBody {
@@ -2764,7 +2841,7 @@ pub fn parse_if_stmt<P: Parser>(
     let alt = if accept(p, Keyword(Kw::Else)) {
         Some(ast::StmtBody {
             id: Default::default(),
-            stmts: repeat_until(p, Keyword(Kw::End), parse_stmt)?,
+            stmts: repeat_sync_until(p, Keyword(Kw::End), parse_stmt),
         })
     } else {
         None
@@ -2792,6 +2869,10 @@ pub fn parse_case_stmt<P: Parser>(
 ) -> ReportedResult<ast::StmtData> {
     require(p, Keyword(Kw::Case))?;
     let has_qm = accept(p, Qmark);
+    if has_qm {
+        let span = p.last_span();
+        require_revision(p, Revision::Vhdl2008, "matching case statement", span);
+    }
     let switch = parse_expr(p)?;
     require(p, Keyword(Kw::Is))?;
 
@@ -2802,11 +2883,11 @@ pub fn parse_case_stmt<P: Parser>(
             let choices = separated_nonempty(p, Pipe, Arrow, "choice", parse_expr)?;
             choices_span.expand(p.last_span());
             require(p, Arrow)?;
-            let stmts = repeat_until(
+            let stmts = repeat_sync_until(
                 p,
                 token_predicate!(Keyword(Kw::When), Keyword(Kw::End)),
                 parse_stmt,
-            )?;
+            );
             Ok(Some((
                 Spanned::new(choices, choices_span),
                 ast::StmtBody {
@@ -2861,7 +2942,7 @@ pub fn parse_loop_stmt<P: Parser>(
 
     // Parse the rest.
     require(p, Keyword(Kw::Loop))?;
-    let stmts = repeat_until(p, Keyword(Kw::End), parse_stmt)?;
+    let stmts = repeat_sync_until(p, Keyword(Kw::End), parse_stmt);
     require(p, Keyword(Kw::End))?;
     require(p, Keyword(Kw::Loop))?;
     parse_optional_matching_ident(p, label, "loop statement", "section 10.10");
@@ -3132,7 +3213,7 @@ where
     }
 
     // Parse the statements in the body.
-    let stmts = repeat_until(p, term, parse_stmt)?;
+    let stmts = repeat_sync_until(p, term, parse_stmt);
 
     // Parse the `end` and optional trailing label.
     let has_end = if p.peek(0).value == Keyword(Kw::End) && p.peek(1).value != Keyword(Kw::Generate)
@@ -3158,7 +3239,8 @@ where
 /// Parse a block statement. See IEEE 1076-2008 section 11.2.
 ///
 /// ```text
-/// block_stmt := "block" ["(" expr ")"] ["is"] {decl_item} "begin" {stmt} "end" "block" [ident] ";"
+/// block_stmt := "block" ["(" expr ")"] ["is"] block_header {decl_item} "begin" {stmt} "end" "block" [ident] ";"
+/// block_header := [generic_clause [generic_map_aspect ";"]] [port_clause [port_map_aspect ";"]]
 /// ```
 pub fn parse_block_stmt<P: Parser>(
     p: &mut P,
@@ -3167,15 +3249,32 @@ pub fn parse_block_stmt<P: Parser>(
     require(p, Keyword(Kw::Block))?;
     let guard = try_flanked(p, Paren, parse_expr)?;
     accept(p, Keyword(Kw::Is));
+
+    // Parse the block header.
+    let generics = try_generic_clause(p)?;
+    let generic_map = try_map_aspect(p, Kw::Generic)?;
+    if generic_map.is_some() {
+        require(p, Semicolon)?;
+    }
+    let ports = try_port_clause(p)?;
+    let port_map = try_map_aspect(p, Kw::Port)?;
+    if port_map.is_some() {
+        require(p, Semicolon)?;
+    }
+
     let decl_items = repeat(p, try_decl_item)?;
     require(p, Keyword(Kw::Begin))?;
-    let stmts = repeat_until(p, Keyword(Kw::End), parse_stmt)?;
+    let stmts = repeat_sync_until(p, Keyword(Kw::End), parse_stmt);
     require(p, Keyword(Kw::End))?;
     require(p, Keyword(Kw::Block))?;
     parse_optional_matching_ident(p, label, "block", "section 11.2");
     require(p, Semicolon)?;
     Ok(ast::BlockStmt {
         guard: guard,
+        generics,
+        generic_map,
+        ports,
+        port_map,
         decls: decl_items,
         stmts: stmts,
     })
@@ -3208,7 +3307,7 @@ pub fn parse_proc_stmt<P: Parser>(
 
     // Parse the statement body.
     require(p, Keyword(Kw::Begin))?;
-    let stmts = repeat_until(p, Keyword(Kw::End), parse_stmt)?;
+    let stmts = repeat_sync_until(p, Keyword(Kw::End), parse_stmt);
     require(p, Keyword(Kw::End))?;
 
     // Parse the rest.
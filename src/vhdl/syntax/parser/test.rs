@@ -6,6 +6,7 @@ use crate::lexer::Lexer;
 use crate::parser::basic::BasicParser;
 use crate::parser::core::*;
 use crate::parser::rules::*;
+use crate::parser::TokenStream;
 use moore_common::errors::*;
 use moore_common::grind::{self, Grinder};
 use moore_common::source::*;
@@ -155,6 +156,34 @@ fn entity_header() {
     );
 }
 
+#[test]
+fn block_header() {
+    parse!(
+        "
+        b: block
+            generic (N: Natural);
+            generic map (N => 4);
+            port (I: in Bit; O: out Bit);
+            port map (I => X, O => Y);
+        begin
+            O <= I;
+        end block b;
+    ",
+        parse_stmt
+    );
+    parse!(
+        "
+        b: block is
+            port (I: in Bit);
+            port map (I => X);
+            signal S: Bit;
+        begin
+        end block;
+    ",
+        parse_stmt
+    );
+}
+
 #[test]
 fn entity_decl_part() {
     parse!(
@@ -282,6 +311,24 @@ fn package_inst() {
     });
 }
 
+#[test]
+fn package_generics() {
+    let pkg = parse!(
+        "package fifo is
+            generic (type elem; depth : natural := 16);
+            generic map (depth => 8);
+            constant size : natural := depth;
+        end package;",
+        parse_package_decl
+    );
+    assert_eq!(pkg.generics.map(|g| g.value.len()), Some(2));
+    assert!(pkg.generic_map.is_some());
+    assert_eq!(pkg.decls.len(), 1);
+
+    let pkg = parse!("package fifo is end;", parse_package_decl);
+    assert_eq!(pkg.generics, None);
+}
+
 #[test]
 fn decl_items() {
     parse!(
@@ -298,6 +345,31 @@ fn decl_items() {
     );
 }
 
+#[test]
+fn physical_type_decl() {
+    parse!(
+        "
+        type resistance is range 0 to 1e9 units
+            ohm;
+            kohm = 1000 ohm;
+            mohm = 1000 kohm;
+        end units;
+    ",
+        |p| parse_type_decl(p, true)
+    );
+
+    parse!(
+        "
+        type DURATION is range -1E18 to 1E18 units
+            fs;
+            ps = 1000 fs;
+            ns = 1000 ps;
+        end units DURATION;
+    ",
+        |p| parse_type_decl(p, true)
+    );
+}
+
 #[test]
 fn protected_type_decl() {
     parse!(
@@ -492,6 +564,24 @@ fn expr() {
 #[test]
 fn subtype_decl() {
     parse!("subtype foo is integer;", parse_subtype_decl);
+
+    // Resolution functions are captured in the subtype indication.
+    let decl = parse!("subtype rsl is resolved std_ulogic;", parse_subtype_decl);
+    match decl.subtype.res {
+        Some(ast::ResolInd::Name(ref name)) => assert_eq!(name.span.extract(), "resolved"),
+        ref res => panic!("unexpected resolution indication {:?}", res),
+    }
+    assert_eq!(decl.subtype.name.span.extract(), "std_ulogic");
+
+    let decl = parse!(
+        "subtype rslv is (resolved) std_ulogic_vector;",
+        parse_subtype_decl
+    );
+    match decl.subtype.res {
+        Some(ast::ResolInd::Exprs(ref elems)) => assert_eq!(elems.span.extract(), "(resolved)"),
+        ref res => panic!("unexpected resolution indication {:?}", res),
+    }
+    assert_eq!(decl.subtype.name.span.extract(), "std_ulogic_vector");
 }
 
 #[test]
@@ -538,3 +628,217 @@ fn config_decl() {
         parse_config_decl
     );
 }
+
+#[test]
+fn warnings_as_errors() {
+    use moore_common::Session;
+
+    // A trailing label without a leading one is only a warning.
+    let content = "entity foo is end;
+        architecture bar of foo is begin
+            process begin wait; end process p;
+        end;";
+    let mut sess = Session::new();
+    let src = get_source_manager().add_anonymous(content);
+    assert!(crate::parse_with_session(src, &sess).is_ok());
+    assert!(crate::parse_iter(src, &sess).all(|unit| unit.is_ok()));
+    sess.opts.warnings_as_errors = true;
+    assert!(crate::parse_with_session(src, &sess).is_err());
+    let units: Vec<_> = crate::parse_iter(src, &sess).map(|u| u.is_ok()).collect();
+    assert_eq!(units, vec![true, false]);
+}
+
+#[test]
+fn oversized_literal() {
+    use moore_common::Session;
+
+    // Literals wider than the configured limit are only a warning.
+    let content = "package foo is constant x : integer := 256; end;";
+    let mut sess = Session::new();
+    sess.opts.warnings_as_errors = true;
+    let src = get_source_manager().add_anonymous(content);
+    assert!(crate::parse_with_session(src, &sess).is_ok());
+    sess.opts.max_literal_bits = Some(8);
+    assert!(crate::parse_with_session(src, &sess).is_err());
+}
+
+#[test]
+fn mismatched_end_label() {
+    assert!(crate::parse_str("architecture a of foo is begin end architecture b;").is_err());
+    assert!(crate::parse_str("entity foo is end entity bar;").is_err());
+    assert!(crate::parse_str("architecture a of foo is begin end architecture a;").is_ok());
+}
+
+#[test]
+fn parse_str() {
+    let units = crate::parse_str("entity foo is end entity foo;").unwrap();
+    assert_eq!(units.len(), 1);
+    assert!(crate::parse_str("entity foo is").is_err());
+}
+
+#[test]
+fn parse_iter() {
+    let src = get_source_manager().add_anonymous(
+        "library ieee; use ieee.std_logic_1164.all;
+        entity foo is end;
+        architecture bar of foo is begin end;
+        package baz is end;",
+    );
+    let batch = crate::parse(src).unwrap();
+    let sess = moore_common::Session::new();
+    let units: Vec<_> = crate::parse_iter(src, &sess)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(units.len(), 3);
+    assert_eq!(units, batch);
+
+    let src = get_source_manager().add_anonymous("entity foo is");
+    let units: Vec<_> = crate::parse_iter(src, &sess).map(|u| u.is_ok()).collect();
+    assert_eq!(units, vec![false]);
+}
+
+#[test]
+fn merge_design_units() {
+    let a = crate::parse_str("entity foo is end; architecture bar of foo is begin end;").unwrap();
+    let b = crate::parse_str("architecture baz of foo is begin end;").unwrap();
+    let c = crate::parse_str("entity foo is end; package bar is end;").unwrap();
+
+    let diags = DiagCollector::new();
+    let units = crate::merge_design_units(vec![a.clone(), b], &diags).unwrap();
+    assert_eq!(units.len(), 3);
+    assert!(diags.diagnostics().is_empty());
+
+    let diags = DiagCollector::new();
+    assert!(crate::merge_design_units(vec![a, c], &diags).is_err());
+    let diags = diags.diagnostics();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(
+        diags[0].get_message(),
+        "entity `foo` declared multiple times"
+    );
+    assert_eq!(diags[0].spans().len(), 2);
+}
+
+#[test]
+fn revision() {
+    use crate::lexer::Revision;
+    use std::cell::RefCell;
+
+    // Parse the content as per a revision and collect the diagnostics.
+    fn parse_diags(content: &str, revision: Revision) -> Vec<String> {
+        let src = get_source_manager().add_anonymous(content);
+        let content = src.get_content();
+        let diags = RefCell::new(Vec::new());
+        let bytes = grind::from_iter(content.bytes().iter().copied())
+            .vent(|err: DiagBuilder2| diags.borrow_mut().push(err.get_message().clone()));
+        let mut tokens = Lexer::new(bytes, src);
+        tokens.set_revision(revision);
+        let mut parser = BasicParser::new(tokens);
+        parse_design_file(&mut parser);
+        drop(parser);
+        diags.into_inner()
+    }
+
+    let content = "context ctx is library ieee; end context ctx;";
+    assert_eq!(
+        parse_diags(content, Revision::Vhdl2008),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        parse_diags(content, Revision::Vhdl1993),
+        vec!["context declaration requires VHDL-2008"]
+    );
+    assert_eq!(
+        parse_diags(
+            "package p is constant k : bit_vector := 12UX\"F\"; end;",
+            Revision::Vhdl2002
+        ),
+        vec!["bit string literal `12UX\"F\"` requires VHDL-2008"]
+    );
+    assert_eq!(
+        parse_diags(
+            "package p is constant k : bit := xor \"0110\"; end;",
+            Revision::Vhdl1993
+        ),
+        vec!["unary logical operator requires VHDL-2008"]
+    );
+    assert_eq!(
+        parse_diags(
+            "package p is generic (k : integer); end;",
+            Revision::Vhdl2002
+        ),
+        vec!["package generic clause requires VHDL-2008"]
+    );
+}
+
+#[test]
+fn nesting_depth() {
+    // Parse an expression nested `depth` times, returning whether parsing
+    // succeeded, the number of errors, and whether all input was consumed.
+    let parse_nested = |depth: usize, max_depth: usize| {
+        let content = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        let src = get_source_manager().add_anonymous(content);
+        let content = src.get_content();
+        let bytes = grind::from_iter(content.bytes().iter().copied())
+            .vent(|err: DiagBuilder2| eprintln!("{}", err));
+        let mut parser = BasicParser::new(Lexer::new(bytes, src));
+        parser.set_max_nesting_depth(max_depth);
+        let result = parse_expr(&mut parser);
+        (
+            result.is_ok(),
+            parser.num_errors(),
+            parser.peek(0).value == token::Eof,
+        )
+    };
+
+    // Pathological nesting is rejected without overflowing the stack, and the
+    // parser recovers to the end of the expression. Unoptimized builds need
+    // more stack for the default depth than test threads have by default.
+    let default = crate::parser::basic::DEFAULT_MAX_DEPTH;
+    let results = std::thread::Builder::new()
+        .stack_size(64 << 20)
+        .spawn(move || {
+            (
+                parse_nested(100_000, default),
+                parse_nested(default - 1, default),
+            )
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(results, ((false, 1, true), (true, 0, true)));
+
+    // The limit is configurable.
+    assert_eq!(parse_nested(3, 4), (true, 0, true));
+    assert_eq!(parse_nested(4, 4), (false, 1, true));
+}
+
+#[test]
+fn recover_until_sync_token() {
+    let parser = |content: &str| {
+        let src = get_source_manager().add_anonymous(content);
+        // The grinder owns the content, since the parser outlives this
+        // closure.
+        let content = src.get_content();
+        let bytes = grind::from_iter((0..content.bytes().len()).map(move |i| content.bytes()[i]))
+            .vent(|err: DiagBuilder2| eprintln!("{}", err));
+        BasicParser::new(Lexer::new(bytes, src))
+    };
+
+    // After an error, parsing resumes past the next semicolon.
+    let mut p = parser("a <= ; b <= c;");
+    assert!(parse_stmt(&mut p).is_err());
+    p.recover_until(SYNC_TOKENS);
+    assert!(parse_stmt(&mut p).is_ok());
+    assert_eq!(p.peek(0).value, token::Eof);
+
+    // An erroneous statement does not discard the statements around it.
+    let mut p = parser("process begin a <= b; c <= ; d <= e; end process;");
+    let stmt = parse_stmt(&mut p).unwrap();
+    match stmt.data {
+        ast::StmtData::ProcStmt { ref stmts, .. } => assert_eq!(stmts.len(), 2),
+        _ => panic!("expected a process"),
+    }
+    assert_eq!(p.num_errors(), 1);
+    assert_eq!(p.peek(0).value, token::Eof);
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2016-2021 Fabian Schuiki
 
+use crate::lexer::Revision;
 use moore_common::errors::*;
 use moore_common::source::*;
 
@@ -44,4 +45,21 @@ pub trait TokenStream<T> {
     fn is_error(&self) -> bool {
         self.severity() >= Severity::Error
     }
+
+    /// Get the revision of the standard being accepted.
+    fn revision(&self) -> Revision {
+        Revision::default()
+    }
+
+    /// Enter a nested construct, such as a parenthesized expression.
+    ///
+    /// Returns `false` if the construct would exceed the maximum nesting depth
+    /// the stream supports, in which case the caller must not descend into it.
+    /// Every call that returns `true` must be matched by `leave_nested`.
+    fn enter_nested(&mut self) -> bool {
+        true
+    }
+
+    /// Leave a nested construct entered with `enter_nested`.
+    fn leave_nested(&mut self) {}
 }
@@ -10,7 +10,7 @@ use std::marker::PhantomData;
 use num::{BigInt, BigRational};
 
 use crate::common::errors::*;
-use crate::common::name::Name;
+use crate::common::name::{get_name_table, Name};
 use crate::common::score::Result;
 use crate::common::source::*;
 use crate::common::util::*;
@@ -24,6 +24,7 @@ use crate::op::*;
 use crate::scope2::{Def2, ScopeData, TypeVariantDef};
 use crate::score::*;
 use crate::syntax::ast::{self, Dir};
+use crate::syntax::lexer::number::parse_integer;
 use crate::syntax::lexer::token::{Exponent, ExponentSign, Literal};
 use crate::ty::*;
 
@@ -63,12 +64,22 @@ pub enum Term<'t> {
     Enum(Vec<Spanned<EnumRef>>),
     /// A term that refers to an enum variant.
     Enum2(Vec<Spanned<Def2<'t>>>),
+    /// A term that refers to a set of overloaded subprograms.
+    Subprog(Vec<Spanned<Def>>),
     /// A term of the form `T.<name>`.
     Select(Subterm<'t>, Spanned<ResolvableName>),
     /// A term of the form `T.all`.
     SelectAll(Subterm<'t>),
     /// A term of the form `T (to|downto) T`.
     Range(Spanned<Dir>, Subterm<'t>, Subterm<'t>),
+    /// A term of the form `T'simple_name`, `T'path_name`, or
+    /// `T'instance_name`. Consists of the simple name of `T` and the attribute.
+    NameAttr(Spanned<Name>, Spanned<hir::NameAttr>),
+    /// A term of the form `T'image`, where `T` is a type mark. Applied to its
+    /// argument like a function.
+    ImageAttr(Spanned<TypeMarkRef>),
+    /// A term of the form `T'range` or `T'reverse_range`.
+    RangeAttr(Subterm<'t>, Spanned<hir::RangeAttr>),
     /// A term of the form `T range T`.
     RangeSuffix(Subterm<'t>, Subterm<'t>),
     /// A term of the form `T range <>`.
@@ -94,6 +105,9 @@ pub enum Term<'t> {
     Binary(Spanned<BinaryOp>, Subterm<'t>, Subterm<'t>),
     /// A term of the form `T'T`.
     Qual(Subterm<'t>, Subterm<'t>),
+    /// A term of the form `S'attr`, where `S` is a signal and `attr` one of
+    /// its predefined attributes.
+    SignalAttr(SignalRef, Spanned<hir::SignalAttr>),
     /// A term of the form `new T`.
     New(Subterm<'t>),
 }
@@ -192,8 +206,10 @@ where
                         },
                         None => 10,
                     };
-                    let int = match BigInt::parse_bytes(int.as_str().as_bytes(), base) {
-                        Some(v) => v,
+                    // Oversized literals have already been reported by the
+                    // lexer, so the digits are parsed without a limit here.
+                    let int = match parse_integer(&int.as_str(), base, u64::MAX) {
+                        Some(v) => v.value,
                         None => {
                             self.emit(
                                 DiagBuilder2::error(format!(
@@ -232,8 +248,8 @@ where
                     if let Some(frac) = frac {
                         use num::pow;
                         let shift = frac.as_str().len();
-                        let frac = match BigInt::parse_bytes(frac.as_str().as_bytes(), base) {
-                            Some(v) => v,
+                        let frac = match parse_integer(&frac.as_str(), base, u64::MAX) {
+                            Some(v) => v.value,
                             None => {
                                 self.emit(
                                     DiagBuilder2::error(format!(
@@ -342,27 +358,74 @@ where
                     return Err(());
                 }
                 ast::NamePart::Attribute(ident) => {
-                    let attr = self.termify_name(Spanned::new(ident.name.into(), ident.span))?;
-                    match attr.value {
-                        // TODO: Enable this as soon as we handle attribute
-                        // declarations.
-                        // Term::Ident(Spanned { value: Def::Attr(id), span }) => {
-                        //  let sp = Span::union(term.span, attr.span);
-                        //  Spanned::new(Term::Attribute(Box::new(term), Spanned::new(id, span)), sp)
-                        // }
-                        Term::Ident(other) => {
-                            self.emit(
-                                DiagBuilder2::error(format!(
-                                    "`{}` is not an attribute name",
-                                    ident.name
-                                ))
-                                .span(ident.span)
-                                .add_note("Declared here:")
-                                .span(other.span),
-                            );
-                            return Err(());
+                    let signal_attr = match term.value {
+                        Term::Ident(Spanned {
+                            value: Def::Signal(sig),
+                            ..
+                        }) => hir::SignalAttr::from_name(&ident.name.as_str()).map(|a| (sig, a)),
+                        _ => None,
+                    };
+                    let range_attr = hir::RangeAttr::from_name(&ident.name.as_str());
+                    let name_attr = hir::NameAttr::from_name(&ident.name.as_str());
+                    let image_attr = match term.value {
+                        Term::TypeMark(tm) if ident.name.as_str().eq_ignore_ascii_case("image") => {
+                            Some(tm)
+                        }
+                        _ => None,
+                    };
+                    if let Some((sig, attr)) = signal_attr {
+                        let sp = Span::union(term.span, ident.span);
+                        Spanned::new(Term::SignalAttr(sig, Spanned::new(attr, ident.span)), sp)
+                    } else if let Some(attr) = range_attr {
+                        let sp = Span::union(term.span, ident.span);
+                        let attr = Spanned::new(attr, ident.span);
+                        Spanned::new(Term::RangeAttr(Box::new(term), attr), sp)
+                    } else if let Some(tm) = image_attr {
+                        let sp = Span::union(term.span, ident.span);
+                        Spanned::new(Term::ImageAttr(tm), sp)
+                    } else if let Some(attr) = name_attr {
+                        // The simple name is the last identifier of the
+                        // prefix, which may be a selected name.
+                        let sp = Span::union(term.span, ident.span);
+                        let text = term.span.extract();
+                        let simple = text.rsplit('.').next().unwrap_or("").trim();
+                        let name = get_name_table().intern(simple, simple.starts_with('\\'));
+                        let name = Spanned::new(name, term.span);
+                        Spanned::new(Term::NameAttr(name, Spanned::new(attr, ident.span)), sp)
+                    } else {
+                        let attr =
+                            self.termify_name(Spanned::new(ident.name.into(), ident.span))?;
+                        match attr.value {
+                            // TODO: Enable this as soon as we handle attribute
+                            // declarations.
+                            // Term::Ident(Spanned { value: Def::Attr(id), span }) => {
+                            //  let sp = Span::union(term.span, attr.span);
+                            //  Spanned::new(Term::Attribute(Box::new(term), Spanned::new(id, span)), sp)
+                            // }
+                            Term::Ident(Spanned { span, .. }) => {
+                                self.emit(
+                                    DiagBuilder2::error(format!(
+                                        "`{}` is not an attribute name",
+                                        ident.name
+                                    ))
+                                    .span(ident.span)
+                                    .add_note("Declared here:")
+                                    .span(span),
+                                );
+                                return Err(());
+                            }
+                            Term::Subprog(..) => {
+                                self.emit(
+                                    DiagBuilder2::error(format!(
+                                        "`{}` is not an attribute name",
+                                        ident.name
+                                    ))
+                                    .span(ident.span),
+                                );
+                                return Err(());
+                            }
+                            _ => unreachable!(),
                         }
-                        _ => unreachable!(),
                     }
                 }
                 ast::NamePart::Call(ref paren_elems) => {
@@ -580,9 +643,17 @@ impl<'t, C: DiagEmitter, S> DefSpecificTermContext<'t, Def> for TermContext<C, S
         }
         let all_enum = defs.iter().all(is_enum);
 
+        fn is_subprog(def: &Spanned<Def>) -> bool {
+            matches!(def.value, Def::Subprog(..))
+        }
+        let all_subprog = defs.iter().all(is_subprog);
+
         // Handle overloading. Basically if the definitions are all enum fields
         // or functions, that's fine. For everything else the name must be
         // unique.
+        if all_subprog {
+            return Ok(Spanned::new(Term::Subprog(defs), name.span));
+        }
         let first_def = defs.pop().unwrap();
         let term = match first_def.value {
             Def::Enum(id) if all_enum => {
@@ -595,7 +666,6 @@ impl<'t, C: DiagEmitter, S> DefSpecificTermContext<'t, Def> for TermContext<C, S
                 }
                 Term::Enum(ids)
             }
-            // TODO: Handle the function case.
             _ if !defs.is_empty() => {
                 let mut d =
                     DiagBuilder2::error(format!("`{}` is ambiguous", name.value)).span(name.span);
@@ -760,6 +830,13 @@ where
         }
     }
 
+    /// Emit a diagnostic for a name that does not resolve to a definition, and
+    /// record it on the scoreboard.
+    fn emit_unknown(&self, name: Spanned<ResolvableName>) {
+        self.emit(DiagBuilder2::error(format!("`{}` is unknown", name.value)).span(name.span));
+        self.ctx.record_unresolved_name(name);
+    }
+
     /// Map a latent name to a term.
     pub fn termify_latent_name(&self, name: LatentName<'ast>) -> Result<Spanned<Term>> {
         match name {
@@ -788,10 +865,25 @@ where
         let term_span = term.span;
         let data = match term.value {
             Term::Unresolved(name) => {
-                self.emit(DiagBuilder2::error(format!("`{}` is unknown", name)).span(term.span));
+                self.emit_unknown(Spanned::new(name, term.span));
                 return Err(());
             }
             Term::IntLit(value) => hir::ExprData::IntegerLiteral(ConstInt::new(None, value)),
+            Term::PhysLit(value, unit) => match unit.value {
+                EitherUnit::Old(u) => {
+                    hir::ExprData::PhysicalLiteral(value, Spanned::new(u, unit.span))
+                }
+                EitherUnit::New(_) => {
+                    self.emit(
+                        DiagBuilder2::bug(format!(
+                            "physical literal `{}` refers to a unit unknown to the scoreboard",
+                            term.span.extract()
+                        ))
+                        .span(term.span),
+                    );
+                    return Err(());
+                }
+            },
             Term::StrLit(value) => {
                 // Create a set of characters used in the literal. Then resolve
                 // each as an individual bit literal. This yields multiple enums
@@ -808,10 +900,7 @@ where
                         let term = self.termify_defs(rn, defs)?;
                         match term.value {
                             Term::Unresolved(name) => {
-                                self.emit(
-                                    DiagBuilder2::error(format!("`{}` is unknown", name))
-                                        .span(term.span),
-                                );
+                                self.emit_unknown(Spanned::new(name, term.span));
                                 Err(())
                             }
                             Term::Enum(ids) => Ok((chr, ids.into_iter().collect())),
@@ -909,7 +998,13 @@ where
                 }
             },
             Term::Enum(defs) => hir::ExprData::EnumName(defs),
+            Term::Subprog(defs) => {
+                hir::ExprData::SubprogCall(defs, Spanned::new(vec![], term_span))
+            }
             Term::Select(term, name) => hir::ExprData::Select(self.term_to_expr(*term)?, name),
+            Term::SelectAll(term) => hir::ExprData::Deref(self.term_to_expr(*term)?),
+            Term::SignalAttr(sig, attr) => hir::ExprData::SignalAttr(sig, attr),
+            Term::NameAttr(name, attr) => hir::ExprData::NameAttr(name, attr),
             Term::Paren(subterm) => {
                 // A parenthesis with only one element is just a parenthesized
                 // expression. If there's more than one element, this is a
@@ -1002,6 +1097,35 @@ where
                         );
                         hir::ExprData::Cast(tm, arg)
                     }
+                    Term::Subprog(defs) => hir::ExprData::SubprogCall(defs, args),
+                    Term::ImageAttr(tm) => {
+                        let arg = match args.value.as_slice() {
+                            [hir::AssocElement {
+                                formal: None,
+                                actual:
+                                    Spanned {
+                                        value: hir::AssocActual::Expr(id),
+                                        ..
+                                    },
+                                ..
+                            }] => *id,
+                            _ => {
+                                self.emit(
+                                    DiagBuilder2::error(format!(
+                                        "`{}` must have exactly one argument",
+                                        callee.span.extract()
+                                    ))
+                                    .span(args.span),
+                                );
+                                return Err(());
+                            }
+                        };
+                        self.ctx.set_type_context(
+                            arg,
+                            self.ctx.intern_ty(Ty::Named(tm.span.into(), tm.value)),
+                        );
+                        hir::ExprData::ImageAttr(tm, arg)
+                    }
                     other => hir::ExprData::Call(
                         self.term_to_expr(Spanned::new(other, callee.span))?,
                         args,
@@ -1055,7 +1179,7 @@ where
     pub fn fold_term_as_type<'t>(&self, term: Spanned<Term<'t>>) -> Result<Spanned<Term<'t>>> {
         let (new, new_term) = match term.value {
             Term::Unresolved(name) => {
-                self.emit(DiagBuilder2::error(format!("`{}` is unknown", name)).span(term.span));
+                self.emit_unknown(Spanned::new(name, term.span));
                 return Err(());
             }
             Term::RangeSuffix(subterm, range) => {
@@ -1097,6 +1221,18 @@ where
                     ),
                 }
             }
+            Term::PrefixParen(resol, subterm) => {
+                let subterm = self.fold_term_as_type(*subterm)?;
+                match subterm.value {
+                    // Fold `T TypeMark` to `SubtypeInd`.
+                    Term::TypeMark(tm) => (true, Term::SubtypeInd(tm, Some(resol), None)),
+                    // Fold `T SubtypeInd` to `SubtypeInd`.
+                    Term::SubtypeInd(tm, None, con) => {
+                        (true, Term::SubtypeInd(tm, Some(resol), con))
+                    }
+                    _ => (false, Term::PrefixParen(resol, Box::new(subterm))),
+                }
+            }
             others => (false, others),
         };
         let new_term = Spanned::new(new_term, term.span);
@@ -1125,7 +1261,7 @@ where
                 return Err(());
             }
         };
-        let _resol = match resol {
+        let resol = match resol {
             Some(x) => Some(self.term_to_resolution_indication(*x)?),
             None => None,
         };
@@ -1137,7 +1273,7 @@ where
             hir::SubtypeInd {
                 span: term.span,
                 type_mark: tm,
-                // TODO: Track resolution indication.
+                resol,
                 constraint: con,
             },
             term.span,
@@ -1148,22 +1284,46 @@ where
     }
 
     /// Map a term to a resolution indication.
-    pub fn term_to_resolution_indication(&self, term: Spanned<Term>) -> Result<Spanned<()>> {
-        self.emit(
-            DiagBuilder2::bug(format!(
-                "interpretation of `{}` as a resolution indication not implemented",
-                term.span.extract()
-            ))
-            .span(term.span),
-        );
-        Err(())
+    pub fn term_to_resolution_indication(
+        &self,
+        term: Spanned<Term>,
+    ) -> Result<Spanned<hir::ResolInd>> {
+        let resol = match term.value {
+            Term::Subprog(defs) => hir::ResolInd::Func(defs),
+            Term::Paren(mut elems) if elems.len() == 1 => {
+                let elem = self.term_to_resolution_indication(elems.pop().unwrap())?;
+                hir::ResolInd::Elem(Box::new(elem))
+            }
+            Term::Paren(..) | Term::Aggregate(..) => {
+                self.emit(
+                    DiagBuilder2::bug(format!(
+                        "record element resolution `{}` not implemented",
+                        term.span.extract()
+                    ))
+                    .span(term.span),
+                );
+                return Err(());
+            }
+            _ => {
+                self.emit(
+                    DiagBuilder2::error(format!(
+                        "`{}` is not a resolution function",
+                        term.span.extract()
+                    ))
+                    .span(term.span),
+                );
+                debugln!("It is a {:#?}", term);
+                return Err(());
+            }
+        };
+        Ok(Spanned::new(resol, term.span))
     }
 
     /// Map a term to a constraint.
     pub fn term_to_constraint(&self, term: Spanned<Term>) -> Result<Spanned<hir::Constraint>> {
         // Handle range constraints.
         match term.value {
-            Term::Range(..) => {
+            Term::Range(..) | Term::RangeAttr(..) => {
                 return Ok(self.term_to_range(term)?.map(|r| hir::Constraint::Range(r)))
             }
             _ => (),
@@ -1367,7 +1527,7 @@ where
                 let add_ctx = AddContext::new(self.ctx, self.scope);
                 Spanned::new(add_ctx.add_subtype_ind_hir(hir.value)?.into(), hir.span)
             }
-            Term::Range(..) => self.term_to_range(term)?.map_into(),
+            Term::Range(..) | Term::RangeAttr(..) => self.term_to_range(term)?.map_into(),
             _ => {
                 self.emit(
                     DiagBuilder2::error(format!(
@@ -1393,12 +1553,14 @@ where
     pub fn term_to_range(&self, term: Spanned<Term>) -> Result<Spanned<hir::Range>> {
         Ok(Spanned::new(
             match term.value {
-                // Term::Attr(..) => ...
                 Term::Range(dir, lb, rb) => hir::Range::Immediate(
                     dir.value,
                     self.term_to_expr(*lb)?,
                     self.term_to_expr(*rb)?,
                 ),
+                Term::RangeAttr(prefix, attr) => {
+                    hir::Range::Attr(self.term_to_expr(*prefix)?, attr)
+                }
                 _ => {
                     self.emit(
                         DiagBuilder2::error(format!(
@@ -1426,10 +1588,11 @@ where
     pub fn term_to_ident(&self, term: Spanned<Term>) -> Result<Spanned<Def>> {
         Ok(match term.value {
             Term::Unresolved(name) => {
-                self.emit(DiagBuilder2::error(format!("`{}` is unknown", name)).span(term.span));
+                self.emit_unknown(Spanned::new(name, term.span));
                 return Err(());
             }
             Term::Ident(def) => def,
+            Term::Subprog(ref defs) if defs.len() == 1 => defs[0],
             Term::TypeMark(tm) => tm.map_into(),
             Term::Enum(defs) => {
                 if defs.len() == 1 {
@@ -1514,12 +1677,17 @@ where
             match term.value {
                 Term::Unresolved(ResolvableName::Ident(name)) => hir::Choice::Element(name),
                 Term::Others => hir::Choice::Others,
-                Term::SubtypeInd(..) | Term::TypeMark(..) | Term::Range(..) => {
+                Term::SubtypeInd(..)
+                | Term::TypeMark(..)
+                | Term::Range(..)
+                | Term::RangeAttr(..) => {
                     hir::Choice::DiscreteRange(self.term_to_discrete_range(term)?.value)
                 }
-                Term::IntLit(..) | Term::Unary(..) | Term::Binary(..) => {
-                    hir::Choice::Expr(self.term_to_expr(term)?)
-                }
+                Term::IntLit(..)
+                | Term::Enum(..)
+                | Term::Ident(..)
+                | Term::Unary(..)
+                | Term::Binary(..) => hir::Choice::Expr(self.term_to_expr(term)?),
                 _ => {
                     self.emit(
                         DiagBuilder2::error(format!(
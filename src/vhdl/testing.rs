@@ -0,0 +1,89 @@
+// Copyright (c) 2016-2021 Fabian Schuiki
+
+//! Utilities shared by the unit tests of the crate.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use crate::common::name::get_name_table;
+use crate::common::score::NodeRef;
+use crate::common::NodeId;
+use crate::score::{ArchRef, LibRef, ScoreContext};
+use crate::syntax::ast;
+
+/// A test to be run on the scoring thread, and the channel its outcome is
+/// reported on.
+type ScoreTest = (Box<dyn FnOnce() + Send>, mpsc::Sender<thread::Result<()>>);
+
+lazy_static! {
+    /// The channel to submit tests to the scoring thread.
+    static ref SCORE_THREAD: Mutex<mpsc::Sender<ScoreTest>> = {
+        let (tx, rx) = mpsc::channel::<ScoreTest>();
+        thread::spawn(move || {
+            for (test, result) in rx {
+                let _ = result.send(panic::catch_unwind(AssertUnwindSafe(test)));
+            }
+        });
+        Mutex::new(tx)
+    };
+}
+
+/// Run a test that parses or scores VHDL code.
+///
+/// Names are only valid in the name table of the thread that interned them,
+/// but the builtins are global and keep the names they interned on first use.
+/// Therefore all such tests run one after another on the same thread. Panics
+/// of the test are forwarded to the caller.
+pub fn score_test<F: FnOnce() + Send + 'static>(test: F) {
+    let (tx, rx) = mpsc::channel();
+    SCORE_THREAD
+        .lock()
+        .unwrap()
+        .send((Box::new(test), tx))
+        .unwrap();
+    if let Err(payload) = rx.recv().unwrap() {
+        panic::resume_unwind(payload);
+    }
+}
+
+/// Set up a score context named `$ctx` in the current scope.
+///
+/// The context emits diagnostics to the session `$sess`. If a block is given,
+/// it runs with the scoreboard bound to `$sb` before the context is created,
+/// such that tests may change the scoreboard's options.
+macro_rules! score_context {
+    ($ctx:ident, $sess:expr) => {
+        score_context!($ctx, $sess, |sb| {})
+    };
+    ($ctx:ident, $sess:expr, |$sb:ident| $configure:block) => {
+        let arenas = $crate::score::Arenas::new();
+        #[allow(unused_mut)]
+        let mut $sb = $crate::score::ScoreBoard::new(&arenas);
+        $configure;
+        let lazy = $crate::lazy::LazyPhaseTable::new(&$sb);
+        let $ctx = $crate::score::ScoreContext {
+            sess: $sess,
+            global: &$crate::driver::Global,
+            sb: &$sb,
+            lazy: &lazy,
+        };
+    };
+}
+
+/// Add a set of design units to a new library called `work`.
+pub fn add_work<'ast>(
+    ctx: &ScoreContext<'_, '_, 'ast, '_>,
+    units: &'ast [ast::DesignUnit],
+) -> LibRef {
+    let lib = LibRef::new(NodeId::alloc());
+    let work = get_name_table().intern("work", false);
+    ctx.add_library(work, lib, units.iter().collect());
+    lib
+}
+
+/// Find the first architecture of the first entity in a library.
+pub fn first_arch(ctx: &ScoreContext, lib: LibRef) -> ArchRef {
+    let archs = ctx.archs(lib).unwrap();
+    archs.by_entity.values().next().unwrap().ordered[0]
+}
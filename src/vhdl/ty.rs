@@ -46,6 +46,8 @@ pub enum Ty {
     Record(RecordTy),
     /// A subprogram type.
     Subprog(SubprogTy),
+    /// A protected type, referring to its declaration.
+    Protected(TypeDeclRef),
 }
 
 impl Ty {
@@ -64,6 +66,7 @@ impl Ty {
             Ty::File(..) => "file type",
             Ty::Record(_) => "record type",
             Ty::Subprog(_) => "subprogram type",
+            Ty::Protected(_) => "protected type",
         }
     }
 
@@ -134,10 +137,19 @@ impl fmt::Display for Ty {
             Ty::File(ref ty) => write!(f, "file of {}", ty),
             Ty::Record(ref ty) => write!(f, "{}", ty),
             Ty::Subprog(ref ty) => write!(f, "{}", ty),
+            Ty::Protected(_) => write!(f, "protected"),
         }
     }
 }
 
+/// The identifier of a canonical type.
+///
+/// Equivalent types are assigned the same identifier by
+/// `ScoreContext::canonical_ty`, regardless of how they are spelled in the
+/// source code.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct TyId(pub usize);
+
 /// A type name.
 ///
 /// Generally types are named by the source file. Builtin types on the other
@@ -172,11 +184,16 @@ impl From<Name> for TyName {
 }
 
 /// An integer type.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Two integer types are equal if their ranges are equal. The base type does
+/// not take part in the comparison.
+#[derive(Debug, Clone)]
 pub struct IntTy {
     pub dir: Dir,
     pub left_bound: BigInt,
     pub right_bound: BigInt,
+    /// The base type this type constrains, if it is an anonymous subtype.
+    pub base: Option<TypeMarkRef>,
 }
 
 impl IntTy {
@@ -186,9 +203,15 @@ impl IntTy {
             dir: dir,
             left_bound: left_bound,
             right_bound: right_bound,
+            base: None,
         }
     }
 
+    /// Record the base type this type constrains.
+    pub fn with_base(self, base: Option<TypeMarkRef>) -> IntTy {
+        IntTy { base, ..self }
+    }
+
     /// Map the type to itself if the range has a positive length, or to `null`
     /// if the range has a negative or zero length.
     pub fn maybe_null(self) -> Ty {
@@ -202,12 +225,22 @@ impl IntTy {
     /// The length of the range.
     pub fn len(&self) -> BigInt {
         match self.dir {
-            Dir::To => &self.left_bound + BigInt::one() - &self.right_bound,
-            Dir::Downto => &self.right_bound + BigInt::one() - &self.left_bound,
+            Dir::To => &self.right_bound + BigInt::one() - &self.left_bound,
+            Dir::Downto => &self.left_bound + BigInt::one() - &self.right_bound,
         }
     }
 }
 
+impl PartialEq for IntTy {
+    fn eq(&self, other: &IntTy) -> bool {
+        self.dir == other.dir
+            && self.left_bound == other.left_bound
+            && self.right_bound == other.right_bound
+    }
+}
+
+impl Eq for IntTy {}
+
 impl fmt::Display for IntTy {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{} {} {}", self.left_bound, self.dir, self.right_bound)
@@ -188,8 +188,45 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     }
 
     /// Ensure that one type can be cast into the other.
+    ///
+    /// Array types are closely related if they have the same number of
+    /// dimensions and the same element type. See IEEE 1076-2008 section
+    /// 9.3.6.
     pub fn must_cast(&self, into: &'ctx Ty, from: &'ctx Ty, span: Span) -> bool {
-        self.must_match(into, from, span)
+        let (into_flat, from_flat) = match (
+            self.ctx.deref_named_type(into),
+            self.ctx.deref_named_type(from),
+        ) {
+            (Ok(i), Ok(f)) => (i, f),
+            _ => return false,
+        };
+        let (into_array, from_array) = match (into_flat, from_flat) {
+            (Ty::Array(i), Ty::Array(f)) => (i, f),
+            _ => return self.must_match(into, from, span),
+        };
+        let same_element = match (
+            self.ctx.deref_named_type(&into_array.element),
+            self.ctx.deref_named_type(&from_array.element),
+        ) {
+            (Ok(i), Ok(f)) => i == f,
+            _ => return false,
+        };
+        if same_element && into_array.indices.len() == from_array.indices.len() {
+            return true;
+        }
+        self.emit(
+            DiagBuilder2::error(format!(
+                "cannot convert `{}` of type {} to type {}",
+                span.extract(),
+                from,
+                into
+            ))
+            .span(span)
+            .add_note(format!("  target type: {}", into_flat))
+            .add_note(format!(" operand type: {}", from_flat))
+            .add_note("array types must have the same dimensions and element type"),
+        );
+        false
     }
 
     /// Type check the time expression in a delay mechanism.
@@ -228,6 +265,28 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         }
     }
 
+    /// Type check a type declaration.
+    ///
+    /// The body of a protected type does not declare a type, so only the
+    /// declarations within it are checked.
+    pub fn typeck_type_decl(&self, id: TypeDeclRef) {
+        let hir = match self.ctx.lazy_hir(id) {
+            Ok(hir) => hir,
+            Err(()) => {
+                self.failed.set(true);
+                return;
+            }
+        };
+        match hir.data.as_ref().map(|data| &data.value) {
+            Some(hir::TypeData::Protected(methods)) => {
+                self.typeck_slice(methods);
+                self.typeck(id);
+            }
+            Some(hir::TypeData::ProtectedBody(decls)) => self.typeck_slice(decls),
+            _ => self.typeck(id),
+        }
+    }
+
     /// Type check any node that can have its type calculated.
     pub fn typeck_node<I>(&self, id: I, exp: &'ctx Ty)
     where
@@ -262,13 +321,10 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Apply a range constraint to a type.
     pub fn apply_range_constraint(&self, ty: &Ty, con: Spanned<&hir::Range>) -> Result<&'ctx Ty> {
         // Determine the applied range.
-        let (dir, lb, rb) = match *con.value {
-            hir::Range::Immediate(dir, lb, rb) => (dir, lb, rb),
-        };
-        let lb = self.ctx.const_value(lb)?;
-        let rb = self.ctx.const_value(rb)?;
+        let (dir, lb, rb) = self.ctx.const_range(con)?;
 
         // Determine the inner type to which the constraint shall be applied.
+        let base = self.ctx.int_base_type(ty)?;
         let ty = self.ctx.deref_named_type(ty)?;
         match *ty {
             Ty::Int(ref ty) => {
@@ -300,9 +356,11 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                 }
 
                 // Create the new type.
-                Ok(self
-                    .ctx
-                    .intern_ty(IntTy::new(ty.dir, lb.value.clone(), rb.value.clone()).maybe_null()))
+                Ok(self.ctx.intern_ty(
+                    IntTy::new(ty.dir, lb.value.clone(), rb.value.clone())
+                        .with_base(base)
+                        .maybe_null(),
+                ))
             }
 
             // All other types we simply cannot constrain by range.
@@ -483,30 +541,15 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
         match (deref, self.ctx.deref_named_type(subty.value)?) {
             (&Ty::Int(ref ty), &Ty::Int(ref subty)) => {
                 use std::cmp::{max, min};
-                if ty.dir != subty.dir {
-                    self.emit(
-                        DiagBuilder2::error(format!(
-                            "directions disagree; `{}` and `{}`",
-                            subty, ty
-                        ))
-                        .span(span),
-                    );
-                    return Err(());
-                }
-                let (ty_lo, ty_hi, subty_lo, subty_hi) = match ty.dir {
-                    Dir::To => (
-                        &ty.left_bound,
-                        &ty.right_bound,
-                        &subty.left_bound,
-                        &subty.right_bound,
-                    ),
-                    Dir::Downto => (
-                        &ty.right_bound,
-                        &ty.left_bound,
-                        &subty.right_bound,
-                        &subty.left_bound,
-                    ),
+                // The subtype may run in the opposite direction of the type,
+                // as in `bit_vector(3 downto 0)`, so compare the bounds by
+                // their value rather than their position.
+                let bounds = |ty: &IntTy| match ty.dir {
+                    Dir::To => (ty.left_bound.clone(), ty.right_bound.clone()),
+                    Dir::Downto => (ty.right_bound.clone(), ty.left_bound.clone()),
                 };
+                let (ty_lo, ty_hi) = bounds(ty);
+                let (subty_lo, subty_hi) = bounds(subty);
                 if ty_lo > subty_lo || ty_hi < subty_hi {
                     self.emit(
                         DiagBuilder2::error(format!("`{}` is not a subrange of `{}`", subty, ty))
@@ -519,11 +562,12 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
                 }
                 let lo = max(ty_lo, subty_lo);
                 let hi = min(ty_hi, subty_hi);
-                let (lb, rb) = match ty.dir {
+                let (lb, rb) = match subty.dir {
                     Dir::To => (lo, hi),
                     Dir::Downto => (hi, lo),
                 };
-                let new_ty: Ty = IntTy::new(ty.dir, lb.clone(), rb.clone()).into();
+                let base = self.ctx.int_base_type(orig_ty)?;
+                let new_ty: Ty = IntTy::new(subty.dir, lb, rb).with_base(base).into();
                 if &new_ty == deref {
                     Ok(orig_ty)
                 } else {
@@ -558,9 +602,12 @@ impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
     /// Evaluate a range as a type.
     pub fn type_from_range(&self, range: Spanned<&hir::Range>) -> Result<&'ctx Ty> {
         match *range.value {
-            hir::Range::Immediate(dir, lb, rb) => {
-                let lb = self.ctx.const_value(lb)?;
-                let rb = self.ctx.const_value(rb)?;
+            // The range of an array's index denotes the index type itself.
+            hir::Range::Attr(prefix, attr) if attr.value == hir::RangeAttr::Range => {
+                self.ctx.range_attr_index_ty(prefix, range.span)
+            }
+            _ => {
+                let (dir, lb, rb) = self.ctx.const_range(range)?;
                 match (lb, rb) {
                     (&Const::Int(ref lb), &Const::Int(ref rb)) => Ok(self
                         .ctx
@@ -610,9 +657,10 @@ macro_rules! impl_typeck_err {
     ($slf:tt, $id:ident: $id_ty:ty => $blk:block) => {
         impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> Typeck<$id_ty> for TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
             fn typeck(&$slf, $id: $id_ty) {
-                use std;
                 let res = (move || -> Result<()> { $blk })();
-                std::mem::forget(res);
+                if res.is_err() {
+                    $slf.failed.set(true);
+                }
             }
         }
     }
@@ -715,8 +763,10 @@ impl_typeck_err!(self, id: PkgInstRef => {
     Ok(())
 });
 
-impl_typeck!(self, id: CtxRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: CtxRef => {
+    let (_, _, decl) = self.ctx.ast(id);
+    self.ctx.expand_ctx_items(&decl.items)?;
+    Ok(())
 });
 
 impl_typeck!(self, id: CfgRef => {
@@ -775,8 +825,8 @@ impl_typeck!(self, id: IntfPkgRef => {
 });
 
 impl_make!(self, id: IntfConstRef => &Ty {
-    // TODO: Implement this.
-    unimp_err!(self, id)
+    let (_, _, subty, _) = self.ast(id);
+    self.ty(subty)
 });
 
 impl_make!(self, id: IntfVarRef => &Ty {
@@ -800,7 +850,7 @@ impl_typeck!(self, id: DeclInPkgRef => {
         DeclInPkgRef::SubprogInst(id) => self.typeck(id),
         DeclInPkgRef::Pkg(id)         => self.typeck(id),
         DeclInPkgRef::PkgInst(id)     => self.typeck(id),
-        DeclInPkgRef::Type(id)        => self.typeck(id),
+        DeclInPkgRef::Type(id)        => self.typeck_type_decl(id),
         DeclInPkgRef::Subtype(id)     => self.typeck(id),
         DeclInPkgRef::Const(id)       => self.typeck(id),
         DeclInPkgRef::Signal(id)      => self.typeck(id),
@@ -824,7 +874,7 @@ impl_typeck!(self, id: DeclInPkgBodyRef => {
         DeclInPkgBodyRef::Pkg(id)         => self.typeck(id),
         DeclInPkgBodyRef::PkgBody(id)     => self.typeck(id),
         DeclInPkgBodyRef::PkgInst(id)     => self.typeck(id),
-        DeclInPkgBodyRef::Type(id)        => self.typeck(id),
+        DeclInPkgBodyRef::Type(id)        => self.typeck_type_decl(id),
         DeclInPkgBodyRef::Subtype(id)     => self.typeck(id),
         DeclInPkgBodyRef::Const(id)       => self.typeck(id),
         DeclInPkgBodyRef::Var(id)         => self.typeck(id),
@@ -845,7 +895,7 @@ impl_typeck!(self, id: DeclInSubprogRef => {
         DeclInSubprogRef::Pkg(id)         => self.typeck(id),
         DeclInSubprogRef::PkgBody(id)     => self.typeck(id),
         DeclInSubprogRef::PkgInst(id)     => self.typeck(id),
-        DeclInSubprogRef::Type(id)        => self.typeck(id),
+        DeclInSubprogRef::Type(id)        => self.typeck_type_decl(id),
         DeclInSubprogRef::Subtype(id)     => self.typeck(id),
         DeclInSubprogRef::Const(id)       => self.typeck(id),
         DeclInSubprogRef::Var(id)         => self.typeck(id),
@@ -866,7 +916,7 @@ impl_typeck!(self, id: DeclInBlockRef => {
         DeclInBlockRef::Pkg(id)         => self.typeck(id),
         DeclInBlockRef::PkgBody(id)     => self.typeck(id),
         DeclInBlockRef::PkgInst(id)     => self.typeck(id),
-        DeclInBlockRef::Type(id)        => self.typeck(id),
+        DeclInBlockRef::Type(id)        => self.typeck_type_decl(id),
         DeclInBlockRef::Subtype(id)     => self.typeck(id),
         DeclInBlockRef::Const(id)       => self.typeck(id),
         DeclInBlockRef::Signal(id)      => self.typeck(id),
@@ -891,7 +941,7 @@ impl_typeck!(self, id: DeclInProcRef => {
         DeclInProcRef::Pkg(id)         => self.typeck(id),
         DeclInProcRef::PkgBody(id)     => self.typeck(id),
         DeclInProcRef::PkgInst(id)     => self.typeck(id),
-        DeclInProcRef::Type(id)        => self.typeck(id),
+        DeclInProcRef::Type(id)        => self.typeck_type_decl(id),
         DeclInProcRef::Subtype(id)     => self.typeck(id),
         DeclInProcRef::Const(id)       => self.typeck(id),
         DeclInProcRef::Var(id)         => self.typeck(id),
@@ -912,7 +962,7 @@ impl_typeck!(self, id: ConcStmtRef => {
         ConcStmtRef::ConcAssert(id)    => self.typeck(id),
         ConcStmtRef::ConcSigAssign(id) => self.typeck(id),
         ConcStmtRef::CompInst(id)      => self.typeck(id),
-        ConcStmtRef::ForGen(id)        => self.typeck(id),
+        ConcStmtRef::ForGen(id)        => self.typeck_for_gen(id),
         ConcStmtRef::IfGen(id)         => self.typeck(id),
         ConcStmtRef::CaseGen(id)       => self.typeck(id),
     }
@@ -975,29 +1025,27 @@ impl_typeck_err!(self, id: FileDeclRef => {
     Ok(())
 });
 
-impl_typeck!(self, id: AliasDeclRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: AliasDeclRef => {
+    self.ctx.alias_defs(id)?;
+    Ok(())
 });
 
 impl_typeck!(self, id: CompDeclRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: AttrDeclRef => {
-    unimp!(self, id)
-});
+// User-defined attributes are interpreted where they take effect, such as the
+// `enum_encoding` attribute during code generation.
+impl_typeck!(self, _id: AttrDeclRef => {});
 
-impl_typeck!(self, id: AttrSpecRef => {
-    unimp!(self, id)
-});
+impl_typeck!(self, _id: AttrSpecRef => {});
 
 impl_typeck!(self, id: CfgSpecRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: DisconSpecRef => {
-    unimp!(self, id)
-});
+// Disconnection specifications are rejected during code generation.
+impl_typeck!(self, _id: DisconSpecRef => {});
 
 impl_typeck!(self, id: GroupTempRef => {
     unimp!(self, id)
@@ -1007,8 +1055,47 @@ impl_typeck!(self, id: GroupDeclRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: BlockStmtRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: BlockStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    if let Some(guard) = hir.guard {
+        let ty = self.ctx.lazy_typeval(guard)?;
+        let is_boolean = match *self.ctx.deref_named_type(ty)? {
+            Ty::Enum(ref ty) => ty.decl == BOOLEAN_TYPE.id,
+            _ => false,
+        };
+        if !is_boolean {
+            let expr: &hir::Expr = self.ctx.lazy_hir(guard)?;
+            let span = expr.span;
+            self.emit(
+                DiagBuilder2::error(format!(
+                    "guard expression `{}` must be of type `boolean`, but is `{}`",
+                    span.extract(),
+                    ty
+                ))
+                .span(span),
+            );
+            return Err(());
+        }
+    }
+    for &(formal, actual) in &hir.generic_map {
+        self.ctx.set_type_context(actual, self.ctx.ty(formal)?);
+        self.ctx.lazy_typeval(actual)?;
+    }
+    for &(formal, actual) in &hir.port_map {
+        let ty = self.ctx.ty(formal)?;
+        self.ctx.set_type_context(actual, ty);
+        let actual_ty = self.ctx.lazy_typeval(actual)?;
+        if !self.must_match(ty, actual_ty, self.ctx.span(actual).unwrap()) {
+            return Err(());
+        }
+    }
+    for &decl in &hir.decls {
+        self.typeck(decl);
+    }
+    for &stmt in &hir.stmts {
+        self.typeck(stmt);
+    }
+    Ok(())
 });
 
 impl_typeck_err!(self, id: ProcessStmtRef => {
@@ -1030,17 +1117,62 @@ impl_typeck!(self, id: ConcAssertStmtRef => {
     unimp!(self, id)
 });
 
-impl_typeck!(self, id: ConcSigAssignStmtRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: ConcSigAssignStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    self.typeck(hir.assign);
+    Ok(())
 });
 
-impl_typeck!(self, id: CompInstStmtRef => {
-    unimp!(self, id)
+impl_typeck_err!(self, id: CompInstStmtRef => {
+    let hir = self.ctx.hir(id)?;
+    for &(formal, actual) in &hir.generic_map {
+        self.ctx.set_type_context(actual, self.ctx.ty(formal)?);
+        self.ctx.lazy_typeval(actual)?;
+    }
+    // The types of the ports may depend on the generics, which are only known
+    // once the entity is specialized. Thus the actuals are not checked against
+    // the ports here.
+    for &(_, actual) in &hir.port_map {
+        self.ctx.lazy_typeval(actual)?;
+    }
+    Ok(())
 });
 
-impl_typeck!(self, id: ForGenStmtRef => {
-    unimp!(self, id)
-});
+impl<'sbc, 'lazy, 'sb, 'ast, 'ctx> TypeckContext<'sbc, 'lazy, 'sb, 'ast, 'ctx> {
+    /// Check a for-generate statement.
+    ///
+    /// The statement's `Typeck` implementation is taken by the one derived
+    /// from the type of its parameter, hence the separate function.
+    fn typeck_for_gen(&self, id: ForGenStmtRef) {
+        let res = (move || -> Result<()> {
+            let hir = self.ctx.hir(id)?;
+            let ty = self.ctx.ty(id)?;
+            match *self.ctx.deref_named_type(ty)? {
+                Ty::Int(_) | Ty::Enum(_) => (),
+                _ => {
+                    self.emit(
+                        DiagBuilder2::error(format!(
+                            "`{}` is not a discrete range",
+                            hir.range.span.extract()
+                        ))
+                        .span(hir.range.span),
+                    );
+                    return Err(());
+                }
+            }
+            for &decl in &hir.decls {
+                self.typeck(decl);
+            }
+            for &stmt in &hir.stmts {
+                self.typeck(stmt);
+            }
+            Ok(())
+        })();
+        if res.is_err() {
+            self.failed.set(true);
+        }
+    }
+}
 
 impl_typeck!(self, id: IfGenStmtRef => {
     unimp!(self, id)
@@ -1054,6 +1186,7 @@ impl_typeck_err!(self, id: SigAssignStmtRef => {
     let hir = self.ctx.hir(id)?;
     let lhs_ty = match hir.target {
         hir::SigAssignTarget::Name(sig) => self.ctx.ty(sig)?,
+        hir::SigAssignTarget::Part(_, expr) => self.ctx.lazy_typeval(expr)?,
         hir::SigAssignTarget::Aggregate => unimpmsg!(self, hir.target_span, "assignment to aggregate signal"),
     };
     // let mut ctx = TypeckContext::new(self);
@@ -1103,6 +1236,107 @@ impl<'lazy, 'sb, 'ast, 'ctx> ScoreContext<'lazy, 'sb, 'ast, 'ctx> {
             other => Ok(other),
         }
     }
+
+    /// Replace all occurrences of `Ty::Named` within a type by the actual type
+    /// definition.
+    pub fn strip_named_types(&self, ty: &Ty) -> Result<Ty> {
+        Ok(match *self.deref_named_type(ty)? {
+            Ty::Access(ref ty) => Ty::Access(Box::new(self.strip_named_types(ty)?)),
+            Ty::File(ref ty) => Ty::File(Box::new(self.strip_named_types(ty)?)),
+            Ty::Array(ref ty) => {
+                let indices = ty
+                    .indices
+                    .iter()
+                    .map(|index| {
+                        Ok(match *index {
+                            ArrayIndex::Unbounded(ref ty) => {
+                                ArrayIndex::Unbounded(Box::new(self.strip_named_types(ty)?))
+                            }
+                            ArrayIndex::Constrained(ref ty) => {
+                                ArrayIndex::Constrained(Box::new(self.strip_named_types(ty)?))
+                            }
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                let element = self.strip_named_types(&ty.element)?;
+                ArrayTy::new(indices, Box::new(element)).into()
+            }
+            Ty::Record(ref ty) => {
+                let fields = ty
+                    .fields
+                    .iter()
+                    .map(|&(name, ref ty)| Ok((name, Box::new(self.strip_named_types(ty)?))))
+                    .collect::<Result<_>>()?;
+                RecordTy::new(fields).into()
+            }
+            Ty::Subprog(ref ty) => {
+                let args = ty
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        Ok(SubprogTyArg {
+                            ty: self.strip_named_types(&arg.ty)?,
+                            name: arg.name,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                let ret = match ty.ret {
+                    Some(ref ret) => Some(self.strip_named_types(ret)?),
+                    None => None,
+                };
+                SubprogTy::new(args, ret).into()
+            }
+            ref other => other.clone(),
+        })
+    }
+
+    /// Assign a canonical identifier to a type.
+    ///
+    /// Types which are equal once all type names have been resolved share the
+    /// same identifier. For example, two subtype indications that spell out
+    /// the same constraint on the same base type canonicalize to the same id.
+    pub fn canonical_ty(&self, ty: &Ty) -> Result<TyId> {
+        let ty = self.strip_named_types(ty)?;
+        let mut tys = self.sb.canon_tys.borrow_mut();
+        if let Some(index) = tys.iter().position(|&t| *t == ty) {
+            return Ok(TyId(index));
+        }
+        tys.push(self.intern_ty(ty));
+        Ok(TyId(tys.len() - 1))
+    }
+
+    /// Get the type assigned a canonical identifier.
+    pub fn canonical_ty_def(&self, id: TyId) -> &'ctx Ty {
+        self.sb.canon_tys.borrow()[id.0]
+    }
+
+    /// Determine the type of the parameter of a for-generate statement or a
+    /// for loop, which iterates over a discrete range.
+    ///
+    /// A range of integers of no particular type iterates over `INTEGER`, as
+    /// per IEEE 1076-2008 section 5.3.2.2. A range attribute iterates over the
+    /// index type of its prefix.
+    fn param_type(&self, range: Spanned<&hir::DiscreteRange>) -> Result<&'ctx Ty> {
+        match *range.value {
+            hir::DiscreteRange::Subtype(id) => self.ty(id),
+            hir::DiscreteRange::Range(hir::Range::Attr(prefix, _)) => {
+                self.range_attr_index_ty(prefix, range.span)
+            }
+            hir::DiscreteRange::Range(hir::Range::Immediate(_, left, right)) => {
+                let ty = self.lazy_typeval(left)?;
+                match *self.deref_named_type(ty)? {
+                    Ty::UniversalInt => {
+                        let ty = self.lazy_typeval(right)?;
+                        match *self.deref_named_type(ty)? {
+                            Ty::UniversalInt => Ok(self.intern_ty(INTEGER_TYPE.named_ty())),
+                            _ => Ok(ty),
+                        }
+                    }
+                    _ => Ok(ty),
+                }
+            }
+        }
+    }
 }
 
 /// Determine the type of a type mark.
@@ -1239,6 +1473,16 @@ impl_make!(self, id: TypeDeclRef => &Ty {
             }
             Ok(self.intern_ty(RecordTy::new(mapped_fields)))
         }
+
+        hir::TypeData::Protected(..) => Ok(self.intern_ty(Ty::Protected(id))),
+
+        hir::TypeData::ProtectedBody(..) => {
+            self.emit(
+                DiagBuilder2::bug(format!("protected type body `{}` is not a type", hir.name.value))
+                .span(hir.name.span)
+            );
+            Err(())
+        }
     }
 });
 
@@ -1341,6 +1585,7 @@ impl_make!(self, id: TypedNodeRef => &Ty {
     match id {
         TypedNodeRef::SubtypeInd(id) => self.make(id),
         TypedNodeRef::Signal(id)     => self.make(id),
+        TypedNodeRef::Expr(id)       => self.lazy_typeval(id),
     }
 });
 
@@ -1348,6 +1593,22 @@ impl_make!(self, id: SignalRef => &Ty {
     match id {
         SignalRef::Intf(id) => self.make(id),
         SignalRef::Decl(id) => self.lazy_typeval(id),
+        SignalRef::Guard(_) => Ok(self.intern_ty(BOOLEAN_TYPE.named_ty())),
+    }
+});
+
+/// Determine the type of the parameter of a for-generate statement.
+impl_make!(self, id: ForGenStmtRef => &Ty {
+    let hir = self.hir(id)?;
+    self.param_type(hir.range.as_ref())
+});
+
+/// Determine the type of the parameter of a for loop.
+impl_make!(self, id: LoopStmtRef => &Ty {
+    let hir = self.lazy_hir(id)?;
+    match hir.stmt.scheme {
+        hir::LoopScheme::For(_, ref range) => self.param_type(range.as_ref()),
+        _ => unreachable!("loop without parameter"),
     }
 });
 
@@ -48,6 +48,10 @@ extract_elabs() {
 	sed -n 's#^@\s*elab\s*##p'
 }
 
+extract_args() {
+	sed -n 's#^@\s*arg\s*##p'
+}
+
 extract_output() {
 	sed -nE 's#^\|\s?##p'
 }
@@ -70,14 +74,17 @@ test_file() {
 		ARGS+=(-e $e)
 		TOPS+=($e)
 	done
+	for a in $(cat "$1" | extract_comments | extract_args); do
+		ARGS+=($a)
+	done
 	cat "$1" | extract_comments | extract_output > $TMPDIFFEXP
 	if [ ${#ARGS[@]} -gt 0 ]; then
 		LOG="$SRCFILE(${TOPS[@]})"
 		check elaborate "$LOG" $MOORE "${ARGS[@]}" $SRCFILE
 		cp $TMPOUT $TMPDIFFACT
-		# if [ -s $TMPDIFFEXP ]; then
-		# 	check codegen "$LOG" check_diff $TMPDIFFEXP $TMPDIFFACT
-		# fi
+		if [ -s $TMPDIFFEXP ]; then
+			check codegen "$LOG" check_diff $TMPDIFFEXP $TMPDIFFACT
+		fi
 	fi
 }
 
@@ -27,110 +27,132 @@ module B #(int K);
 endmodule
 
 //@ elab A
-//| entity @B.param1 () () {
-//|     %x = sig i32 61
+//@ arg -O0
+//| entity @B.param1 () -> () {
+//|     %0 = const i32 61
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param2 () () {
-//|     %x = sig i32 23
+//| entity @B.param2 () -> () {
+//|     %0 = const i32 23
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param3 () () {
-//|     %x = sig i32 0
+//| entity @B.param3 () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param4 () () {
-//|     %x = sig i32 1
+//| entity @B.param4 () -> () {
+//|     %0 = const i32 1
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param5 () () {
-//|     %x = sig i32 1
+//| entity @B.param5 () -> () {
+//|     %0 = const i32 1
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param6 () () {
-//|     %x = sig i32 1
+//| entity @B.param6 () -> () {
+//|     %0 = const i32 1
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param7 () () {
-//|     %x = sig i32 0
+//| entity @B.param7 () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param8 () () {
-//|     %x = sig i32 0
+//| entity @B.param8 () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param9 () () {
-//|     %x = sig i32 0
+//| entity @B.param9 () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param10 () () {
-//|     %x = sig i32 4294967253
+//| entity @B.param10 () -> () {
+//|     %0 = const i32 4294967253
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param11 () () {
-//|     %x = sig i32 798
+//| entity @B.param11 () -> () {
+//|     %0 = const i32 798
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param12 () () {
-//|     %x = sig i32 2
+//| entity @B.param12 () -> () {
+//|     %0 = const i32 2
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param13 () () {
-//|     %x = sig i32 4
+//| entity @B.param13 () -> () {
+//|     %0 = const i32 4
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param14 () () {
-//|     %x = sig i32 22020096
+//| entity @B.param14 () -> () {
+//|     %0 = const i32 22020096
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param15 () () {
-//|     %x = sig i32 22020096
+//| entity @B.param15 () -> () {
+//|     %0 = const i32 22020096
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param16 () () {
-//|     %x = sig i32 0
+//| entity @B.param16 () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param17 () () {
-//|     %x = sig i32 0
+//| entity @B.param17 () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param18 () () {
-//|     %x = sig i32 9001
+//| entity @B.param18 () -> () {
+//|     %0 = const i32 9001
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param19 () () {
-//|     %x = sig i32 42
+//| entity @B.param19 () -> () {
+//|     %0 = const i32 42
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param20 () () {
-//|     %x = sig i32 -42
+//| entity @B.param20 () -> () {
+//|     %0 = const i32 4294967254
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @B.param21 () () {
-//|     %x = sig i32 1332215808
+//| entity @B.param21 () -> () {
+//|     %0 = const i32 1332215808
+//|     %x = sig i32 %0
 //| }
 //|
-//| entity @A () () {
-//|     %b1 = inst @B.param1 () ()
-//|     %b2 = inst @B.param2 () ()
-//|     %b3 = inst @B.param3 () ()
-//|     %b4 = inst @B.param4 () ()
-//|     %b5 = inst @B.param5 () ()
-//|     %b6 = inst @B.param6 () ()
-//|     %b7 = inst @B.param7 () ()
-//|     %b8 = inst @B.param8 () ()
-//|     %b9 = inst @B.param9 () ()
-//|     %b10 = inst @B.param10 () ()
-//|     %b11 = inst @B.param11 () ()
-//|     %b12 = inst @B.param12 () ()
-//|     %b13 = inst @B.param13 () ()
-//|     %b14 = inst @B.param14 () ()
-//|     %b15 = inst @B.param15 () ()
-//|     %b16 = inst @B.param16 () ()
-//|     %b17 = inst @B.param17 () ()
-//|     %b18 = inst @B.param18 () ()
-//|     %b19 = inst @B.param19 () ()
-//|     %b20 = inst @B.param20 () ()
-//|     %b21 = inst @B.param21 () ()
+//| entity @A () -> () {
+//|     inst @B.param1 () -> ()
+//|     inst @B.param2 () -> ()
+//|     inst @B.param3 () -> ()
+//|     inst @B.param4 () -> ()
+//|     inst @B.param5 () -> ()
+//|     inst @B.param6 () -> ()
+//|     inst @B.param7 () -> ()
+//|     inst @B.param8 () -> ()
+//|     inst @B.param9 () -> ()
+//|     inst @B.param10 () -> ()
+//|     inst @B.param11 () -> ()
+//|     inst @B.param12 () -> ()
+//|     inst @B.param13 () -> ()
+//|     inst @B.param14 () -> ()
+//|     inst @B.param15 () -> ()
+//|     inst @B.param16 () -> ()
+//|     inst @B.param17 () -> ()
+//|     inst @B.param18 () -> ()
+//|     inst @B.param19 () -> ()
+//|     inst @B.param20 () -> ()
+//|     inst @B.param21 () -> ()
 //| }
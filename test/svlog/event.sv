@@ -4,40 +4,47 @@ module A;
 endmodule
 
 //@ elab A
-//| proc @A.always_ff.227.0 (i1$ %0, i1$ %1, i1$ %2, i1$ %3) () {
-//| %4:
-//|     br label %init
-//| %init:
-//|     %a = prb %0
-//|     %b = prb %1
-//|     %c = prb %2
-//|     wait %check, %0, %1, %2
-//| %check:
-//|     %a0 = prb %0
-//|     %impledge = cmp neq i1 %a %a0
-//|     %b0 = prb %1
-//|     %7 = cmp eq i1 %b 0
-//|     %8 = cmp neq i1 %b0 0
-//|     %posedge = and i1 %7 %8
-//|     %event_or = or i1 %impledge %posedge
-//|     %c0 = prb %2
-//|     %9 = cmp eq i1 %c0 0
-//|     %10 = cmp neq i1 %c 0
-//|     %negedge = and i1 %9 %10
-//|     %d = prb %3
-//|     %iff = and i1 %negedge %d
-//|     %event_or0 = or i1 %event_or %iff
-//|     br %event_or0 label %event %init
-//| %event:
-//|     br label %4
+//@ arg -O0
+//| proc %A.always_ff.22.0 (i1$ %a, i1$ %b, i1$ %c, i1$ %d) -> () {
+//| 0:
+//|     br %init
+//| init:
+//|     %a.prb = prb i1$ %a
+//|     %b.prb = prb i1$ %b
+//|     %c.prb = prb i1$ %c
+//|     wait %check, %a, %b, %c
+//| check:
+//|     %a.prb1 = prb i1$ %a
+//|     %impledge = neq i1 %a.prb, %a.prb1
+//|     %b.prb1 = prb i1$ %b
+//|     %1 = const i1 0
+//|     %2 = eq i1 %b.prb, %1
+//|     %3 = neq i1 %b.prb1, %1
+//|     %posedge = and i1 %2, %3
+//|     %event_or = or i1 %impledge, %posedge
+//|     %c.prb1 = prb i1$ %c
+//|     %4 = const i1 0
+//|     %5 = neq i1 %c.prb, %4
+//|     %6 = eq i1 %c.prb1, %4
+//|     %negedge = and i1 %6, %5
+//|     %d.prb = prb i1$ %d
+//|     %iff = and i1 %negedge, %d.prb
+//|     %event_or1 = or i1 %event_or, %iff
+//|     br %event_or1, %init, %event
+//| event:
+//|     br %0
 //| }
 //|
-//| entity @A () () {
-//|     %a = sig i1
-//|     %b = sig i1
-//|     %c = sig i1
-//|     %d = sig i1
-//|     inst @A.always_ff.227.0 (%a, %b, %c, %d) ()
+//| entity @A () -> () {
+//|     %0 = const i1 0
+//|     %a = sig i1 %0
+//|     %1 = const i1 0
+//|     %b = sig i1 %1
+//|     %2 = const i1 0
+//|     %c = sig i1 %2
+//|     %3 = const i1 0
+//|     %d = sig i1 %3
+//|     inst %A.always_ff.22.0 (i1$ %a, i1$ %b, i1$ %c, i1$ %d) -> ()
 //| }
 
 module B;
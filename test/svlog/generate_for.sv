@@ -7,12 +7,13 @@ module B;
 endmodule
 
 //@ elab A
-//| entity @B () () {
+//@ arg -O0
+//| entity @B.param1 () -> () {
 //| }
 //|
-//| entity @A () () {
-//|     %b = inst @B () ()
-//|     %b0 = inst @B () ()
-//|     %b1 = inst @B () ()
-//|     %b2 = inst @B () ()
+//| entity @A () -> () {
+//|     inst @B.param1 () -> ()
+//|     inst @B.param1 () -> ()
+//|     inst @B.param1 () -> ()
+//|     inst @B.param1 () -> ()
 //| }
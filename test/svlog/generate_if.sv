@@ -12,33 +12,36 @@ module B #(int K);
 endmodule
 
 //@ elab A
-//| proc @B.param1.initial.235.1 () (i32$ %0) {
-//| %1:
-//|     drv %0 19 0s 1e
-//|     wait %4 for 0s 1e
-//| %4:
+//@ arg -O0
+//| proc %B.param1.initial.64.1 () -> (i32$ %x) {
+//| 0:
+//|     %1 = const i32 19
+//|     %2 = const time 0s 1e
+//|     drv i32$ %x, %1, %2
 //|     halt
 //| }
 //|
-//| entity @B.param1 () () {
-//|     %x = sig i32 11
-//|     inst @B.param1.initial.235.1 () (%x)
+//| entity @B.param1 () -> () {
+//|     %0 = const i32 11
+//|     %x = sig i32 %0
+//|     inst %B.param1.initial.64.1 () -> (i32$ %x)
 //| }
 //|
-//| proc @B.param2.initial.234.2 () (i32$ %0) {
-//| %1:
-//|     drv %0 42 0s 1e
-//|     wait %4 for 0s 1e
-//| %4:
+//| proc %B.param2.initial.51.2 () -> (i32$ %x) {
+//| 0:
+//|     %1 = const i32 42
+//|     %2 = const time 0s 1e
+//|     drv i32$ %x, %1, %2
 //|     halt
 //| }
 //|
-//| entity @B.param2 () () {
-//|     %x = sig i32 13
-//|     inst @B.param2.initial.234.2 () (%x)
+//| entity @B.param2 () -> () {
+//|     %0 = const i32 13
+//|     %x = sig i32 %0
+//|     inst %B.param2.initial.51.2 () -> (i32$ %x)
 //| }
 //|
-//| entity @A () () {
-//|     %b1 = inst @B.param1 () ()
-//|     %b2 = inst @B.param2 () ()
+//| entity @A () -> () {
+//|     inst @B.param1 () -> ()
+//|     inst @B.param2 () -> ()
 //| }
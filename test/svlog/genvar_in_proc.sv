@@ -17,39 +17,40 @@ module A #(int K = 42);
 endmodule
 
 //@ elab A
-//| proc @A.initial.228.0 () () {
-//| %0:
-//|     %v = var i32
-//|     store i32 %v 0
-//|     %2 = add i32 0 0
-//|     store i32 %v %2
+//@ arg -O0
+//| proc %A.initial.109.0 () -> () {
+//| 0:
+//|     %1 = const i32 0
+//|     %v = var i32 %1
+//|     %2 = const i32 0
+//|     st i32* %v, %2
 //|     halt
 //| }
 //|
-//| proc @A.initial.228.1 () () {
-//| %0:
-//|     %v = var i32
-//|     store i32 %v 1
-//|     %2 = add i32 0 1
-//|     store i32 %v %2
+//| proc %A.initial.109.1 () -> () {
+//| 0:
+//|     %1 = const i32 1
+//|     %v = var i32 %1
+//|     %2 = const i32 1
+//|     st i32* %v, %2
 //|     halt
 //| }
 //|
-//| proc @A.initial.223.0 () () {
-//| %0:
-//|     %v = var i32
-//|     store i32 %v 42
-//|     %2 = add i32 0 42
-//|     store i32 %v %2
-//|     %w = var i32
-//|     store i32 %w 9001
-//|     %5 = add i32 0 9001
-//|     store i32 %w %5
+//| proc %A.initial.68.0 () -> () {
+//| 0:
+//|     %1 = const i32 42
+//|     %v = var i32 %1
+//|     %2 = const i32 42
+//|     st i32* %v, %2
+//|     %3 = const i32 9001
+//|     %w = var i32 %3
+//|     %4 = const i32 9001
+//|     st i32* %w, %4
 //|     halt
 //| }
 //|
-//| entity @A () () {
-//|     inst @A.initial.228.0 () ()
-//|     inst @A.initial.228.1 () ()
-//|     inst @A.initial.223.0 () ()
+//| entity @A () -> () {
+//|     inst %A.initial.109.0 () -> ()
+//|     inst %A.initial.109.1 () -> ()
+//|     inst %A.initial.68.0 () -> ()
 //| }
@@ -4,26 +4,35 @@ module A;
 endmodule
 
 //@ elab A
-//| proc @A.initial.223.0 (i32$ %0) (i32$ %1) {
-//| %2:
-//|     %a = prb %1
-//|     %3 = cmp eq i32 %a 42
-//|     br %3 label %if_true %if_false
-//| %if_true:
-//|     drv %1 16 0s 1e
-//|     wait %7 for 0s 1e
-//| %if_false:
-//|     drv %1 9001 0s 1e
-//|     wait %10 for 0s 1e
-//| %if_exit:
+//@ arg -O0
+//| proc %A.initial.31.0 () -> (i32$ %a) {
+//| 0:
+//|     %1 = prb i32$ %a
+//|     %a.shadow = var i32 %1
+//|     %a.shadow.ld = ld i32* %a.shadow
+//|     %2 = const i32 42
+//|     %3 = eq i32 %a.shadow.ld, %2
+//|     %4 = const i1 0
+//|     %5 = neq i1 %3, %4
+//|     br %5, %if_false, %if_true
+//| if_true:
+//|     %6 = const i32 16
+//|     %7 = const time 0s 1e
+//|     drv i32$ %a, %6, %7
+//|     st i32* %a.shadow, %6
+//|     br %if_exit
+//| if_false:
+//|     %8 = const i32 9001
+//|     %9 = const time 0s 1e
+//|     drv i32$ %a, %8, %9
+//|     st i32* %a.shadow, %8
+//|     br %if_exit
+//| if_exit:
 //|     halt
-//| %7:
-//|     br label %if_exit
-//| %10:
-//|     br label %if_exit
 //| }
 //|
-//| entity @A () () {
-//|     %a = sig i32
-//|     inst @A.initial.223.0 (%a) (%a)
+//| entity @A () -> () {
+//|     %0 = const i32 0
+//|     %a = sig i32 %0
+//|     inst %A.initial.31.0 () -> (i32$ %a)
 //| }
@@ -12,15 +12,26 @@ module A;
 endmodule
 
 //@ elab A
-//| entity @A () () {
-//|     %k0 = sig i1 0
-//|     %k1 = sig i1 1
-//|     %k2 = sig i2 3
-//|     %k3 = sig i6 39
-//|     %k4 = sig i9 511
-//|     %k5 = sig i6 42
-//|     %k6 = sig i32 3
-//|     %k7 = sig i64 39
-//|     %k8 = sig i16 511
-//|     %k9 = sig i9001 42
+//@ arg -O0
+//| entity @A () -> () {
+//|     %0 = const i1 0
+//|     %k0 = sig i1 %0
+//|     %1 = const i1 1
+//|     %k1 = sig i1 %1
+//|     %2 = const i2 3
+//|     %k2 = sig i2 %2
+//|     %3 = const i6 39
+//|     %k3 = sig i6 %3
+//|     %4 = const i9 511
+//|     %k4 = sig i9 %4
+//|     %5 = const i6 42
+//|     %k5 = sig i6 %5
+//|     %6 = const i32 3
+//|     %k6 = sig i32 %6
+//|     %7 = const i64 39
+//|     %k7 = sig i64 %7
+//|     %8 = const i16 511
+//|     %k8 = sig i16 %8
+//|     %9 = const i9001 42
+//|     %k9 = sig i9001 %9
 //| }
@@ -13,19 +13,26 @@ module B;
 endmodule
 
 //@ elab A
-//| entity @A () () {
-//|     %a = sig i32
-//|     %b = sig i32 42
-//|     %c = sig i32 9001
+//@ arg -O0
+//| entity @A () -> () {
+//|     %0 = const i32 0
+//|     %a = sig i32 %0
+//|     %1 = const i32 42
+//|     %b = sig i32 %1
+//|     %2 = const i32 9001
+//|     %c = sig i32 %2
 //| }
 
 //@ elab B
-//| entity @A.param1 () () {
-//|     %a = sig i32
-//|     %b = sig i32 42
-//|     %c = sig i32 1
+//| entity @A.param1 () -> () {
+//|     %0 = const i32 0
+//|     %a = sig i32 %0
+//|     %1 = const i32 42
+//|     %b = sig i32 %1
+//|     %2 = const i32 1
+//|     %c = sig i32 %2
 //| }
 //|
-//| entity @B () () {
-//|     %a1 = inst @A.param1 () ()
+//| entity @B () -> () {
+//|     inst @A.param1 () -> ()
 //| }
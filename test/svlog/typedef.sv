@@ -8,8 +8,12 @@ module A;
 endmodule
 
 //@ elab A
-//| entity @A () () {
-//|     %x = sig i32
-//|     %y = sig i1
-//|     %z = sig i32
+//@ arg -O0
+//| entity @A () -> () {
+//|     %0 = const i32 0
+//|     %x = sig i32 %0
+//|     %1 = const i1 0
+//|     %y = sig i1 %1
+//|     %2 = const i32 0
+//|     %z = sig i32 %2
 //| }
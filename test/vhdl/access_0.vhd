@@ -0,0 +1,40 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type int_ptr is access integer;
+	signal q : integer;
+begin
+	p : process
+		variable v : int_ptr := new integer'(5);
+	begin
+		q <= v.all;
+		v.all := 7;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i32 2147483648
+--|     %q = sig i32 %0
+--|     inst @work.foo.bar.p () -> (i32$ %q)
+--| }
+--|
+--| proc @work.foo.bar.p () -> (i32$ %q) {
+--| entry:
+--|     %0 = const i32 5
+--|     %1 = var i32 %0
+--|     %v = var i32* %1
+--|     br %body
+--| body:
+--|     %2 = ld i32** %v
+--|     %3 = ld i32* %2
+--|     %4 = const time 0s 1d
+--|     drv i32$ %q, %3, %4
+--|     %5 = ld i32** %v
+--|     %6 = const i32 7
+--|     st i32* %5, %6
+--|     br %body
+--| }
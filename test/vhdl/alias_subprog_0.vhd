@@ -0,0 +1,52 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type state is (idle, busy);
+	signal a, q : boolean;
+	signal s : state;
+	function inv (x : boolean) return boolean;
+	function inv (x : boolean) return boolean is
+	begin
+		return not x;
+	end;
+	alias flip is inv [boolean return boolean];
+	alias rest is idle [return state];
+begin
+	p : process (a)
+	begin
+		q <= flip(a);
+		s <= rest;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %q = sig i1 %1
+--|     %2 = const i1 0
+--|     %s = sig i1 %2
+--|     inst @work.foo.bar.p (i1$ %a) -> (i1$ %q, i1$ %s)
+--| }
+--|
+--| func @work.foo.bar.inv (i1 %x) i1 {
+--| entry:
+--|     %0 = not i1 %x
+--|     ret i1 %0
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a) -> (i1$ %q, i1$ %s) {
+--| entry:
+--|     %0 = prb i1$ %a
+--|     %1 = call i1 @work.foo.bar.inv (i1 %0)
+--|     %2 = const time 0s 1d
+--|     drv i1$ %q, %1, %2
+--|     %3 = const i1 0
+--|     %4 = const time 0s 1d
+--|     drv i1$ %s, %3, %4
+--|     wait %entry, %a
+--| }
@@ -0,0 +1,57 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type mat is array (0 to 3, 0 to 7) of bit;
+	signal m : mat;
+	signal i : integer range 0 to 3;
+	signal j : integer range 0 to 7;
+	signal q, r : bit;
+begin
+	p : process (m, i, j)
+	begin
+		q <= m(i, j);
+		r <= m(2, 5);
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %1 = [32 x i1 %0]
+--|     %m = sig [32 x i1] %1
+--|     %2 = const i2 0
+--|     %i = sig i2 %2
+--|     %3 = const i3 0
+--|     %j = sig i3 %3
+--|     %4 = const i1 0
+--|     %q = sig i1 %4
+--|     %5 = const i1 0
+--|     %r = sig i1 %5
+--|     inst @work.foo.bar.p ([32 x i1]$ %m, i2$ %i, i3$ %j) -> (i1$ %q, i1$ %r)
+--| }
+--|
+--| proc @work.foo.bar.p ([32 x i1]$ %m, i2$ %i, i3$ %j) -> (i1$ %q, i1$ %r) {
+--| entry:
+--|     %0 = prb [32 x i1]$ %m
+--|     %1 = prb i2$ %i
+--|     %2 = const i5 0
+--|     %3 = inss i5 %2, i2 %1, 0, 2
+--|     %4 = prb i3$ %j
+--|     %5 = const i5 0
+--|     %6 = inss i5 %5, i3 %4, 0, 3
+--|     %7 = const i5 8
+--|     %8 = umul i5 %3, %7
+--|     %9 = add i5 %8, %6
+--|     %10 = shr [32 x i1] %0, [32 x i1] %0, i5 %9
+--|     %11 = extf i1, [32 x i1] %10, 0
+--|     %12 = const time 0s 1d
+--|     drv i1$ %q, %11, %12
+--|     %13 = prb [32 x i1]$ %m
+--|     %14 = extf i1, [32 x i1] %13, 21
+--|     %15 = const time 0s 1d
+--|     drv i1$ %r, %14, %15
+--|     wait %entry, %m, %i, %j
+--| }
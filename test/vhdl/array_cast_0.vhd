@@ -0,0 +1,38 @@
+package p is
+	type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
+	type std_logic_vector is array (natural range <>) of std_ulogic;
+	type unsigned is array (natural range <>) of std_ulogic;
+end;
+
+use work.p.all;
+
+entity foo is
+end;
+
+architecture bar of foo is
+	signal slv : std_logic_vector(7 downto 0);
+	signal u : unsigned(7 downto 0);
+begin
+	u <= unsigned(slv);
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 0
+--|     %1 = [8 x i4 %0]
+--|     %slv = sig [8 x i4] %1
+--|     %2 = const i4 0
+--|     %3 = [8 x i4 %2]
+--|     %u = sig [8 x i4] %3
+--|     inst @work.foo.bar.assign0 ([8 x i4]$ %slv) -> ([8 x i4]$ %u)
+--| }
+--|
+--| proc @work.foo.bar.assign0 ([8 x i4]$ %slv) -> ([8 x i4]$ %u) {
+--| entry:
+--|     %0 = prb [8 x i4]$ %slv
+--|     %1 = const time 0s 1d
+--|     drv [8 x i4]$ %u, %0, %1
+--|     wait %entry, %slv
+--| }
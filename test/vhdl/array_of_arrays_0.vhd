@@ -0,0 +1,104 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type row is array (0 to 7) of bit;
+	type mat is array (0 to 3) of row;
+	signal m : mat := (0 => (others => '1'), others => (others => '0'));
+	signal n : mat;
+	signal i : integer range 0 to 3;
+	signal j : integer range 0 to 7;
+	signal q : bit;
+begin
+	p : process (m, i, j)
+	begin
+		q <= m(i)(j);
+	end process;
+
+	n <= (1 => (others => '1'), others => (0 => '1', others => '0'));
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 1
+--|     %1 = const i1 1
+--|     %2 = const i1 1
+--|     %3 = const i1 1
+--|     %4 = const i1 1
+--|     %5 = const i1 1
+--|     %6 = const i1 1
+--|     %7 = const i1 1
+--|     %8 = [i1 %0, %1, %2, %3, %4, %5, %6, %7]
+--|     %9 = const i1 0
+--|     %10 = const i1 0
+--|     %11 = const i1 0
+--|     %12 = const i1 0
+--|     %13 = const i1 0
+--|     %14 = const i1 0
+--|     %15 = const i1 0
+--|     %16 = const i1 0
+--|     %17 = [i1 %9, %10, %11, %12, %13, %14, %15, %16]
+--|     %18 = const i1 0
+--|     %19 = const i1 0
+--|     %20 = const i1 0
+--|     %21 = const i1 0
+--|     %22 = const i1 0
+--|     %23 = const i1 0
+--|     %24 = const i1 0
+--|     %25 = const i1 0
+--|     %26 = [i1 %18, %19, %20, %21, %22, %23, %24, %25]
+--|     %27 = const i1 0
+--|     %28 = const i1 0
+--|     %29 = const i1 0
+--|     %30 = const i1 0
+--|     %31 = const i1 0
+--|     %32 = const i1 0
+--|     %33 = const i1 0
+--|     %34 = const i1 0
+--|     %35 = [i1 %27, %28, %29, %30, %31, %32, %33, %34]
+--|     %36 = [[8 x i1] %8, %17, %26, %35]
+--|     %m = sig [4 x [8 x i1]] %36
+--|     %37 = const time 0s
+--|     drv [4 x [8 x i1]]$ %m, %36, %37
+--|     %38 = const i1 0
+--|     %39 = [8 x i1 %38]
+--|     %40 = [4 x [8 x i1] %39]
+--|     %n = sig [4 x [8 x i1]] %40
+--|     %41 = const i2 0
+--|     %i = sig i2 %41
+--|     %42 = const i3 0
+--|     %j = sig i3 %42
+--|     %43 = const i1 0
+--|     %q = sig i1 %43
+--|     inst @work.foo.bar.p ([4 x [8 x i1]]$ %m, i2$ %i, i3$ %j) -> (i1$ %q)
+--|     inst @work.foo.bar.assign1 () -> ([4 x [8 x i1]]$ %n)
+--| }
+--|
+--| proc @work.foo.bar.assign1 () -> ([4 x [8 x i1]]$ %n) {
+--| entry:
+--|     %0 = const i1 1
+--|     %1 = [i1 %0, %0, %0, %0, %0, %0, %0, %0]
+--|     %2 = const i1 1
+--|     %3 = const i1 0
+--|     %4 = [i1 %2, %3, %3, %3, %3, %3, %3, %3]
+--|     %5 = [[8 x i1] %4, %1, %4, %4]
+--|     %6 = const time 0s 1d
+--|     drv [4 x [8 x i1]]$ %n, %5, %6
+--|     halt
+--| }
+--|
+--| proc @work.foo.bar.p ([4 x [8 x i1]]$ %m, i2$ %i, i3$ %j) -> (i1$ %q) {
+--| entry:
+--|     %0 = prb [4 x [8 x i1]]$ %m
+--|     %1 = prb i2$ %i
+--|     %2 = shr [4 x [8 x i1]] %0, [4 x [8 x i1]] %0, i2 %1
+--|     %3 = extf [8 x i1], [4 x [8 x i1]] %2, 0
+--|     %4 = prb i3$ %j
+--|     %5 = shr [8 x i1] %3, [8 x i1] %3, i3 %4
+--|     %6 = extf i1, [8 x i1] %5, 0
+--|     %7 = const time 0s 1d
+--|     drv i1$ %q, %6, %7
+--|     wait %entry, %m, %i, %j
+--| }
@@ -0,0 +1,32 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal x, y : bit;
+begin
+	b : block
+		port (i : in bit; o : out bit);
+		port map (i => x, o => y);
+	begin
+		o <= i;
+	end block;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %x = sig i1 %0
+--|     %1 = const i1 0
+--|     %y = sig i1 %1
+--|     inst @work.foo.bar.b.assign0 (i1$ %x) -> (i1$ %y)
+--| }
+--|
+--| proc @work.foo.bar.b.assign0 (i1$ %i) -> (i1$ %o) {
+--| entry:
+--|     %0 = prb i1$ %i
+--|     %1 = const time 0s 1d
+--|     drv i1$ %o, %0, %1
+--|     wait %entry, %i
+--| }
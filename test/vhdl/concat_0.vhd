@@ -0,0 +1,54 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b : bit_vector(0 to 1);
+	signal c : bit_vector(0 to 3);
+	signal d : bit_vector(0 to 2);
+begin
+	p : process
+	begin
+		c <= a & b;
+		d <= a & '1';
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %1 = [2 x i1 %0]
+--|     %a = sig [2 x i1] %1
+--|     %2 = const i1 0
+--|     %3 = [2 x i1 %2]
+--|     %b = sig [2 x i1] %3
+--|     %4 = const i1 0
+--|     %5 = [4 x i1 %4]
+--|     %c = sig [4 x i1] %5
+--|     %6 = const i1 0
+--|     %7 = [3 x i1 %6]
+--|     %d = sig [3 x i1] %7
+--|     inst @work.foo.bar.p ([2 x i1]$ %a, [2 x i1]$ %b) -> ([4 x i1]$ %c, [3 x i1]$ %d)
+--| }
+--|
+--| proc @work.foo.bar.p ([2 x i1]$ %a, [2 x i1]$ %b) -> ([4 x i1]$ %c, [3 x i1]$ %d) {
+--| entry:
+--|     %0 = prb [2 x i1]$ %a
+--|     %1 = prb [2 x i1]$ %b
+--|     %2 = extf i1, [2 x i1] %0, 0
+--|     %3 = extf i1, [2 x i1] %0, 1
+--|     %4 = extf i1, [2 x i1] %1, 0
+--|     %5 = extf i1, [2 x i1] %1, 1
+--|     %6 = [i1 %2, %3, %4, %5]
+--|     %7 = const time 0s 1d
+--|     drv [4 x i1]$ %c, %6, %7
+--|     %8 = prb [2 x i1]$ %a
+--|     %9 = const i1 1
+--|     %10 = extf i1, [2 x i1] %8, 0
+--|     %11 = extf i1, [2 x i1] %8, 1
+--|     %12 = [i1 %10, %11, %9]
+--|     %13 = const time 0s 1d
+--|     drv [3 x i1]$ %d, %12, %13
+--|     br %entry
+--| }
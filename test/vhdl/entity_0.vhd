@@ -5,5 +5,5 @@ architecture bar of foo is
 begin
 end;
 
--- entity @foo_bar () () {
+-- entity @work.foo.bar () () {
 -- }
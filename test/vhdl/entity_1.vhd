@@ -13,5 +13,5 @@ architecture bar of foo is
 begin
 end;
 
--- entity @foo_bar (i32 %A, i32 %D) (i32 %B, i32 %C, i32 %D0, i32 %E) {
+-- entity @work.foo.bar (i32 %A, i32 %D) (i32 %B, i32 %C, i32 %D0, i32 %E) {
 -- }
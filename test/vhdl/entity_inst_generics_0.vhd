@@ -0,0 +1,49 @@
+entity reg is
+	generic (W : natural := 1);
+	port (d : in bit_vector(1 to W); q : out bit_vector(1 to W));
+end;
+
+architecture rtl of reg is
+begin
+end;
+
+library work;
+
+entity top is
+end;
+
+library work;
+
+architecture tb of top is
+	signal a, b : bit_vector(1 to 4);
+	signal c, d : bit_vector(1 to 8);
+begin
+	u0 : entity work.reg(rtl) generic map (W => 4) port map (d => a, q => b);
+	u1 : entity work.reg(rtl) generic map (8) port map (c, d);
+end;
+
+--@ elab top(tb)
+--@ arg -O0
+
+--| entity @work.reg.rtl.param0 ([4 x i1]$ %d) -> ([4 x i1]$ %q) {
+--| }
+--|
+--| entity @work.reg.rtl.param1 ([8 x i1]$ %d) -> ([8 x i1]$ %q) {
+--| }
+--|
+--| entity @work.top.tb () -> () {
+--|     %0 = const i1 0
+--|     %1 = [4 x i1 %0]
+--|     %a = sig [4 x i1] %1
+--|     %2 = const i1 0
+--|     %3 = [4 x i1 %2]
+--|     %b = sig [4 x i1] %3
+--|     %4 = const i1 0
+--|     %5 = [8 x i1 %4]
+--|     %c = sig [8 x i1] %5
+--|     %6 = const i1 0
+--|     %7 = [8 x i1 %6]
+--|     %d = sig [8 x i1] %7
+--|     inst @work.reg.rtl.param0 ([4 x i1]$ %a) -> ([4 x i1]$ %b)
+--|     inst @work.reg.rtl.param1 ([8 x i1]$ %c) -> ([8 x i1]$ %d)
+--| }
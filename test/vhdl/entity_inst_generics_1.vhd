@@ -0,0 +1,41 @@
+entity reg is
+	generic (N : positive := 1);
+	port (d : in bit_vector(N-1 downto 0); q : out bit_vector(N-1 downto 0));
+end;
+
+architecture rtl of reg is
+	signal s : bit_vector(N-1 downto 0);
+begin
+end;
+
+library work;
+
+entity top is
+end;
+
+library work;
+
+architecture tb of top is
+	signal a, b : bit_vector(15 downto 0);
+begin
+	u0 : entity work.reg(rtl) generic map (N => 16) port map (d => a, q => b);
+end;
+
+--@ elab top(tb)
+--@ arg -O0
+
+--| entity @work.reg.rtl.param0 ([16 x i1]$ %d) -> ([16 x i1]$ %q) {
+--|     %0 = const i1 0
+--|     %1 = [16 x i1 %0]
+--|     %s = sig [16 x i1] %1
+--| }
+--|
+--| entity @work.top.tb () -> () {
+--|     %0 = const i1 0
+--|     %1 = [16 x i1 %0]
+--|     %a = sig [16 x i1] %1
+--|     %2 = const i1 0
+--|     %3 = [16 x i1 %2]
+--|     %b = sig [16 x i1] %3
+--|     inst @work.reg.rtl.param0 ([16 x i1]$ %a) -> ([16 x i1]$ %b)
+--| }
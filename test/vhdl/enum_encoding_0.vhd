@@ -0,0 +1,28 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type state_t is (idle, run, done, fault);
+	attribute enum_encoding : string;
+	attribute enum_encoding of state_t : type is "0001 0010 0100 1000";
+	signal s : state_t;
+begin
+	s <= done;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 1
+--|     %s = sig i4 %0
+--|     inst @work.foo.bar.assign0 () -> (i4$ %s)
+--| }
+--|
+--| proc @work.foo.bar.assign0 () -> (i4$ %s) {
+--| entry:
+--|     %0 = const i4 4
+--|     %1 = const time 0s 1d
+--|     drv i4$ %s, %0, %1
+--|     halt
+--| }
@@ -0,0 +1,35 @@
+package pkg is
+	type state_t is (idle, run, done, fault);
+	attribute enum_encoding : string;
+	attribute enum_encoding of state_t : type is "0001 0010 0100 1000";
+end;
+
+library work;
+use work.pkg.all;
+entity foo is
+end;
+
+architecture bar of foo is
+	signal s, t : state_t;
+begin
+	t <= s;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 1
+--|     %s = sig i4 %0
+--|     %1 = const i4 1
+--|     %t = sig i4 %1
+--|     inst @work.foo.bar.assign0 (i4$ %s) -> (i4$ %t)
+--| }
+--|
+--| proc @work.foo.bar.assign0 (i4$ %s) -> (i4$ %t) {
+--| entry:
+--|     %0 = prb i4$ %s
+--|     %1 = const time 0s 1d
+--|     drv i4$ %t, %0, %1
+--|     wait %entry, %s
+--| }
@@ -0,0 +1,57 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type color is (red, green, blue);
+	type color_bits is array (color) of bit;
+	signal a, b : color_bits;
+begin
+	g : for c in color generate
+		b(c) <= a(c);
+	end generate;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %1 = [3 x i1 %0]
+--|     %a = sig [3 x i1] %1
+--|     %2 = const i1 0
+--|     %3 = [3 x i1 %2]
+--|     %b = sig [3 x i1] %3
+--|     inst @work.foo.bar.g.0.assign0 ([3 x i1]$ %a) -> ([3 x i1]$ %b)
+--|     inst @work.foo.bar.g.1.assign0 ([3 x i1]$ %a) -> ([3 x i1]$ %b)
+--|     inst @work.foo.bar.g.2.assign0 ([3 x i1]$ %a) -> ([3 x i1]$ %b)
+--| }
+--|
+--| proc @work.foo.bar.g.0.assign0 ([3 x i1]$ %a) -> ([3 x i1]$ %b) {
+--| entry:
+--|     %0 = extf i1$, [3 x i1]$ %b, 0
+--|     %1 = prb [3 x i1]$ %a
+--|     %2 = extf i1, [3 x i1] %1, 0
+--|     %3 = const time 0s 1d
+--|     drv i1$ %0, %2, %3
+--|     wait %entry, %a
+--| }
+--|
+--| proc @work.foo.bar.g.1.assign0 ([3 x i1]$ %a) -> ([3 x i1]$ %b) {
+--| entry:
+--|     %0 = extf i1$, [3 x i1]$ %b, 1
+--|     %1 = prb [3 x i1]$ %a
+--|     %2 = extf i1, [3 x i1] %1, 1
+--|     %3 = const time 0s 1d
+--|     drv i1$ %0, %2, %3
+--|     wait %entry, %a
+--| }
+--|
+--| proc @work.foo.bar.g.2.assign0 ([3 x i1]$ %a) -> ([3 x i1]$ %b) {
+--| entry:
+--|     %0 = extf i1$, [3 x i1]$ %b, 2
+--|     %1 = prb [3 x i1]$ %a
+--|     %2 = extf i1, [3 x i1] %1, 2
+--|     %3 = const time 0s 1d
+--|     drv i1$ %0, %2, %3
+--|     wait %entry, %a
+--| }
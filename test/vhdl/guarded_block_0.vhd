@@ -0,0 +1,50 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal clk, d, q : bit;
+begin
+	b : block (clk = '1')
+	begin
+		q <= guarded d;
+	end block;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %clk = sig i1 %0
+--|     %1 = const i1 0
+--|     %d = sig i1 %1
+--|     %2 = const i1 0
+--|     %q = sig i1 %2
+--|     %3 = const i1 0
+--|     %GUARD = sig i1 %3
+--|     inst @work.foo.bar.b.guard (i1$ %clk) -> (i1$ %GUARD)
+--|     inst @work.foo.bar.b.assign0 (i1$ %d, i1$ %GUARD) -> (i1$ %q)
+--| }
+--|
+--| proc @work.foo.bar.b.assign0 (i1$ %d, i1$ %GUARD) -> (i1$ %q) {
+--| entry:
+--|     %0 = prb i1$ %GUARD
+--|     br %0, %exit, %assign
+--| assign:
+--|     %1 = prb i1$ %d
+--|     %2 = const time 0s 1d
+--|     drv i1$ %q, %1, %2
+--|     br %exit
+--| exit:
+--|     wait %entry, %d, %GUARD
+--| }
+--|
+--| proc @work.foo.bar.b.guard (i1$ %clk) -> (i1$ %GUARD) {
+--| entry:
+--|     %0 = prb i1$ %clk
+--|     %1 = const i1 1
+--|     %2 = eq i1 %0, %1
+--|     %3 = const time 0s 1d
+--|     drv i1$ %GUARD, %2, %3
+--|     wait %entry, %clk
+--| }
@@ -0,0 +1,45 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b, q : boolean;
+	impure function both return boolean;
+	impure function both return boolean is
+	begin
+		return a and b;
+	end;
+begin
+	p : process (a, b)
+	begin
+		q <= both;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %b = sig i1 %1
+--|     %2 = const i1 0
+--|     %q = sig i1 %2
+--|     inst @work.foo.bar.p (i1$ %a, i1$ %b) -> (i1$ %q)
+--| }
+--|
+--| func @work.foo.bar.both (i1 %a, i1 %b) i1 {
+--| entry:
+--|     %0 = and i1 %a, %b
+--|     ret i1 %0
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a, i1$ %b) -> (i1$ %q) {
+--| entry:
+--|     %0 = prb i1$ %a
+--|     %1 = prb i1$ %b
+--|     %2 = call i1 @work.foo.bar.both (i1 %0, i1 %1)
+--|     %3 = const time 0s 1d
+--|     drv i1$ %q, %2, %3
+--|     wait %entry, %a, %b
+--| }
@@ -0,0 +1,56 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a : integer range -8 to 7 := 7;
+	signal b : integer range -8 to 7 := 3;
+	signal q, r, s : integer range -8 to 7 := 0;
+begin
+	p : process (a, b)
+	begin
+		q <= a / b;
+		r <= a mod b;
+		s <= a rem b;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 7
+--|     %a = sig i4 %0
+--|     %1 = const time 0s
+--|     drv i4$ %a, %0, %1
+--|     %2 = const i4 3
+--|     %b = sig i4 %2
+--|     %3 = const time 0s
+--|     drv i4$ %b, %2, %3
+--|     %4 = const i4 0
+--|     %q = sig i4 %4
+--|     %5 = const i4 0
+--|     %r = sig i4 %5
+--|     %6 = const i4 0
+--|     %s = sig i4 %6
+--|     inst @work.foo.bar.p (i4$ %a, i4$ %b) -> (i4$ %q, i4$ %r, i4$ %s)
+--| }
+--|
+--| proc @work.foo.bar.p (i4$ %a, i4$ %b) -> (i4$ %q, i4$ %r, i4$ %s) {
+--| entry:
+--|     %0 = prb i4$ %a
+--|     %1 = prb i4$ %b
+--|     %2 = sdiv i4 %0, %1
+--|     %3 = const time 0s 1d
+--|     drv i4$ %q, %2, %3
+--|     %4 = prb i4$ %a
+--|     %5 = prb i4$ %b
+--|     %6 = smod i4 %4, %5
+--|     %7 = const time 0s 1d
+--|     drv i4$ %r, %6, %7
+--|     %8 = prb i4$ %a
+--|     %9 = prb i4$ %b
+--|     %10 = srem i4 %8, %9
+--|     %11 = const time 0s 1d
+--|     drv i4$ %s, %10, %11
+--|     wait %entry, %a, %b
+--| }
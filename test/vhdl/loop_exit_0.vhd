@@ -0,0 +1,42 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b : boolean;
+begin
+	p : process
+	begin
+		outer : loop
+			inner : loop
+				exit outer when a;
+				next inner when b;
+			end loop;
+		end loop;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %b = sig i1 %1
+--|     inst @work.foo.bar.p (i1$ %a, i1$ %b) -> ()
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a, i1$ %b) -> () {
+--| entry:
+--|     br %loop_body
+--| loop_exit:
+--|     br %entry
+--| loop_body:
+--|     %0 = prb i1$ %a
+--|     br %0, %exit_cont, %loop_exit
+--| exit_cont:
+--|     %1 = prb i1$ %b
+--|     br %1, %next_cont, %loop_body
+--| next_cont:
+--|     br %loop_body
+--| }
@@ -0,0 +1,43 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b : boolean;
+begin
+	p : process
+	begin
+		while a loop
+			if b then
+				exit;
+			end if;
+		end loop;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %b = sig i1 %1
+--|     inst @work.foo.bar.p (i1$ %a, i1$ %b) -> ()
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a, i1$ %b) -> () {
+--| entry:
+--|     br %loop_cond
+--| loop_body:
+--|     %0 = prb i1$ %b
+--|     br %0, %if_false, %if_true
+--| loop_exit:
+--|     br %entry
+--| loop_cond:
+--|     %1 = prb i1$ %a
+--|     br %1, %loop_exit, %loop_body
+--| if_true:
+--|     br %loop_exit
+--| if_false:
+--|     br %loop_cond
+--| }
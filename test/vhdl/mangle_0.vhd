@@ -0,0 +1,51 @@
+entity foo is
+end;
+
+architecture a of foo is
+begin
+	p : process
+	begin
+	end process;
+end;
+
+architecture b of foo is
+begin
+	p : process
+	begin
+	end process;
+end;
+
+entity top is
+end;
+
+architecture rtl of top is
+begin
+	ia : entity work.foo(a);
+	ib : entity work.foo(b);
+end;
+
+--@ elab top(rtl)
+--@ arg -O0
+
+--| entity @work.foo.a () -> () {
+--|     inst @work.foo.a.p () -> ()
+--| }
+--|
+--| proc @work.foo.a.p () -> () {
+--| entry:
+--|     br %entry
+--| }
+--|
+--| entity @work.foo.b () -> () {
+--|     inst @work.foo.b.p () -> ()
+--| }
+--|
+--| proc @work.foo.b.p () -> () {
+--| entry:
+--|     br %entry
+--| }
+--|
+--| entity @work.top.rtl () -> () {
+--|     inst @work.foo.a () -> ()
+--|     inst @work.foo.b () -> ()
+--| }
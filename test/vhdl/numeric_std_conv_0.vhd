@@ -0,0 +1,72 @@
+package numeric_std is
+	type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
+	type unsigned is array (natural range <>) of std_ulogic;
+	type signed is array (natural range <>) of std_ulogic;
+	function to_integer (arg : unsigned) return natural;
+	function to_integer (arg : signed) return integer;
+	function to_unsigned (arg, size : natural) return unsigned;
+	function to_signed (arg : integer; size : natural) return signed;
+end;
+
+use work.numeric_std.all;
+
+entity foo is
+end;
+
+architecture bar of foo is
+	signal u : unsigned(1 downto 0);
+	signal n : natural;
+begin
+	p : process
+	begin
+		n <= to_integer(u);
+		u <= to_unsigned(5, 2);
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 0
+--|     %1 = [2 x i4 %0]
+--|     %u = sig [2 x i4] %1
+--|     %2 = const i31 0
+--|     %n = sig i31 %2
+--|     inst @work.foo.bar.p () -> ([2 x i4]$ %u, i31$ %n)
+--| }
+--|
+--| proc @work.foo.bar.p () -> ([2 x i4]$ %u, i31$ %n) {
+--| entry:
+--|     %0 = prb [2 x i4]$ %u
+--|     %1 = const i4 3
+--|     %2 = const i4 7
+--|     %3 = const i2 0
+--|     %4 = extf i4, [2 x i4] %0, 0
+--|     %5 = eq i4 %4, %1
+--|     %6 = eq i4 %4, %2
+--|     %7 = or i1 %5, %6
+--|     %8 = inss i2 %3, i1 %7, 1, 1
+--|     %9 = extf i4, [2 x i4] %0, 1
+--|     %10 = eq i4 %9, %1
+--|     %11 = eq i4 %9, %2
+--|     %12 = or i1 %10, %11
+--|     %13 = inss i2 %8, i1 %12, 0, 1
+--|     %14 = const i31 0
+--|     %15 = inss i31 %14, i2 %13, 0, 2
+--|     %16 = const time 0s 1d
+--|     drv i31$ %n, %15, %16
+--|     %17 = const i31 5
+--|     %18 = exts i2, i31 %17, 0, 2
+--|     %19 = const i4 2
+--|     %20 = const i4 3
+--|     %21 = [i4 %19, %20]
+--|     %22 = exts i1, i2 %18, 1, 1
+--|     %23 = mux [2 x i4] %21, i1 %22
+--|     %24 = exts i1, i2 %18, 0, 1
+--|     %25 = mux [2 x i4] %21, i1 %24
+--|     %26 = [i4 %23, %25]
+--|     %27 = const time 0s 1d
+--|     drv [2 x i4]$ %u, %26, %27
+--|     br %entry
+--| }
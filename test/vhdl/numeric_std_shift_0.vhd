@@ -0,0 +1,48 @@
+package numeric_std is
+	type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
+	type unsigned is array (natural range <>) of std_ulogic;
+	function shift_right (arg : unsigned; count : natural) return unsigned;
+end;
+
+use work.numeric_std.all;
+
+entity foo is
+end;
+
+architecture bar of foo is
+	signal u, v : unsigned(3 downto 0);
+begin
+	p : process
+	begin
+		v <= shift_right(u, 2);
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 0
+--|     %1 = [4 x i4 %0]
+--|     %u = sig [4 x i4] %1
+--|     %2 = const i4 0
+--|     %3 = [4 x i4 %2]
+--|     %v = sig [4 x i4] %3
+--|     inst @work.foo.bar.p ([4 x i4]$ %u) -> ([4 x i4]$ %v)
+--| }
+--|
+--| proc @work.foo.bar.p ([4 x i4]$ %u) -> ([4 x i4]$ %v) {
+--| entry:
+--|     %0 = prb [4 x i4]$ %u
+--|     %1 = const i31 2
+--|     %2 = const i4 2
+--|     %3 = [4 x i4 %2]
+--|     %4 = shl [4 x i4] %0, [4 x i4] %3, i31 %1
+--|     %5 = const i31 4
+--|     %6 = ult i31 %1, %5
+--|     %7 = [[4 x i4] %3, %4]
+--|     %8 = mux [2 x [4 x i4]] %7, i1 %6
+--|     %9 = const time 0s 1d
+--|     drv [4 x i4]$ %v, %8, %9
+--|     br %entry
+--| }
@@ -0,0 +1,45 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type rec is record
+		x : boolean;
+		y : boolean;
+	end record;
+	function "+" (a, b : rec) return rec;
+	signal r1, r2, r3 : rec;
+begin
+	p : process
+	begin
+		r3 <= r1 + r2;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %1 = const i1 0
+--|     %2 = {i1 %0, i1 %1}
+--|     %r1 = sig {i1, i1} %2
+--|     %3 = const i1 0
+--|     %4 = const i1 0
+--|     %5 = {i1 %3, i1 %4}
+--|     %r2 = sig {i1, i1} %5
+--|     %6 = const i1 0
+--|     %7 = const i1 0
+--|     %8 = {i1 %6, i1 %7}
+--|     %r3 = sig {i1, i1} %8
+--|     inst @work.foo.bar.p ({i1, i1}$ %r1, {i1, i1}$ %r2) -> ({i1, i1}$ %r3)
+--| }
+--|
+--| proc @work.foo.bar.p ({i1, i1}$ %r1, {i1, i1}$ %r2) -> ({i1, i1}$ %r3) {
+--| entry:
+--|     %0 = prb {i1, i1}$ %r1
+--|     %1 = prb {i1, i1}$ %r2
+--|     %2 = call {i1, i1} @work.foo.bar._add ({i1, i1} %0, {i1, i1} %1)
+--|     %3 = const time 0s 1d
+--|     drv {i1, i1}$ %r3, %2, %3
+--|     br %entry
+--| }
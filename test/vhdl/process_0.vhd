@@ -8,11 +8,14 @@ begin
 	end process;
 end;
 
---@ +elab foo(bar)
+--@ elab foo(bar)
+--@ arg -O0
 
---| proc @foo_bar_empty () () {
+--| entity @work.foo.bar () -> () {
+--|     inst @work.foo.bar.empty () -> ()
 --| }
 --|
---| entity @foo_bar () () {
---|     %empty = inst @foo_bar_empty () ()
+--| proc @work.foo.bar.empty () -> () {
+--| entry:
+--|     br %entry
 --| }
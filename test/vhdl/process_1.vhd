@@ -12,14 +12,22 @@ begin
 	end process;
 end;
 
---@ +elab foo(bar)
+--@ elab foo(bar)
+--@ arg -O0
 
---| proc @foo_bar_toggle () (i1$ a) {
---|     drv %a 0
---|     drv %a 1
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     inst @work.foo.bar.toggle () -> (i1$ %a)
 --| }
 --|
---| entity @foo_bar () () {
---|     %a = sig i1 0
---|     %toggle = inst @foo_bar_toggle () (i1$ %a)
+--| proc @work.foo.bar.toggle () -> (i1$ %a) {
+--| entry:
+--|     %0 = const i1 0
+--|     %1 = const time 0s 1d
+--|     drv i1$ %a, %0, %1
+--|     %2 = const i1 1
+--|     %3 = const time 0s 1d
+--|     drv i1$ %a, %2, %3
+--|     br %entry
 --| }
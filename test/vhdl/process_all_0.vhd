@@ -0,0 +1,41 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b, c : boolean;
+begin
+	p : process (all)
+	begin
+		if a then
+			c <= b;
+		end if;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %b = sig i1 %1
+--|     %2 = const i1 0
+--|     %c = sig i1 %2
+--|     inst @work.foo.bar.p (i1$ %a, i1$ %b) -> (i1$ %c)
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a, i1$ %b) -> (i1$ %c) {
+--| entry:
+--|     %0 = prb i1$ %a
+--|     br %0, %if_false, %if_true
+--| if_true:
+--|     %1 = prb i1$ %b
+--|     %2 = const time 0s 1d
+--|     drv i1$ %c, %1, %2
+--|     br %if_exit
+--| if_false:
+--|     br %if_exit
+--| if_exit:
+--|     wait %entry, %a, %b
+--| }
@@ -0,0 +1,27 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type rec is record
+		x : boolean;
+		y : boolean;
+		z : boolean;
+	end record;
+	function fill (a, b : boolean) return rec is
+	begin
+		return (x => a, others => b);
+	end;
+begin
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--| }
+--|
+--| func @work.foo.bar.fill (i1 %a, i1 %b) {i1, i1, i1} {
+--| entry:
+--|     %0 = {i1 %a, i1 %b, i1 %b}
+--|     ret {i1, i1, i1} %0
+--| }
@@ -0,0 +1,36 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal data : bit_vector(3 downto 0);
+	signal parity : bit;
+begin
+	parity <= xor data;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %1 = [4 x i1 %0]
+--|     %data = sig [4 x i1] %1
+--|     %2 = const i1 0
+--|     %parity = sig i1 %2
+--|     inst @work.foo.bar.assign0 ([4 x i1]$ %data) -> (i1$ %parity)
+--| }
+--|
+--| proc @work.foo.bar.assign0 ([4 x i1]$ %data) -> (i1$ %parity) {
+--| entry:
+--|     %0 = prb [4 x i1]$ %data
+--|     %1 = extf i1, [4 x i1] %0, 0
+--|     %2 = extf i1, [4 x i1] %0, 1
+--|     %3 = xor i1 %1, %2
+--|     %4 = extf i1, [4 x i1] %0, 2
+--|     %5 = xor i1 %3, %4
+--|     %6 = extf i1, [4 x i1] %0, 3
+--|     %7 = xor i1 %5, %6
+--|     %8 = const time 0s 1d
+--|     drv i1$ %parity, %7, %8
+--|     wait %entry, %data
+--| }
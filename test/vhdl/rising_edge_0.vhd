@@ -0,0 +1,60 @@
+package std_logic_1164 is
+	type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
+	function rising_edge (signal s : std_ulogic) return boolean;
+end;
+
+use work.std_logic_1164.all;
+
+entity foo is
+end;
+
+architecture bar of foo is
+	signal clk, d, q : std_ulogic;
+begin
+	p : process (clk)
+	begin
+		if rising_edge(clk) then
+			q <= d;
+		end if;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 0
+--|     %clk = sig i4 %0
+--|     %1 = const i4 0
+--|     %d = sig i4 %1
+--|     %2 = const i4 0
+--|     %q = sig i4 %2
+--|     inst @work.foo.bar.p (i4$ %clk, i4$ %d) -> (i4$ %q)
+--| }
+--|
+--| proc @work.foo.bar.p (i4$ %clk, i4$ %d) -> (i4$ %q) {
+--| entry:
+--|     %0 = prb i4$ %clk
+--|     %1 = var i4 %0
+--|     br %body
+--| body:
+--|     %2 = ld i4* %1
+--|     %3 = prb i4$ %clk
+--|     %4 = neq i4 %3, %2
+--|     %5 = const i4 3
+--|     %6 = prb i4$ %clk
+--|     %7 = eq i4 %6, %5
+--|     %8 = and i1 %4, %7
+--|     br %8, %if_false, %if_true
+--| if_true:
+--|     %9 = prb i4$ %d
+--|     %10 = const time 0s 1d
+--|     drv i4$ %q, %9, %10
+--|     br %if_exit
+--| if_false:
+--|     br %if_exit
+--| if_exit:
+--|     %11 = prb i4$ %clk
+--|     st i4* %1, %11
+--|     wait %body, %clk
+--| }
@@ -0,0 +1,42 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, w, y, z : boolean;
+begin
+	p : process
+	begin
+		w <= a after 3 ns;
+		y <= reject 0 ns inertial a after 5 ns;
+		z <= transport a after 2 ns;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %w = sig i1 %1
+--|     %2 = const i1 0
+--|     %y = sig i1 %2
+--|     %3 = const i1 0
+--|     %z = sig i1 %3
+--|     inst @work.foo.bar.p (i1$ %a) -> (i1$ %w, i1$ %y, i1$ %z)
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a) -> (i1$ %w, i1$ %y, i1$ %z) {
+--| entry:
+--|     %0 = prb i1$ %a
+--|     %1 = const time 3ns
+--|     drv i1$ %w, %0, %1
+--|     %2 = prb i1$ %a
+--|     %3 = const time 5ns
+--|     drv i1$ %y, %2, %3
+--|     %4 = prb i1$ %a
+--|     %5 = const time 2ns
+--|     drv i1$ %z, %4, %5
+--|     br %entry
+--| }
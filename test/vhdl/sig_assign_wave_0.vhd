@@ -0,0 +1,40 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal y, z : bit;
+begin
+	p : process
+	begin
+		y <= transport '1' after 2 ns, '0' after 4 ns;
+		z <= '1' after 2 ns, '0' after 4 ns;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %y = sig i1 %0
+--|     %1 = const i1 0
+--|     %z = sig i1 %1
+--|     inst @work.foo.bar.p () -> (i1$ %y, i1$ %z)
+--| }
+--|
+--| proc @work.foo.bar.p () -> (i1$ %y, i1$ %z) {
+--| entry:
+--|     %0 = const i1 1
+--|     %1 = const time 2ns
+--|     drv i1$ %y, %0, %1
+--|     %2 = const i1 0
+--|     %3 = const time 4ns
+--|     drv i1$ %y, %2, %3
+--|     %4 = const i1 1
+--|     %5 = const time 2ns
+--|     drv i1$ %z, %4, %5
+--|     %6 = const i1 0
+--|     %7 = const time 4ns
+--|     drv i1$ %z, %6, %7
+--|     br %entry
+--| }
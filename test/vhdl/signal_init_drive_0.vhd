@@ -0,0 +1,20 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a : bit := '1';
+	signal b : bit;
+begin
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 1
+--|     %a = sig i1 %0
+--|     %1 = const time 0s
+--|     drv i1$ %a, %0, %1
+--|     %2 = const i1 0
+--|     %b = sig i1 %2
+--| }
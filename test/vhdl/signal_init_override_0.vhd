@@ -0,0 +1,30 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type state is (idle, busy, done);
+	signal a : integer range 0 to 15 := 3;
+	signal b : state;
+	signal c : integer range 0 to 15 := 3;
+begin
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+--@ arg --init-signal work.foo.bar.a=9
+--@ arg --init-signal work.foo.bar.b=2
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i4 9
+--|     %a = sig i4 %0
+--|     %1 = const time 0s
+--|     drv i4$ %a, %0, %1
+--|     %2 = const i2 2
+--|     %b = sig i2 %2
+--|     %3 = const time 0s
+--|     drv i2$ %b, %2, %3
+--|     %4 = const i4 3
+--|     %c = sig i4 %4
+--|     %5 = const time 0s
+--|     drv i4$ %c, %4, %5
+--| }
@@ -0,0 +1,26 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type rec is record
+		x : boolean;
+		y : boolean;
+	end record;
+	function swap (a, b : boolean) return rec is
+	begin
+		return (b, a);
+	end;
+begin
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--| }
+--|
+--| func @work.foo.bar.swap (i1 %a, i1 %b) {i1, i1} {
+--| entry:
+--|     %0 = {i1 %b, i1 %a}
+--|     ret {i1, i1} %0
+--| }
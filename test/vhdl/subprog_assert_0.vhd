@@ -0,0 +1,27 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	procedure check (a : boolean) is
+	begin
+		assert a severity failure;
+	end;
+begin
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--| }
+--|
+--| func @work.foo.bar.check (i1 %a) void {
+--| entry:
+--|     br %a, %assert_fail, %assert_exit
+--| assert_fail:
+--|     %0 = const i2 3
+--|     %1 = call void @vhdl.report (i2 %0)
+--|     br %assert_exit
+--| assert_exit:
+--|     ret
+--| }
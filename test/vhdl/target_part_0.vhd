@@ -0,0 +1,65 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	type entry is record
+		valid : boolean;
+		data : integer range 0 to 255;
+	end record;
+	type entries is array (0 to 3) of entry;
+	signal mem : entries;
+	signal addr : integer range 0 to 3;
+begin
+	p : process
+		variable v : entries;
+	begin
+		mem(addr).valid <= true;
+		v(2).data := 7;
+		v(addr).valid := v(1).valid;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %1 = const i8 0
+--|     %2 = {i1 %0, i8 %1}
+--|     %3 = [4 x {i1, i8} %2]
+--|     %mem = sig [4 x {i1, i8}] %3
+--|     %4 = const i2 0
+--|     %addr = sig i2 %4
+--|     inst @work.foo.bar.p (i2$ %addr) -> ([4 x {i1, i8}]$ %mem)
+--| }
+--|
+--| proc @work.foo.bar.p (i2$ %addr) -> ([4 x {i1, i8}]$ %mem) {
+--| entry:
+--|     %0 = const i1 0
+--|     %1 = const i8 0
+--|     %2 = {i1 %0, i8 %1}
+--|     %3 = [4 x {i1, i8} %2]
+--|     %v = var [4 x {i1, i8}] %3
+--|     br %body
+--| body:
+--|     %4 = prb i2$ %addr
+--|     %5 = shr [4 x {i1, i8}]$ %mem, [4 x {i1, i8}]$ %mem, i2 %4
+--|     %6 = extf {i1, i8}$, [4 x {i1, i8}]$ %5, 0
+--|     %7 = extf i1$, {i1, i8}$ %6, 0
+--|     %8 = const i1 1
+--|     %9 = const time 0s 1d
+--|     drv i1$ %7, %8, %9
+--|     %10 = extf {i1, i8}*, [4 x {i1, i8}]* %v, 2
+--|     %11 = extf i8*, {i1, i8}* %10, 1
+--|     %12 = const i8 7
+--|     st i8* %11, %12
+--|     %13 = prb i2$ %addr
+--|     %14 = shr [4 x {i1, i8}]* %v, [4 x {i1, i8}]* %v, i2 %13
+--|     %15 = extf {i1, i8}*, [4 x {i1, i8}]* %14, 0
+--|     %16 = extf i1*, {i1, i8}* %15, 0
+--|     %17 = ld [4 x {i1, i8}]* %v
+--|     %18 = extf {i1, i8}, [4 x {i1, i8}] %17, 1
+--|     %19 = extf i1, {i1, i8} %18, 0
+--|     st i1* %16, %19
+--|     br %body
+--| }
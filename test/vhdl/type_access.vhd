@@ -19,7 +19,7 @@ architecture bar of foo is
 	--signal c0: BUFFER_PTR;
 begin end;
 
---| entity @foo_bar () () {
+--| entity @work.foo.bar () () {
 --|     %a0 = sig n2* null
 --|     %b0 = sig [0 x [32 x n2]]* null
 --|     %c0 = sig [32 x n2]* null
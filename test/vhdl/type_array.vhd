@@ -38,7 +38,7 @@ begin end;
 
 --!@ elab foo(bar)
 
---| entity @foo_bar () () {
+--| entity @work.foo.bar () () {
 --|     %a = sig [8 x n5] [.. 0]
 --|     %b = sig [256 x [32 x n2]] [.. [.. 0]]
 --|     %c = sig [8 x n2] [.. 0]
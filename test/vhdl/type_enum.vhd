@@ -34,7 +34,7 @@ begin end;
 
 --!@ elab foo(bar)
 
---| entity @foo_bar () () {
+--| entity @work.foo.bar () () {
 --|     %a0 = sig n5 0
 --|     %a1 = sig n5 0
 --|     %a2 = sig n5 1
@@ -35,7 +35,7 @@ begin end;
 
 --!@ elab foo(bar)
 
---| entity @foo_bar () () {
+--| entity @work.foo.bar () () {
 --|     %a0 = sig i16 -32768
 --|     %a1 = sig i16 0
 --|     %a2 = sig i16 -42
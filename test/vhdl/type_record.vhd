@@ -31,7 +31,7 @@ begin end;
 
 --!@ elab foo(bar)
 
---| entity @foo_bar () () {
+--| entity @work.foo.bar () () {
 --|     %a0 = sig {i5, n12, i12} {1, 0, 0}
 --|     %b0 = sig {[21 x n2], [17 x n2]} {[.. 0], [.. 0]}
 --|     %b1 = sig {void, [17 x n2]} {void, [.. 0]}
@@ -0,0 +1,39 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	function id (a : boolean) return boolean is
+	begin
+		return a;
+	end;
+begin
+	zz : process
+	begin
+	end process;
+	aa : process
+	begin
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     inst @work.foo.bar.zz () -> ()
+--|     inst @work.foo.bar.aa () -> ()
+--| }
+--|
+--| proc @work.foo.bar.aa () -> () {
+--| entry:
+--|     br %entry
+--| }
+--|
+--| func @work.foo.bar.id (i1 %a) i1 {
+--| entry:
+--|     ret i1 %a
+--| }
+--|
+--| proc @work.foo.bar.zz () -> () {
+--| entry:
+--|     br %entry
+--| }
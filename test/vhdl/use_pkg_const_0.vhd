@@ -0,0 +1,34 @@
+package pkg is
+	constant K : boolean := true;
+end;
+
+use work.pkg.all;
+
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a : boolean;
+begin
+	p : process
+	begin
+		a <= K;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     inst @work.foo.bar.p () -> (i1$ %a)
+--| }
+--|
+--| proc @work.foo.bar.p () -> (i1$ %a) {
+--| entry:
+--|     %0 = const i1 1
+--|     %1 = const time 0s 1d
+--|     drv i1$ %a, %0, %1
+--|     br %entry
+--| }
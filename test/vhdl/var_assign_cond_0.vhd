@@ -0,0 +1,51 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b, c, d : boolean;
+begin
+	p : process
+		variable v : boolean;
+	begin
+		v := a when c else b;
+		d <= v;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %b = sig i1 %1
+--|     %2 = const i1 0
+--|     %c = sig i1 %2
+--|     %3 = const i1 0
+--|     %d = sig i1 %3
+--|     inst @work.foo.bar.p (i1$ %a, i1$ %b, i1$ %c) -> (i1$ %d)
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a, i1$ %b, i1$ %c) -> (i1$ %d) {
+--| entry:
+--|     %0 = const i1 0
+--|     %v = var i1 %0
+--|     br %body
+--| body:
+--|     %1 = prb i1$ %c
+--|     br %1, %assign_false, %assign_true
+--| assign_exit:
+--|     %2 = ld i1* %v
+--|     %3 = const time 0s 1d
+--|     drv i1$ %d, %2, %3
+--|     br %body
+--| assign_true:
+--|     %4 = prb i1$ %a
+--|     st i1* %v, %4
+--|     br %assign_exit
+--| assign_false:
+--|     %5 = prb i1$ %b
+--|     st i1* %v, %5
+--|     br %assign_exit
+--| }
@@ -0,0 +1,42 @@
+entity foo is
+end;
+
+architecture bar of foo is
+	signal a, b, c : bit;
+begin
+	p : process
+	begin
+		wait until a = b;
+		c <= a;
+	end process;
+end;
+
+--@ elab foo(bar)
+--@ arg -O0
+
+--| entity @work.foo.bar () -> () {
+--|     %0 = const i1 0
+--|     %a = sig i1 %0
+--|     %1 = const i1 0
+--|     %b = sig i1 %1
+--|     %2 = const i1 0
+--|     %c = sig i1 %2
+--|     inst @work.foo.bar.p (i1$ %a, i1$ %b) -> (i1$ %c)
+--| }
+--|
+--| proc @work.foo.bar.p (i1$ %a, i1$ %b) -> (i1$ %c) {
+--| entry:
+--|     br %wait
+--| wait:
+--|     wait %resume, %a, %b
+--| resume:
+--|     %0 = prb i1$ %a
+--|     %1 = prb i1$ %b
+--|     %2 = eq i1 %0, %1
+--|     br %2, %wait, %wait_exit
+--| wait_exit:
+--|     %3 = prb i1$ %a
+--|     %4 = const time 0s 1d
+--|     drv i1$ %c, %3, %4
+--|     br %entry
+--| }